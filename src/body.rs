@@ -0,0 +1,220 @@
+//! A minimal trait for message bodies.
+//!
+//! `Request<B>` and `Response<B>` don't put any bounds on the body type.
+//! The [`Body`] trait gives generic code a way to ask a body how large it
+//! is and whether there is anything left to read, without knowing its type.
+//! It is implemented for the common in-memory bodies of this crate's
+//! ecosystem.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::Request;
+//! use httplike::body::Body;
+//!
+//! // A middleware that only buffers small bodies.
+//! fn is_small<B: Body>(request: &Request<B>) -> bool {
+//!     match request.body().size_hint().upper() {
+//!         Some(len) => len <= 1024,
+//!         None => false,
+//!     }
+//! }
+//!
+//! assert!(is_small(&Request::new("hello")));
+//! assert!(!is_small(&Request::new(vec![0u8; 4096])));
+//! ```
+//!
+//! [`Body`]: trait.Body.html
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
+/// A message body.
+///
+/// Both methods have defaults describing a body of unknown size, so a
+/// streaming body only needs to override what it knows about itself.
+pub trait Body {
+    /// Returns the bounds on the remaining length of the body.
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+
+    /// Returns true if there is nothing left to read from the body.
+    ///
+    /// A return value of `false` does not guarantee that there is more
+    /// data, only that the body can't tell yet.
+    fn is_end_stream(&self) -> bool {
+        false
+    }
+}
+
+/// The bounds on the remaining length of a body.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::body::SizeHint;
+/// let mut hint = SizeHint::new();
+/// assert_eq!(hint.lower(), 0);
+/// assert_eq!(hint.upper(), None);
+///
+/// hint.set_exact(42);
+/// assert_eq!(hint.exact(), Some(42));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SizeHint {
+    lower: u64,
+    upper: Option<u64>,
+}
+
+impl SizeHint {
+    /// Returns a `SizeHint` with a lower bound of 0 and no upper bound.
+    #[inline]
+    pub fn new() -> SizeHint {
+        SizeHint::default()
+    }
+
+    /// Returns a `SizeHint` with both bounds equal to `len`.
+    #[inline]
+    pub fn with_exact(len: u64) -> SizeHint {
+        SizeHint {
+            lower: len,
+            upper: Some(len),
+        }
+    }
+
+    /// Returns the lower bound.
+    #[inline]
+    pub fn lower(&self) -> u64 {
+        self.lower
+    }
+
+    /// Returns the upper bound, if known.
+    #[inline]
+    pub fn upper(&self) -> Option<u64> {
+        self.upper
+    }
+
+    /// Returns the exact length, if the bounds are equal.
+    #[inline]
+    pub fn exact(&self) -> Option<u64> {
+        if Some(self.lower) == self.upper {
+            self.upper
+        } else {
+            None
+        }
+    }
+
+    /// Sets the lower bound.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `lower` is larger than the upper bound.
+    #[inline]
+    pub fn set_lower(&mut self, lower: u64) {
+        if let Some(upper) = self.upper {
+            assert!(lower <= upper, "`lower` is larger than the upper bound");
+        }
+        self.lower = lower;
+    }
+
+    /// Sets the upper bound.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `upper` is smaller than the lower bound.
+    #[inline]
+    pub fn set_upper(&mut self, upper: u64) {
+        assert!(upper >= self.lower, "`upper` is smaller than the lower bound");
+        self.upper = Some(upper);
+    }
+
+    /// Sets both bounds to `len`.
+    #[inline]
+    pub fn set_exact(&mut self, len: u64) {
+        self.lower = len;
+        self.upper = Some(len);
+    }
+}
+
+impl Body for () {
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(0)
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_body_for_buf {
+    ($($ty:ty),+) => {
+        $(
+            impl Body for $ty {
+                #[inline]
+                fn size_hint(&self) -> SizeHint {
+                    SizeHint::with_exact(self.len() as u64)
+                }
+
+                #[inline]
+                fn is_end_stream(&self) -> bool {
+                    self.is_empty()
+                }
+            }
+        )+
+    }
+}
+
+impl_body_for_buf!(Bytes, Vec<u8>, String, &'_ [u8], &'_ str);
+
+impl<B: Body + ?Sized> Body for alloc::boxed::Box<B> {
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        (**self).size_hint()
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        (**self).is_end_stream()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_memory_bodies_are_exact() {
+        assert_eq!(().size_hint().exact(), Some(0));
+        assert!(().is_end_stream());
+
+        assert_eq!(Bytes::from_static(b"abc").size_hint().exact(), Some(3));
+        assert_eq!(String::from("hello").size_hint().exact(), Some(5));
+        assert!(!"x".is_end_stream());
+        assert!(Vec::<u8>::new().is_end_stream());
+
+        let boxed: alloc::boxed::Box<dyn Body> = alloc::boxed::Box::new(vec![1u8, 2]);
+        assert_eq!(boxed.size_hint().exact(), Some(2));
+    }
+
+    #[test]
+    fn bounds() {
+        let mut hint = SizeHint::new();
+        hint.set_lower(10);
+        assert_eq!(hint.exact(), None);
+        hint.set_upper(10);
+        assert_eq!(hint, SizeHint::with_exact(10));
+    }
+
+    #[test]
+    #[should_panic]
+    fn upper_below_lower() {
+        let mut hint = SizeHint::new();
+        hint.set_lower(10);
+        hint.set_upper(9);
+    }
+}
@@ -0,0 +1,985 @@
+use core::cmp;
+use core::convert::TryFrom;
+use core::ops::Range;
+
+use bytes::{Bytes, BytesMut};
+
+#[cfg(feature = "sip")]
+use super::sip;
+use super::{has_text_head, HeaderCase, Limits, ParseError, ParseErrorKind};
+use crate::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH};
+use crate::profile::Repairs;
+use crate::response::ReasonPhrase;
+use crate::{Method, Request, Response, StatusCode, Uri, Version};
+
+/// Reads the head of a request from `src` with the default `Limits`.
+///
+/// See `decode_request_head_with` for details.
+///
+/// # Examples
+///
+/// ```
+/// # use bytes::BytesMut;
+/// # use httplike::{codec, Method};
+/// let mut buf = BytesMut::from(&b"GET /index.html HTTP/1.1\r\nHost: "[..]);
+/// assert!(codec::decode_request_head(&mut buf).unwrap().is_none());
+///
+/// buf.extend_from_slice(b"example.com\r\n\r\nbody");
+/// let request = codec::decode_request_head(&mut buf).unwrap().unwrap();
+///
+/// assert_eq!(request.method(), Method::GET);
+/// assert_eq!(request.headers()["host"], "example.com");
+/// assert_eq!(&buf[..], &b"body"[..]);
+/// ```
+pub fn decode_request_head(src: &mut BytesMut) -> Result<Option<Request<()>>, ParseError> {
+    decode_request_head_with(src, &Limits::default())
+}
+
+/// Reads the head of a request from `src`.
+///
+/// Returns `Ok(None)`, leaving `src` untouched, if `src` doesn't contain a
+/// complete head yet. Otherwise the head is removed from `src`, and the
+/// bytes following it are left for the body or the next message.
+///
+/// The request line is the method, the request target and the version,
+/// separated by single spaces. Any version with a text message format is
+/// accepted, so the same call reads HTTP/1.x, RTSP and SIP requests, and the
+/// protocol of the request is set from the version. Empty lines before the
+/// request line are skipped, and lines may end with a bare LF.
+///
+/// The request doesn't copy the head: header values, the URI, extension
+/// methods longer than 15 bytes and custom header names that are already
+/// lowercase share its memory, while shorter methods are stored inline and
+/// common header values are static. Only names that need to be lowercased
+/// are copied. When a header name isn't all lowercase, the received
+/// spellings are recorded in a `HeaderCase` extension, so encoding the
+/// request again keeps them.
+///
+/// SIP messages follow the rules of RFC 3261 for header lines, with the
+/// `sip` feature: whitespace is allowed before the colon, compact names like
+/// `v` are expanded to their full names, and the comma-separated entries of
+/// `Via`, `Route`, `Record-Route` and `Contact` are split into one value
+/// each, in order.
+///
+/// # Errors
+///
+/// Returns an error if the head is malformed or exceeds `limits`. Heads
+/// longer than the limit are detected before they are complete, the other
+/// limits once the head is. The head is removed from `src` when it is
+/// complete but malformed.
+pub fn decode_request_head_with(
+    src: &mut BytesMut,
+    limits: &Limits,
+) -> Result<Option<Request<()>>, ParseError> {
+    decode_request(src, limits, &mut 0)
+}
+
+fn decode_request(
+    src: &mut BytesMut,
+    limits: &Limits,
+    scanned: &mut usize,
+) -> Result<Option<Request<()>>, ParseError> {
+    let head = match take_head(src, limits, scanned)? {
+        Some(head) => head,
+        None => return Ok(None),
+    };
+
+    let mut lines = Lines::new(&head);
+    let line = lines.next().unwrap_or(0..0);
+    let (method, target, version) = split_start_line(&head, line)?;
+    let (target, version) = match version {
+        // Spaces in the target end up in the version at first.
+        Some(version) if limits.profile().is_lenient() => {
+            match find_last(&head, version.clone(), b' ') {
+                Some(last) => (target.start..last, last + 1..version.end),
+                None => (target, version),
+            }
+        }
+        Some(version) => (target, version),
+        None => return Err(start_line()),
+    };
+
+    if method.len() > limits.max_method_len() || target.len() > limits.max_uri_len() {
+        return Err(ParseError::new(ParseErrorKind::TooLarge));
+    }
+
+    let mut repairs = Repairs::new();
+    let method = Method::from_shared(head.slice(method)).map_err(|_| start_line())?;
+    let uri = decode_uri(&head, target, limits, &mut repairs)?;
+    let version = parse_version(&head[version])?;
+
+    let mut request = Request::new(());
+    *request.method_mut() = method;
+    *request.uri_mut() = uri;
+    *request.version_mut() = version;
+    *request.protocol_mut() = version.protocol();
+    let headers = request.headers_mut();
+    let sip = is_sip(version);
+    if let Some(case) = decode_headers(&head, lines, limits, sip, headers, &mut repairs)? {
+        request.extensions_mut().insert(case);
+    }
+    if !repairs.is_empty() {
+        request.extensions_mut().insert(repairs);
+    }
+    Ok(Some(request))
+}
+
+/// Reads the head of a response from `src` with the default `Limits`.
+///
+/// See `decode_response_head_with` for details.
+///
+/// # Examples
+///
+/// ```
+/// # use bytes::BytesMut;
+/// # use httplike::{codec, StatusCode};
+/// let mut buf = BytesMut::from(&b"HTTP/1.1 404 Nothing Here\r\n\r\n"[..]);
+/// let response = codec::decode_response_head(&mut buf).unwrap().unwrap();
+///
+/// assert_eq!(response.status(), StatusCode::NOT_FOUND);
+/// assert_eq!(response.reason(), Some("Nothing Here"));
+/// ```
+pub fn decode_response_head(src: &mut BytesMut) -> Result<Option<Response<()>>, ParseError> {
+    decode_response_head_with(src, &Limits::default())
+}
+
+/// Reads the head of a response from `src`.
+///
+/// This works like `decode_request_head_with`, with a status line made of
+/// the version, the three digit status code and the reason phrase. The
+/// reason phrase may be empty, but the space before it is required, except
+/// in SIP responses.
+///
+/// A reason phrase other than the canonical reason of the status code is
+/// kept in the response, and returned by `Response::reason_phrase`.
+///
+/// # Errors
+///
+/// Returns an error if the head is malformed or exceeds `limits`.
+pub fn decode_response_head_with(
+    src: &mut BytesMut,
+    limits: &Limits,
+) -> Result<Option<Response<()>>, ParseError> {
+    decode_response(src, limits, &mut 0)
+}
+
+fn decode_response(
+    src: &mut BytesMut,
+    limits: &Limits,
+    scanned: &mut usize,
+) -> Result<Option<Response<()>>, ParseError> {
+    let head = match take_head(src, limits, scanned)? {
+        Some(head) => head,
+        None => return Ok(None),
+    };
+
+    let mut lines = Lines::new(&head);
+    let line = lines.next().unwrap_or(0..0);
+    let (version, status, reason) = split_start_line(&head, line)?;
+
+    let version = parse_version(&head[version])?;
+    if status.len() != 3 {
+        return Err(start_line());
+    }
+    let status = StatusCode::from_bytes(&head[status]).map_err(|_| start_line())?;
+    let canonical = status.canonical_reason().unwrap_or("");
+    let reason = match reason {
+        Some(reason) if head[reason.clone()] != *canonical.as_bytes() => {
+            Some(ReasonPhrase::try_from(head.slice(reason)).map_err(|_| start_line())?)
+        }
+        Some(_) => None,
+        // Some SIP implementations leave out the space of an empty reason.
+        None if is_sip(version) => None,
+        None => return Err(start_line()),
+    };
+
+    let mut response = Response::new(());
+    *response.status_mut() = status;
+    *response.version_mut() = version;
+    *response.protocol_mut() = version.protocol();
+    *response.reason_phrase_mut() = reason;
+    let mut repairs = Repairs::new();
+    let headers = response.headers_mut();
+    let sip = is_sip(version);
+    if let Some(case) = decode_headers(&head, lines, limits, sip, headers, &mut repairs)? {
+        response.extensions_mut().insert(case);
+    }
+    if !repairs.is_empty() {
+        response.extensions_mut().insert(repairs);
+    }
+    Ok(Some(response))
+}
+
+/// Reads heads from a receive buffer that only grows between calls.
+///
+/// The free functions search `src` for the end of the head from the start
+/// on every call. A `HeadDecoder` remembers how far it got, so a head that
+/// arrives in small pieces is only searched once. It must be called with
+/// the same buffer until a head is returned or an error occurs, without
+/// removing anything from it in between.
+///
+/// # Examples
+///
+/// ```
+/// # use bytes::BytesMut;
+/// # use httplike::codec::HeadDecoder;
+/// let mut decoder = HeadDecoder::new();
+/// let mut buf = BytesMut::new();
+/// for &b in b"HTTP/1.1 200 OK\r\nServer: x\r\n\r\n" {
+///     assert!(decoder.decode_response(&mut buf).unwrap().is_none());
+///     buf.extend_from_slice(&[b]);
+/// }
+///
+/// let response = decoder.decode_response(&mut buf).unwrap().unwrap();
+/// assert_eq!(response.headers()["server"], "x");
+/// assert!(buf.is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HeadDecoder {
+    limits: Limits,
+    scanned: usize,
+}
+
+impl HeadDecoder {
+    /// Creates a decoder with the default `Limits`.
+    pub fn new() -> HeadDecoder {
+        HeadDecoder::default()
+    }
+
+    /// Creates a decoder with `limits`.
+    pub fn with_limits(limits: Limits) -> HeadDecoder {
+        HeadDecoder { limits, scanned: 0 }
+    }
+
+    /// Returns the limits of the decoder.
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Reads the head of a request from `src`, like
+    /// `decode_request_head_with`.
+    pub fn decode_request(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Option<Request<()>>, ParseError> {
+        decode_request(src, &self.limits, &mut self.scanned)
+    }
+
+    /// Reads the head of a response from `src`, like
+    /// `decode_response_head_with`.
+    pub fn decode_response(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Option<Response<()>>, ParseError> {
+        decode_response(src, &self.limits, &mut self.scanned)
+    }
+}
+
+/// Splits a complete head off the front of `src`.
+///
+/// `scanned` is how much of `src` an earlier call already searched for the
+/// end of the head. It is updated when the head is incomplete, and reset
+/// otherwise.
+fn take_head(
+    src: &mut BytesMut,
+    limits: &Limits,
+    scanned: &mut usize,
+) -> Result<Option<Bytes>, ParseError> {
+    // Empty lines before the start line are ignored, RFC 7230 section 3.5.
+    let mut skip = 0;
+    while src[skip..].starts_with(b"\n") || src[skip..].starts_with(b"\r\n") {
+        skip += if src[skip] == b'\r' { 2 } else { 1 };
+    }
+    let _ = src.split_to(skip);
+
+    let from = if skip == 0 { *scanned } else { 0 };
+    *scanned = 0;
+    match find_head_end(src, from) {
+        Some(end) if end <= limits.max_head_len() => Ok(Some(src.split_to(end).freeze())),
+        None if src.len() <= limits.max_head_len() => {
+            *scanned = src.len();
+            Ok(None)
+        }
+        _ => Err(ParseError::new(ParseErrorKind::TooLarge)),
+    }
+}
+
+/// Splits a complete block of header lines off the front of `src` and
+/// decodes it, like the header lines of a head.
+///
+/// Unlike a head, the block can be just the empty line.
+pub(crate) fn decode_header_block(
+    src: &mut BytesMut,
+    limits: &Limits,
+) -> Result<Option<HeaderMap>, ParseError> {
+    let end = if src.starts_with(b"\n") {
+        Some(1)
+    } else if src.starts_with(b"\r\n") {
+        Some(2)
+    } else {
+        find_head_end(src, 0)
+    };
+    let block = match end {
+        Some(end) if end <= limits.max_head_len() => src.split_to(end).freeze(),
+        None if src.len() <= limits.max_head_len() => return Ok(None),
+        _ => return Err(ParseError::new(ParseErrorKind::TooLarge)),
+    };
+
+    let mut headers = HeaderMap::new();
+    let mut repairs = Repairs::new();
+    decode_headers(
+        &block,
+        Lines::new(&block),
+        limits,
+        false,
+        &mut headers,
+        &mut repairs,
+    )?;
+    Ok(Some(headers))
+}
+
+/// Returns the length of the head up to and including the empty line.
+///
+/// The first `from` bytes are known not to hold the end of the head, but
+/// may hold the start of the empty line that ends it.
+fn find_head_end(buf: &[u8], from: usize) -> Option<usize> {
+    let mut i = cmp::min(from, buf.len()).saturating_sub(3);
+    while i < buf.len() {
+        if buf[i] == b'\n' {
+            let rest = &buf[i + 1..];
+            if rest.starts_with(b"\n") {
+                return Some(i + 2);
+            } else if rest.starts_with(b"\r\n") {
+                return Some(i + 3);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+type StartLine = (Range<usize>, Range<usize>, Option<Range<usize>>);
+
+/// Splits a start line into its three parts at the first two spaces.
+///
+/// The third part is `None` if there is no second space.
+fn split_start_line(head: &[u8], line: Range<usize>) -> Result<StartLine, ParseError> {
+    let first = find(head, line.clone(), b' ').ok_or_else(start_line)?;
+    let (second, third) = match find(head, first + 1..line.end, b' ') {
+        Some(second) => (first + 1..second, Some(second + 1..line.end)),
+        None => (first + 1..line.end, None),
+    };
+
+    if first == line.start || second.start == second.end {
+        return Err(start_line());
+    }
+    Ok((line.start..first, second, third))
+}
+
+fn parse_version(src: &[u8]) -> Result<Version, ParseError> {
+    Version::ALL
+        .iter()
+        .cloned()
+        .find(|version| version.as_str().as_bytes() == src)
+        .filter(|&version| has_text_head(version, true))
+        .ok_or_else(|| ParseError::new(ParseErrorKind::Version))
+}
+
+/// Appends the header lines to `headers`, returning their spellings if any
+/// of them isn't lowercase.
+fn decode_headers(
+    head: &Bytes,
+    lines: Lines<'_>,
+    limits: &Limits,
+    sip: bool,
+    headers: &mut HeaderMap,
+    repairs: &mut Repairs,
+) -> Result<Option<HeaderCase>, ParseError> {
+    // Most heads use lowercase names, so the case is only recorded once a
+    // name isn't.
+    let mut case: Option<HeaderCase> = None;
+
+    for (i, line) in lines.enumerate() {
+        if i == limits.max_headers() {
+            return Err(ParseError::new(ParseErrorKind::TooManyHeaders));
+        }
+        if line.len() > limits.max_header_len() {
+            return Err(ParseError::new(ParseErrorKind::TooLarge));
+        }
+
+        // A name can't be empty, and obsolete line folding starts with
+        // whitespace, so both are rejected here.
+        let colon = match find(head, line.clone(), b':') {
+            Some(colon) if colon > line.start => colon,
+            _ => return Err(header_line()),
+        };
+        let raw = if sip {
+            trim(head, line.start..colon)
+        } else {
+            line.start..colon
+        };
+        let name = decode_name(head.slice(raw.clone()), sip, limits, repairs)?;
+        let value = trim(head, colon + 1..line.end);
+        let raw = head.slice(raw);
+        if case.is_none() && name.as_str().as_bytes() != &raw[..] {
+            case = Some(lowercase_case(headers));
+        }
+
+        #[cfg(feature = "sip")]
+        {
+            if sip && sip::is_list(&name) {
+                for value in sip::split_list(head, value) {
+                    if let Some(case) = case.as_mut() {
+                        case.append(&name, raw.clone());
+                    }
+                    headers.append(&name, decode_value(head, value, limits, repairs)?);
+                }
+                continue;
+            }
+        }
+
+        if let Some(case) = case.as_mut() {
+            case.append(&name, raw);
+        }
+        headers.append(name, decode_value(head, value, limits, repairs)?);
+    }
+
+    if limits.profile().is_lenient() {
+        collapse_content_length(headers, repairs);
+    }
+    Ok(case)
+}
+
+/// Records the spelling of the headers decoded so far, which were all
+/// lowercase.
+fn lowercase_case(headers: &HeaderMap) -> HeaderCase {
+    let mut case = HeaderCase::new();
+    for (name, _) in headers {
+        let original = Bytes::copy_from_slice(name.as_str().as_bytes());
+        case.append(name, original);
+    }
+    case
+}
+
+/// Replaces `Content-Length` headers that all repeat the same length with
+/// a single one.
+fn collapse_content_length(headers: &mut HeaderMap, repairs: &mut Repairs) {
+    let mut lengths = headers
+        .get_all(CONTENT_LENGTH)
+        .iter()
+        .flat_map(|value| value.as_bytes().split(|&b| b == b','))
+        .map(|length| &length[trim(length, 0..length.len())]);
+
+    let first = match lengths.next() {
+        Some(first) => first,
+        None => return,
+    };
+    let mut count = 1;
+    for length in lengths {
+        if length != first {
+            return;
+        }
+        count += 1;
+    }
+    if count == 1 {
+        return;
+    }
+
+    if let Ok(value) = HeaderValue::from_bytes(first) {
+        headers.insert(CONTENT_LENGTH, value);
+        repairs.collapsed(count - 1);
+    }
+}
+
+fn decode_uri(
+    head: &Bytes,
+    target: Range<usize>,
+    limits: &Limits,
+    repairs: &mut Repairs,
+) -> Result<Uri, ParseError> {
+    Uri::from_maybe_shared(head.slice(target.clone()))
+        .or_else(|err| match limits.profile() {
+            profile if profile.is_lenient() => {
+                Uri::from_bytes_with(&head[target], profile, repairs)
+            }
+            _ => Err(err),
+        })
+        .map_err(|_| start_line())
+}
+
+#[allow(unused_variables)]
+fn decode_name(
+    src: Bytes,
+    sip: bool,
+    limits: &Limits,
+    repairs: &mut Repairs,
+) -> Result<HeaderName, ParseError> {
+    #[cfg(feature = "sip")]
+    {
+        if let Some(name) = sip::expand_compact(&src).filter(|_| sip) {
+            return Ok(HeaderName::from_static(name));
+        }
+    }
+    HeaderName::from_shared(src.clone())
+        .or_else(|err| match limits.profile() {
+            profile if profile.is_lenient() => HeaderName::from_bytes_with(&src, profile, repairs),
+            _ => Err(err),
+        })
+        .map_err(|_| header_line())
+}
+
+fn decode_value(
+    head: &Bytes,
+    value: Range<usize>,
+    limits: &Limits,
+    repairs: &mut Repairs,
+) -> Result<HeaderValue, ParseError> {
+    HeaderValue::from_maybe_shared(head.slice(value.clone()))
+        .or_else(|err| match limits.profile() {
+            profile if profile.is_lenient() => {
+                let repaired = HeaderValue::from_bytes_with(&head[value], profile, repairs)?;
+                // Stripping controls can leave whitespace at either end.
+                let bytes = repaired.as_bytes();
+                let trimmed = trim(bytes, 0..bytes.len());
+                if trimmed.len() == bytes.len() {
+                    Ok(repaired)
+                } else {
+                    HeaderValue::from_bytes(&bytes[trimmed])
+                }
+            }
+            _ => Err(err),
+        })
+        .map_err(|_| header_line())
+}
+
+#[allow(unused_variables)]
+fn is_sip(version: Version) -> bool {
+    #[cfg(feature = "sip")]
+    {
+        version == Version::SIP_2
+    }
+    #[cfg(not(feature = "sip"))]
+    {
+        false
+    }
+}
+
+/// The lines of a complete head, without their terminators.
+///
+/// The empty line ending the head isn't yielded.
+struct Lines<'a> {
+    head: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(head: &'a [u8]) -> Lines<'a> {
+        Lines { head, pos: 0 }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        let start = self.pos;
+        let lf = find(self.head, start..self.head.len(), b'\n')?;
+        self.pos = lf + 1;
+
+        let end = if lf > start && self.head[lf - 1] == b'\r' {
+            lf - 1
+        } else {
+            lf
+        };
+        if start == end {
+            None
+        } else {
+            Some(start..end)
+        }
+    }
+}
+
+fn find(buf: &[u8], range: Range<usize>, byte: u8) -> Option<usize> {
+    let start = range.start;
+    buf[range]
+        .iter()
+        .position(|&b| b == byte)
+        .map(|i| start + i)
+}
+
+fn find_last(buf: &[u8], range: Range<usize>, byte: u8) -> Option<usize> {
+    let start = range.start;
+    buf[range]
+        .iter()
+        .rposition(|&b| b == byte)
+        .map(|i| start + i)
+}
+
+pub(super) fn trim(buf: &[u8], mut range: Range<usize>) -> Range<usize> {
+    let is_ows = |b: u8| b == b' ' || b == b'\t';
+    while range.start < range.end && is_ows(buf[range.start]) {
+        range.start += 1;
+    }
+    while range.start < range.end && is_ows(buf[range.end - 1]) {
+        range.end -= 1;
+    }
+    range
+}
+
+fn start_line() -> ParseError {
+    ParseError::new(ParseErrorKind::StartLine)
+}
+
+fn header_line() -> ParseError {
+    ParseError::new(ParseErrorKind::Header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{encode_request_head, encode_response_head};
+
+    #[test]
+    fn partial_heads_are_left_in_place() {
+        let input = b"\r\nGET /a HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let mut buf = BytesMut::new();
+
+        for chunk in input.chunks(3) {
+            assert!(!buf.ends_with(b"\r\n\r\n"));
+            buf.extend_from_slice(chunk);
+            if let Some(request) = decode_request_head(&mut buf).unwrap() {
+                assert!(buf.is_empty());
+                assert_eq!(request.uri(), "/a");
+                return;
+            }
+        }
+        panic!("head never completed");
+    }
+
+    #[test]
+    fn decoder_resumes_the_search() {
+        let inputs: &[&[u8]] = &[
+            b"\r\nGET /a HTTP/1.1\r\nHost: example.com\r\n\r\nx",
+            b"GET /a HTTP/1.1\nHost: example.com\n\nx",
+            b"GET /a HTTP/1.1\nHost: example.com\n\r\nx",
+        ];
+        for input in inputs {
+            for size in 1..5 {
+                let mut decoder = HeadDecoder::new();
+                let mut buf = BytesMut::new();
+                let mut request = None;
+                for chunk in input.chunks(size) {
+                    buf.extend_from_slice(chunk);
+                    request = decoder.decode_request(&mut buf).unwrap();
+                    if request.is_some() {
+                        break;
+                    }
+                }
+                assert_eq!(request.unwrap().headers()["host"], "example.com");
+                assert!(b"x".starts_with(&buf[..]));
+            }
+        }
+
+        let mut limits = Limits::new();
+        limits.set_max_head_len(8);
+        let mut decoder = HeadDecoder::with_limits(limits);
+        let mut buf = BytesMut::from(&b"GET / HT"[..]);
+        assert!(decoder.decode_request(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(b"TP/1.1");
+        assert!(decoder.decode_request(&mut buf).unwrap_err().is_too_large());
+    }
+
+    #[test]
+    fn debug_redacts_sensitive_headers() {
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\nCookie: id=secret\r\n\r\n"[..]);
+        let request = decode_request_head(&mut buf).unwrap().unwrap();
+        let out = format!("{:?}", request);
+        assert!(!out.contains("secret"), "{}", out);
+
+        let mut buf = BytesMut::from(&b"HTTP/1.1 200 OK\r\nSet-Cookie: id=secret\r\n\r\n"[..]);
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert!(response.headers()["set-cookie"].is_sensitive());
+        assert!(!format!("{:?}", response).contains("secret"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut buf = BytesMut::from(
+            &b"POST /upload HTTP/1.0\nContent-Length:5\r\nx-dup: 1 \r\nX-Dup:\t2\n\nhello"[..],
+        );
+        let request = decode_request_head(&mut buf).unwrap().unwrap();
+        assert_eq!(&buf[..], b"hello");
+        assert_eq!(request.headers()["content-length"], "5");
+        assert_eq!(request.headers().get_all("x-dup").iter().count(), 2);
+
+        buf.clear();
+        encode_request_head(&request, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"POST /upload HTTP/1.0\r\nContent-Length: 5\r\nx-dup: 1\r\nX-Dup: 2\r\n\r\n"[..]
+        );
+
+        let mut buf = BytesMut::from(&b"HTTP/1.1 200 Fine\r\nserver: x\r\n\r\n"[..]);
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert!(response.extensions().get::<HeaderCase>().is_none());
+        encode_response_head(&response, &mut buf).unwrap();
+        assert_eq!(&buf[..], &b"HTTP/1.1 200 Fine\r\nserver: x\r\n\r\n"[..]);
+    }
+
+    #[test]
+    fn case_after_lowercase_names() {
+        let mut buf =
+            BytesMut::from(&b"GET / HTTP/1.1\r\nhost: a\r\nx-dup: 1\r\nX-Dup: 2\r\n\r\n"[..]);
+        let request = decode_request_head(&mut buf).unwrap().unwrap();
+        let case = request.extensions().get::<HeaderCase>().unwrap();
+        assert_eq!(case.get(&crate::header::HOST, 0), Some(&b"host"[..]));
+        let dup = HeaderName::from_static("x-dup");
+        assert_eq!(case.get(&dup, 0), Some(&b"x-dup"[..]));
+        assert_eq!(case.get(&dup, 1), Some(&b"X-Dup"[..]));
+
+        encode_request_head(&request, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"GET / HTTP/1.1\r\nhost: a\r\nx-dup: 1\r\nX-Dup: 2\r\n\r\n"[..]
+        );
+    }
+
+    #[test]
+    fn heads_share_the_buffer() {
+        let mut buf = BytesMut::from(
+            &b"PROPPATCH-EXTENDED /a/b?c HTTP/1.1\r\nx-custom-name: some value\r\n\
+               X-Mixed: v\r\n\r\n"[..],
+        );
+        let range = buf.as_ptr() as usize..buf.as_ptr() as usize + buf.len();
+        let shared = |s: &str| range.contains(&(s.as_ptr() as usize));
+
+        let request = decode_request_head(&mut buf).unwrap().unwrap();
+        assert!(shared(request.method().as_str()));
+        assert!(shared(request.uri().path()));
+        let (name, value) = request.headers().iter().next().unwrap();
+        assert!(shared(name.as_str()));
+        assert!(shared(value.to_str().unwrap()));
+
+        // Names that have to be lowercased are copied.
+        let name = request.headers().keys().nth(1).unwrap();
+        assert_eq!(name, "x-mixed");
+        assert!(!shared(name.as_str()));
+    }
+
+    #[test]
+    fn canonical_and_empty_reasons() {
+        let mut buf = BytesMut::from(&b"HTTP/1.1 404 Not Found\r\n\r\nHTTP/1.1 404 \r\n\r\n"[..]);
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert_eq!(response.reason_phrase(), None);
+
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert_eq!(response.reason_phrase().unwrap(), "");
+        assert_eq!(response.reason(), Some(""));
+    }
+
+    #[test]
+    fn limits() {
+        let mut limits = Limits::new();
+        limits.set_max_head_len(16);
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\n"[..]);
+        assert!(decode_request_head_with(&mut buf, &limits)
+            .unwrap()
+            .is_none());
+        buf.extend_from_slice(b"a");
+        assert!(decode_request_head_with(&mut buf, &limits)
+            .unwrap_err()
+            .is_too_large());
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\n\r\n"[..]);
+        assert!(decode_request_head_with(&mut buf, &limits)
+            .unwrap_err()
+            .is_too_large());
+
+        let mut limits = Limits::new();
+        limits.set_max_method_len(3);
+        limits.set_max_uri_len(4);
+        limits.set_max_header_len(6);
+        let heads: &[&[u8]] = &[
+            b"POST / HTTP/1.1\r\n\r\n",
+            b"GET /abcd HTTP/1.1\r\n\r\n",
+            b"GET / HTTP/1.1\r\nx: abcd\r\n\r\n",
+        ];
+        for &head in heads {
+            let mut buf = BytesMut::from(head);
+            let err = decode_request_head_with(&mut buf, &limits).unwrap_err();
+            assert!(err.is_too_large(), "{:?}", String::from_utf8_lossy(head));
+        }
+        let mut buf = BytesMut::from(&b"GET /abc HTTP/1.1\r\nx: abc\r\n\r\n"[..]);
+        assert!(decode_request_head_with(&mut buf, &limits).is_ok());
+    }
+
+    #[test]
+    fn malformed_heads() {
+        let bad: &[&[u8]] = &[
+            b"GET  / HTTP/1.1\r\n\r\n",
+            b"GET /\r\n\r\n",
+            b"GET / HTTP/1.1 \r\n\r\n",
+            b"GET / HTTP/1.1\r\nhost : a\r\n\r\n",
+            b"GET / HTTP/1.1\r\nx: a\r\n b\r\n\r\n",
+            b"GET / HTTP/1.1\r\n: a\r\n\r\n",
+            b"GET / HTTP/1.1\r\nx: a\rb\r\n\r\n",
+            b"HTTP/1.1 20 OK\r\n\r\n",
+            b"HTTP/1.1 200\r\n\r\n",
+        ];
+        for &input in bad {
+            let mut buf = BytesMut::from(input);
+            let result = if input.starts_with(b"HTTP") {
+                decode_response_head(&mut buf).map(|_| ())
+            } else {
+                decode_request_head(&mut buf).map(|_| ())
+            };
+            assert!(result.is_err(), "{:?}", String::from_utf8_lossy(input));
+        }
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.2\r\n\r\n"[..]);
+        assert!(decode_request_head(&mut buf).unwrap_err().is_version());
+    }
+
+    #[test]
+    fn lenient_heads() {
+        let mut limits = Limits::new();
+        limits.set_profile(crate::ParseProfile::Lenient);
+
+        let mut buf = BytesMut::from(
+            &b"GET /a b?c d HTTP/1.1\r\nX-\x01Id: 1\x00\r\nContent-Length: 3, 3\r\n\r\n"[..],
+        );
+        let request = decode_request_head_with(&mut buf, &limits)
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.uri(), "/a%20b?c%20d");
+        assert_eq!(request.headers()["x-id"], "1");
+        assert_eq!(request.headers()["content-length"], "3");
+        let repairs = request.extensions().get::<Repairs>().unwrap();
+        assert_eq!(repairs.percent_encoded(), 2);
+        assert_eq!(repairs.stripped_controls(), 2);
+        assert_eq!(repairs.collapsed_content_lengths(), 1);
+
+        // Clean heads aren't marked, and conflicting lengths are kept.
+        let mut buf = BytesMut::from(
+            &b"HTTP/1.1 200 OK\r\ncontent-length: 1\r\ncontent-length: 2\r\n\r\n"[..],
+        );
+        let response = decode_response_head_with(&mut buf, &limits)
+            .unwrap()
+            .unwrap();
+        assert!(response.extensions().get::<Repairs>().is_none());
+        assert_eq!(
+            response.headers().get_all("content-length").iter().count(),
+            2
+        );
+
+        let mut buf = BytesMut::from(&b"GET /a b HTTP/1.1\r\n\r\n"[..]);
+        assert!(decode_request_head(&mut buf).is_err());
+
+        // Whitespace left by the stripped controls is trimmed too.
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\nContent-Length:\x00 3\x01\t\r\n\r\n"[..]);
+        let request = decode_request_head_with(&mut buf, &limits)
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.headers()["content-length"], "3");
+        encode_request_head(&request, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"GET / HTTP/1.1\r\nContent-Length: 3\r\n\r\n"[..]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn versions_without_text_heads() {
+        let mut buf = BytesMut::from(&b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n"[..]);
+        assert!(decode_request_head(&mut buf).unwrap_err().is_version());
+    }
+
+    #[test]
+    #[cfg(feature = "rtsp")]
+    fn rtsp_heads() {
+        let mut buf = BytesMut::from(
+            &b"SETUP rtsp://example.com/stream/track1 RTSP/1.0\r\nCSeq: 3\r\n\r\n"[..],
+        );
+        let request = decode_request_head(&mut buf).unwrap().unwrap();
+        assert_eq!(request.method(), Method::SETUP);
+        assert_eq!(request.version(), Version::RTSP_1);
+        assert_eq!(request.protocol(), crate::Protocol::Rtsp);
+        assert_eq!(request.headers()["cseq"], "3");
+
+        let mut buf = BytesMut::from(&b"RTSP/1.0 454 Session Not Found\r\n\r\n"[..]);
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert_eq!(response.status(), 454);
+        assert_eq!(response.reason(), Some("Session Not Found"));
+    }
+
+    #[test]
+    #[cfg(feature = "sip")]
+    fn sip_heads() {
+        let mut buf =
+            BytesMut::from(&b"SIP/2.0 180 Ringing\r\nCall-ID: a84b4c76e66710\r\n\r\n"[..]);
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert_eq!(response.version(), Version::SIP_2);
+        assert_eq!(response.protocol(), crate::Protocol::Sip);
+        assert_eq!(response.reason(), Some("Ringing"));
+    }
+
+    #[test]
+    #[cfg(feature = "sip")]
+    fn sip_header_rules() {
+        let mut buf = BytesMut::from(
+            &b"SIP/2.0 200\r\n\
+               v: SIP/2.0/UDP a.example.com;branch=z9hG4bK1, SIP/2.0/TCP b.example.com\r\n\
+               Via : SIP/2.0/UDP c.example.com\r\n\
+               m: \"Doe, J\xc3\xa9r\xc3\xb4me\" <sip:j@example.com>, <sip:j@192.0.2.4>\r\n\
+               f: \"J\xc3\xa9r\xc3\xb4me\" <sip:j@example.com>;tag=1\r\n\
+               l: 0\r\n\r\n"[..],
+        );
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert_eq!(response.reason_phrase(), None);
+        assert_eq!(response.reason(), Some("OK"));
+
+        let headers = response.headers();
+        let via: Vec<_> = headers.get_all("via").iter().collect();
+        assert_eq!(
+            via,
+            [
+                "SIP/2.0/UDP a.example.com;branch=z9hG4bK1",
+                "SIP/2.0/TCP b.example.com",
+                "SIP/2.0/UDP c.example.com",
+            ]
+        );
+        let contact: Vec<_> = headers.get_all("contact").iter().collect();
+        assert_eq!(contact.len(), 2);
+        assert_eq!(
+            contact[0].as_bytes(),
+            &b"\"Doe, J\xc3\xa9r\xc3\xb4me\" <sip:j@example.com>"[..]
+        );
+        assert_eq!(
+            headers["from"].as_bytes(),
+            &b"\"J\xc3\xa9r\xc3\xb4me\" <sip:j@example.com>;tag=1"[..]
+        );
+        assert_eq!(headers["content-length"], "0");
+
+        // Compact names are written in full.
+        buf.clear();
+        encode_response_head(&response, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"SIP/2.0 200 OK\r\n\
+               via: SIP/2.0/UDP a.example.com;branch=z9hG4bK1\r\n\
+               via: SIP/2.0/TCP b.example.com\r\n\
+               Via: SIP/2.0/UDP c.example.com\r\n\
+               contact: \"Doe, J\xc3\xa9r\xc3\xb4me\" <sip:j@example.com>\r\n\
+               contact: <sip:j@192.0.2.4>\r\n\
+               from: \"J\xc3\xa9r\xc3\xb4me\" <sip:j@example.com>;tag=1\r\n\
+               content-length: 0\r\n\r\n"[..]
+        );
+
+        // Other protocols keep the stricter rules.
+        let mut buf = BytesMut::from(&b"HTTP/1.1 200 OK\r\nv: 1, 2\r\n\r\n"[..]);
+        let response = decode_response_head(&mut buf).unwrap().unwrap();
+        assert_eq!(response.headers()["v"], "1, 2");
+    }
+}
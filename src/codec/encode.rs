@@ -0,0 +1,263 @@
+use core::fmt::Write;
+
+use bytes::{BufMut, BytesMut};
+
+use super::{has_text_head, is_http09, EncodeError, HeaderCase};
+use crate::header::{HeaderMap, HeaderName};
+use crate::{Extensions, Request, Response};
+
+/// Writes the head of a request to `dst`.
+///
+/// This writes the request line with the method, request target and
+/// version, one line per header value in the order of the header map, and
+/// the empty line ending the head. Names recorded in a `HeaderCase`
+/// extension are written with their original case.
+///
+/// The request target is the URI as is, so a request to a proxy should have
+/// an absolute URI and other requests an origin-form one. HTTP/0.9 requests
+/// are written as a bare request line.
+///
+/// # Errors
+///
+/// Returns an error for HTTP/2 and HTTP/3 requests, as these versions have
+/// no text message format. Nothing is written in that case.
+///
+/// # Examples
+///
+/// ```
+/// # use bytes::BytesMut;
+/// # use httplike::{codec, Request};
+/// let request = Request::post("http://example.com/upload")
+///     .header("content-length", "5")
+///     .body(())
+///     .unwrap();
+///
+/// let mut buf = BytesMut::new();
+/// codec::encode_request_head(&request, &mut buf).unwrap();
+///
+/// assert_eq!(
+///     &buf[..],
+///     &b"POST http://example.com/upload HTTP/1.1\r\ncontent-length: 5\r\n\r\n"[..]
+/// );
+/// ```
+pub fn encode_request_head<T>(
+    request: &Request<T>,
+    dst: &mut BytesMut,
+) -> Result<(), EncodeError> {
+    let version = request.version();
+    if !has_text_head(version, false) {
+        return Err(EncodeError::new());
+    }
+
+    let method = request.method().as_str();
+    let headers = request.headers();
+    dst.reserve(method.len() + request.uri().display_len() + 16 + headers_len(headers));
+
+    dst.put_slice(method.as_bytes());
+    dst.put_u8(b' ');
+    // Writing to a `BytesMut` can't fail.
+    let _ = write!(dst, "{}", request.uri());
+
+    if is_http09(version) {
+        dst.put_slice(b"\r\n");
+        return Ok(());
+    }
+
+    dst.put_u8(b' ');
+    dst.put_slice(version.as_str().as_bytes());
+    dst.put_slice(b"\r\n");
+    encode_headers(headers, request.extensions(), dst);
+    Ok(())
+}
+
+/// Writes the head of a response to `dst`.
+///
+/// This writes the status line with the version, status code and reason
+/// phrase, one line per header value in the order of the header map, and
+/// the empty line ending the head. Names recorded in a `HeaderCase`
+/// extension are written with their original case.
+///
+/// The reason phrase is the one received with the response, as returned by
+/// `Response::reason_phrase`, or the canonical reason of the status code
+/// otherwise. The reason phrase is empty for unknown status codes.
+///
+/// # Errors
+///
+/// Returns an error for HTTP/0.9, HTTP/2 and HTTP/3 responses, as these
+/// versions have no text response head. Nothing is written in that case.
+///
+/// # Examples
+///
+/// ```
+/// # use bytes::BytesMut;
+/// # use httplike::{codec, Response, StatusCode};
+/// let response = Response::builder()
+///     .status(StatusCode::NOT_FOUND)
+///     .reason_phrase("Nothing Here")
+///     .body(())
+///     .unwrap();
+///
+/// let mut buf = BytesMut::new();
+/// codec::encode_response_head(&response, &mut buf).unwrap();
+///
+/// assert_eq!(&buf[..], &b"HTTP/1.1 404 Nothing Here\r\n\r\n"[..]);
+/// ```
+pub fn encode_response_head<T>(
+    response: &Response<T>,
+    dst: &mut BytesMut,
+) -> Result<(), EncodeError> {
+    let version = response.version();
+    if !has_text_head(version, true) {
+        return Err(EncodeError::new());
+    }
+
+    let reason = match response.reason_phrase() {
+        Some(reason) => reason.as_bytes(),
+        None => response
+            .status()
+            .canonical_reason()
+            .unwrap_or("")
+            .as_bytes(),
+    };
+    let headers = response.headers();
+    dst.reserve(reason.len() + 16 + headers_len(headers));
+
+    dst.put_slice(version.as_str().as_bytes());
+    dst.put_u8(b' ');
+    dst.put_slice(response.status().as_str().as_bytes());
+    dst.put_u8(b' ');
+    dst.put_slice(reason);
+    dst.put_slice(b"\r\n");
+    encode_headers(headers, response.extensions(), dst);
+    Ok(())
+}
+
+fn encode_headers(headers: &HeaderMap, extensions: &Extensions, dst: &mut BytesMut) {
+    let case = extensions.get::<HeaderCase>();
+    let mut prev: Option<&HeaderName> = None;
+    let mut index = 0;
+
+    for (name, value) in headers {
+        // Values of the same name are yielded together.
+        index = if prev == Some(name) { index + 1 } else { 0 };
+        prev = Some(name);
+
+        let original = case.and_then(|case| case.get(name, index));
+        dst.put_slice(original.unwrap_or_else(|| name.as_str().as_bytes()));
+        dst.put_slice(b": ");
+        dst.put_slice(value.as_bytes());
+        dst.put_slice(b"\r\n");
+    }
+    dst.put_slice(b"\r\n");
+}
+
+fn headers_len(headers: &HeaderMap) -> usize {
+    headers.byte_len() + headers.len() * 4 + 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use crate::Version;
+
+    #[test]
+    fn case_is_restored_per_value() {
+        let mut case = HeaderCase::new();
+        case.append(&HeaderName::from_static("x-dup"), Bytes::from_static(b"X-Dup"));
+        case.append(&HeaderName::from_static("x-dup"), Bytes::from_static(b"x-DUP"));
+        // Not the same name, so never written.
+        case.append(&HeaderName::from_static("host"), Bytes::from_static(b"Hots"));
+
+        let request = Request::get("/")
+            .header("host", "example.com")
+            .header("x-dup", "1")
+            .header("x-dup", "2")
+            .header("x-dup", "3")
+            .extension(case)
+            .body(())
+            .unwrap();
+
+        let mut buf = BytesMut::new();
+        encode_request_head(&request, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"GET / HTTP/1.1\r\nhost: example.com\r\nX-Dup: 1\r\nx-DUP: 2\r\nx-dup: 3\r\n\r\n"[..]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn versions_without_text_heads() {
+        let mut buf = BytesMut::new();
+
+        let request = Request::get("/").version(Version::HTTP_2).body(()).unwrap();
+        assert!(encode_request_head(&request, &mut buf).is_err());
+
+        let request = Request::get("/old").version(Version::HTTP_09).body(()).unwrap();
+        encode_request_head(&request, &mut buf).unwrap();
+        assert_eq!(&buf[..], &b"GET /old\r\n"[..]);
+
+        let response = Response::builder().version(Version::HTTP_09).body(()).unwrap();
+        assert!(encode_response_head(&response, &mut buf).is_err());
+        assert_eq!(&buf[..], &b"GET /old\r\n"[..]);
+    }
+
+    #[test]
+    fn raw_and_missing_reasons() {
+        let mut buf = BytesMut::new();
+        let response = Response::builder()
+            .status(200)
+            .reason_phrase(&b"Tr\xe8s Bien"[..])
+            .body(())
+            .unwrap();
+        encode_response_head(&response, &mut buf).unwrap();
+        assert_eq!(&buf[..], &b"HTTP/1.1 200 Tr\xe8s Bien\r\n\r\n"[..]);
+
+        buf.clear();
+        let response = Response::builder().status(299).body(()).unwrap();
+        encode_response_head(&response, &mut buf).unwrap();
+        assert_eq!(&buf[..], &b"HTTP/1.1 299 \r\n\r\n"[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "rtsp")]
+    fn rtsp_heads() {
+        let mut buf = BytesMut::new();
+        let request = Request::pause("rtsp://example.com/stream")
+            .header("session", "12345678")
+            .body(())
+            .unwrap();
+        encode_request_head(&request, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"PAUSE rtsp://example.com/stream RTSP/1.0\r\ncseq: 0\r\nsession: 12345678\r\n\r\n"[..]
+        );
+
+        buf.clear();
+        let response = Response::builder()
+            .version(Version::RTSP_1)
+            .header("cseq", "0")
+            .body(())
+            .unwrap();
+        encode_response_head(&response, &mut buf).unwrap();
+        assert_eq!(&buf[..], &b"RTSP/1.0 200 OK\r\ncseq: 0\r\n\r\n"[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "sip")]
+    fn sip_heads() {
+        let mut buf = BytesMut::new();
+        let response = Response::builder()
+            .status(180)
+            .version(Version::SIP_2)
+            .header("call-id", "a84b4c76e66710")
+            .body(())
+            .unwrap();
+        encode_response_head(&response, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"SIP/2.0 180 \r\ncall-id: a84b4c76e66710\r\n\r\n"[..]
+        );
+    }
+}
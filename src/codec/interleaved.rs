@@ -0,0 +1,164 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use super::{ParseError, ParseErrorKind};
+
+/// An RTSP interleaved binary data frame.
+///
+/// When RTSP runs over TCP, RTP and RTCP packets are sent on the same
+/// connection as the RTSP messages, each in a frame made of a `$`, a one
+/// byte channel id, a two byte big-endian length and the payload, as
+/// described in RFC 2326 section 10.12. The channels are assigned with the
+/// `interleaved` parameter of the `Transport` header.
+///
+/// A frame can be told apart from a message head by its first byte, see
+/// `is_interleaved_frame`.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{Bytes, BytesMut};
+/// use httplike::codec::{self, InterleavedFrame};
+///
+/// let mut buf = BytesMut::from(&b"$\x00\x00\x03rtpRTSP/1.0 200 OK\r\n\r\n"[..]);
+///
+/// assert!(codec::is_interleaved_frame(&buf));
+/// let frame = InterleavedFrame::decode(&mut buf).unwrap().unwrap();
+/// assert_eq!(frame.channel(), 0);
+/// assert_eq!(frame.payload(), &Bytes::from_static(b"rtp"));
+///
+/// assert!(!codec::is_interleaved_frame(&buf));
+/// let response = codec::decode_response_head(&mut buf).unwrap().unwrap();
+/// assert_eq!(response.status(), 200);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InterleavedFrame {
+    channel: u8,
+    payload: Bytes,
+}
+
+/// The length of the `$`, channel and length prefix of a frame.
+const PREFIX_LEN: usize = 4;
+
+impl InterleavedFrame {
+    /// The largest payload a frame can carry.
+    pub const MAX_PAYLOAD_LEN: usize = 0xffff;
+
+    /// Creates a frame carrying `payload` on `channel`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `payload` is longer than `MAX_PAYLOAD_LEN`.
+    pub fn new(channel: u8, payload: Bytes) -> InterleavedFrame {
+        assert!(
+            payload.len() <= InterleavedFrame::MAX_PAYLOAD_LEN,
+            "interleaved frame payload too long"
+        );
+        InterleavedFrame { channel, payload }
+    }
+
+    /// Returns the channel id of the frame.
+    #[inline]
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Returns the payload of the frame.
+    #[inline]
+    pub fn payload(&self) -> &Bytes {
+        &self.payload
+    }
+
+    /// Consumes the frame, returning its payload.
+    #[inline]
+    pub fn into_payload(self) -> Bytes {
+        self.payload
+    }
+
+    /// Reads a frame from `src`.
+    ///
+    /// Returns `Ok(None)`, leaving `src` untouched, if `src` doesn't contain
+    /// a complete frame yet. Otherwise the frame is removed from `src`, and
+    /// its payload shares the memory of `src`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `src` doesn't start with a `$`.
+    pub fn decode(src: &mut BytesMut) -> Result<Option<InterleavedFrame>, ParseError> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+        if !is_interleaved_frame(src) {
+            return Err(ParseError::new(ParseErrorKind::Frame));
+        }
+        if src.len() < PREFIX_LEN {
+            return Ok(None);
+        }
+
+        let len = (usize::from(src[2]) << 8) | usize::from(src[3]);
+        if src.len() < PREFIX_LEN + len {
+            return Ok(None);
+        }
+
+        let channel = src[1];
+        src.advance(PREFIX_LEN);
+        let payload = src.split_to(len).freeze();
+        Ok(Some(InterleavedFrame { channel, payload }))
+    }
+
+    /// Writes the frame to `dst`.
+    pub fn encode(&self, dst: &mut BytesMut) {
+        dst.reserve(PREFIX_LEN + self.payload.len());
+        dst.put_u8(b'$');
+        dst.put_u8(self.channel);
+        dst.put_u16(self.payload.len() as u16);
+        dst.put_slice(&self.payload);
+    }
+}
+
+/// Returns true if the next item in `buf` is an interleaved frame rather
+/// than a message head.
+///
+/// Message heads never start with a `$`, so the first byte is enough to
+/// tell them apart. An empty `buf` is not a frame.
+#[inline]
+pub fn is_interleaved_frame(buf: &[u8]) -> bool {
+    buf.first() == Some(&b'$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_frames() {
+        let mut encoded = BytesMut::new();
+        InterleavedFrame::new(1, Bytes::from_static(b"rtcp")).encode(&mut encoded);
+        assert_eq!(&encoded[..], b"$\x01\x00\x04rtcp");
+
+        let mut buf = BytesMut::new();
+        for &b in &encoded[..encoded.len() - 1] {
+            buf.put_u8(b);
+            assert_eq!(InterleavedFrame::decode(&mut buf).unwrap(), None);
+        }
+        buf.put_u8(b'p');
+        buf.put_u8(b'$');
+
+        let frame = InterleavedFrame::decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.channel(), 1);
+        assert_eq!(frame.into_payload(), Bytes::from_static(b"rtcp"));
+        assert_eq!(&buf[..], b"$");
+    }
+
+    #[test]
+    fn not_a_frame() {
+        let mut buf = BytesMut::from(&b"RTSP/1.0 200 OK\r\n\r\n"[..]);
+        assert!(InterleavedFrame::decode(&mut buf).is_err());
+        assert!(!is_interleaved_frame(b""));
+    }
+
+    #[test]
+    #[should_panic]
+    fn payload_too_long() {
+        InterleavedFrame::new(0, Bytes::from(vec![0; 0x10000]));
+    }
+}
@@ -0,0 +1,502 @@
+//! Reading and writing message heads in their text wire format.
+//!
+//! HTTP/1.x, RTSP and SIP share the same message format: a start line,
+//! header lines and an empty line, all terminated by CRLF. This module
+//! converts between that format and `Request` and `Response` heads. Bodies
+//! are left to the caller, as their framing depends on the headers.
+//!
+//! The decoders parse a head out of a receive buffer as soon as it is
+//! complete, and leave the buffer alone until then, so they can simply be
+//! called again after each read; a `HeadDecoder` does that without searching
+//! the buffer from the start each time. The parsed components share the
+//! memory of the buffer instead of copying it where the types allow it.
+//!
+//! With the `rtsp` feature, this module also reads and writes the binary
+//! frames RTSP interleaves with messages on a TCP connection.
+//!
+//! # Examples
+//!
+//! ```
+//! use bytes::BytesMut;
+//! use httplike::Request;
+//! use httplike::codec;
+//!
+//! let request = Request::get("/index.html")
+//!     .header("host", "example.com")
+//!     .body(())
+//!     .unwrap();
+//!
+//! let mut buf = BytesMut::new();
+//! codec::encode_request_head(&request, &mut buf).unwrap();
+//! assert_eq!(&buf[..], &b"GET /index.html HTTP/1.1\r\nhost: example.com\r\n\r\n"[..]);
+//!
+//! let decoded = codec::decode_request_head(&mut buf).unwrap().unwrap();
+//! assert_eq!(decoded.uri(), "/index.html");
+//! assert_eq!(decoded.headers()["host"], "example.com");
+//! assert!(buf.is_empty());
+//! ```
+
+#[cfg(feature = "std")]
+use std::error::Error;
+use core::fmt;
+
+use bytes::Bytes;
+
+use crate::header::{HeaderMap, HeaderName};
+use crate::{request, response, ParseProfile, Version};
+
+mod decode;
+mod encode;
+#[cfg(feature = "rtsp")]
+mod interleaved;
+#[cfg(feature = "sip")]
+mod sip;
+
+pub use self::decode::{
+    decode_request_head, decode_request_head_with, decode_response_head,
+    decode_response_head_with, HeadDecoder,
+};
+pub use self::encode::{encode_request_head, encode_response_head};
+pub(crate) use self::decode::decode_header_block;
+#[cfg(feature = "rtsp")]
+pub use self::interleaved::{is_interleaved_frame, InterleavedFrame};
+
+/// The original spelling of header names.
+///
+/// `HeaderName`s are always lowercase. Some peers depend on the case of the
+/// header names they receive though, so a parser can record the names as
+/// they were received in a `HeaderCase` stored in the message extensions.
+/// The encoder writes the recorded spellings instead of the lowercase names.
+///
+/// Spellings are recorded per value, so each of several values under one
+/// name keeps its own. A spelling that doesn't match its name, ignoring
+/// ASCII case, is never written.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::{Bytes, BytesMut};
+/// use httplike::Request;
+/// use httplike::codec::{self, HeaderCase};
+/// use httplike::header::CONTENT_TYPE;
+///
+/// let mut case = HeaderCase::new();
+/// case.append(&CONTENT_TYPE, Bytes::from_static(b"Content-Type"));
+///
+/// let request = Request::post("/")
+///     .header(CONTENT_TYPE, "text/plain")
+///     .extension(case)
+///     .body(())
+///     .unwrap();
+///
+/// let mut buf = BytesMut::new();
+/// codec::encode_request_head(&request, &mut buf).unwrap();
+/// assert_eq!(&buf[..], &b"POST / HTTP/1.1\r\nContent-Type: text/plain\r\n\r\n"[..]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HeaderCase {
+    names: HeaderMap<Bytes>,
+}
+
+impl HeaderCase {
+    /// Creates an empty `HeaderCase`.
+    pub fn new() -> HeaderCase {
+        HeaderCase::default()
+    }
+
+    /// Records the spelling of the next value of `name`.
+    pub fn append(&mut self, name: &HeaderName, original: Bytes) {
+        self.names.append(name, original);
+    }
+
+    /// Returns the spelling recorded for the value of `name` at `index`.
+    pub fn get(&self, name: &HeaderName, index: usize) -> Option<&[u8]> {
+        self.names
+            .get_all(name)
+            .iter()
+            .nth(index)
+            .map(|original| &original[..])
+            .filter(|original| original.eq_ignore_ascii_case(name.as_str().as_bytes()))
+    }
+}
+
+/// An error returned when a message head can't be encoded.
+///
+/// Only versions with a text message format can be encoded. HTTP/2 and
+/// HTTP/3 messages are encoded in frames by their own protocol, and HTTP/0.9
+/// responses have no head at all.
+pub struct EncodeError {
+    _priv: (),
+}
+
+impl EncodeError {
+    fn new() -> EncodeError {
+        EncodeError { _priv: () }
+    }
+}
+
+impl fmt::Debug for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncodeError")
+            // skip _priv noise
+            .finish()
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("version has no text message head")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for EncodeError {}
+
+/// Size limits enforced while decoding a message head.
+///
+/// The decoders keep waiting for the end of a head while it is within the
+/// limits, so these bound the memory a peer can make the receive buffer
+/// grow to before getting an error.
+///
+/// The same limits can be enforced on the messages a service builds, with
+/// `limits` on `request::Builder` and `response::Builder`, so one value
+/// describes everything a message may cost.
+///
+/// The limits also hold the `ParseProfile` of the decoders, which is
+/// `ParseProfile::Strict` unless set otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use httplike::codec::{self, Limits};
+///
+/// let mut limits = Limits::new();
+/// limits.set_max_headers(1);
+///
+/// let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\nhost: a\r\naccept: */*\r\n\r\n"[..]);
+/// let err = codec::decode_request_head_with(&mut buf, &limits).unwrap_err();
+/// assert!(err.is_too_large());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Limits {
+    max_head_len: usize,
+    max_headers: usize,
+    max_header_len: usize,
+    max_uri_len: usize,
+    max_method_len: usize,
+    profile: ParseProfile,
+}
+
+impl Limits {
+    /// Returns the default limits.
+    ///
+    /// A head may be 64 KiB long with 100 header lines of 16 KiB each, and
+    /// a request line may have a URI of 8 KiB and a method of 32 bytes.
+    #[inline]
+    pub fn new() -> Limits {
+        Limits::default()
+    }
+
+    /// Returns the maximum length of a head in bytes, including the line
+    /// terminators.
+    #[inline]
+    pub fn max_head_len(&self) -> usize {
+        self.max_head_len
+    }
+
+    /// Sets the maximum length of a head in bytes.
+    #[inline]
+    pub fn set_max_head_len(&mut self, len: usize) {
+        self.max_head_len = len;
+    }
+
+    /// Returns the maximum number of header lines in a head.
+    #[inline]
+    pub fn max_headers(&self) -> usize {
+        self.max_headers
+    }
+
+    /// Sets the maximum number of header lines in a head.
+    #[inline]
+    pub fn set_max_headers(&mut self, count: usize) {
+        self.max_headers = count;
+    }
+
+    /// Returns the maximum length of a header line in bytes, counting the
+    /// name, the value and the separator between them, but not the line
+    /// terminator.
+    #[inline]
+    pub fn max_header_len(&self) -> usize {
+        self.max_header_len
+    }
+
+    /// Sets the maximum length of a header line in bytes.
+    #[inline]
+    pub fn set_max_header_len(&mut self, len: usize) {
+        self.max_header_len = len;
+    }
+
+    /// Returns the maximum length of a request URI in bytes.
+    #[inline]
+    pub fn max_uri_len(&self) -> usize {
+        self.max_uri_len
+    }
+
+    /// Sets the maximum length of a request URI in bytes.
+    #[inline]
+    pub fn set_max_uri_len(&mut self, len: usize) {
+        self.max_uri_len = len;
+    }
+
+    /// Returns the maximum length of a request method in bytes.
+    #[inline]
+    pub fn max_method_len(&self) -> usize {
+        self.max_method_len
+    }
+
+    /// Sets the maximum length of a request method in bytes.
+    #[inline]
+    pub fn set_max_method_len(&mut self, len: usize) {
+        self.max_method_len = len;
+    }
+
+    /// Checks the method, URI and headers of a request against the limits.
+    ///
+    /// The length of the head isn't checked, as it depends on how the
+    /// request is encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::Request;
+    /// # use httplike::codec::Limits;
+    /// let mut limits = Limits::new();
+    /// limits.set_max_uri_len(8);
+    ///
+    /// let (parts, ()) = Request::get("/a/long/path").body(()).unwrap().into_parts();
+    /// let err = limits.check_request(&parts).unwrap_err();
+    /// assert_eq!(err.to_string(), "uri is longer than the limit");
+    /// ```
+    pub fn check_request(&self, parts: &request::Parts) -> Result<(), LimitError> {
+        if parts.method.as_str().len() > self.max_method_len {
+            return Err(LimitError::new(LimitErrorKind::Method));
+        }
+        if parts.uri.display_len() > self.max_uri_len {
+            return Err(LimitError::new(LimitErrorKind::Uri));
+        }
+        self.check_headers(&parts.headers)
+    }
+
+    /// Checks the headers of a response against the limits.
+    ///
+    /// The length of the head isn't checked, as it depends on how the
+    /// response is encoded.
+    pub fn check_response(&self, parts: &response::Parts) -> Result<(), LimitError> {
+        self.check_headers(&parts.headers)
+    }
+
+    fn check_headers(&self, headers: &HeaderMap) -> Result<(), LimitError> {
+        if headers.len() > self.max_headers {
+            return Err(LimitError::new(LimitErrorKind::Headers));
+        }
+        let too_long = headers
+            .iter()
+            .any(|(name, value)| name.as_str().len() + 2 + value.len() > self.max_header_len);
+        if too_long {
+            return Err(LimitError::new(LimitErrorKind::HeaderLine));
+        }
+        Ok(())
+    }
+
+    /// Returns the profile the decoders parse heads with.
+    #[inline]
+    pub fn profile(&self) -> ParseProfile {
+        self.profile
+    }
+
+    /// Sets the profile the decoders parse heads with.
+    ///
+    /// With `ParseProfile::Lenient`, the request target may contain spaces,
+    /// which are percent-encoded, and the repairs made to a head are stored
+    /// in the extensions of the message as a `Repairs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BytesMut;
+    /// use httplike::ParseProfile;
+    /// use httplike::codec::{self, Limits};
+    /// use httplike::profile::Repairs;
+    ///
+    /// let mut limits = Limits::new();
+    /// limits.set_profile(ParseProfile::Lenient);
+    ///
+    /// let mut buf = BytesMut::from(
+    ///     &b"GET /my file HTTP/1.1\r\ncontent-length: 0\r\ncontent-length: 0\r\n\r\n"[..],
+    /// );
+    /// let request = codec::decode_request_head_with(&mut buf, &limits).unwrap().unwrap();
+    /// assert_eq!(request.uri(), "/my%20file");
+    /// assert_eq!(request.headers().get_all("content-length").iter().count(), 1);
+    ///
+    /// let repairs = request.extensions().get::<Repairs>().unwrap();
+    /// assert_eq!(repairs.percent_encoded(), 1);
+    /// assert_eq!(repairs.collapsed_content_lengths(), 1);
+    /// ```
+    #[inline]
+    pub fn set_profile(&mut self, profile: ParseProfile) {
+        self.profile = profile;
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_head_len: 64 * 1024,
+            max_headers: 100,
+            max_header_len: 16 * 1024,
+            max_uri_len: 8 * 1024,
+            max_method_len: 32,
+            profile: ParseProfile::Strict,
+        }
+    }
+}
+
+/// An error returned when a message built with `Limits` exceeds them.
+pub struct LimitError {
+    kind: LimitErrorKind,
+}
+
+#[derive(Debug)]
+enum LimitErrorKind {
+    Method,
+    Uri,
+    Headers,
+    HeaderLine,
+}
+
+impl LimitError {
+    fn new(kind: LimitErrorKind) -> LimitError {
+        LimitError { kind }
+    }
+}
+
+impl fmt::Debug for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LimitError").field(&self.kind).finish()
+    }
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            LimitErrorKind::Method => "method is longer than the limit",
+            LimitErrorKind::Uri => "uri is longer than the limit",
+            LimitErrorKind::Headers => "more headers than the limit",
+            LimitErrorKind::HeaderLine => "header is longer than the limit",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for LimitError {}
+
+/// An error returned when a message head can't be decoded.
+///
+/// The connection should be closed after this error, as there is no way to
+/// find the start of the next message.
+pub struct ParseError {
+    kind: ParseErrorKind,
+}
+
+#[derive(Debug)]
+enum ParseErrorKind {
+    TooLarge,
+    TooManyHeaders,
+    StartLine,
+    Version,
+    Header,
+    #[cfg(feature = "rtsp")]
+    Frame,
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind) -> ParseError {
+        ParseError { kind }
+    }
+
+    /// Returns true if the head exceeded one of the `Limits`.
+    pub fn is_too_large(&self) -> bool {
+        match self.kind {
+            ParseErrorKind::TooLarge | ParseErrorKind::TooManyHeaders => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the version of the message isn't supported.
+    pub fn is_version(&self) -> bool {
+        match self.kind {
+            ParseErrorKind::Version => true,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> crate::ErrorKind {
+        use crate::ErrorKind as Kind;
+
+        match self.kind {
+            ParseErrorKind::TooLarge => Kind::HeadTooLarge,
+            ParseErrorKind::TooManyHeaders => Kind::TooManyHeaders,
+            ParseErrorKind::StartLine => Kind::StartLine,
+            ParseErrorKind::Version => Kind::Version,
+            ParseErrorKind::Header => Kind::HeaderLine,
+            #[cfg(feature = "rtsp")]
+            ParseErrorKind::Frame => Kind::Frame,
+        }
+    }
+}
+
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ParseError").field(&self.kind).finish()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            ParseErrorKind::TooLarge => "message head is too large",
+            ParseErrorKind::TooManyHeaders => "message head has too many headers",
+            ParseErrorKind::StartLine => "invalid start line",
+            ParseErrorKind::Version => "unsupported version",
+            ParseErrorKind::Header => "invalid header line",
+            #[cfg(feature = "rtsp")]
+            ParseErrorKind::Frame => "invalid interleaved frame",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseError {}
+
+pub(crate) fn has_text_head(version: Version, response: bool) -> bool {
+    #[cfg(feature = "http")]
+    {
+        if version == Version::HTTP_2 || version == Version::HTTP_3 {
+            return false;
+        }
+    }
+    !(response && is_http09(version))
+}
+
+#[allow(unused_variables)]
+fn is_http09(version: Version) -> bool {
+    #[cfg(feature = "http")]
+    {
+        version == Version::HTTP_09
+    }
+    #[cfg(not(feature = "http"))]
+    {
+        false
+    }
+}
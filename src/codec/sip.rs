@@ -0,0 +1,94 @@
+//! Header line rules specific to SIP, from RFC 3261 section 7.3.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::decode::trim;
+use crate::header::HeaderName;
+
+/// Returns the full name of a compact header name.
+///
+/// The compact forms are those of RFC 3261 section 7.3.3 and of the SIP
+/// extensions registered with IANA since.
+pub(super) fn expand_compact(name: &[u8]) -> Option<&'static str> {
+    if name.len() != 1 {
+        return None;
+    }
+
+    let name = match name[0].to_ascii_lowercase() {
+        b'a' => "accept-contact",
+        b'b' => "referred-by",
+        b'c' => "content-type",
+        b'd' => "request-disposition",
+        b'e' => "content-encoding",
+        b'f' => "from",
+        b'i' => "call-id",
+        b'j' => "reject-contact",
+        b'k' => "supported",
+        b'l' => "content-length",
+        b'm' => "contact",
+        b'n' => "identity-info",
+        b'o' => "event",
+        b'r' => "refer-to",
+        b's' => "subject",
+        b't' => "to",
+        b'u' => "allow-events",
+        b'v' => "via",
+        b'x' => "session-expires",
+        b'y' => "identity",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Returns true for the headers whose entries are kept as one value each.
+///
+/// The order of `Via`, `Route` and `Record-Route` entries is the order of
+/// the hops, and a router works on one entry at a time, so a line with
+/// several entries is split instead of being kept as one value.
+pub(super) fn is_list(name: &HeaderName) -> bool {
+    match name.as_str() {
+        "via" | "route" | "record-route" | "contact" => true,
+        _ => false,
+    }
+}
+
+/// Splits the comma-separated entries of a value.
+///
+/// Commas in quoted strings, like a display name, or between `<` and `>`,
+/// like in a URI, don't separate entries. Empty entries are left out.
+pub(super) fn split_list(head: &[u8], value: Range<usize>) -> Vec<Range<usize>> {
+    let mut entries = Vec::new();
+    let mut start = value.start;
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut angle = false;
+
+    for i in value.clone() {
+        let b = head[i];
+        if escaped {
+            escaped = false;
+        } else if quoted {
+            match b {
+                b'\\' => escaped = true,
+                b'"' => quoted = false,
+                _ => {}
+            }
+        } else {
+            match b {
+                b'"' => quoted = true,
+                b'<' => angle = true,
+                b'>' => angle = false,
+                b',' if !angle => {
+                    entries.push(trim(head, start..i));
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    entries.push(trim(head, start..value.end));
+
+    entries.retain(|entry| entry.start < entry.end);
+    entries
+}
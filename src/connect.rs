@@ -0,0 +1,281 @@
+//! `CONNECT` requests, which ask a proxy to open a tunnel.
+//!
+//! The target of a `CONNECT` request is only a host and a port, in the
+//! authority form of RFC 7230 section 5.3.3, and the request has no body.
+//! In HTTP/2 and HTTP/3 the target is in `:authority` instead, which the
+//! `pseudo` module maps to the same URI, so the checks of this module work
+//! for every version.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::connect::{self, Target};
+//!
+//! // A client.
+//! let target = Target::new("example.com", 443).unwrap();
+//! let request = connect::request(&target).body(()).unwrap();
+//! assert_eq!(request.uri(), "example.com:443");
+//! assert_eq!(request.headers()["host"], "example.com:443");
+//!
+//! // A proxy.
+//! let target = connect::validate(&request).unwrap();
+//! assert_eq!(target.host(), "example.com");
+//! assert_eq!(target.port(), 443);
+//! ```
+
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::body::Body;
+use crate::header::{self, HeaderValue};
+use crate::request::{self, TargetForm};
+use crate::uri::{self, Authority};
+use crate::{Method, Request, Uri};
+
+/// The host and port a `CONNECT` request asks to connect to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Target {
+    authority: Authority,
+    port: u16,
+}
+
+/// An error returned when a `CONNECT` request or target is not valid.
+pub struct ConnectError {
+    kind: ConnectErrorKind,
+}
+
+#[derive(Debug)]
+enum ConnectErrorKind {
+    Method,
+    Target,
+    Port,
+    UserInfo,
+    Body,
+}
+
+impl Target {
+    /// Creates a target from a host and a port.
+    ///
+    /// The host is a registered name, an IPv4 address or an IPv6 address,
+    /// with or without brackets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the host is not valid in an authority.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::connect::Target;
+    /// let target = Target::new("::1", 8080).unwrap();
+    /// assert_eq!(target.authority(), "[::1]:8080");
+    /// ```
+    pub fn new(host: &str, port: u16) -> Result<Target, ConnectError> {
+        let mut s = String::with_capacity(host.len() + 8);
+        if host.contains(':') && !host.starts_with('[') {
+            s.push('[');
+            s.push_str(host);
+            s.push(']');
+        } else {
+            s.push_str(host);
+        }
+        // Writing to a `String` can't fail.
+        let _ = write!(s, ":{}", port);
+
+        let authority = Authority::try_from(s.as_str())
+            .map_err(|_| ConnectError::new(ConnectErrorKind::Target))?;
+        Target::from_authority(&authority)
+    }
+
+    /// Creates a target from an authority.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the authority has no port, or has user
+    /// information, which a `CONNECT` target can't have.
+    pub fn from_authority(authority: &Authority) -> Result<Target, ConnectError> {
+        if authority.as_str().contains('@') {
+            return Err(ConnectError::new(ConnectErrorKind::UserInfo));
+        }
+        let port = authority
+            .port_u16()
+            .ok_or_else(|| ConnectError::new(ConnectErrorKind::Port))?;
+        Ok(Target {
+            authority: authority.clone(),
+            port,
+        })
+    }
+
+    /// Returns the host of the target, with brackets around an IPv6 address.
+    pub fn host(&self) -> &str {
+        self.authority.host()
+    }
+
+    /// Returns the port of the target.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the target as an authority.
+    pub fn authority(&self) -> &Authority {
+        &self.authority
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.authority, f)
+    }
+}
+
+/// Creates a builder for a `CONNECT` request to `target`.
+///
+/// The URI of the request is the target in authority form, and the `Host`
+/// header is set to the target too, as HTTP/1.1 requires.
+pub fn request(target: &Target) -> request::Builder {
+    let mut parts = uri::Parts::default();
+    parts.authority = Some(target.authority.clone());
+    // An authority alone is a valid URI.
+    let uri = Uri::from_parts(parts).expect("authority-form URI");
+    let host = HeaderValue::from_str(target.authority.as_str()).expect("authority is a valid host");
+
+    Request::builder()
+        .method(Method::CONNECT)
+        .uri(uri)
+        .header(header::HOST, host)
+}
+
+/// Checks that `request` is a valid `CONNECT` request, and returns its
+/// target.
+///
+/// The target must be in authority form, with a port and without user
+/// information. The request must not have a body: a `Content-Length` other
+/// than 0, a `Transfer-Encoding`, or a body that isn't empty are rejected.
+///
+/// # Errors
+///
+/// Returns an error describing the first requirement the request doesn't
+/// meet.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::{connect, Request};
+/// let request = Request::connect("https://example.com/").body(()).unwrap();
+/// assert!(connect::validate(&request).is_err());
+///
+/// let request = Request::connect("example.com:443").body("hello").unwrap();
+/// assert!(connect::validate(&request).is_err());
+/// ```
+pub fn validate<B: Body>(request: &Request<B>) -> Result<Target, ConnectError> {
+    if request.method() != Method::CONNECT {
+        return Err(ConnectError::new(ConnectErrorKind::Method));
+    }
+
+    let uri = request.uri();
+    let authority = match (request.target_form(), uri.authority()) {
+        (TargetForm::Authority, Some(authority)) => authority,
+        _ => return Err(ConnectError::new(ConnectErrorKind::Target)),
+    };
+    let target = Target::from_authority(authority)?;
+
+    let headers = request.headers();
+    let has_length = headers
+        .get_all(header::CONTENT_LENGTH)
+        .iter()
+        .any(|value| value != "0");
+    if has_length
+        || headers.contains_key(header::TRANSFER_ENCODING)
+        || request.body().size_hint().lower() > 0
+    {
+        return Err(ConnectError::new(ConnectErrorKind::Body));
+    }
+
+    Ok(target)
+}
+
+impl ConnectError {
+    fn new(kind: ConnectErrorKind) -> ConnectError {
+        ConnectError { kind }
+    }
+}
+
+impl fmt::Debug for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConnectError").field(&self.kind).finish()
+    }
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            ConnectErrorKind::Method => "not a CONNECT request",
+            ConnectErrorKind::Target => "connect target is not an authority",
+            ConnectErrorKind::Port => "connect target has no port",
+            ConnectErrorKind::UserInfo => "connect target has user information",
+            ConnectErrorKind::Body => "connect request has a body",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ConnectError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Version;
+
+    #[test]
+    fn targets() {
+        assert_eq!(Target::new("[::1]", 22).unwrap().authority(), "[::1]:22");
+        assert_eq!(Target::new("10.0.0.1", 80).unwrap().host(), "10.0.0.1");
+        assert!(Target::new("exa mple.com", 80).is_err());
+
+        let authority = Authority::from_static("user@example.com:443");
+        assert_eq!(
+            Target::from_authority(&authority).unwrap_err().to_string(),
+            "connect target has user information"
+        );
+        let authority = Authority::from_static("example.com");
+        assert_eq!(
+            Target::from_authority(&authority).unwrap_err().to_string(),
+            "connect target has no port"
+        );
+    }
+
+    #[test]
+    fn requests() {
+        let target = Target::new("example.com", 443).unwrap();
+
+        let request = request(&target)
+            .version(Version::HTTP_2)
+            .body(Vec::<u8>::new())
+            .unwrap();
+        assert_eq!(validate(&request).unwrap(), target);
+
+        let request = Request::get("example.com:443").body(()).unwrap();
+        assert_eq!(
+            validate(&request).unwrap_err().to_string(),
+            "not a CONNECT request"
+        );
+
+        let request = Request::connect("example.com:443")
+            .header("content-length", "0")
+            .body(())
+            .unwrap();
+        assert!(validate(&request).is_ok());
+
+        let request = Request::connect("example.com:443")
+            .header("transfer-encoding", "chunked")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            validate(&request).unwrap_err().to_string(),
+            "connect request has a body"
+        );
+    }
+}
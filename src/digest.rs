@@ -0,0 +1,1182 @@
+//! Digest access authentication, enabled with the `digest` feature.
+//!
+//! HTTP, RTSP and SIP share the Digest scheme of RFC 7616: a server sends a
+//! `Challenge` in `WWW-Authenticate` or `Proxy-Authenticate`, and the client
+//! answers in `Authorization` or `Proxy-Authorization` with `Credentials`
+//! holding a hash of the password, the challenge and the request. This
+//! module parses and writes both, computes the response of a client, and
+//! verifies it on a server.
+//!
+//! The `MD5` and `SHA-256` algorithms are supported, with their `-sess`
+//! variants, and the `auth` quality of protection. Challenges without `qop`,
+//! from servers following RFC 2069, are answered without it. `auth-int`,
+//! which hashes the body, is not supported.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::digest::{Challenge, Client};
+//! use httplike::Method;
+//!
+//! let challenge: Challenge = "Digest realm=\"camera\", qop=\"auth\", \
+//!     nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", algorithm=SHA-256"
+//!     .parse()
+//!     .unwrap();
+//!
+//! let mut client = Client::new(challenge, "admin", "secret");
+//! let credentials = client
+//!     .authorize(&Method::GET, "/stream", "0a4f113b")
+//!     .unwrap();
+//! assert_eq!(credentials.nonce_count(), Some(1));
+//!
+//! // A server checks the credentials against the password it knows.
+//! let received = credentials.to_string().parse::<httplike::digest::Credentials>().unwrap();
+//! assert!(received.verify(&Method::GET, "secret"));
+//! assert!(!received.verify(&Method::GET, "guess"));
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::header::{HeaderValue, InvalidHeaderValue};
+use crate::Method;
+
+/// The hash algorithm of a challenge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// `MD5`, the default when a challenge has no algorithm.
+    Md5,
+    /// `MD5-sess`, which hashes the client nonce into the password hash.
+    Md5Sess,
+    /// `SHA-256`.
+    Sha256,
+    /// `SHA-256-sess`, which hashes the client nonce into the password
+    /// hash.
+    Sha256Sess,
+}
+
+/// A `Digest` challenge, sent by a server in `WWW-Authenticate` or
+/// `Proxy-Authenticate`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    qop_auth: bool,
+    stale: bool,
+}
+
+/// `Digest` credentials, sent by a client in `Authorization` or
+/// `Proxy-Authorization`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Credentials {
+    username: String,
+    realm: String,
+    nonce: String,
+    uri: String,
+    response: String,
+    algorithm: Algorithm,
+    cnonce: Option<String>,
+    nonce_count: Option<u32>,
+    opaque: Option<String>,
+}
+
+/// A client answering the challenges of a server.
+///
+/// The client counts the requests made with the nonce of its challenge, and
+/// sends the count in the `nc` parameter, so the server can detect replayed
+/// requests. Setting a challenge with a new nonce starts the count again.
+#[derive(Clone)]
+pub struct Client {
+    challenge: Challenge,
+    username: String,
+    password: String,
+    nonce_count: u32,
+}
+
+/// An error returned when a digest challenge or credentials are not valid,
+/// or can't be answered.
+pub struct DigestError {
+    kind: DigestErrorKind,
+}
+
+#[derive(Debug)]
+enum DigestErrorKind {
+    Syntax,
+    Missing,
+    Algorithm,
+    Qop,
+    NonceCount,
+}
+
+impl Algorithm {
+    /// Returns the name of the algorithm, as in the `algorithm` parameter.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Md5Sess => "MD5-sess",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_sess(&self) -> bool {
+        *self == Algorithm::Md5Sess || *self == Algorithm::Sha256Sess
+    }
+
+    // Returns the hash of the parts joined with colons, in lowercase
+    // hexadecimal.
+    fn hash(&self, parts: &[&str]) -> String {
+        let mut hasher = match *self {
+            Algorithm::Md5 | Algorithm::Md5Sess => Hasher::md5(),
+            Algorithm::Sha256 | Algorithm::Sha256Sess => Hasher::sha256(),
+        };
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                hasher.update(b":");
+            }
+            hasher.update(part.as_bytes());
+        }
+
+        let mut hex = String::with_capacity(64);
+        for b in hasher.finish() {
+            let _ = write!(hex, "{:02x}", b);
+        }
+        hex
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = DigestError;
+
+    fn from_str(s: &str) -> Result<Algorithm, DigestError> {
+        let known = [
+            Algorithm::Md5,
+            Algorithm::Md5Sess,
+            Algorithm::Sha256,
+            Algorithm::Sha256Sess,
+        ];
+        known
+            .iter()
+            .find(|algorithm| algorithm.as_str().eq_ignore_ascii_case(s))
+            .cloned()
+            .ok_or_else(|| DigestError::new(DigestErrorKind::Algorithm))
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Challenge {
+    /// Creates a challenge for a realm, with a nonce the server generated.
+    ///
+    /// The challenge uses `MD5` and `qop=auth`.
+    pub fn new(realm: &str, nonce: &str) -> Challenge {
+        Challenge {
+            realm: realm.into(),
+            nonce: nonce.into(),
+            opaque: None,
+            algorithm: Algorithm::Md5,
+            qop_auth: true,
+            stale: false,
+        }
+    }
+
+    /// Sets the algorithm of the challenge.
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Challenge {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the opaque value, which the client sends back unchanged.
+    pub fn with_opaque(mut self, opaque: &str) -> Challenge {
+        self.opaque = Some(opaque.into());
+        self
+    }
+
+    /// Marks the challenge as stale: the credentials of the client were
+    /// right, but with a nonce the server doesn't accept anymore.
+    pub fn with_stale(mut self, stale: bool) -> Challenge {
+        self.stale = stale;
+        self
+    }
+
+    /// Returns the realm of the challenge.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// Returns the nonce of the challenge.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Returns the opaque value of the challenge.
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_ref().map(|s| &s[..])
+    }
+
+    /// Returns the algorithm of the challenge.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Returns whether the challenge offers `qop=auth`.
+    pub fn has_qop_auth(&self) -> bool {
+        self.qop_auth
+    }
+
+    /// Returns whether the challenge is stale, in which case the client
+    /// should retry with the new nonce without asking for the password
+    /// again.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Computes the credentials answering the challenge for a request.
+    ///
+    /// `uri` is the request target, as sent in the request line, `cnonce`
+    /// a nonce the client generated, and `nonce_count` the number of
+    /// requests made with the nonce of the challenge, this one included.
+    /// The client nonce and count are only used with `qop=auth`, and when
+    /// the algorithm is a `-sess` one.
+    pub fn respond(
+        &self,
+        username: &str,
+        password: &str,
+        method: &Method,
+        uri: &str,
+        cnonce: &str,
+        nonce_count: u32,
+    ) -> Credentials {
+        let uses_cnonce = self.qop_auth || self.algorithm.is_sess();
+        let mut credentials = Credentials {
+            username: username.into(),
+            realm: self.realm.clone(),
+            nonce: self.nonce.clone(),
+            uri: uri.into(),
+            response: String::new(),
+            algorithm: self.algorithm,
+            cnonce: if uses_cnonce {
+                Some(cnonce.into())
+            } else {
+                None
+            },
+            nonce_count: if self.qop_auth {
+                Some(nonce_count)
+            } else {
+                None
+            },
+            opaque: self.opaque.clone(),
+        };
+        let ha1 = credentials.ha1(password);
+        credentials.response = credentials.expected(&ha1, method);
+        credentials
+    }
+
+    /// Returns the challenge as a header value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the realm, nonce or opaque value has bytes that
+    /// can't be in a header value.
+    pub fn to_header_value(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        HeaderValue::try_from(s)
+    }
+}
+
+impl FromStr for Challenge {
+    type Err = DigestError;
+
+    /// Parses the `Digest` challenge of a `WWW-Authenticate` or
+    /// `Proxy-Authenticate` value, which may list other challenges too.
+    fn from_str(s: &str) -> Result<Challenge, DigestError> {
+        let params = parse(s)?;
+
+        let mut challenge = Challenge::new("", "");
+        let mut realm = None;
+        let mut nonce = None;
+        let mut qop = None;
+        for (name, value) in params {
+            match &name.to_ascii_lowercase()[..] {
+                "realm" => realm = Some(value),
+                "nonce" => nonce = Some(value),
+                "opaque" => challenge.opaque = Some(value),
+                "algorithm" => challenge.algorithm = value.parse()?,
+                "qop" => qop = Some(value),
+                "stale" => challenge.stale = value.eq_ignore_ascii_case("true"),
+                _ => {}
+            }
+        }
+
+        challenge.realm = realm.ok_or_else(|| DigestError::new(DigestErrorKind::Missing))?;
+        challenge.nonce = nonce.ok_or_else(|| DigestError::new(DigestErrorKind::Missing))?;
+        challenge.qop_auth = match qop {
+            None => false,
+            Some(qop) => {
+                if !qop
+                    .split(',')
+                    .any(|qop| qop.trim().eq_ignore_ascii_case("auth"))
+                {
+                    return Err(DigestError::new(DigestErrorKind::Qop));
+                }
+                true
+            }
+        };
+        Ok(challenge)
+    }
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Digest realm=")?;
+        quoted(f, &self.realm)?;
+        if self.qop_auth {
+            f.write_str(", qop=\"auth\"")?;
+        }
+        write!(f, ", algorithm={}, nonce=", self.algorithm)?;
+        quoted(f, &self.nonce)?;
+        if let Some(ref opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            quoted(f, opaque)?;
+        }
+        if self.stale {
+            f.write_str(", stale=true")?;
+        }
+        Ok(())
+    }
+}
+
+impl Credentials {
+    /// Returns the user name of the credentials.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Returns the realm the credentials are for.
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    /// Returns the nonce of the challenge the credentials answer.
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    /// Returns the request target the credentials were computed for.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Returns the algorithm of the credentials.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Returns the opaque value of the challenge the credentials answer.
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_ref().map(|s| &s[..])
+    }
+
+    /// Returns the nonce count of the credentials, with `qop=auth`.
+    ///
+    /// A server should reject a count it has already seen for the nonce, as
+    /// the request may be replayed.
+    pub fn nonce_count(&self) -> Option<u32> {
+        self.nonce_count
+    }
+
+    /// Returns whether the credentials are those of a user with `password`,
+    /// for a request with `method`.
+    ///
+    /// This only checks the response hash. The server should also check that
+    /// the realm, nonce and opaque value are those of a challenge it sent,
+    /// that the nonce count is new, and that the URI is the request target.
+    pub fn verify(&self, method: &Method, password: &str) -> bool {
+        let ha1 = self.ha1(password);
+        constant_time_eq(
+            self.response.as_bytes(),
+            self.expected(&ha1, method).as_bytes(),
+        )
+    }
+
+    /// Returns the credentials as a header value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field has bytes that can't be in a header
+    /// value.
+    pub fn to_header_value(&self) -> Result<HeaderValue, InvalidHeaderValue> {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        HeaderValue::try_from(s).map(|mut value| {
+            value.set_sensitive(true);
+            value
+        })
+    }
+
+    // H(A1) of RFC 7616 section 3.4.2.
+    fn ha1(&self, password: &str) -> String {
+        let ha1 = self
+            .algorithm
+            .hash(&[&self.username, &self.realm, password]);
+        if self.algorithm.is_sess() {
+            let cnonce = self.cnonce.as_ref().map_or("", |s| &s[..]);
+            self.algorithm.hash(&[&ha1, &self.nonce, cnonce])
+        } else {
+            ha1
+        }
+    }
+
+    // The response of RFC 7616 section 3.4.1, or of RFC 2069 without `qop`.
+    fn expected(&self, ha1: &str, method: &Method) -> String {
+        let ha2 = self.algorithm.hash(&[method.as_str(), &self.uri]);
+        match (self.nonce_count, self.cnonce.as_ref()) {
+            (Some(nc), Some(cnonce)) => {
+                let mut nc_hex = String::with_capacity(8);
+                let _ = write!(nc_hex, "{:08x}", nc);
+                self.algorithm
+                    .hash(&[ha1, &self.nonce, &nc_hex, cnonce, "auth", &ha2])
+            }
+            _ => self.algorithm.hash(&[ha1, &self.nonce, &ha2]),
+        }
+    }
+}
+
+impl FromStr for Credentials {
+    type Err = DigestError;
+
+    /// Parses the `Digest` credentials of an `Authorization` or
+    /// `Proxy-Authorization` value.
+    fn from_str(s: &str) -> Result<Credentials, DigestError> {
+        let missing = || DigestError::new(DigestErrorKind::Missing);
+
+        let mut username = None;
+        let mut realm = None;
+        let mut nonce = None;
+        let mut uri = None;
+        let mut response = None;
+        let mut algorithm = Algorithm::Md5;
+        let mut cnonce = None;
+        let mut nonce_count = None;
+        let mut qop = None;
+        let mut opaque = None;
+        for (name, value) in parse(s)? {
+            match &name.to_ascii_lowercase()[..] {
+                "username" => username = Some(value),
+                "realm" => realm = Some(value),
+                "nonce" => nonce = Some(value),
+                "uri" => uri = Some(value),
+                "response" => response = Some(value),
+                "algorithm" => algorithm = value.parse()?,
+                "cnonce" => cnonce = Some(value),
+                "nc" => {
+                    if value.len() != 8 {
+                        return Err(DigestError::new(DigestErrorKind::Syntax));
+                    }
+                    let nc = u32::from_str_radix(&value, 16)
+                        .map_err(|_| DigestError::new(DigestErrorKind::Syntax))?;
+                    nonce_count = Some(nc);
+                }
+                "qop" => qop = Some(value),
+                "opaque" => opaque = Some(value),
+                _ => {}
+            }
+        }
+
+        match qop {
+            Some(ref qop) if !qop.eq_ignore_ascii_case("auth") => {
+                return Err(DigestError::new(DigestErrorKind::Qop));
+            }
+            Some(_) if nonce_count.is_none() || cnonce.is_none() => return Err(missing()),
+            None => nonce_count = None,
+            Some(_) => {}
+        }
+
+        Ok(Credentials {
+            username: username.ok_or_else(missing)?,
+            realm: realm.ok_or_else(missing)?,
+            nonce: nonce.ok_or_else(missing)?,
+            uri: uri.ok_or_else(missing)?,
+            response: response.ok_or_else(missing)?,
+            algorithm,
+            cnonce,
+            nonce_count,
+            opaque,
+        })
+    }
+}
+
+impl fmt::Display for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Digest username=")?;
+        quoted(f, &self.username)?;
+        f.write_str(", realm=")?;
+        quoted(f, &self.realm)?;
+        f.write_str(", nonce=")?;
+        quoted(f, &self.nonce)?;
+        f.write_str(", uri=")?;
+        quoted(f, &self.uri)?;
+        write!(f, ", algorithm={}", self.algorithm)?;
+        if let Some(nc) = self.nonce_count {
+            write!(f, ", qop=auth, nc={:08x}", nc)?;
+        }
+        if let Some(ref cnonce) = self.cnonce {
+            f.write_str(", cnonce=")?;
+            quoted(f, cnonce)?;
+        }
+        f.write_str(", response=")?;
+        quoted(f, &self.response)?;
+        if let Some(ref opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            quoted(f, opaque)?;
+        }
+        Ok(())
+    }
+}
+
+impl Client {
+    /// Creates a client answering `challenge` for a user.
+    pub fn new(challenge: Challenge, username: &str, password: &str) -> Client {
+        Client {
+            challenge,
+            username: username.into(),
+            password: password.into(),
+            nonce_count: 0,
+        }
+    }
+
+    /// Replaces the challenge of the client, after a server rejected a
+    /// request.
+    ///
+    /// The nonce count starts again if the nonce is new.
+    pub fn set_challenge(&mut self, challenge: Challenge) {
+        if challenge.nonce != self.challenge.nonce {
+            self.nonce_count = 0;
+        }
+        self.challenge = challenge;
+    }
+
+    /// Returns the challenge of the client.
+    pub fn challenge(&self) -> &Challenge {
+        &self.challenge
+    }
+
+    /// Computes the credentials of the next request, for `method` and the
+    /// request target `uri`, with a new client nonce.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the nonce was used for so many requests that
+    /// the count would overflow. The client then needs a new challenge.
+    pub fn authorize(
+        &mut self,
+        method: &Method,
+        uri: &str,
+        cnonce: &str,
+    ) -> Result<Credentials, DigestError> {
+        self.nonce_count = self
+            .nonce_count
+            .checked_add(1)
+            .ok_or_else(|| DigestError::new(DigestErrorKind::NonceCount))?;
+        Ok(self.challenge.respond(
+            &self.username,
+            &self.password,
+            method,
+            uri,
+            cnonce,
+            self.nonce_count,
+        ))
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("challenge", &self.challenge)
+            .field("username", &self.username)
+            .field("nonce_count", &self.nonce_count)
+            .finish()
+    }
+}
+
+impl DigestError {
+    fn new(kind: DigestErrorKind) -> DigestError {
+        DigestError { kind }
+    }
+}
+
+impl fmt::Debug for DigestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DigestError").field(&self.kind).finish()
+    }
+}
+
+impl fmt::Display for DigestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            DigestErrorKind::Syntax => "invalid digest parameters",
+            DigestErrorKind::Missing => "missing digest parameter",
+            DigestErrorKind::Algorithm => "unsupported digest algorithm",
+            DigestErrorKind::Qop => "unsupported digest quality of protection",
+            DigestErrorKind::NonceCount => "digest nonce used for too many requests",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for DigestError {}
+
+/// Returns the parameters of the `Digest` challenge or credentials of a
+/// value, with quoted strings unescaped.
+///
+/// The value is a list of schemes, each followed by its parameters or by a
+/// token68, as in RFC 7235 section 2.1. Only the parameters of the first
+/// `Digest` scheme are returned.
+fn parse(s: &str) -> Result<Vec<(String, String)>, DigestError> {
+    let syntax = || DigestError::new(DigestErrorKind::Syntax);
+    let b = s.as_bytes();
+    let mut i = 0;
+    let mut in_scheme = false;
+    let mut in_digest = false;
+    let mut params = Vec::new();
+
+    loop {
+        i = skip(b, i, b" \t,");
+        if i == b.len() {
+            break;
+        }
+        let (name, end) = token(b, i).ok_or_else(syntax)?;
+        let after = skip(b, end, b" \t");
+
+        if after < b.len() && b[after] == b'=' {
+            if !in_scheme {
+                return Err(syntax());
+            }
+            let start = skip(b, after + 1, b" \t");
+            let (value, end) = if start < b.len() && b[start] == b'"' {
+                quoted_string(b, start).ok_or_else(syntax)?
+            } else {
+                let (value, end) = token(b, start).ok_or_else(syntax)?;
+                (value.into(), end)
+            };
+            if in_digest {
+                params.push((name.into(), value));
+            }
+            i = end;
+        } else if in_digest {
+            break;
+        } else {
+            in_scheme = true;
+            in_digest = name.eq_ignore_ascii_case("digest");
+            i = after;
+
+            // A token68, like `abc==`, instead of parameters.
+            if let Some((_, end)) = token(b, after) {
+                let padded = skip(b, end, b"=");
+                let next = skip(b, padded, b" \t");
+                if next == b.len() || b[next] == b',' {
+                    i = padded;
+                }
+            }
+        }
+    }
+
+    if in_digest {
+        Ok(params)
+    } else {
+        Err(syntax())
+    }
+}
+
+fn skip(b: &[u8], mut i: usize, set: &[u8]) -> usize {
+    while i < b.len() && set.contains(&b[i]) {
+        i += 1;
+    }
+    i
+}
+
+// A token, or a token68, whose characters are tokens too except `=`.
+fn token(b: &[u8], start: usize) -> Option<(&str, usize)> {
+    let mut end = start;
+    while end < b.len() && (b[end].is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~/".contains(&b[end]))
+    {
+        end += 1;
+    }
+    if end == start {
+        return None;
+    }
+    // The bytes are ASCII.
+    Some((core::str::from_utf8(&b[start..end]).ok()?, end))
+}
+
+fn quoted_string(b: &[u8], start: usize) -> Option<(String, usize)> {
+    let mut value = Vec::new();
+    let mut i = start + 1;
+    while i < b.len() {
+        match b[i] {
+            b'"' => return Some((String::from_utf8(value).ok()?, i + 1)),
+            b'\\' if i + 1 < b.len() => {
+                value.push(b[i + 1]);
+                i += 2;
+            }
+            c => {
+                value.push(c);
+                i += 1;
+            }
+        }
+    }
+    None
+}
+
+fn quoted(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    f.write_char('"')
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// MD5, RFC 1321, and SHA-256, FIPS 180-4. Both hash blocks of 64 bytes and
+// pad them the same way, except for the byte order of the length.
+struct Hasher {
+    sha256: bool,
+    state: [u32; 8],
+    block: [u8; 64],
+    block_len: usize,
+    len: u64,
+}
+
+impl Hasher {
+    fn md5() -> Hasher {
+        Hasher::with_state(
+            false,
+            [
+                0x6745_2301,
+                0xefcd_ab89,
+                0x98ba_dcfe,
+                0x1032_5476,
+                0,
+                0,
+                0,
+                0,
+            ],
+        )
+    }
+
+    fn sha256() -> Hasher {
+        Hasher::with_state(
+            true,
+            [
+                0x6a09_e667,
+                0xbb67_ae85,
+                0x3c6e_f372,
+                0xa54f_f53a,
+                0x510e_527f,
+                0x9b05_688c,
+                0x1f83_d9ab,
+                0x5be0_cd19,
+            ],
+        )
+    }
+
+    fn with_state(sha256: bool, state: [u32; 8]) -> Hasher {
+        Hasher {
+            sha256,
+            state,
+            block: [0; 64],
+            block_len: 0,
+            len: 0,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.len += data.len() as u64;
+        for &b in data {
+            self.block[self.block_len] = b;
+            self.block_len += 1;
+            if self.block_len == 64 {
+                self.compress();
+                self.block_len = 0;
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let bits = self.len * 8;
+        self.update(&[0x80]);
+        while self.block_len != 56 {
+            self.update(&[0]);
+        }
+        let len = if self.sha256 {
+            bits.to_be_bytes()
+        } else {
+            bits.to_le_bytes()
+        };
+        self.block[56..].copy_from_slice(&len);
+        self.compress();
+
+        if self.sha256 {
+            self.state
+                .iter()
+                .flat_map(|word| word.to_be_bytes().to_vec())
+                .collect()
+        } else {
+            self.state[..4]
+                .iter()
+                .flat_map(|word| word.to_le_bytes().to_vec())
+                .collect()
+        }
+    }
+
+    fn compress(&mut self) {
+        if self.sha256 {
+            self.compress_sha256();
+        } else {
+            self.compress_md5();
+        }
+    }
+
+    fn compress_md5(&mut self) {
+        const SHIFTS: [[u32; 4]; 4] = [
+            [7, 12, 17, 22],
+            [5, 9, 14, 20],
+            [4, 11, 16, 23],
+            [6, 10, 15, 21],
+        ];
+
+        let mut m = [0u32; 16];
+        for (word, b) in m.iter_mut().zip(self.block.chunks(4)) {
+            *word = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+        }
+
+        let (mut a, mut b, mut c, mut d) =
+            (self.state[0], self.state[1], self.state[2], self.state[3]);
+        for (i, &k) in MD5_K.iter().enumerate() {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let f = f.wrapping_add(a).wrapping_add(k).wrapping_add(m[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(SHIFTS[i / 16][i % 4]));
+        }
+
+        for (state, x) in self.state.iter_mut().zip(&[a, b, c, d]) {
+            *state = state.wrapping_add(*x);
+        }
+    }
+
+    fn compress_sha256(&mut self) {
+        let mut w = [0u32; 64];
+        for (word, b) in w.iter_mut().zip(self.block.chunks(4)) {
+            *word = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let mut v = self.state;
+        for (&k, &word) in SHA256_K.iter().zip(w.iter()) {
+            let s1 = v[4].rotate_right(6) ^ v[4].rotate_right(11) ^ v[4].rotate_right(25);
+            let ch = (v[4] & v[5]) ^ (!v[4] & v[6]);
+            let temp1 = v[7]
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            let s0 = v[0].rotate_right(2) ^ v[0].rotate_right(13) ^ v[0].rotate_right(22);
+            let maj = (v[0] & v[1]) ^ (v[0] & v[2]) ^ (v[1] & v[2]);
+            let temp2 = s0.wrapping_add(maj);
+
+            v = [
+                temp1.wrapping_add(temp2),
+                v[0],
+                v[1],
+                v[2],
+                v[3].wrapping_add(temp1),
+                v[4],
+                v[5],
+                v[6],
+            ];
+        }
+
+        for (state, x) in self.state.iter_mut().zip(&v) {
+            *state = state.wrapping_add(*x);
+        }
+    }
+}
+
+const MD5_K: [u32; 64] = [
+    0xd76a_a478, 0xe8c7_b756, 0x2420_70db, 0xc1bd_ceee,
+    0xf57c_0faf, 0x4787_c62a, 0xa830_4613, 0xfd46_9501,
+    0x6980_98d8, 0x8b44_f7af, 0xffff_5bb1, 0x895c_d7be,
+    0x6b90_1122, 0xfd98_7193, 0xa679_438e, 0x49b4_0821,
+    0xf61e_2562, 0xc040_b340, 0x265e_5a51, 0xe9b6_c7aa,
+    0xd62f_105d, 0x0244_1453, 0xd8a1_e681, 0xe7d3_fbc8,
+    0x21e1_cde6, 0xc337_07d6, 0xf4d5_0d87, 0x455a_14ed,
+    0xa9e3_e905, 0xfcef_a3f8, 0x676f_02d9, 0x8d2a_4c8a,
+    0xfffa_3942, 0x8771_f681, 0x6d9d_6122, 0xfde5_380c,
+    0xa4be_ea44, 0x4bde_cfa9, 0xf6bb_4b60, 0xbebf_bc70,
+    0x289b_7ec6, 0xeaa1_27fa, 0xd4ef_3085, 0x0488_1d05,
+    0xd9d4_d039, 0xe6db_99e5, 0x1fa2_7cf8, 0xc4ac_5665,
+    0xf429_2244, 0x432a_ff97, 0xab94_23a7, 0xfc93_a039,
+    0x655b_59c3, 0x8f0c_cc92, 0xffef_f47d, 0x8584_5dd1,
+    0x6fa8_7e4f, 0xfe2c_e6e0, 0xa301_4314, 0x4e08_11a1,
+    0xf753_7e82, 0xbd3a_f235, 0x2ad7_d2bb, 0xeb86_d391,
+];
+
+const SHA256_K: [u32; 64] = [
+    0x428a_2f98, 0x7137_4491, 0xb5c0_fbcf, 0xe9b5_dba5,
+    0x3956_c25b, 0x59f1_11f1, 0x923f_82a4, 0xab1c_5ed5,
+    0xd807_aa98, 0x1283_5b01, 0x2431_85be, 0x550c_7dc3,
+    0x72be_5d74, 0x80de_b1fe, 0x9bdc_06a7, 0xc19b_f174,
+    0xe49b_69c1, 0xefbe_4786, 0x0fc1_9dc6, 0x240c_a1cc,
+    0x2de9_2c6f, 0x4a74_84aa, 0x5cb0_a9dc, 0x76f9_88da,
+    0x983e_5152, 0xa831_c66d, 0xb003_27c8, 0xbf59_7fc7,
+    0xc6e0_0bf3, 0xd5a7_9147, 0x06ca_6351, 0x1429_2967,
+    0x27b7_0a85, 0x2e1b_2138, 0x4d2c_6dfc, 0x5338_0d13,
+    0x650a_7354, 0x766a_0abb, 0x81c2_c92e, 0x9272_2c85,
+    0xa2bf_e8a1, 0xa81a_664b, 0xc24b_8b70, 0xc76c_51a3,
+    0xd192_e819, 0xd699_0624, 0xf40e_3585, 0x106a_a070,
+    0x19a4_c116, 0x1e37_6c08, 0x2748_774c, 0x34b0_bcb5,
+    0x391c_0cb3, 0x4ed8_aa4a, 0x5b9c_ca4f, 0x682e_6ff3,
+    0x748f_82ee, 0x78a5_636f, 0x84c8_7814, 0x8cc7_0208,
+    0x90be_fffa, 0xa450_6ceb, 0xbef9_a3f7, 0xc671_78f2,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: &str = "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v";
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    #[test]
+    fn hash_vectors() {
+        assert_eq!(
+            Algorithm::Md5.hash(&[""]),
+            "d41d8cd98f00b204e9800998ecf8427e"
+        );
+        let a = "a".repeat(1000);
+        assert_eq!(
+            Algorithm::Md5.hash(&[&a]),
+            "cabe45dcc9ae5b66ba86600cca6b8ba8"
+        );
+        assert_eq!(
+            Algorithm::Sha256.hash(&[&a]),
+            "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+        );
+    }
+
+    #[test]
+    fn rfc_examples() {
+        // RFC 2617 section 3.5.
+        let challenge: Challenge = "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""
+            .parse()
+            .unwrap();
+        let credentials = challenge.respond(
+            "Mufasa",
+            "Circle Of Life",
+            &Method::GET,
+            "/dir/index.html",
+            "0a4f113b",
+            1,
+        );
+        assert_eq!(credentials.response, "6629fae49393a05397450978507c4ef1");
+        assert_eq!(
+            credentials.opaque(),
+            Some("5ccc069c403ebaf9f0171e9517f40e41")
+        );
+
+        // RFC 7616 section 3.9.1.
+        let challenge = Challenge::new("http-auth@example.org", NONCE);
+        let credentials = challenge.respond(
+            "Mufasa",
+            "Circle of Life",
+            &Method::GET,
+            "/dir/index.html",
+            CNONCE,
+            1,
+        );
+        assert_eq!(credentials.response, "8ca523f5e9506fed4657c9700eebdbec");
+        let credentials = challenge.with_algorithm(Algorithm::Sha256).respond(
+            "Mufasa",
+            "Circle of Life",
+            &Method::GET,
+            "/dir/index.html",
+            CNONCE,
+            1,
+        );
+        assert_eq!(
+            credentials.response,
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+        );
+    }
+
+    #[test]
+    fn without_qop_and_sess() {
+        let challenge: Challenge =
+            "Digest realm=\"testrealm@host.com\", nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\""
+                .parse()
+                .unwrap();
+        assert!(!challenge.has_qop_auth());
+        let credentials = challenge.respond(
+            "Mufasa",
+            "Circle Of Life",
+            &Method::GET,
+            "/dir/index.html",
+            "x",
+            1,
+        );
+        assert_eq!(credentials.response, "670fd8c2df070c60b045671b8b24ff02");
+        assert_eq!(credentials.nonce_count(), None);
+        assert!(!credentials.to_string().contains("cnonce"));
+
+        let register = Method::from_bytes(b"REGISTER").unwrap();
+        let challenge =
+            Challenge::new("http-auth@example.org", NONCE).with_algorithm(Algorithm::Sha256Sess);
+        let credentials = challenge.respond(
+            "Mufasa",
+            "Circle of Life",
+            &register,
+            "sip:example.org",
+            CNONCE,
+            2,
+        );
+        assert_eq!(
+            credentials.response,
+            "8d3974116150d0cb3552c0c35f5da82c3121a090f87631d4d7d81d61d20f9192"
+        );
+        assert!(credentials.verify(&register, "Circle of Life"));
+        assert!(!credentials.verify(&Method::GET, "Circle of Life"));
+    }
+
+    #[test]
+    fn parse_and_write() {
+        let challenge: Challenge = "Basic realm=\"simple\", Digest realm=\"a \\\"b\\\"\", \
+             nonce=abc, algorithm=sha-256-SESS, stale=TRUE, Negotiate abc=="
+            .parse()
+            .unwrap();
+        assert_eq!(challenge.realm(), "a \"b\"");
+        assert_eq!(challenge.nonce(), "abc");
+        assert_eq!(challenge.algorithm(), Algorithm::Sha256Sess);
+        assert!(challenge.is_stale());
+        assert_eq!(
+            challenge.to_string(),
+            "Digest realm=\"a \\\"b\\\"\", algorithm=SHA-256-sess, nonce=\"abc\", stale=true"
+        );
+        assert_eq!(
+            challenge.to_string().parse::<Challenge>().unwrap(),
+            challenge
+        );
+
+        assert!("Negotiate abc==, Digest realm=\"r\", nonce=\"n\""
+            .parse::<Challenge>()
+            .is_ok());
+        assert!("Basic realm=\"r\"".parse::<Challenge>().is_err());
+        assert!("Digest realm=\"r\"".parse::<Challenge>().is_err());
+        assert!("Digest realm=\"r, nonce=\"n\""
+            .parse::<Challenge>()
+            .is_err());
+        assert!("Digest realm=r, nonce=n, algorithm=SHA-512"
+            .parse::<Challenge>()
+            .is_err());
+        assert!("Digest realm=r, nonce=n, qop=auth-int"
+            .parse::<Challenge>()
+            .is_err());
+
+        let credentials = Challenge::new("r", "n").with_opaque("o").respond(
+            "u",
+            "p",
+            &Method::GET,
+            "/",
+            "c",
+            0x1f,
+        );
+        let value = credentials.to_header_value().unwrap();
+        assert!(value.is_sensitive());
+        assert_eq!(
+            value,
+            "Digest username=\"u\", realm=\"r\", nonce=\"n\", uri=\"/\", algorithm=MD5, \
+             qop=auth, nc=0000001f, cnonce=\"c\", \
+             response=\"c9f2787afc157d75d652c0ce435cd3af\", opaque=\"o\""
+        );
+        let parsed: Credentials = value.to_str().unwrap().parse().unwrap();
+        assert_eq!(parsed, credentials);
+
+        assert!(
+            "Digest username=u, realm=r, nonce=n, uri=/, response=x, qop=auth"
+                .parse::<Credentials>()
+                .is_err()
+        );
+        assert!(
+            "Digest username=u, realm=r, nonce=n, uri=/, response=x, qop=auth, nc=1, cnonce=c"
+                .parse::<Credentials>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn client_counts_nonces() {
+        let mut client = Client::new(Challenge::new("r", "n1"), "u", "p");
+        assert_eq!(
+            client
+                .authorize(&Method::GET, "/", "c")
+                .unwrap()
+                .nonce_count(),
+            Some(1)
+        );
+        assert_eq!(
+            client
+                .authorize(&Method::GET, "/", "c")
+                .unwrap()
+                .nonce_count(),
+            Some(2)
+        );
+
+        client.set_challenge(Challenge::new("r", "n1").with_opaque("o"));
+        assert_eq!(
+            client
+                .authorize(&Method::GET, "/", "c")
+                .unwrap()
+                .nonce_count(),
+            Some(3)
+        );
+
+        client.set_challenge(Challenge::new("r", "n2").with_stale(true));
+        let credentials = client.authorize(&Method::GET, "/", "c").unwrap();
+        assert_eq!(credentials.nonce_count(), Some(1));
+        assert_eq!(credentials.nonce(), "n2");
+        assert!(!format!("{:?}", client).contains("\"p\""));
+    }
+}
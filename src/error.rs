@@ -1,11 +1,24 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::fmt;
+use core::result;
+#[cfg(feature = "std")]
 use std::error;
-use std::fmt;
-use std::result;
 
 use crate::header;
+use crate::codec;
+#[cfg(feature = "http")]
+use crate::connect;
+#[cfg(feature = "digest")]
+use crate::digest;
 use crate::method;
+#[cfg(feature = "http")]
+use crate::pseudo;
+use crate::response;
 use crate::status;
 use crate::uri;
+#[cfg(feature = "ws")]
+use crate::ws;
 
 /// A generic "error" for HTTP connections
 ///
@@ -13,37 +26,239 @@ use crate::uri;
 /// functions in this crate, but all other errors can be converted to this
 /// error. Consumers of this crate can typically consume and work with this form
 /// of error for conversions with the `?` operator.
+///
+/// What went wrong is told by [`kind`], which is stable across releases,
+/// unlike the messages of the errors. Errors about a single bad byte, like
+/// an invalid character in a URI, also tell the [`offset`] of that byte.
+///
+/// [`kind`]: #method.kind
+/// [`offset`]: #method.offset
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::{Error, ErrorKind, Uri};
+/// let err = Error::from("http://exa mple.com/".parse::<Uri>().unwrap_err());
+/// assert_eq!(err.kind(), ErrorKind::UriChar);
+/// assert_eq!(err.offset(), Some(10));
+///
+/// let err = Error::from("ftp:/example.com".parse::<Uri>().unwrap_err());
+/// assert_eq!(err.kind(), ErrorKind::UriFormat);
+/// assert_eq!(err.offset(), None);
+/// ```
 pub struct Error {
-    inner: ErrorKind,
+    inner: Inner,
 }
 
 /// A `Result` typedef to use with the `httplike::Error` type
 pub type Result<T> = result::Result<T, Error>;
 
-enum ErrorKind {
+/// The kind of an `Error`.
+///
+/// Kinds may be added in later releases, so a `match` on them needs a
+/// wildcard arm.
+// `#[non_exhaustive]` needs a newer compiler than the minimum one.
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// A status code out of the range 100 to 999.
+    StatusCode,
+    /// An invalid method.
+    Method,
+    /// An invalid character in a URI.
+    UriChar,
+    /// An invalid or too long scheme.
+    UriScheme,
+    /// An invalid authority.
+    UriAuthority,
+    /// An invalid port.
+    UriPort,
+    /// A URI that is none of the forms a request target can take.
+    UriFormat,
+    /// URI parts missing a scheme, authority, or path that the others need.
+    UriPartMissing,
+    /// A URI longer than a `Uri` can hold.
+    UriTooLong,
+    /// An empty URI.
+    UriEmpty,
+    /// An invalid header name.
+    HeaderName,
+    /// A header value with a byte that isn't allowed in values.
+    HeaderValue,
+    /// A reason phrase with a byte that isn't allowed in it.
+    ReasonPhrase,
+    /// A message rejected by a builder interceptor.
+    Rejected,
+    /// A message whose version has no text message head.
+    Encode,
+    /// A message head larger than the parser allows.
+    HeadTooLarge,
+    /// A message head with more header fields than the parser allows.
+    TooManyHeaders,
+    /// An invalid request or status line.
+    StartLine,
+    /// A version the parser doesn't support.
+    Version,
+    /// An invalid header line.
+    HeaderLine,
+    /// An invalid RTSP interleaved frame.
+    Frame,
+    /// A built message exceeding its `codec::Limits`.
+    LimitExceeded,
+    /// An invalid websocket upgrade.
+    Upgrade,
+    /// Invalid pseudo-header fields.
+    Pseudo,
+    /// An invalid `CONNECT` request.
+    Connect,
+    /// Invalid digest authentication.
+    Digest,
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+/// Every error encountered while using a request or response builder.
+///
+/// Returned by `body_all_errors` on `request::Builder` and
+/// `response::Builder`. The errors are listed in the order of the builder
+/// calls that caused them.
+pub struct BuildErrors {
+    errors: Vec<Error>,
+}
+
+/// A message rejected by a builder interceptor.
+///
+/// Interceptors registered with `with_interceptor` on `request::Builder` or
+/// `response::Builder` return this, converted into an `Error`, to make the
+/// builder fail.
+pub struct Rejected {
+    reason: Cow<'static, str>,
+}
+
+enum Inner {
     StatusCode(status::InvalidStatusCode),
     Method(method::InvalidMethod),
     Uri(uri::InvalidUri),
     UriParts(uri::InvalidUriParts),
     HeaderName(header::InvalidHeaderName),
     HeaderValue(header::InvalidHeaderValue),
+    Rejected(Rejected),
+    ReasonPhrase(response::InvalidReasonPhrase),
+    Encode(codec::EncodeError),
+    Parse(codec::ParseError),
+    Limit(codec::LimitError),
+    #[cfg(feature = "ws")]
+    Upgrade(ws::UpgradeError),
+    #[cfg(feature = "http")]
+    Pseudo(pseudo::PseudoError),
+    #[cfg(feature = "http")]
+    Connect(connect::ConnectError),
+    #[cfg(feature = "digest")]
+    Digest(digest::DigestError),
+}
+
+// Evaluates `$body` with `$e` bound to the error wrapped in `$kind`.
+//
+// Without `std` there is no `dyn std::error::Error` to return from one
+// accessor, so each use matches on the kind instead.
+macro_rules! with_inner {
+    ($kind:expr, $e:ident => $body:expr) => {{
+        use self::Inner::*;
+
+        match $kind {
+            StatusCode(ref $e) => $body,
+            Method(ref $e) => $body,
+            Uri(ref $e) => $body,
+            UriParts(ref $e) => $body,
+            HeaderName(ref $e) => $body,
+            HeaderValue(ref $e) => $body,
+            Rejected(ref $e) => $body,
+            ReasonPhrase(ref $e) => $body,
+            Encode(ref $e) => $body,
+            Parse(ref $e) => $body,
+            Limit(ref $e) => $body,
+            #[cfg(feature = "ws")]
+            Upgrade(ref $e) => $body,
+            #[cfg(feature = "http")]
+            Pseudo(ref $e) => $body,
+            #[cfg(feature = "http")]
+            Connect(ref $e) => $body,
+            #[cfg(feature = "digest")]
+            Digest(ref $e) => $body,
+        }
+    }};
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_tuple("httplike::Error")
-            // Skip the noise of the ErrorKind enum
-            .field(&self.get_ref())
+            // Skip the noise of the Inner enum
+            .field(with_inner!(self.inner, e => e as &dyn fmt::Debug))
             .finish()
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(self.get_ref(), f)
+        with_inner!(self.inner, e => fmt::Display::fmt(e, f))
     }
 }
 
+impl Error {
+    /// Returns the kind of the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::{ErrorKind, Request};
+    /// let err = Request::get("/").header("x-id", "a\nb").body(()).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::HeaderValue);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        use self::Inner::*;
+
+        match self.inner {
+            StatusCode(_) => ErrorKind::StatusCode,
+            Method(_) => ErrorKind::Method,
+            Uri(ref e) => e.kind(),
+            UriParts(ref e) => e.kind(),
+            HeaderName(_) => ErrorKind::HeaderName,
+            HeaderValue(_) => ErrorKind::HeaderValue,
+            Rejected(_) => ErrorKind::Rejected,
+            ReasonPhrase(_) => ErrorKind::ReasonPhrase,
+            Encode(_) => ErrorKind::Encode,
+            Parse(ref e) => e.kind(),
+            Limit(_) => ErrorKind::LimitExceeded,
+            #[cfg(feature = "ws")]
+            Upgrade(_) => ErrorKind::Upgrade,
+            #[cfg(feature = "http")]
+            Pseudo(_) => ErrorKind::Pseudo,
+            #[cfg(feature = "http")]
+            Connect(_) => ErrorKind::Connect,
+            #[cfg(feature = "digest")]
+            Digest(_) => ErrorKind::Digest,
+        }
+    }
+
+    /// Returns the offset of the byte that caused the error, if the error
+    /// was caused by one.
+    ///
+    /// The offset is into the input that was converted: the whole URI, the
+    /// header name or the header value.
+    pub fn offset(&self) -> Option<usize> {
+        use self::Inner::*;
+
+        match self.inner {
+            Uri(ref e) => e.offset(),
+            UriParts(ref e) => e.offset(),
+            HeaderName(ref e) => e.offset(),
+            HeaderValue(ref e) => Some(e.offset()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl Error {
     /// Return true if the underlying error has the same type as T.
     pub fn is<T: error::Error + 'static>(&self) -> bool {
@@ -52,22 +267,14 @@ impl Error {
 
     /// Return a reference to the lower level, inner error.
     pub fn get_ref(&self) -> &(dyn error::Error + 'static) {
-        use self::ErrorKind::*;
-
-        match self.inner {
-            StatusCode(ref e) => e,
-            Method(ref e) => e,
-            Uri(ref e) => e,
-            UriParts(ref e) => e,
-            HeaderName(ref e) => e,
-            HeaderValue(ref e) => e,
-        }
+        with_inner!(self.inner, e => e)
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for Error {
     fn description(&self) -> &str {
-        use self::ErrorKind::*;
+        use self::Inner::*;
 
         match self.inner {
             StatusCode(ref e) => e.description(),
@@ -76,6 +283,19 @@ impl error::Error for Error {
             UriParts(ref e) => e.description(),
             HeaderName(ref e) => e.description(),
             HeaderValue(ref e) => e.description(),
+            Rejected(_) => "message rejected",
+            ReasonPhrase(_) => "invalid reason phrase",
+            Encode(_) => "version has no text message head",
+            Parse(_) => "invalid message head",
+            Limit(_) => "message exceeds limits",
+            #[cfg(feature = "ws")]
+            Upgrade(_) => "invalid websocket upgrade",
+            #[cfg(feature = "http")]
+            Pseudo(_) => "invalid pseudo-header fields",
+            #[cfg(feature = "http")]
+            Connect(_) => "invalid connect request",
+            #[cfg(feature = "digest")]
+            Digest(_) => "invalid digest authentication",
         }
     }
 
@@ -89,7 +309,7 @@ impl error::Error for Error {
 impl From<status::InvalidStatusCode> for Error {
     fn from(err: status::InvalidStatusCode) -> Error {
         Error {
-            inner: ErrorKind::StatusCode(err),
+            inner: Inner::StatusCode(err),
         }
     }
 }
@@ -97,7 +317,7 @@ impl From<status::InvalidStatusCode> for Error {
 impl From<method::InvalidMethod> for Error {
     fn from(err: method::InvalidMethod) -> Error {
         Error {
-            inner: ErrorKind::Method(err),
+            inner: Inner::Method(err),
         }
     }
 }
@@ -105,7 +325,7 @@ impl From<method::InvalidMethod> for Error {
 impl From<uri::InvalidUri> for Error {
     fn from(err: uri::InvalidUri) -> Error {
         Error {
-            inner: ErrorKind::Uri(err),
+            inner: Inner::Uri(err),
         }
     }
 }
@@ -113,7 +333,7 @@ impl From<uri::InvalidUri> for Error {
 impl From<uri::InvalidUriParts> for Error {
     fn from(err: uri::InvalidUriParts) -> Error {
         Error {
-            inner: ErrorKind::UriParts(err),
+            inner: Inner::UriParts(err),
         }
     }
 }
@@ -121,7 +341,7 @@ impl From<uri::InvalidUriParts> for Error {
 impl From<header::InvalidHeaderName> for Error {
     fn from(err: header::InvalidHeaderName) -> Error {
         Error {
-            inner: ErrorKind::HeaderName(err),
+            inner: Inner::HeaderName(err),
         }
     }
 }
@@ -129,21 +349,239 @@ impl From<header::InvalidHeaderName> for Error {
 impl From<header::InvalidHeaderValue> for Error {
     fn from(err: header::InvalidHeaderValue) -> Error {
         Error {
-            inner: ErrorKind::HeaderValue(err),
+            inner: Inner::HeaderValue(err),
+        }
+    }
+}
+
+impl From<response::InvalidReasonPhrase> for Error {
+    fn from(err: response::InvalidReasonPhrase) -> Error {
+        Error {
+            inner: Inner::ReasonPhrase(err),
+        }
+    }
+}
+
+impl From<codec::EncodeError> for Error {
+    fn from(err: codec::EncodeError) -> Error {
+        Error {
+            inner: Inner::Encode(err),
+        }
+    }
+}
+
+impl From<codec::ParseError> for Error {
+    fn from(err: codec::ParseError) -> Error {
+        Error {
+            inner: Inner::Parse(err),
+        }
+    }
+}
+
+impl From<codec::LimitError> for Error {
+    fn from(err: codec::LimitError) -> Error {
+        Error {
+            inner: Inner::Limit(err),
+        }
+    }
+}
+
+#[cfg(feature = "ws")]
+impl From<ws::UpgradeError> for Error {
+    fn from(err: ws::UpgradeError) -> Error {
+        Error {
+            inner: Inner::Upgrade(err),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<pseudo::PseudoError> for Error {
+    fn from(err: pseudo::PseudoError) -> Error {
+        Error {
+            inner: Inner::Pseudo(err),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<connect::ConnectError> for Error {
+    fn from(err: connect::ConnectError) -> Error {
+        Error {
+            inner: Inner::Connect(err),
         }
     }
 }
 
-impl From<std::convert::Infallible> for Error {
-    fn from(err: std::convert::Infallible) -> Error {
+#[cfg(feature = "digest")]
+impl From<digest::DigestError> for Error {
+    fn from(err: digest::DigestError) -> Error {
+        Error {
+            inner: Inner::Digest(err),
+        }
+    }
+}
+
+impl From<Rejected> for Error {
+    fn from(err: Rejected) -> Error {
+        Error {
+            inner: Inner::Rejected(err),
+        }
+    }
+}
+
+impl From<core::convert::Infallible> for Error {
+    fn from(err: core::convert::Infallible) -> Error {
         match err {}
     }
 }
 
+impl Rejected {
+    /// Creates a new `Rejected` with the reason for the rejection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::Rejected;
+    /// let rejected = Rejected::new("missing x-request-id");
+    /// assert_eq!(rejected.reason(), "missing x-request-id");
+    /// assert_eq!(rejected.to_string(), "message rejected: missing x-request-id");
+    /// ```
+    pub fn new<R: Into<Cow<'static, str>>>(reason: R) -> Rejected {
+        Rejected {
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the reason for the rejection.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Debug for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Rejected").field(&self.reason).finish()
+    }
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "message rejected: {}", self.reason)
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for Rejected {}
+
+impl BuildErrors {
+    pub(crate) fn new(first: Error, rest: Vec<Error>) -> BuildErrors {
+        let mut errors = Vec::with_capacity(rest.len() + 1);
+        errors.push(first);
+        errors.extend(rest);
+        BuildErrors { errors }
+    }
+
+    /// Returns the number of errors.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Always returns `false`; a `BuildErrors` holds at least one error.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the error caused by the earliest failing builder call.
+    ///
+    /// This is the error `body` would have returned.
+    pub fn first(&self) -> &Error {
+        &self.errors[0]
+    }
+
+    /// Returns an iterator over all errors.
+    pub fn iter(&self) -> core::slice::Iter<'_, Error> {
+        self.errors.iter()
+    }
+
+    /// Consumes the `BuildErrors`, returning all errors.
+    pub fn into_vec(self) -> Vec<Error> {
+        self.errors
+    }
+}
+
+impl fmt::Debug for BuildErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("httplike::BuildErrors")
+            .field(&self.errors)
+            .finish()
+    }
+}
+
+impl fmt::Display for BuildErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, err) in self.errors.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            fmt::Display::fmt(err, f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for BuildErrors {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(self.first())
+    }
+}
+
+impl From<BuildErrors> for Error {
+    fn from(errors: BuildErrors) -> Error {
+        errors.errors.into_iter().next().expect("at least one error")
+    }
+}
+
+impl<'a> IntoIterator for &'a BuildErrors {
+    type Item = &'a Error;
+    type IntoIter = core::slice::Iter<'a, Error>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn kinds_and_offsets() {
+        let err = Error::from("/a\x7fb".parse::<uri::Uri>().unwrap_err());
+        assert_eq!(err.kind(), ErrorKind::UriChar);
+        assert_eq!(err.offset(), Some(2));
+
+        let err = Error::from("http://a:b:c/".parse::<uri::Uri>().unwrap_err());
+        assert_eq!(err.kind(), ErrorKind::UriAuthority);
+        assert_eq!(err.offset(), None);
+
+        let err = Error::from("ex ample.com".parse::<uri::Authority>().unwrap_err());
+        assert_eq!((err.kind(), err.offset()), (ErrorKind::UriChar, Some(2)));
+
+        let err = Error::from(header::HeaderName::from_bytes(b"x-a b").unwrap_err());
+        assert_eq!((err.kind(), err.offset()), (ErrorKind::HeaderName, Some(3)));
+
+        let err = Error::from(header::HeaderName::from_lowercase(b"x-Id").unwrap_err());
+        assert_eq!(err.offset(), Some(2));
+
+        let err = Error::from(header::HeaderValue::from_bytes(b"ok\r\n").unwrap_err());
+        assert_eq!((err.kind(), err.offset()), (ErrorKind::HeaderValue, Some(2)));
+
+        let err = Error::from(status::StatusCode::from_u16(6666).unwrap_err());
+        assert_eq!((err.kind(), err.offset()), (ErrorKind::StatusCode, None));
+    }
+
     #[test]
     fn inner_error_is_invalid_status_code() {
         if let Err(e) = status::StatusCode::from_u16(6666) {
@@ -1,16 +1,78 @@
-use std::any::{Any, TypeId};
-use std::collections::HashMap;
-use std::fmt;
-use std::hash::{BuildHasherDefault, Hasher};
+//! Protocol extensions attached to requests and responses.
+//!
+//! This module contains the [`Extensions`] type map used by `Request` and
+//! `Response`, along with its iterator and entry types. Typically you'll import the
+//! `httplike::Extensions` type rather than reaching into this module itself.
+//!
+//! It also defines extension types for metadata that is commonly attached to
+//! messages: [`Deadline`], [`Priority`] and [`PeerAddr`], and the details of
+//! the connection a message was received on: [`LocalAddr`], [`Alpn`],
+//! [`ServerName`] and [`ClientCertificate`]. Servers, clients, TLS
+//! terminators and middleware that agree on these types can exchange the
+//! metadata without depending on each other. `Deadline`, `PeerAddr` and
+//! `LocalAddr` need the `std` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use httplike::Request;
+//! use httplike::extensions::{Deadline, Priority};
+//!
+//! let request = Request::builder()
+//!     .extension(Deadline::after(Duration::from_secs(5)))
+//!     .extension(Priority::HIGHEST)
+//!     .body(())
+//!     .unwrap();
+//!
+//! let deadline = request.extensions().get::<Deadline>().unwrap();
+//! assert!(deadline.0 > Instant::now());
+//! ```
+//!
+//! [`Extensions`]: struct.Extensions.html
+//! [`Deadline`]: struct.Deadline.html
+//! [`Priority`]: struct.Priority.html
+//! [`PeerAddr`]: struct.PeerAddr.html
+//! [`LocalAddr`]: struct.LocalAddr.html
+//! [`Alpn`]: struct.Alpn.html
+//! [`ServerName`]: struct.ServerName.html
+//! [`ClientCertificate`]: struct.ClientCertificate.html
 
-type AnyMap = HashMap<TypeId, Box<dyn Any + Send + Sync>, BuildHasherDefault<IdHasher>>;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::collections::btree_map::{self as any_map, BTreeMap};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+use core::fmt;
+#[cfg(feature = "std")]
+use core::hash::{BuildHasherDefault, Hasher};
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::collections::hash_map::{self as any_map, HashMap};
+#[cfg(feature = "std")]
+use std::net::SocketAddr;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "std")]
+type AnyMap = HashMap<TypeId, Box<dyn AnyClone + Send + Sync>, BuildHasherDefault<IdHasher>>;
+
+// Without `std` there is no `HashMap`. A message rarely has more than a
+// few extensions, so a `BTreeMap` does as well.
+#[cfg(not(feature = "std"))]
+type AnyMap = BTreeMap<TypeId, Box<dyn AnyClone + Send + Sync>>;
 
 // With TypeIds as keys, there's no need to hash them. They are already hashes
 // themselves, coming from the compiler. The IdHasher just holds the u64 of
 // the TypeId, and then returns it, instead of doing any bit fiddling.
+#[cfg(feature = "std")]
 #[derive(Default)]
 struct IdHasher(u64);
 
+#[cfg(feature = "std")]
 impl Hasher for IdHasher {
     fn write(&mut self, _: &[u8]) {
         unreachable!("TypeId calls write_u64");
@@ -27,15 +89,77 @@ impl Hasher for IdHasher {
     }
 }
 
+// An `Any` that can be cloned through a trait object, so that the values
+// stored in `Extensions` (and with them whole messages) can be cloned.
+trait AnyClone: Any {
+    fn clone_box(&self) -> Box<dyn AnyClone + Send + Sync>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    fn type_name(&self) -> &'static str;
+}
+
+impl<T: Clone + Send + Sync + 'static> AnyClone for T {
+    fn clone_box(&self) -> Box<dyn AnyClone + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<T>()
+    }
+}
+
+impl Clone for Box<dyn AnyClone + Send + Sync> {
+    fn clone(&self) -> Self {
+        // Deref to the inner value, `Box` itself implements `AnyClone` too.
+        (**self).clone_box()
+    }
+}
+
 /// A type map of protocol extensions.
 ///
 /// `Extensions` can be used by `Request` and `Response` to store
 /// extra data derived from the underlying protocol.
-#[derive(Default)]
+///
+/// Extensions must be `Clone`, so that cloning a `Request` or `Response`
+/// also clones all data attached to it. Cloning is cheap, as the copies
+/// share their values until the first write: a copy that inserts, removes
+/// or mutably borrows an extension clones the values for itself, and from
+/// then on a change to one copy isn't seen by the other. Looking up
+/// extensions with `get` never clones anything.
+///
+/// # Example
+///
+/// ```
+/// # use httplike::Extensions;
+/// let mut ext = Extensions::new();
+/// ext.insert(vec![1u8]);
+///
+/// let mut copy = ext.clone();
+/// copy.get_mut::<Vec<u8>>().unwrap().push(2);
+///
+/// assert_eq!(ext.get::<Vec<u8>>(), Some(&vec![1]));
+/// assert_eq!(copy.get::<Vec<u8>>(), Some(&vec![1, 2]));
+/// ```
+#[derive(Clone, Default)]
 pub struct Extensions {
     // If extensions are never used, no need to carry around an empty HashMap.
     // That's 3 words. Instead, this is only 1 word.
-    map: Option<Box<AnyMap>>,
+    //
+    // The map is shared between clones and copied on the first write.
+    map: Option<Arc<AnyMap>>,
 }
 
 impl Extensions {
@@ -50,6 +174,9 @@ impl Extensions {
     /// If a extension of this type already existed, it will
     /// be returned.
     ///
+    /// The value must be `Clone`, so that the `Extensions`, and the message
+    /// holding it, can be cloned.
+    ///
     /// # Example
     ///
     /// ```
@@ -59,16 +186,10 @@ impl Extensions {
     /// assert!(ext.insert(4u8).is_none());
     /// assert_eq!(ext.insert(9i32), Some(5i32));
     /// ```
-    pub fn insert<T: Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
-        self.map
-            .get_or_insert_with(|| Box::new(HashMap::default()))
+    pub fn insert<T: Clone + Send + Sync + 'static>(&mut self, val: T) -> Option<T> {
+        self.map_mut()
             .insert(TypeId::of::<T>(), Box::new(val))
-            .and_then(|boxed| {
-                (boxed as Box<dyn Any + 'static>)
-                    .downcast()
-                    .ok()
-                    .map(|boxed| *boxed)
-            })
+            .and_then(|boxed| boxed.into_any().downcast().ok().map(|boxed| *boxed))
     }
 
     /// Get a reference to a type previously inserted on this `Extensions`.
@@ -87,7 +208,7 @@ impl Extensions {
         self.map
             .as_ref()
             .and_then(|map| map.get(&TypeId::of::<T>()))
-            .and_then(|boxed| (&**boxed as &(dyn Any + 'static)).downcast_ref())
+            .and_then(|boxed| (**boxed).as_any().downcast_ref())
     }
 
     /// Get a mutable reference to a type previously inserted on this `Extensions`.
@@ -103,10 +224,64 @@ impl Extensions {
     /// assert_eq!(ext.get::<String>().unwrap(), "Hello World");
     /// ```
     pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
-        self.map
-            .as_mut()
+        self.map_mut_if_contains(TypeId::of::<T>())
             .and_then(|map| map.get_mut(&TypeId::of::<T>()))
-            .and_then(|boxed| (&mut **boxed as &mut (dyn Any + 'static)).downcast_mut())
+            .and_then(|boxed| (**boxed).as_any_mut().downcast_mut())
+    }
+
+    /// Get a mutable reference to a type previously inserted on this
+    /// `Extensions`, inserting the result of `f` if it is not present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// let mut ext = Extensions::new();
+    /// *ext.get_or_insert_with(|| 0u32) += 1;
+    /// *ext.get_or_insert_with(|| 0u32) += 1;
+    ///
+    /// assert_eq!(ext.get::<u32>(), Some(&2));
+    /// ```
+    pub fn get_or_insert_with<T, F>(&mut self, f: F) -> &mut T
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> T,
+    {
+        self.entry().or_insert_with(f)
+    }
+
+    /// Gets the entry for the type `T` for in-place manipulation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// # use httplike::extensions::Entry;
+    /// let mut ext = Extensions::new();
+    ///
+    /// ext.entry::<Vec<&str>>().or_default().push("auth");
+    /// ext.entry::<Vec<&str>>().or_default().push("rate-limit");
+    /// assert_eq!(ext.get::<Vec<&str>>().unwrap(), &["auth", "rate-limit"]);
+    ///
+    /// match ext.entry::<u8>() {
+    ///     Entry::Occupied(_) => unreachable!(),
+    ///     Entry::Vacant(e) => {
+    ///         e.insert(1);
+    ///     }
+    /// }
+    /// assert_eq!(ext.get::<u8>(), Some(&1));
+    /// ```
+    pub fn entry<T: Clone + Send + Sync + 'static>(&mut self) -> Entry<'_, T> {
+        match self.map_mut().entry(TypeId::of::<T>()) {
+            any_map::Entry::Occupied(inner) => Entry::Occupied(OccupiedEntry {
+                inner,
+                _marker: PhantomData,
+            }),
+            any_map::Entry::Vacant(inner) => Entry::Vacant(VacantEntry {
+                inner,
+                _marker: PhantomData,
+            }),
+        }
     }
 
     /// Remove a type from this `Extensions`.
@@ -123,15 +298,67 @@ impl Extensions {
     /// assert!(ext.get::<i32>().is_none());
     /// ```
     pub fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
-        self.map
-            .as_mut()
+        self.map_mut_if_contains(TypeId::of::<T>())
             .and_then(|map| map.remove(&TypeId::of::<T>()))
-            .and_then(|boxed| {
-                (boxed as Box<dyn Any + 'static>)
-                    .downcast()
-                    .ok()
-                    .map(|boxed| *boxed)
-            })
+            .and_then(|boxed| boxed.into_any().downcast().ok().map(|boxed| *boxed))
+    }
+
+    /// Remove the extension with the given `TypeId`, without knowing its
+    /// type.
+    ///
+    /// Returns true if an extension was removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// # use std::any::TypeId;
+    /// let mut ext = Extensions::new();
+    /// ext.insert(5i32);
+    ///
+    /// assert!(ext.remove_by_type_id(TypeId::of::<i32>()));
+    /// assert!(!ext.remove_by_type_id(TypeId::of::<i32>()));
+    /// assert!(ext.get::<i32>().is_none());
+    /// ```
+    pub fn remove_by_type_id(&mut self, id: TypeId) -> bool {
+        match self.map_mut_if_contains(id) {
+            Some(map) => map.remove(&id).is_some(),
+            None => false,
+        }
+    }
+
+    /// Retain only the extensions whose `TypeId` satisfies the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// # use std::any::TypeId;
+    /// #[derive(Clone)]
+    /// struct InternalToken(u64);
+    ///
+    /// let mut ext = Extensions::new();
+    /// ext.insert(5i32);
+    /// ext.insert(InternalToken(42));
+    ///
+    /// ext.retain(|id| id != TypeId::of::<InternalToken>());
+    /// assert_eq!(ext.len(), 1);
+    /// assert!(ext.get::<InternalToken>().is_none());
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(TypeId) -> bool,
+    {
+        if let Some(ref mut map) = self.map {
+            // Only copy a shared map if something is removed.
+            let removed = map.keys().cloned().filter(|id| !f(*id)).collect::<Vec<_>>();
+            if !removed.is_empty() {
+                let map = Arc::make_mut(map);
+                for id in removed {
+                    map.remove(&id);
+                }
+            }
+        }
     }
 
     /// Clear the `Extensions` of all inserted extensions.
@@ -149,20 +376,547 @@ impl Extensions {
     #[inline]
     pub fn clear(&mut self) {
         if let Some(ref mut map) = self.map {
-            map.clear();
+            if let Some(map) = Arc::get_mut(map) {
+                map.clear();
+                return;
+            }
+        }
+        // Don't copy a shared map just to clear it.
+        self.map = None;
+    }
+
+    /// Moves all extensions of `other` into this `Extensions`.
+    ///
+    /// Extensions of a type already present are replaced by the ones from
+    /// `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// let mut ext_a = Extensions::new();
+    /// ext_a.insert(8u8);
+    /// ext_a.insert(16u16);
+    ///
+    /// let mut ext_b = Extensions::new();
+    /// ext_b.insert(4u8);
+    /// ext_b.insert("hello");
+    ///
+    /// ext_a.extend(ext_b);
+    /// assert_eq!(ext_a.len(), 3);
+    /// assert_eq!(ext_a.get::<u8>(), Some(&4u8));
+    /// assert_eq!(ext_a.get::<u16>(), Some(&16u16));
+    /// assert_eq!(ext_a.get::<&'static str>().copied(), Some("hello"));
+    /// ```
+    pub fn extend(&mut self, other: Extensions) {
+        if let Some(other) = other.map {
+            match self.map {
+                Some(ref mut map) => {
+                    let other = Arc::try_unwrap(other).unwrap_or_else(|shared| (*shared).clone());
+                    Arc::make_mut(map).extend(other)
+                }
+                None => self.map = Some(other),
+            }
+        }
+    }
+
+    /// Returns the number of extensions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// let mut ext = Extensions::new();
+    /// assert_eq!(ext.len(), 0);
+    ///
+    /// ext.insert(5i32);
+    /// ext.insert(5i32);
+    /// assert_eq!(ext.len(), 1);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.as_ref().map_or(0, |map| map.len())
+    }
+
+    /// Returns true if there are no extensions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// let mut ext = Extensions::new();
+    /// assert!(ext.is_empty());
+    ///
+    /// ext.insert(5i32);
+    /// assert!(!ext.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the extensions, yielding the `TypeId` of
+    /// each extension along with a reference to it.
+    ///
+    /// The iteration order is unspecified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::Extensions;
+    /// # use std::any::TypeId;
+    /// let mut ext = Extensions::new();
+    /// ext.insert(5i32);
+    ///
+    /// for (id, value) in ext.iter() {
+    ///     assert_eq!(id, TypeId::of::<i32>());
+    ///     assert_eq!(value.downcast_ref::<i32>(), Some(&5));
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.map.as_ref().map(|map| map.iter()),
+        }
+    }
+
+    // Returns the map for writing, creating it if needed, and copying it if
+    // it is shared with a clone.
+    fn map_mut(&mut self) -> &mut AnyMap {
+        Arc::make_mut(self.map.get_or_insert_with(|| Arc::new(AnyMap::default())))
+    }
+
+    // Returns the map for writing only if it holds `id`, so that a shared
+    // map is not copied when the write would be a no-op.
+    fn map_mut_if_contains(&mut self, id: TypeId) -> Option<&mut AnyMap> {
+        match self.map {
+            Some(ref mut map) if map.contains_key(&id) => Some(Arc::make_mut(map)),
+            _ => None,
         }
     }
 }
 
 impl fmt::Debug for Extensions {
+    /// Lists the type names of the contained extensions, sorted so the
+    /// output does not depend on the iteration order.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Extensions").finish()
+        let mut names = self
+            .map
+            .iter()
+            .flat_map(|map| map.values())
+            .map(|boxed| (**boxed).type_name())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+
+        f.debug_struct("Extensions").field("types", &names).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Extensions {
+    type Item = (TypeId, &'a dyn Any);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
     }
 }
 
+/// An iterator over the extensions in an `Extensions`.
+///
+/// This struct is created by the [`iter`] method on [`Extensions`].
+///
+/// [`iter`]: struct.Extensions.html#method.iter
+/// [`Extensions`]: struct.Extensions.html
+pub struct Iter<'a> {
+    inner: Option<any_map::Iter<'a, TypeId, Box<dyn AnyClone + Send + Sync>>>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (TypeId, &'a dyn Any);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .as_mut()
+            .and_then(|iter| iter.next())
+            .map(|(id, boxed)| (*id, (**boxed).as_any()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), |iter| iter.size_hint())
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+impl<'a> fmt::Debug for Iter<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").finish()
+    }
+}
+
+/// A view into a single extension type in an `Extensions`, which may be
+/// either vacant or occupied.
+///
+/// This enum is constructed from the [`entry`] method on [`Extensions`].
+///
+/// [`entry`]: struct.Extensions.html#method.entry
+/// [`Extensions`]: struct.Extensions.html
+pub enum Entry<'a, T> {
+    /// An occupied entry
+    Occupied(OccupiedEntry<'a, T>),
+
+    /// A vacant entry
+    Vacant(VacantEntry<'a, T>),
+}
+
+/// A view into an occupied entry in an `Extensions`.
+///
+/// It is part of the [`Entry`] enum.
+///
+/// [`Entry`]: enum.Entry.html
+pub struct OccupiedEntry<'a, T> {
+    inner: any_map::OccupiedEntry<'a, TypeId, Box<dyn AnyClone + Send + Sync>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// A view into a vacant entry in an `Extensions`.
+///
+/// It is part of the [`Entry`] enum.
+///
+/// [`Entry`]: enum.Entry.html
+pub struct VacantEntry<'a, T> {
+    inner: any_map::VacantEntry<'a, TypeId, Box<dyn AnyClone + Send + Sync>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> Entry<'a, T> {
+    /// Ensures a value is in the entry by inserting `default` if empty, and
+    /// returns a mutable reference to the value in the entry.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default`
+    /// if empty, and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts.
+    pub fn and_modify<F: FnOnce(&mut T)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut e) => {
+                f(e.get_mut());
+                Entry::Occupied(e)
+            }
+            Entry::Vacant(e) => Entry::Vacant(e),
+        }
+    }
+}
+
+impl<'a, T: Clone + Default + Send + Sync + 'static> Entry<'a, T> {
+    /// Ensures a value is in the entry by inserting `T::default()` if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut T {
+        self.or_insert_with(T::default)
+    }
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> OccupiedEntry<'a, T> {
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &T {
+        downcast_ref(&**self.inner.get())
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut T {
+        downcast_mut(&mut **self.inner.get_mut())
+    }
+
+    /// Converts the entry into a mutable reference to its value, bound to
+    /// the lifetime of the `Extensions`.
+    pub fn into_mut(self) -> &'a mut T {
+        downcast_mut(&mut **self.inner.into_mut())
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    pub fn insert(&mut self, value: T) -> T {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Takes the value out of the entry, and returns it.
+    pub fn remove(self) -> T {
+        *self
+            .inner
+            .remove()
+            .into_any()
+            .downcast()
+            .expect("entry holds a value of its type")
+    }
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> VacantEntry<'a, T> {
+    /// Sets the value of the entry, and returns a mutable reference to it.
+    pub fn insert(self, value: T) -> &'a mut T {
+        downcast_mut(&mut **self.inner.insert(Box::new(value)))
+    }
+}
+
+impl<'a, T: fmt::Debug + 'static> fmt::Debug for Entry<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Entry::Occupied(ref e) => f.debug_tuple("Entry").field(e).finish(),
+            Entry::Vacant(ref e) => f.debug_tuple("Entry").field(e).finish(),
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug + 'static> fmt::Debug for OccupiedEntry<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = (**self.inner.get()).as_any().downcast_ref::<T>();
+        f.debug_struct("OccupiedEntry").field("value", &value).finish()
+    }
+}
+
+impl<'a, T> fmt::Debug for VacantEntry<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantEntry").finish()
+    }
+}
+
+/// The point in time by which a message should be fully handled.
+///
+/// A client sets it on a request to bound how long it is willing to wait,
+/// and a server may set it from a timeout of its own. Anything handling the
+/// message should give up once the deadline has passed, and forward the
+/// earlier of its own deadline and this one.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(pub Instant);
+
+#[cfg(feature = "std")]
+impl Deadline {
+    /// Returns a deadline `timeout` from now.
+    pub fn after(timeout: Duration) -> Deadline {
+        Deadline(Instant::now() + timeout)
+    }
+
+    /// Returns the time left until the deadline, or zero if it has passed.
+    pub fn remaining(&self) -> Duration {
+        let now = Instant::now();
+        if self.0 > now {
+            self.0 - now
+        } else {
+            Duration::from_secs(0)
+        }
+    }
+
+    /// Returns true if the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.0
+    }
+}
+
+/// The urgency of a message, relative to other messages on the same
+/// connection or in the same queue.
+///
+/// Lower values are more urgent, like the urgency of the HTTP `Priority`
+/// header (RFC 9218): `0` is the most urgent, and a message without a
+/// `Priority` should be treated as `Priority::DEFAULT`. Values above `7` are
+/// allowed and are less urgent than `7`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Priority(pub u8);
+
+impl Priority {
+    /// The most urgent priority, `0`.
+    pub const HIGHEST: Priority = Priority(0);
+
+    /// The priority of messages without a `Priority`, `3`.
+    pub const DEFAULT: Priority = Priority(3);
+
+    /// The least urgent priority of the RFC 9218 range, `7`.
+    pub const LOWEST: Priority = Priority(7);
+
+    /// Returns true if `self` is more urgent than `other`.
+    pub fn is_more_urgent_than(&self, other: Priority) -> bool {
+        self.0 < other.0
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Priority {
+        Priority::DEFAULT
+    }
+}
+
+/// The address of the peer a message was received from.
+///
+/// This is the address of the other end of the connection, set by the
+/// server or client that read the message. It is not taken from headers like
+/// `Forwarded` or `X-Forwarded-For`, so behind a proxy it is the address of
+/// the proxy.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PeerAddr(pub SocketAddr);
+
+/// The local address of the connection a message was received on.
+///
+/// Servers listening on several addresses set it so handlers can tell
+/// which one a request came in through.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LocalAddr(pub SocketAddr);
+
+/// The application protocol negotiated with ALPN in the TLS handshake of
+/// the connection a message was received on.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::Version;
+/// # use httplike::extensions::Alpn;
+/// let alpn = Alpn::from_bytes(b"h2");
+/// assert_eq!(alpn, Alpn::H2);
+/// assert_eq!(alpn.version(), Some(Version::HTTP_2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Alpn(Cow<'static, [u8]>);
+
+impl Alpn {
+    /// HTTP/1.1, `http/1.1`.
+    pub const HTTP_11: Alpn = Alpn(Cow::Borrowed(b"http/1.1"));
+
+    /// HTTP/2, `h2`.
+    pub const H2: Alpn = Alpn(Cow::Borrowed(b"h2"));
+
+    /// HTTP/3, `h3`.
+    pub const H3: Alpn = Alpn(Cow::Borrowed(b"h3"));
+
+    /// Creates an `Alpn` from a protocol identifier with a static lifetime.
+    pub fn from_static(id: &'static [u8]) -> Alpn {
+        Alpn(Cow::Borrowed(id))
+    }
+
+    /// Creates an `Alpn` by copying a protocol identifier.
+    pub fn from_bytes(id: &[u8]) -> Alpn {
+        Alpn(Cow::Owned(id.to_vec()))
+    }
+
+    /// Returns the protocol identifier.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the HTTP version the protocol identifies, if any.
+    #[cfg(feature = "http")]
+    pub fn version(&self) -> Option<crate::Version> {
+        use crate::Version;
+
+        match self.as_bytes() {
+            b"http/1.0" => Some(Version::HTTP_10),
+            b"http/1.1" => Some(Version::HTTP_11),
+            b"h2" => Some(Version::HTTP_2),
+            b"h3" => Some(Version::HTTP_3),
+            _ => None,
+        }
+    }
+}
+
+/// The server name a client asked for with SNI in the TLS handshake of the
+/// connection a message was received on.
+///
+/// The name is a DNS host name, without a port or a trailing dot. Unlike
+/// the `Host` header, it is checked by the TLS terminator against the
+/// certificate it presented, so servers can compare the two to reject
+/// requests for other hosts sent over a shared connection.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ServerName(pub String);
+
+/// A summary of the certificate a client authenticated with in the TLS
+/// handshake of the connection a message was received on.
+///
+/// TLS terminators set it once they have verified the certificate, so
+/// applications can authorize clients without parsing certificates.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::extensions::ClientCertificate;
+/// let cert = ClientCertificate::new("CN=client", "CN=Example CA", [0xab; 32]);
+/// assert_eq!(cert.subject(), "CN=client");
+/// assert!(cert.fingerprint_hex().starts_with("abab"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClientCertificate {
+    subject: String,
+    issuer: String,
+    fingerprint: [u8; 32],
+}
+
+impl ClientCertificate {
+    /// Creates a summary from the distinguished names of the subject and
+    /// the issuer, in the string form of RFC 4514, and the SHA-256
+    /// fingerprint of the DER encoded certificate.
+    pub fn new<S, I>(subject: S, issuer: I, fingerprint: [u8; 32]) -> ClientCertificate
+    where
+        S: Into<String>,
+        I: Into<String>,
+    {
+        ClientCertificate {
+            subject: subject.into(),
+            issuer: issuer.into(),
+            fingerprint,
+        }
+    }
+
+    /// Returns the distinguished name of the subject.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the distinguished name of the issuer.
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Returns the SHA-256 fingerprint of the certificate.
+    pub fn fingerprint(&self) -> &[u8; 32] {
+        &self.fingerprint
+    }
+
+    /// Returns the fingerprint as 64 lowercase hex digits.
+    pub fn fingerprint_hex(&self) -> String {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let mut hex = String::with_capacity(64);
+        for &b in &self.fingerprint {
+            hex.push(HEX[(b >> 4) as usize] as char);
+            hex.push(HEX[(b & 0xf) as usize] as char);
+        }
+        hex
+    }
+}
+
+// Entries are keyed by `TypeId::of::<T>()`, so their value is always a `T`.
+fn downcast_ref<T: 'static>(boxed: &(dyn AnyClone + Send + Sync)) -> &T {
+    boxed.as_any().downcast_ref().expect("entry holds a value of its type")
+}
+
+fn downcast_mut<T: 'static>(boxed: &mut (dyn AnyClone + Send + Sync)) -> &mut T {
+    boxed
+        .as_any_mut()
+        .downcast_mut()
+        .expect("entry holds a value of its type")
+}
+
 #[test]
 fn test_extensions() {
-    #[derive(Debug, PartialEq)]
+    #[derive(Clone, Debug, PartialEq)]
     struct MyType(i32);
 
     let mut extensions = Extensions::new();
@@ -179,3 +933,112 @@ fn test_extensions() {
     assert_eq!(extensions.get::<bool>(), None);
     assert_eq!(extensions.get(), Some(&MyType(10)));
 }
+
+#[test]
+fn test_extensions_clone() {
+    let mut extensions = Extensions::new();
+    extensions.insert(String::from("hello"));
+
+    let mut cloned = extensions.clone();
+    cloned.get_mut::<String>().unwrap().push_str(" world");
+
+    assert_eq!(extensions.get::<String>().unwrap(), "hello");
+    assert_eq!(cloned.get::<String>().unwrap(), "hello world");
+}
+
+#[test]
+fn test_extensions_debug_lists_types() {
+    let mut extensions = Extensions::new();
+    assert_eq!(format!("{:?}", extensions), "Extensions { types: [] }");
+
+    extensions.insert(5u8);
+    extensions.insert(true);
+    assert_eq!(format!("{:?}", extensions), r#"Extensions { types: ["bool", "u8"] }"#);
+}
+
+#[test]
+fn test_extensions_entry() {
+    let mut extensions = Extensions::new();
+
+    extensions.entry::<i32>().and_modify(|v| *v += 1).or_insert(1);
+    extensions.entry::<i32>().and_modify(|v| *v += 1).or_insert(1);
+    assert_eq!(extensions.get::<i32>(), Some(&2));
+
+    match extensions.entry::<i32>() {
+        Entry::Occupied(mut e) => {
+            assert_eq!(e.insert(10), 2);
+            assert_eq!(e.remove(), 10);
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert!(extensions.is_empty());
+}
+
+#[test]
+fn test_extensions_clone_on_write() {
+    let mut extensions = Extensions::new();
+    extensions.insert(5i32);
+
+    let mut cloned = extensions.clone();
+    let shared = |a: &Extensions, b: &Extensions| match (&a.map, &b.map) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        _ => false,
+    };
+    assert!(shared(&extensions, &cloned));
+
+    // Misses don't copy the map.
+    assert!(cloned.get_mut::<u8>().is_none());
+    assert!(cloned.remove::<u8>().is_none());
+    cloned.retain(|_| true);
+    assert!(shared(&extensions, &cloned));
+
+    *cloned.get_mut::<i32>().unwrap() = 6;
+    assert!(!shared(&extensions, &cloned));
+    assert_eq!(extensions.get::<i32>(), Some(&5));
+    assert_eq!(cloned.get::<i32>(), Some(&6));
+
+    let mut other = extensions.clone();
+    other.clear();
+    assert!(other.is_empty());
+    assert_eq!(extensions.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_standard_extension_types() {
+    let deadline = Deadline::after(Duration::from_secs(60));
+    assert!(!deadline.is_expired());
+    assert!(deadline.remaining() > Duration::from_secs(0));
+
+    let past = Deadline(Instant::now() - Duration::from_millis(1));
+    assert!(past.is_expired());
+    assert_eq!(past.remaining(), Duration::from_secs(0));
+    assert!(past < deadline);
+
+    assert_eq!(Priority::default(), Priority(3));
+    assert!(Priority::HIGHEST.is_more_urgent_than(Priority::DEFAULT));
+    assert!(!Priority::LOWEST.is_more_urgent_than(Priority::LOWEST));
+
+    let mut extensions = Extensions::new();
+    extensions.insert(PeerAddr(([127, 0, 0, 1], 8080).into()));
+    assert_eq!(extensions.get::<PeerAddr>().unwrap().0.port(), 8080);
+}
+
+#[test]
+fn test_connection_extension_types() {
+    assert_eq!(Alpn::from_static(b"http/1.1"), Alpn::HTTP_11);
+    assert_eq!(Alpn::from_bytes(b"h3").as_bytes(), b"h3");
+    #[cfg(feature = "http")]
+    assert_eq!(Alpn::from_static(b"rtsp").version(), None);
+
+    let cert = ClientCertificate::new("CN=a", String::from("CN=b"), [0x0f; 32]);
+    assert_eq!(cert.issuer(), "CN=b");
+    assert_eq!(cert.fingerprint_hex(), "0f".repeat(32));
+
+    let mut extensions = Extensions::new();
+    extensions.insert(Alpn::H2);
+    extensions.insert(ServerName("example.com".into()));
+    extensions.insert(cert.clone());
+    assert_eq!(extensions.get::<ClientCertificate>(), Some(&cert));
+    assert_eq!(extensions.get::<ServerName>().unwrap().0, "example.com");
+}
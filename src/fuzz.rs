@@ -0,0 +1,402 @@
+//! Generators and invariant checks for fuzzing, enabled with the `fuzz`
+//! feature.
+//!
+//! A fuzz target gets arbitrary bytes. Those can be fed to the checks as is,
+//! but most random bytes are rejected on the first line, so the `Generator`
+//! turns them into message heads instead: `request_head` and
+//! `response_head` build heads that are valid, and `near_request_head` and
+//! `near_response_head` make one small change to such a head, the kind of
+//! change that finds the edge cases of a parser.
+//!
+//! The checks decode a head with `codec`, encode it, and decode it again.
+//! They panic if the second decoding fails or differs from the first one,
+//! or if encoding the second decoding doesn't give the same bytes, so a fuzzer
+//! reports it. Input that is rejected or incomplete is fine.
+//!
+//! # Examples
+//!
+//! A `cargo fuzz` target checking both generated heads and raw input:
+//!
+//! ```
+//! use httplike::fuzz::{self, Generator};
+//!
+//! fn fuzz_target(data: &[u8]) {
+//!     fuzz::check_request_head(data);
+//!
+//!     let mut gen = Generator::new(data);
+//!     fuzz::check_request_head(&gen.request_head());
+//!     fuzz::check_response_head(&gen.near_response_head());
+//! }
+//!
+//! fuzz_target(b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n");
+//! fuzz_target(b"\x03\x7f\x10\x00\xaa");
+//! ```
+
+use alloc::vec::Vec;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::codec::{self, has_text_head, Limits};
+use crate::{Request, Response, StatusCode, Version};
+
+/// Turns arbitrary bytes into message heads.
+///
+/// Every choice the generator makes takes the next byte of the data, and
+/// zero once the data is used up, so the same data always gives the same
+/// heads and short data gives small heads.
+#[derive(Debug)]
+pub struct Generator<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+const METHODS: &[&str] = &[
+    "GET", "POST", "HEAD", "OPTIONS", "CONNECT", "DESCRIBE", "SETUP", "INVITE", "REGISTER",
+    "X-Custom",
+];
+
+const TARGETS: &[&str] = &[
+    "/",
+    "*",
+    "/index.html",
+    "/a/b;c?d=e&f",
+    "/%7Euser/",
+    "example.com:443",
+    "http://example.com",
+    "https://user@EXAMPLE.com:8443/p?q#f",
+    "rtsp://example.com/stream/trackID=1",
+];
+
+const NAMES: &[&str] = &[
+    "Host",
+    "content-length",
+    "Content-Type",
+    "x-dup",
+    "X-DUP",
+    "CSeq",
+    "Via",
+    "v",
+    "Contact",
+    "m",
+    "Transfer-Encoding",
+];
+
+const VALUES: &[&str] = &[
+    "",
+    "5",
+    "example.com",
+    "text/plain; charset=utf-8",
+    "SIP/2.0/UDP a.example.com;branch=z9hG4bK1, SIP/2.0/UDP b.example.com",
+    "\"Bob, Jr.\" <sip:bob@example.com>, <sip:carol@example.com;x=a,b>",
+    "a,  b ,,c",
+    "chunked",
+    "\u{e9}t\u{e9}",
+];
+
+const REASONS: &[&str] = &["", "OK", "Not Found", "Trying", "Tout Va Bien"];
+
+// Bytes that separate the parts of a head, to put where they don't belong.
+const DELIMITERS: &[u8] = &[b' ', b'\t', b':', b'\r', b'\n', b',', b'"', 0, 0x7f, 0xff];
+
+impl<'a> Generator<'a> {
+    /// Creates a generator making its choices from `data`.
+    pub fn new(data: &'a [u8]) -> Generator<'a> {
+        Generator { data, pos: 0 }
+    }
+
+    /// Returns true once all of the data has been used.
+    pub fn is_exhausted(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Generates a valid request head.
+    ///
+    /// The method, target and version are separated by single spaces, and
+    /// the headers use a mix of name cases, separators and line endings.
+    pub fn request_head(&mut self) -> Vec<u8> {
+        let mut head = Vec::new();
+        head.extend_from_slice(self.pick(METHODS).as_bytes());
+        head.push(b' ');
+        head.extend_from_slice(self.pick(TARGETS).as_bytes());
+        head.push(b' ');
+        head.extend_from_slice(self.version().as_str().as_bytes());
+        self.line_end(&mut head);
+        self.headers(&mut head);
+        head
+    }
+
+    /// Generates a valid response head.
+    ///
+    /// The status code is any valid status code, with a reason phrase that
+    /// may or may not be its canonical reason.
+    pub fn response_head(&mut self) -> Vec<u8> {
+        let mut head = Vec::new();
+        head.extend_from_slice(self.version().as_str().as_bytes());
+        head.push(b' ');
+        let status = 100 + (u16::from(self.byte()) << 8 | u16::from(self.byte())) % 500;
+        let status = StatusCode::from_u16(status).expect("status code in range");
+        head.extend_from_slice(status.as_str().as_bytes());
+        head.push(b' ');
+        head.extend_from_slice(self.pick(REASONS).as_bytes());
+        self.line_end(&mut head);
+        self.headers(&mut head);
+        head
+    }
+
+    /// Generates a request head with one byte removed, repeated, replaced
+    /// or inserted.
+    pub fn near_request_head(&mut self) -> Vec<u8> {
+        let head = self.request_head();
+        self.mutate(head)
+    }
+
+    /// Generates a response head with one byte removed, repeated, replaced
+    /// or inserted.
+    pub fn near_response_head(&mut self) -> Vec<u8> {
+        let head = self.response_head();
+        self.mutate(head)
+    }
+
+    fn byte(&mut self) -> u8 {
+        let b = self.data.get(self.pos).cloned().unwrap_or(0);
+        self.pos += 1;
+        b
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[usize::from(self.byte()) % items.len()]
+    }
+
+    fn version(&mut self) -> Version {
+        let versions = Version::ALL
+            .iter()
+            .cloned()
+            .filter(|&version| has_text_head(version, true))
+            .collect::<Vec<_>>();
+        self.pick(&versions)
+    }
+
+    fn line_end(&mut self, head: &mut Vec<u8>) {
+        if self.byte() & 1 == 0 {
+            head.extend_from_slice(b"\r\n");
+        } else {
+            head.push(b'\n');
+        }
+    }
+
+    fn headers(&mut self, head: &mut Vec<u8>) {
+        for _ in 0..self.byte() % 8 {
+            head.extend_from_slice(self.pick(NAMES).as_bytes());
+            head.extend_from_slice(self.pick(&[":", ": ", ":\t", ":  "]).as_bytes());
+            head.extend_from_slice(self.pick(VALUES).as_bytes());
+            if self.byte() & 1 == 0 {
+                head.push(b' ');
+            }
+            self.line_end(head);
+        }
+        self.line_end(head);
+    }
+
+    fn mutate(&mut self, mut head: Vec<u8>) -> Vec<u8> {
+        let i = (usize::from(self.byte()) << 8 | usize::from(self.byte())) % head.len();
+        match self.byte() % 4 {
+            0 => {
+                head.remove(i);
+            }
+            1 => {
+                let b = head[i];
+                head.insert(i, b);
+            }
+            2 => head[i] = self.pick(DELIMITERS),
+            _ => {
+                let b = self.pick(DELIMITERS);
+                head.insert(i, b);
+            }
+        }
+        head
+    }
+}
+
+/// Checks that a request head read from `input` reads the same once encoded.
+///
+/// The head is decoded with the default `Limits`. The encoded head is
+/// decoded without limits, as encoding can make it longer.
+///
+/// # Panics
+///
+/// Panics if the encoded head can't be decoded, if it decodes to a
+/// different request, or if it encodes differently the second time.
+pub fn check_request_head(input: &[u8]) {
+    let mut src = BytesMut::from(input);
+    let first = match codec::decode_request_head(&mut src) {
+        Ok(Some(request)) => request,
+        _ => return,
+    };
+
+    let encoded = encode_request(&first);
+    let mut src = BytesMut::from(&encoded[..]);
+    let second = match codec::decode_request_head_with(&mut src, &unlimited()) {
+        Ok(Some(request)) => request,
+        other => panic!(
+            "encoded request head {:?} of input {:?} reads as {:?}",
+            encoded,
+            Bytes::copy_from_slice(input),
+            other
+        ),
+    };
+    assert!(
+        src.is_empty(),
+        "encoded request head {:?} read partially",
+        encoded
+    );
+    assert_eq!(
+        encode_request(&second),
+        encoded,
+        "request head of input {:?} encodes differently after a round trip",
+        Bytes::copy_from_slice(input)
+    );
+
+    let (first, ()) = first.into_parts();
+    let (second, ()) = second.into_parts();
+    assert!(
+        first.semantic_eq(&second),
+        "request head of input {:?} changes after a round trip: {:?} and {:?}",
+        Bytes::copy_from_slice(input),
+        first,
+        second
+    );
+}
+
+/// Checks that a response head read from `input` reads the same once
+/// encoded.
+///
+/// This works like `check_request_head`.
+///
+/// # Panics
+///
+/// Panics if the encoded head can't be decoded, if it decodes to a
+/// different response, or if it encodes differently the second time.
+pub fn check_response_head(input: &[u8]) {
+    let mut src = BytesMut::from(input);
+    let first = match codec::decode_response_head(&mut src) {
+        Ok(Some(response)) => response,
+        _ => return,
+    };
+
+    let encoded = encode_response(&first);
+    let mut src = BytesMut::from(&encoded[..]);
+    let second = match codec::decode_response_head_with(&mut src, &unlimited()) {
+        Ok(Some(response)) => response,
+        other => panic!(
+            "encoded response head {:?} of input {:?} reads as {:?}",
+            encoded,
+            Bytes::copy_from_slice(input),
+            other
+        ),
+    };
+    assert!(
+        src.is_empty(),
+        "encoded response head {:?} read partially",
+        encoded
+    );
+    assert_eq!(
+        encode_response(&second),
+        encoded,
+        "response head of input {:?} encodes differently after a round trip",
+        Bytes::copy_from_slice(input)
+    );
+
+    let (first, ()) = first.into_parts();
+    let (second, ()) = second.into_parts();
+    assert!(
+        first.semantic_eq(&second),
+        "response head of input {:?} changes after a round trip: {:?} and {:?}",
+        Bytes::copy_from_slice(input),
+        first,
+        second
+    );
+}
+
+fn encode_request(request: &Request<()>) -> Bytes {
+    let mut dst = BytesMut::new();
+    codec::encode_request_head(request, &mut dst).expect("decoded request head can't be encoded");
+    dst.freeze()
+}
+
+fn encode_response(response: &Response<()>) -> Bytes {
+    let mut dst = BytesMut::new();
+    codec::encode_response_head(response, &mut dst)
+        .expect("decoded response head can't be encoded");
+    dst.freeze()
+}
+
+fn unlimited() -> Limits {
+    let mut limits = Limits::new();
+    limits.set_max_head_len(::core::usize::MAX);
+    limits.set_max_headers(::core::usize::MAX);
+    limits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic pseudo-random data, so failures can be replayed.
+    fn data(seed: u32) -> Vec<u8> {
+        let mut x = seed.wrapping_mul(2_654_435_761) | 1;
+        (0..64)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                x as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generated_heads_are_valid() {
+        for seed in 0..2000 {
+            let data = data(seed);
+
+            let head = Generator::new(&data).request_head();
+            let mut src = BytesMut::from(&head[..]);
+            match codec::decode_request_head(&mut src) {
+                Ok(Some(_)) => {}
+                other => panic!("{:?} reads as {:?}", Bytes::from(head), other),
+            }
+
+            let head = Generator::new(&data).response_head();
+            let mut src = BytesMut::from(&head[..]);
+            match codec::decode_response_head(&mut src) {
+                Ok(Some(_)) => {}
+                other => panic!("{:?} reads as {:?}", Bytes::from(head), other),
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        for seed in 0..2000 {
+            let data = data(seed);
+            check_request_head(&data);
+            check_response_head(&data);
+
+            let mut gen = Generator::new(&data);
+            check_request_head(&gen.request_head());
+            check_response_head(&gen.response_head());
+            check_request_head(&gen.near_request_head());
+            check_response_head(&gen.near_response_head());
+        }
+    }
+
+    #[test]
+    fn empty_data() {
+        let mut gen = Generator::new(b"");
+        assert!(gen.is_exhausted());
+        #[cfg(feature = "http")]
+        assert_eq!(&gen.request_head()[..], &b"GET / HTTP/1.0\r\n\r\n"[..]);
+        check_request_head(b"");
+        check_response_head(b"");
+    }
+}
@@ -0,0 +1,252 @@
+use alloc::vec::Vec;
+
+use super::{HeaderMap, HeaderName, HeaderValue};
+
+/// The difference between two `HeaderMap`s.
+///
+/// Returned by [`HeaderMap::diff`]. A header name is considered *changed*
+/// when both maps contain it but the sequence of values associated with it
+/// differs, either in content or in order.
+///
+/// [`HeaderMap::diff`]: struct.HeaderMap.html#method.diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff<T = HeaderValue> {
+    added: HeaderMap<T>,
+    removed: Vec<HeaderName>,
+    changed: HeaderMap<T>,
+}
+
+impl<T> HeaderMap<T> {
+    /// Computes the difference needed to turn `self` into `other`.
+    ///
+    /// Applying the returned `Diff` to a copy of `self` yields a map equal to
+    /// `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::{ACCEPT, ETAG, HOST};
+    /// let mut stored = HeaderMap::new();
+    /// stored.insert(HOST, "example.com".parse().unwrap());
+    /// stored.insert(ETAG, "\"v1\"".parse().unwrap());
+    ///
+    /// let mut fresh = HeaderMap::new();
+    /// fresh.insert(ETAG, "\"v2\"".parse().unwrap());
+    /// fresh.insert(ACCEPT, "*/*".parse().unwrap());
+    ///
+    /// let diff = stored.diff(&fresh);
+    /// assert!(diff.added().contains_key(ACCEPT));
+    /// assert_eq!(diff.removed(), &[HOST]);
+    /// assert_eq!(diff.changed()[ETAG], "\"v2\"");
+    ///
+    /// diff.apply(&mut stored);
+    /// assert_eq!(stored, fresh);
+    /// ```
+    pub fn diff(&self, other: &HeaderMap<T>) -> Diff<T>
+    where
+        T: Clone + PartialEq,
+    {
+        let mut diff = Diff {
+            added: HeaderMap::with_capacity(0),
+            removed: Vec::new(),
+            changed: HeaderMap::with_capacity(0),
+        };
+
+        for name in self.keys() {
+            let theirs = other.get_all(name);
+
+            if theirs.iter().next().is_none() {
+                diff.removed.push(name.clone());
+            } else if !self.get_all(name).iter().eq(theirs.iter()) {
+                for value in theirs {
+                    diff.changed.append(name.clone(), value.clone());
+                }
+            }
+        }
+
+        for (name, value) in other.iter() {
+            if !self.contains_key(name) {
+                diff.added.append(name.clone(), value.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+impl<T> Diff<T> {
+    /// Returns the headers that are only present in the target map.
+    pub fn added(&self) -> &HeaderMap<T> {
+        &self.added
+    }
+
+    /// Returns the names of the headers that are only present in the source
+    /// map.
+    pub fn removed(&self) -> &[HeaderName] {
+        &self.removed
+    }
+
+    /// Returns the headers present in both maps whose values differ, along
+    /// with the values found in the target map.
+    pub fn changed(&self) -> &HeaderMap<T> {
+        &self.changed
+    }
+
+    /// Returns true if both maps were equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::HOST;
+    /// let mut map = HeaderMap::new();
+    /// map.insert(HOST, "example.com".parse().unwrap());
+    ///
+    /// assert!(map.diff(&map.clone()).is_empty());
+    /// assert!(!map.diff(&HeaderMap::new()).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Applies the difference to `map`.
+    ///
+    /// Removed headers are dropped from `map`, while the values of added and
+    /// changed headers replace any values `map` currently holds for them.
+    /// Headers not mentioned by the diff are left untouched, which allows a
+    /// diff computed against one map to be merged into another.
+    ///
+    /// To merge the headers of a `304 Not Modified` response, which leaves
+    /// out the headers that didn't change, use
+    /// [`apply_updates`](#method.apply_updates) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::{CACHE_CONTROL, CONTENT_TYPE};
+    /// let mut before = HeaderMap::new();
+    /// before.insert(CACHE_CONTROL, "max-age=60".parse().unwrap());
+    ///
+    /// let mut after = before.clone();
+    /// after.insert(CACHE_CONTROL, "max-age=120".parse().unwrap());
+    ///
+    /// let mut other = HeaderMap::new();
+    /// other.insert(CONTENT_TYPE, "text/html".parse().unwrap());
+    ///
+    /// before.diff(&after).apply(&mut other);
+    /// assert_eq!(other[CACHE_CONTROL], "max-age=120");
+    /// assert_eq!(other[CONTENT_TYPE], "text/html");
+    /// ```
+    pub fn apply(&self, map: &mut HeaderMap<T>)
+    where
+        T: Clone,
+    {
+        for name in &self.removed {
+            map.remove(name);
+        }
+
+        self.apply_updates(map);
+    }
+
+    /// Applies the added and changed headers to `map`, keeping the headers
+    /// the diff removes.
+    ///
+    /// This is how a cache revalidating a stored response merges the
+    /// headers of a `304 Not Modified` response: the headers the 304 sends
+    /// replace the stored ones, and the others are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::{CONTENT_TYPE, ETAG};
+    /// let mut stored = HeaderMap::new();
+    /// stored.insert(CONTENT_TYPE, "text/html".parse().unwrap());
+    /// stored.insert(ETAG, "\"v1\"".parse().unwrap());
+    ///
+    /// let mut not_modified = HeaderMap::new();
+    /// not_modified.insert(ETAG, "\"v2\"".parse().unwrap());
+    ///
+    /// stored.diff(&not_modified).apply_updates(&mut stored);
+    /// assert_eq!(stored[ETAG], "\"v2\"");
+    /// assert_eq!(stored[CONTENT_TYPE], "text/html");
+    /// ```
+    pub fn apply_updates(&self, map: &mut HeaderMap<T>)
+    where
+        T: Clone,
+    {
+        let updates = self.added.iter().chain(self.changed.iter());
+        map.insert_all(updates.map(|(name, value)| (name.clone(), value.clone())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in pairs {
+            map.append(name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn value_order_is_a_change() {
+        let a = map(&[("x-a", "1"), ("x-a", "2")]);
+        let b = map(&[("x-a", "2"), ("x-a", "1")]);
+
+        let diff = a.diff(&b);
+        assert!(diff.added().is_empty());
+        assert!(diff.removed().is_empty());
+        assert_eq!(diff.changed().get_all("x-a").iter().count(), 2);
+    }
+
+    #[test]
+    fn apply_round_trips() {
+        let a = map(&[("x-a", "1"), ("x-b", "2"), ("x-b", "3"), ("x-c", "4")]);
+        let b = map(&[("x-b", "3"), ("x-c", "4"), ("x-d", "5"), ("x-d", "6")]);
+
+        let mut patched = a.clone();
+        a.diff(&b).apply(&mut patched);
+        assert_eq!(patched, b);
+
+        let mut patched = b.clone();
+        b.diff(&a).apply(&mut patched);
+        assert_eq!(patched, a);
+    }
+
+    #[test]
+    fn not_modified_merge_keeps_other_headers() {
+        let mut stored = map(&[
+            ("content-type", "text/html"),
+            ("content-length", "42"),
+            ("etag", "\"v1\""),
+            ("cache-control", "max-age=60"),
+            ("vary", "accept"),
+            ("vary", "cookie"),
+        ]);
+        let not_modified = map(&[
+            ("etag", "\"v1\""),
+            ("cache-control", "max-age=120"),
+            ("vary", "accept"),
+            ("date", "Sun, 06 Nov 1994 08:49:37 GMT"),
+        ]);
+
+        stored.diff(&not_modified).apply_updates(&mut stored);
+        assert_eq!(
+            stored,
+            map(&[
+                ("content-type", "text/html"),
+                ("content-length", "42"),
+                ("etag", "\"v1\""),
+                ("cache-control", "max-age=120"),
+                ("vary", "accept"),
+                ("date", "Sun, 06 Nov 1994 08:49:37 GMT"),
+            ])
+        );
+    }
+}
@@ -1,11 +1,19 @@
-use std::collections::HashMap;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::marker::PhantomData;
+use core::{fmt, mem, ops, ptr};
+#[cfg(feature = "std")]
 use std::collections::hash_map::RandomState;
-use std::convert::TryFrom;
-use std::hash::{BuildHasher, Hash, Hasher};
-use std::iter::{FromIterator, FusedIterator};
-use std::marker::PhantomData;
-use std::{fmt, mem, ops, ptr, vec};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
+#[cfg(feature = "std")]
 use crate::Error;
 
 use super::HeaderValue;
@@ -49,6 +57,10 @@ pub struct HeaderMap<T = HeaderValue> {
     entries: Vec<Bucket<T>>,
     extra_values: Vec<ExtraValue<T>>,
     danger: Danger,
+    // Seeds the safe hashing instead of the OS, see `set_hash_seed`
+    hash_seed: Option<u64>,
+    // Flags values stored under well-known secret headers as sensitive
+    sensitive: Option<fn(&HeaderName, &mut T)>,
 }
 
 // # Implementation notes
@@ -114,7 +126,7 @@ pub struct IntoIter<T> {
 /// associated value.
 #[derive(Debug)]
 pub struct Keys<'a, T> {
-    inner: ::std::slice::Iter<'a, Bucket<T>>,
+    inner: ::core::slice::Iter<'a, Bucket<T>>,
 }
 
 /// `HeaderMap` value iterator.
@@ -307,11 +319,58 @@ enum Link {
 ///
 /// When in the red state, a safe hashing algorithm is used and all values in
 /// the header map have to be rehashed.
+///
+/// Together with the thresholds below, this bounds the work an untrusted peer
+/// can cause by choosing header names: no insertion probes further than
+/// `FORWARD_SHIFT_THRESHOLD` slots or shifts more than `DISPLACEMENT_THRESHOLD`
+/// entries without the next one growing the map or switching it to safe
+/// hashing, and the map grows at most a few times before doing the latter.
 #[derive(Clone)]
 enum Danger {
     Green,
     Yellow,
-    Red(RandomState),
+    Red(SecureState),
+}
+
+/// The hasher used by the red state.
+///
+/// With `std`, the keys come from `RandomState`, unless the map was given a
+/// seed. Without it there is no entropy source to draw from, so the SipHash
+/// keys are derived from the seed, or from the address of the map at the
+/// time it turned red. This is weaker than `RandomState`, but still keeps an
+/// attacker from precomputing collisions against a fixed function.
+#[derive(Clone)]
+enum SecureState {
+    #[cfg(feature = "std")]
+    Random(RandomState),
+    Keyed { k0: u64, k1: u64 },
+}
+
+impl SecureState {
+    fn keyed(seed: u64) -> SecureState {
+        let seed = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        SecureState::Keyed {
+            k0: seed ^ 0x736f_6d65_7073_6575,
+            k1: seed.rotate_left(32) ^ 0x646f_7261_6e64_6f6d,
+        }
+    }
+
+    fn hash<K: ?Sized + Hash>(&self, k: &K) -> u64 {
+        match *self {
+            #[cfg(feature = "std")]
+            SecureState::Random(ref state) => {
+                let mut h = state.build_hasher();
+                k.hash(&mut h);
+                h.finish()
+            }
+            SecureState::Keyed { k0, k1 } => {
+                #[allow(deprecated)]
+                let mut h = core::hash::SipHasher::new_with_keys(k0, k1);
+                k.hash(&mut h);
+                h.finish()
+            }
+        }
+    }
 }
 
 // Constants related to detecting DOS attacks.
@@ -441,7 +500,74 @@ impl HeaderMap {
     /// assert_eq!(0, map.capacity());
     /// ```
     pub fn new() -> Self {
-        HeaderMap::with_capacity(0)
+        let mut map = HeaderMap::with_capacity(0);
+        map.set_auto_sensitive(true);
+        map
+    }
+
+    /// Sets whether values inserted under well-known secret headers are
+    /// automatically flagged as sensitive.
+    ///
+    /// When enabled, every value stored under `Authorization`,
+    /// `Proxy-Authorization`, `Cookie` or `Set-Cookie` has
+    /// [`HeaderValue::set_sensitive`] called on it as it enters the map.
+    /// Values already in the map are not modified.
+    ///
+    /// This is enabled for maps created with `HeaderMap::new`, which includes
+    /// the headers of every `Request` and `Response`. Maps created with
+    /// `with_capacity`, `default` or `collect` are generic over the value type
+    /// and start with it disabled.
+    ///
+    /// [`HeaderValue::set_sensitive`]: struct.HeaderValue.html#method.set_sensitive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::AUTHORIZATION;
+    /// let mut map = HeaderMap::new();
+    /// map.insert(AUTHORIZATION, "Basic Zm9vOmJhcg==".parse().unwrap());
+    /// assert!(map[AUTHORIZATION].is_sensitive());
+    ///
+    /// map.set_auto_sensitive(false);
+    /// map.insert(AUTHORIZATION, "Basic Zm9vOmJhcg==".parse().unwrap());
+    /// assert!(!map[AUTHORIZATION].is_sensitive());
+    /// ```
+    pub fn set_auto_sensitive(&mut self, enabled: bool) {
+        fn mark(key: &HeaderName, value: &mut HeaderValue) {
+            if key.is_sensitive() {
+                value.set_sensitive(true);
+            }
+        }
+
+        self.sensitive = if enabled { Some(mark) } else { None };
+    }
+
+    /// Returns true if values inserted under well-known secret headers are
+    /// automatically flagged as sensitive.
+    ///
+    /// See [`set_auto_sensitive`](#method.set_auto_sensitive).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// let mut map = HeaderMap::new();
+    /// assert!(map.is_auto_sensitive());
+    ///
+    /// map.set_auto_sensitive(false);
+    /// assert!(!map.is_auto_sensitive());
+    /// ```
+    pub fn is_auto_sensitive(&self) -> bool {
+        self.sensitive.is_some()
+    }
+
+    // The sum of the lengths of every name and value, see
+    // `Request::estimated_size`.
+    pub(crate) fn byte_len(&self) -> usize {
+        self.iter()
+            .map(|(name, value)| name.as_str().len() + value.len())
+            .sum()
     }
 }
 
@@ -474,6 +600,8 @@ impl<T> HeaderMap<T> {
                 entries: Vec::new(),
                 extra_values: Vec::new(),
                 danger: Danger::Green,
+                hash_seed: None,
+                sensitive: None,
             }
         } else {
             let raw_cap = to_raw_capacity(capacity).next_power_of_two();
@@ -485,10 +613,61 @@ impl<T> HeaderMap<T> {
                 entries: Vec::with_capacity(raw_cap),
                 extra_values: Vec::new(),
                 danger: Danger::Green,
+                hash_seed: None,
+                sensitive: None,
             }
         }
     }
 
+    /// Seeds the hashing used once the map detects colliding names,
+    /// instead of drawing random keys from the operating system.
+    ///
+    /// A map starts with a fast hash, and switches to SipHash when so many
+    /// names collide that it may be under attack. With `std` enabled, the
+    /// keys of SipHash come from `RandomState`, which asks the operating
+    /// system for randomness; wasm32-unknown-unknown and bare-metal targets
+    /// may not provide any, and plugin sandboxes may not allow it. A seed
+    /// makes the map deterministic and independent of the operating system.
+    ///
+    /// The seed stays secret only as long as the process does: an attacker
+    /// who learns it, or guesses a fixed one, can choose names that all
+    /// collide, and every insertion then costs time linear in the size of
+    /// the map. Use a seed that differs between deployments, or leave the
+    /// default where randomness is available.
+    ///
+    /// A map already using SipHash is rehashed with the new keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// let mut map = HeaderMap::new();
+    /// map.set_hash_seed(0x5eed);
+    /// assert_eq!(map.hash_seed(), Some(0x5eed));
+    ///
+    /// map.insert("x-name", "value".parse().unwrap());
+    /// assert_eq!(map["x-name"], "value");
+    /// ```
+    pub fn set_hash_seed(&mut self, seed: u64) {
+        self.hash_seed = Some(seed);
+
+        if self.danger.is_red() {
+            self.danger = Danger::Red(SecureState::keyed(seed));
+
+            for index in self.indices.iter_mut() {
+                *index = Pos::none();
+            }
+
+            self.rebuild();
+        }
+    }
+
+    /// Returns the seed set with [`set_hash_seed`](#method.set_hash_seed),
+    /// if any.
+    pub fn hash_seed(&self) -> Option<u64> {
+        self.hash_seed
+    }
+
     /// Returns the number of headers stored in the map.
     ///
     /// This number represents the total number of **values** stored in the map.
@@ -761,6 +940,40 @@ impl<T> HeaderMap<T> {
         }
     }
 
+    /// Returns an iterator over mutable references to all values associated
+    /// with a key.
+    ///
+    /// Values are yielded in insertion order. The iterator is empty if there
+    /// are no values associated with the key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::VIA;
+    /// let mut map = HeaderMap::new();
+    ///
+    /// map.insert(VIA, "1.1 first".parse().unwrap());
+    /// map.append(VIA, "1.1 second".parse().unwrap());
+    ///
+    /// for value in map.get_all_mut(VIA) {
+    ///     let anonymized = value.to_str().unwrap().replace("1.1 ", "1.1 hidden-");
+    ///     *value = anonymized.parse().unwrap();
+    /// }
+    ///
+    /// let mut iter = map.get_all(VIA).iter();
+    /// assert_eq!(&"1.1 hidden-first", iter.next().unwrap());
+    /// assert_eq!(&"1.1 hidden-second", iter.next().unwrap());
+    /// assert!(map.get_all_mut("x-missing").next().is_none());
+    /// ```
+    pub fn get_all_mut<K>(&mut self, key: K) -> ValueIterMut<'_, T>
+    where
+        K: AsHeaderName,
+    {
+        let index = key.find(self).map(|(_, i)| i);
+        self.value_iter_mut(index)
+    }
+
     /// Returns true if the map contains a value for the specified key.
     ///
     /// # Examples
@@ -995,28 +1208,38 @@ impl<T> HeaderMap<T> {
         } else {
             ValueIter {
                 map: self,
-                index: ::std::usize::MAX,
+                index: ::core::usize::MAX,
                 front: None,
                 back: None,
             }
         }
     }
 
-    fn value_iter_mut(&mut self, idx: usize) -> ValueIterMut<'_, T> {
+    fn value_iter_mut(&mut self, idx: Option<usize>) -> ValueIterMut<'_, T> {
         use self::Cursor::*;
 
-        let back = {
-            let entry = &self.entries[idx];
+        if let Some(idx) = idx {
+            let back = {
+                let entry = &self.entries[idx];
 
-            entry.links.map(|l| Values(l.tail)).unwrap_or(Head)
-        };
+                entry.links.map(|l| Values(l.tail)).unwrap_or(Head)
+            };
 
-        ValueIterMut {
-            map: self as *mut _,
-            index: idx,
-            front: Some(Head),
-            back: Some(back),
-            lt: PhantomData,
+            ValueIterMut {
+                map: self as *mut _,
+                index: idx,
+                front: Some(Head),
+                back: Some(back),
+                lt: PhantomData,
+            }
+        } else {
+            ValueIterMut {
+                map: self as *mut _,
+                index: ::core::usize::MAX,
+                front: None,
+                back: None,
+                lt: PhantomData,
+            }
         }
     }
 
@@ -1155,7 +1378,11 @@ impl<T> HeaderMap<T> {
             danger,
             // Vacant
             {
-                drop(danger); // Make lint happy
+                // A long probe has to be handled even if nothing was
+                // displaced, or colliding names could keep the map green.
+                if danger {
+                    self.danger.to_yellow();
+                }
                 let index = self.entries.len();
                 self.insert_entry(hash, key.into(), value);
                 self.indices[probe] = Pos::new(index, hash);
@@ -1174,6 +1401,8 @@ impl<T> HeaderMap<T> {
     /// Set an occupied bucket to the given value
     #[inline]
     fn insert_occupied(&mut self, index: usize, value: T) -> T {
+        let value = self.mark(index, value);
+
         if let Some(links) = self.entries[index].links {
             self.remove_all_extra_values(links.next);
         }
@@ -1183,6 +1412,7 @@ impl<T> HeaderMap<T> {
     }
 
     fn insert_occupied_mult(&mut self, index: usize, value: T) -> ValueDrain<'_, T> {
+        let value = self.mark(index, value);
         let old;
         let links;
 
@@ -1255,7 +1485,11 @@ impl<T> HeaderMap<T> {
             danger,
             // Vacant
             {
-                drop(danger);
+                // A long probe has to be handled even if nothing was
+                // displaced, or colliding names could keep the map green.
+                if danger {
+                    self.danger.to_yellow();
+                }
                 let index = self.entries.len();
                 self.insert_entry(hash, key.into(), value);
                 self.indices[probe] = Pos::new(index, hash);
@@ -1263,6 +1497,7 @@ impl<T> HeaderMap<T> {
             },
             // Occupied
             {
+                let value = self.mark(pos, value);
                 append_value(pos, &mut self.entries[pos], &mut self.extra_values, value);
                 true
             },
@@ -1275,6 +1510,117 @@ impl<T> HeaderMap<T> {
         )
     }
 
+    /// Inserts all key-value pairs yielded by the iterator into the map.
+    ///
+    /// Every key that was already present in the map has its previous values
+    /// removed the first time it is seen. Repeated occurrences of a key within
+    /// the iterator are appended, so a full header block replaces the
+    /// matching headers of the map without losing its own duplicates.
+    ///
+    /// Capacity is reserved once up front using the iterator's size hint and
+    /// consecutive values for the same key are appended without hashing the
+    /// key again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::{ACCEPT, HOST};
+    /// let mut map = HeaderMap::new();
+    /// map.insert(HOST, "world".parse().unwrap());
+    /// map.insert(ACCEPT, "text/plain".parse().unwrap());
+    ///
+    /// map.insert_all(vec![
+    ///     (HOST, "earth".parse().unwrap()),
+    ///     (HOST, "mars".parse().unwrap()),
+    /// ]);
+    ///
+    /// let hosts: Vec<_> = map.get_all(HOST).iter().collect();
+    /// assert_eq!(hosts, ["earth", "mars"]);
+    /// assert_eq!(map[ACCEPT], "text/plain");
+    /// ```
+    pub fn insert_all<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (HeaderName, T)>,
+    {
+        self.bulk_insert(iter, true)
+    }
+
+    /// Appends all key-value pairs yielded by the iterator to the map.
+    ///
+    /// This behaves like calling `append` for every pair, but capacity is
+    /// reserved once up front using the iterator's size hint and consecutive
+    /// values for the same key are appended without hashing the key again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::{ACCEPT, HOST};
+    /// let mut map = HeaderMap::new();
+    /// map.insert(HOST, "world".parse().unwrap());
+    ///
+    /// map.append_all(vec![
+    ///     (HOST, "earth".parse().unwrap()),
+    ///     (ACCEPT, "text/plain".parse().unwrap()),
+    /// ]);
+    ///
+    /// let hosts: Vec<_> = map.get_all(HOST).iter().collect();
+    /// assert_eq!(hosts, ["world", "earth"]);
+    /// assert_eq!(map[ACCEPT], "text/plain");
+    /// ```
+    pub fn append_all<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (HeaderName, T)>,
+    {
+        self.bulk_insert(iter, false)
+    }
+
+    fn bulk_insert<I>(&mut self, iter: I, replace: bool)
+    where
+        I: IntoIterator<Item = (HeaderName, T)>,
+    {
+        let iter = iter.into_iter();
+
+        self.reserve(iter.size_hint().0);
+
+        // Entries below this index existed before the call. When replacing,
+        // those have their old values dropped the first time they are seen.
+        let existing = self.entries.len();
+        let mut replaced = if replace {
+            vec![false; existing]
+        } else {
+            Vec::new()
+        };
+
+        // Index of the entry the previous pair was stored in.
+        let mut last: Option<usize> = None;
+
+        for (key, value) in iter {
+            let index = match last {
+                Some(index) if self.entries[index].key == key => index,
+                _ => match self.entry2(key) {
+                    Entry::Occupied(e) => e.index,
+                    Entry::Vacant(e) => {
+                        last = Some(e.insert_entry(value).index);
+                        continue;
+                    }
+                },
+            };
+
+            if replace && index < existing && !replaced[index] {
+                replaced[index] = true;
+                self.insert_occupied(index, value);
+            } else {
+                let value = self.mark(index, value);
+                let entry = &mut self.entries[index];
+                append_value(index, entry, &mut self.extra_values, value);
+            }
+
+            last = Some(index);
+        }
+    }
+
     #[inline]
     fn find<K: ?Sized>(&self, key: &K) -> Option<(usize, usize)>
     where
@@ -1445,9 +1791,13 @@ impl<T> HeaderMap<T> {
     }
 
     #[inline]
-    fn insert_entry(&mut self, hash: HashValue, key: HeaderName, value: T) {
+    fn insert_entry(&mut self, hash: HashValue, key: HeaderName, mut value: T) {
         assert!(self.entries.len() < MAX_SIZE, "header map at capacity");
 
+        if let Some(mark) = self.sensitive {
+            mark(&key, &mut value);
+        }
+
         self.entries.push(Bucket {
             hash: hash,
             key: key,
@@ -1456,6 +1806,16 @@ impl<T> HeaderMap<T> {
         });
     }
 
+    /// Applies the sensitivity marker, if any, to a value about to be stored
+    /// under the entry at `index`.
+    #[inline]
+    fn mark(&self, index: usize, mut value: T) -> T {
+        if let Some(mark) = self.sensitive {
+            mark(&self.entries[index].key, &mut value);
+        }
+        value
+    }
+
     fn rebuild(&mut self) {
         // Loop over all entries and re-insert them into the map
         'outer: for (index, entry) in self.entries.iter_mut().enumerate() {
@@ -1509,7 +1869,9 @@ impl<T> HeaderMap<T> {
         if self.danger.is_yellow() {
             let load_factor = self.entries.len() as f32 / self.indices.len() as f32;
 
-            if load_factor >= LOAD_FACTOR_THRESHOLD {
+            // Hash values only have 15 bits, so growing past `MAX_SIZE` slots
+            // can't spread colliding keys any further.
+            if load_factor >= LOAD_FACTOR_THRESHOLD && self.indices.len() < MAX_SIZE {
                 // Transition back to green danger level
                 self.danger.to_green();
 
@@ -1518,17 +1880,20 @@ impl<T> HeaderMap<T> {
 
                 // Grow the capacity
                 self.grow(new_cap);
-            } else {
-                self.danger.to_red();
+                return;
+            }
 
-                // Rebuild hash table
-                for index in self.indices.iter_mut() {
-                    *index = Pos::none();
-                }
+            self.danger.to_red(self.hash_seed);
 
-                self.rebuild();
+            // Rebuild hash table
+            for index in self.indices.iter_mut() {
+                *index = Pos::none();
             }
-        } else if len == self.capacity() {
+
+            self.rebuild();
+        }
+
+        if len >= self.capacity() {
             if len == 0 {
                 let new_raw_cap = 8;
                 self.mask = 8 - 1;
@@ -1809,6 +2174,7 @@ impl<T> FromIterator<(HeaderName, T)> for HeaderMap<T> {
 /// let headers: HeaderMap = (&map).try_into().expect("valid headers");
 /// assert_eq!(headers["X-Custom-Header"], "my value");
 /// ```
+#[cfg(feature = "std")]
 impl<'a, K, V, T> TryFrom<&'a HashMap<K, V>> for HeaderMap<T>
     where
         K: Eq + Hash,
@@ -2576,10 +2942,10 @@ impl<'a, T: 'a> Iterator for ValueIterMut<'a, T> {
     fn next(&mut self) -> Option<Self::Item> {
         use self::Cursor::*;
 
-        let entry = unsafe { &mut (*self.map).entries[self.index] };
-
         match self.front {
             Some(Head) => {
+                let entry = unsafe { &mut (*self.map).entries[self.index] };
+
                 if self.back == Some(Head) {
                     self.front = None;
                     self.back = None;
@@ -2619,10 +2985,9 @@ impl<'a, T: 'a> DoubleEndedIterator for ValueIterMut<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         use self::Cursor::*;
 
-        let entry = unsafe { &mut (*self.map).entries[self.index] };
-
         match self.back {
             Some(Head) => {
+                let entry = unsafe { &mut (*self.map).entries[self.index] };
                 self.front = None;
                 self.back = None;
                 Some(&mut entry.value)
@@ -2873,8 +3238,9 @@ impl<'a, T> OccupiedEntry<'a, T> {
     /// ```
     pub fn append(&mut self, value: T) {
         let idx = self.index;
+        let value = self.map.mark(idx, value.into());
         let entry = &mut self.map.entries[idx];
-        append_value(idx, entry, &mut self.map.extra_values, value.into());
+        append_value(idx, entry, &mut self.map.extra_values, value);
     }
 
     /// Remove the entry from the map.
@@ -2997,7 +3363,7 @@ impl<'a, T> OccupiedEntry<'a, T> {
     /// assert_eq!(&"earth-boop", i.next().unwrap());
     /// ```
     pub fn iter_mut(&mut self) -> ValueIterMut<'_, T> {
-        self.map.value_iter_mut(self.index)
+        self.map.value_iter_mut(Some(self.index))
     }
 }
 
@@ -3006,7 +3372,7 @@ impl<'a, T> IntoIterator for OccupiedEntry<'a, T> {
     type IntoIter = ValueIterMut<'a, T>;
 
     fn into_iter(self) -> ValueIterMut<'a, T> {
-        self.map.value_iter_mut(self.index)
+        self.map.value_iter_mut(Some(self.index))
     }
 }
 
@@ -3151,9 +3517,16 @@ impl Danger {
         }
     }
 
-    fn to_red(&mut self) {
+    fn to_red(&mut self, seed: Option<u64>) {
         debug_assert!(self.is_yellow());
-        *self = Danger::Red(RandomState::new());
+        let state = match seed {
+            Some(seed) => SecureState::keyed(seed),
+            #[cfg(feature = "std")]
+            None => SecureState::Random(RandomState::new()),
+            #[cfg(not(feature = "std"))]
+            None => SecureState::keyed(self as *const Danger as u64),
+        };
+        *self = Danger::Red(state);
     }
 
     fn is_yellow(&self) -> bool {
@@ -3211,11 +3584,7 @@ where
 
     let hash = match *danger {
         // Safe hash
-        Danger::Red(ref hasher) => {
-            let mut h = hasher.build_hasher();
-            k.hash(&mut h);
-            h.finish()
-        }
+        Danger::Red(ref state) => state.hash(k),
         // Fast hash
         _ => {
             let mut h = FnvHasher::default();
@@ -3328,6 +3697,7 @@ mod into_header_name {
 
 mod as_header_name {
     use super::{Entry, HdrName, HeaderMap, HeaderName, InvalidHeaderName};
+    use alloc::string::String;
 
     /// A marker trait used to identify values that can be used as search keys
     /// to a `HeaderMap`.
@@ -3480,6 +3850,69 @@ fn test_bounds() {
     check_bounds::<ValueDrain<'static, ()>>();
 }
 
+#[cfg(test)]
+fn colliding_names(count: usize) -> Vec<HeaderName> {
+    use fnv::FnvHasher;
+
+    // FNV is computed byte by byte, so the hash of `x-` can be extended with
+    // each suffix without building a name for every candidate.
+    let prefix = HeaderName::from_static("x-");
+    let mut h = FnvHasher::default();
+    prefix.hash(&mut h);
+    let seed = h.finish();
+
+    let mask = (MAX_SIZE as u64) - 1;
+    let mut target = None;
+    let mut names = Vec::new();
+    let mut i = 0u64;
+    while names.len() < count {
+        let mut suffix = [b'a'; 6];
+        let mut n = i;
+        for b in suffix.iter_mut() {
+            *b += (n % 26) as u8;
+            n /= 26;
+        }
+        i += 1;
+
+        let mut h = FnvHasher::with_key(seed);
+        h.write(&suffix);
+        let hash = h.finish() & mask;
+        if *target.get_or_insert(hash) != hash {
+            continue;
+        }
+
+        let mut name = b"x-".to_vec();
+        name.extend_from_slice(&suffix);
+        let name = HeaderName::from_bytes(&name).unwrap();
+        assert_eq!(hash_elem_using(&Danger::Green, &name).0 as u64, hash);
+        names.push(name);
+    }
+    names
+}
+
+#[test]
+fn colliding_names_switch_to_safe_hashing() {
+    let names = colliding_names(540);
+    let mut map = HeaderMap::new();
+    for name in &names {
+        map.insert(name.clone(), HeaderValue::from_static("x"));
+    }
+    assert!(map.danger.is_red());
+    assert_eq!(map.len(), names.len());
+    for name in &names {
+        assert!(map.contains_key(name));
+    }
+
+    let longest = map
+        .indices
+        .iter()
+        .enumerate()
+        .filter_map(|(i, pos)| pos.resolve().map(|(_, hash)| probe_distance(map.mask, hash, i)))
+        .max()
+        .unwrap();
+    assert!(longest < DISPLACEMENT_THRESHOLD);
+}
+
 #[test]
 fn skip_duplicates_during_key_iteration() {
     let mut map = HeaderMap::new();
@@ -3487,3 +3920,29 @@ fn skip_duplicates_during_key_iteration() {
     map.append("a", HeaderValue::from_static("b"));
     assert_eq!(map.keys().count(), map.keys_len());
 }
+
+#[test]
+fn seeded_maps_hash_the_same() {
+    let names = colliding_names(540);
+    let seeded = |seed| {
+        let mut map = HeaderMap::new();
+        map.set_hash_seed(seed);
+        for name in &names {
+            map.insert(name.clone(), HeaderValue::from_static("x"));
+        }
+        assert!(map.danger.is_red());
+        map.entries.iter().map(|e| e.hash.0).collect::<Vec<_>>()
+    };
+    assert_eq!(seeded(1), seeded(1));
+    assert_ne!(seeded(1), seeded(2));
+
+    let mut map = HeaderMap::new();
+    for name in &names {
+        map.insert(name.clone(), HeaderValue::from_static("x"));
+    }
+    map.set_hash_seed(1);
+    assert_eq!(map.entries.iter().map(|e| e.hash.0).collect::<Vec<_>>(), seeded(1));
+    for name in &names {
+        assert!(map.contains_key(name));
+    }
+}
@@ -70,15 +70,23 @@
 //! [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
 //! [Robin Hood hashing]: https://en.wikipedia.org/wiki/Hash_table#Robin_Hood_hashing
 
+mod diff;
 mod map;
 mod name;
+mod redact;
 mod value;
 
+#[cfg(feature = "http")]
+pub mod static_table;
+pub mod typed;
+
+pub use self::diff::Diff;
 pub use self::map::{
     AsHeaderName, Drain, Entry, GetAll, HeaderMap, IntoHeaderName, IntoIter, Iter, IterMut, Keys,
     OccupiedEntry, VacantEntry, ValueDrain, ValueIter, ValueIterMut, Values, ValuesMut,
 };
 pub use self::name::{HeaderName, InvalidHeaderName};
+pub use self::redact::DebugRedacted;
 pub use self::value::{HeaderValue, InvalidHeaderValue, ToStrError};
 
 // Use header name constants
@@ -114,6 +122,7 @@ pub use self::name::{
     COOKIE,
     DNT,
     DATE,
+    DEPRECATION,
     ETAG,
     EXPECT,
     EXPIRES,
@@ -148,6 +157,7 @@ pub use self::name::{
     SERVER,
     SET_COOKIE,
     STRICT_TRANSPORT_SECURITY,
+    SUNSET,
     TE,
     TRAILER,
     TRANSFER_ENCODING,
@@ -163,9 +173,19 @@ pub use self::name::{
     X_FRAME_OPTIONS,
     X_XSS_PROTECTION,
 };
+#[cfg(any(feature = "rtsp", feature = "sip"))]
+pub use self::name::CSEQ;
 #[cfg(feature = "rtsp")]
 pub use self::name::{
-    CSEQ,
+    MEDIA_PROPERTIES,
+    MEDIA_RANGE,
+    NOTIFY_REASON,
+    PIPELINED_REQUESTS,
+    REQUEST_STATUS,
+    SEEK_STYLE,
+    SESSION,
+    TERMINATE_REASON,
+    TRANSPORT,
 };
 
 /// Maximum length of a header name
@@ -1,12 +1,16 @@
 use crate::byte_str::ByteStr;
+use crate::profile::{self, ParseProfile, Repairs};
+use crate::swar;
 use bytes::{Bytes, BytesMut};
 
-use std::borrow::Borrow;
+use alloc::string::String;
+use core::borrow::Borrow;
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+use core::{cmp, fmt, mem};
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::convert::{TryFrom};
-use std::hash::{Hash, Hasher};
-use std::str::FromStr;
-use std::{fmt, mem};
 
 /// Represents an HTTP header field name
 ///
@@ -57,7 +61,7 @@ struct MaybeLower<'a> {
 
 /// A possible error when converting a `HeaderName` from another type.
 pub struct InvalidHeaderName {
-    _priv: (),
+    offset: Option<usize>,
 }
 
 macro_rules! standard_headers {
@@ -475,7 +479,7 @@ standard_headers! {
     (Cookie, COOKIE, "cookie");
 
     /// Contains the sequence number of the message.
-    #[cfg(feature = "rtsp")]
+    #[cfg(any(feature = "rtsp", feature = "sip"))]
     (CSeq, CSEQ, "cseq");
 
     /// Indicates the client's tracking preference.
@@ -488,6 +492,13 @@ standard_headers! {
     /// Contains the date and time at which the message was originated.
     (Date, DATE, "date");
 
+    /// Signals that a resource is, or will be, deprecated.
+    ///
+    /// RFC 9745 gives the date of the deprecation as a structured field date,
+    /// like `@1688169599`. Earlier drafts used `true` or an HTTP date.
+    #[cfg(feature = "http")]
+    (Deprecation, DEPRECATION, "deprecation");
+
     /// Identifier for a specific version of a resource.
     ///
     /// This header allows caches to be more efficient, and saves bandwidth, as
@@ -730,6 +741,20 @@ standard_headers! {
     #[cfg(feature = "http")]
     (MaxForwards, MAX_FORWARDS, "max-forwards");
 
+    /// Describes the properties of the media of an RTSP/2.0 session, like
+    /// whether it is on demand or live, and how long it stays available.
+    #[cfg(feature = "rtsp")]
+    (MediaProperties, MEDIA_PROPERTIES, "media-properties");
+
+    /// Gives the range of an RTSP/2.0 session's media that can be played.
+    #[cfg(feature = "rtsp")]
+    (MediaRange, MEDIA_RANGE, "media-range");
+
+    /// Gives the reason of an RTSP/2.0 `PLAY_NOTIFY` request, like
+    /// `end-of-stream`.
+    #[cfg(feature = "rtsp")]
+    (NotifyReason, NOTIFY_REASON, "notify-reason");
+
     /// Indicates where a fetch originates from.
     ///
     /// It doesn't include any path information, but only the server name. It is
@@ -739,6 +764,12 @@ standard_headers! {
     #[cfg(feature = "http")]
     (Origin, ORIGIN, "origin");
 
+    /// Identifies a chain of RTSP/2.0 requests sent without waiting for the
+    /// responses, so that `SETUP` and `PLAY` can be pipelined before the
+    /// session is known.
+    #[cfg(feature = "rtsp")]
+    (PipelinedRequests, PIPELINED_REQUESTS, "pipelined-requests");
+
     /// HTTP/1.0 header usually used for backwards compatibility.
     ///
     /// The Pragma HTTP/1.0 general header is an implementation-specific header
@@ -817,6 +848,11 @@ standard_headers! {
     #[cfg(feature = "http")]
     (Refresh, REFRESH, "refresh");
 
+    /// Reports the status of a completed RTSP/2.0 request in a `PLAY_NOTIFY`
+    /// request.
+    #[cfg(feature = "rtsp")]
+    (RequestStatus, REQUEST_STATUS, "request-status");
+
     /// The Retry-After response HTTP header indicates how long the user agent
     /// should wait before making a follow-up request. There are two main cases
     /// this header is used:
@@ -871,6 +907,11 @@ standard_headers! {
     #[cfg(feature = "http")]
     (SecWebSocketVersion, SEC_WEBSOCKET_VERSION, "sec-websocket-version");
 
+    /// Tells the RTSP/2.0 server how to position the media when seeking, like
+    /// `RAP` for the closest random access point.
+    #[cfg(feature = "rtsp")]
+    (SeekStyle, SEEK_STYLE, "seek-style");
+
     /// Contains information about the software used by the origin server to
     /// handle the request.
     ///
@@ -880,6 +921,11 @@ standard_headers! {
     /// holes.
     (Server, SERVER, "server");
 
+    /// Identifies an RTSP session, as returned by the server in response to
+    /// `SETUP`.
+    #[cfg(feature = "rtsp")]
+    (Session, SESSION, "session");
+
     /// Used to send cookies from the server to the user agent.
     #[cfg(feature = "http")]
     (SetCookie, SET_COOKIE, "set-cookie");
@@ -887,6 +933,11 @@ standard_headers! {
     /// Tells the client to communicate with HTTPS instead of using HTTP.
     (StrictTransportSecurity, STRICT_TRANSPORT_SECURITY, "strict-transport-security");
 
+    /// Gives the date after which a resource is expected to become
+    /// unresponsive, as defined by RFC 8594.
+    #[cfg(feature = "http")]
+    (Sunset, SUNSET, "sunset");
+
     /// Informs the server of transfer encodings willing to be accepted as part
     /// of the response.
     ///
@@ -898,6 +949,11 @@ standard_headers! {
     #[cfg(feature = "http")]
     (Te, TE, "te");
 
+    /// Gives the reason a server ends an RTSP/2.0 session in a `TEARDOWN`
+    /// request.
+    #[cfg(feature = "rtsp")]
+    (TerminateReason, TERMINATE_REASON, "terminate-reason");
+
     /// Allows the sender to include additional fields at the end of chunked
     /// messages.
     #[cfg(feature = "http")]
@@ -918,6 +974,11 @@ standard_headers! {
     #[cfg(feature = "http")]
     (TransferEncoding, TRANSFER_ENCODING, "transfer-encoding");
 
+    /// Indicates the transport protocol and parameters to use for an RTSP
+    /// media stream.
+    #[cfg(feature = "rtsp")]
+    (Transport, TRANSPORT, "transport");
+
     /// Contains a string that allows identifying the requesting client's
     /// software.
     (UserAgent, USER_AGENT, "user-agent");
@@ -1110,6 +1171,27 @@ macro_rules! eq {
     };
 }
 
+// Whether a name already went through `HEADER_CHARS`, which maps invalid
+// bytes to 0.
+#[inline]
+fn is_normalized(buf: &[u8]) -> bool {
+    swar::all(buf, |x| swar::eq(x, 0) == 0, |b| b != 0)
+}
+
+// Whether a name is valid and lowercase. Most names are only lowercase
+// letters, digits and dashes, which the word test accepts without the table.
+#[inline]
+fn is_lowercase(buf: &[u8]) -> bool {
+    swar::all(
+        buf,
+        |x| {
+            let ok = swar::range(x, b'a', b'z') | swar::range(x, b'0', b'9') | swar::eq(x, b'-');
+            ok == swar::HIGH
+        },
+        |b| b != 0 && HEADER_CHARS[b as usize] == b,
+    )
+}
+
 #[cfg(any(not(debug_assertions), not(target_arch = "wasm32")))]
 /// This version is best under optimized mode, however in a wasm debug compile,
 /// the `eq` macro expands to 1 + 1 + 1 + 1... and wasm explodes when this chain gets too long
@@ -1125,8 +1207,8 @@ fn parse_hdr<'a>(
 
     let validate = |buf: &'a [u8], len: usize| {
         let buf = &buf[..len];
-        if buf.iter().any(|&b| b == 0) {
-            Err(InvalidHeaderName::new())
+        if !is_normalized(buf) {
+            Err(InvalidHeaderName::invalid_byte(data, table))
         } else {
             Ok(HdrName::custom(buf, true))
         }
@@ -1171,9 +1253,9 @@ fn parse_hdr<'a>(
         ($d:ident, $src:ident, 35) => { to_lower!($d, $src, 34); $d[34] = table[$src[34] as usize]; };
     }
 
-    assert!(len < super::MAX_HEADER_NAME_LEN,
-            "header name too long -- max length is {}",
-            super::MAX_HEADER_NAME_LEN);
+    if len >= super::MAX_HEADER_NAME_LEN {
+        return Err(InvalidHeaderName::new());
+    }
 
     match len {
         0 => Err(InvalidHeaderName::new()),
@@ -1215,7 +1297,7 @@ fn parse_hdr<'a>(
             } else if eq!(b == b'v' b'a' b'r' b'y') {
                 Ok(Vary.into())
             } else {
-                #[cfg(feature = "rtsp")]
+                #[cfg(any(feature = "rtsp", feature = "sip"))]
                 {
                     if eq!(b == b'c' b's' b'e' b'q') {
                         return Ok(CSeq.into());
@@ -1251,6 +1333,8 @@ fn parse_hdr<'a>(
             } else if b[0] == b's' {
                 if eq!(b[1] == b'e' b'r' b'v' b'e' b'r') {
                     return Ok(Server.into());
+                } else if eq!(b[1] == b'u' b'n' b's' b'e' b't') {
+                    return Ok(Sunset.into());
                 }
             }
 
@@ -1274,6 +1358,12 @@ fn parse_hdr<'a>(
             } else if eq!(b == b'w' b'a' b'r' b'n' b'i' b'n' b'g') {
                 Ok(Warning.into())
             } else {
+                #[cfg(feature = "rtsp")]
+                {
+                    if eq!(b == b's' b'e' b's' b's' b'i' b'o' b'n') {
+                        return Ok(Session.into());
+                    }
+                }
                 validate(b, len)
             }
         }
@@ -1298,6 +1388,12 @@ fn parse_hdr<'a>(
             if eq!(b == b'f' b'o' b'r' b'w' b'a' b'r' b'd' b'e' b'd') {
                 Ok(Forwarded.into())
             } else {
+                #[cfg(feature = "rtsp")]
+                {
+                    if eq!(b == b't' b'r' b'a' b'n' b's' b'p' b'o' b'r' b't') {
+                        return Ok(Transport.into());
+                    }
+                }
                 validate(b, len)
             }
         }
@@ -1311,6 +1407,12 @@ fn parse_hdr<'a>(
             } else if eq!(b == b'u' b's' b'e' b'r' b'-' b'a' b'g' b'e' b'n' b't') {
                 Ok(UserAgent.into())
             } else {
+                #[cfg(feature = "rtsp")]
+                {
+                    if eq!(b == b's' b'e' b'e' b'k' b'-' b's' b't' b'y' b'l' b'e') {
+                        return Ok(SeekStyle.into());
+                    }
+                }
                 validate(b, len)
             }
         }
@@ -1319,7 +1421,15 @@ fn parse_hdr<'a>(
 
             if eq!(b == b'r' b'e' b't' b'r' b'y' b'-' b'a' b'f' b't' b'e' b'r') {
                 Ok(RetryAfter.into())
+            } else if eq!(b == b'd' b'e' b'p' b'r' b'e' b'c' b'a' b't' b'i' b'o' b'n') {
+                Ok(Deprecation.into())
             } else {
+                #[cfg(feature = "rtsp")]
+                {
+                    if eq!(b == b'm' b'e' b'd' b'i' b'a' b'-' b'r' b'a' b'n' b'g' b'e') {
+                        return Ok(MediaRange.into());
+                    }
+                }
                 validate(b, len)
             }
         }
@@ -1356,6 +1466,12 @@ fn parse_hdr<'a>(
                 return Ok(LastModified.into());
             }
 
+            #[cfg(feature = "rtsp")]
+            {
+                if eq!(b == b'n' b'o' b't' b'i' b'f' b'y' b'-' b'r' b'e' b'a' b's' b'o' b'n') {
+                    return Ok(NotifyReason.into());
+                }
+            }
             validate(b, len)
         }
         14 => {
@@ -1367,6 +1483,12 @@ fn parse_hdr<'a>(
             {
                 Ok(ContentLength.into())
             } else {
+                #[cfg(feature = "rtsp")]
+                {
+                    if eq!(b == b'r' b'e' b'q' b'u' b'e' b's' b't' b'-' b's' b't' b'a' b't' b'u' b's') {
+                        return Ok(RequestStatus.into());
+                    }
+                }
                 validate(b, len)
             }
         }
@@ -1407,6 +1529,14 @@ fn parse_hdr<'a>(
                 return Ok(XXssProtection.into())
             }
 
+            #[cfg(feature = "rtsp")]
+            {
+                if eq!(b == b'm' b'e' b'd' b'i' b'a' b'-' b'p' b'r' b'o' b'p' b'e' b'r' b't' b'i' b'e' b's') {
+                    return Ok(MediaProperties.into());
+                } else if eq!(b == b't' b'e' b'r' b'm' b'i' b'n' b'a' b't' b'e' b'-' b'r' b'e' b'a' b's' b'o' b'n') {
+                    return Ok(TerminateReason.into());
+                }
+            }
             validate(b, len)
         }
         17 => {
@@ -1428,6 +1558,12 @@ fn parse_hdr<'a>(
             if eq!(b == b'p' b'r' b'o' b'x' b'y' b'-' b'a' b'u' b't' b'h' b'e' b'n' b't' b'i' b'c' b'a' b't' b'e') {
                 Ok(ProxyAuthenticate.into())
             } else {
+                #[cfg(feature = "rtsp")]
+                {
+                    if eq!(b == b'p' b'i' b'p' b'e' b'l' b'i' b'n' b'e' b'd' b'-' b'r' b'e' b'q' b'u' b'e' b's' b't' b's') {
+                        return Ok(PipelinedRequests.into());
+                    }
+                }
                 validate(b, len)
             }
         }
@@ -1597,18 +1733,16 @@ fn parse_hdr<'a>(
 
     let validate = |buf: &'a [u8], len: usize| {
         let buf = &buf[..len];
-        if buf.iter().any(|&b| b == 0) {
-            Err(InvalidHeaderName::new())
+        if !is_normalized(buf) {
+            Err(InvalidHeaderName::invalid_byte(data, table))
         } else {
             Ok(HdrName::custom(buf, true))
         }
     };
 
-    assert!(
-        len < super::MAX_HEADER_NAME_LEN,
-        "header name too long -- max length is {}",
-        super::MAX_HEADER_NAME_LEN
-    );
+    if len >= super::MAX_HEADER_NAME_LEN {
+        return Err(InvalidHeaderName::new());
+    }
 
     match len {
         0 => Err(InvalidHeaderName::new()),
@@ -1621,6 +1755,26 @@ fn parse_hdr<'a>(
                 b"age" => Ok(Age.into()),
                 b"via" => Ok(Via.into()),
                 b"dnt" => Ok(Dnt.into()),
+                #[cfg(any(feature = "rtsp", feature = "sip"))]
+                b"cseq" => Ok(CSeq.into()),
+                #[cfg(feature = "rtsp")]
+                b"session" => Ok(Session.into()),
+                #[cfg(feature = "rtsp")]
+                b"transport" => Ok(Transport.into()),
+                #[cfg(feature = "rtsp")]
+                b"media-properties" => Ok(MediaProperties.into()),
+                #[cfg(feature = "rtsp")]
+                b"media-range" => Ok(MediaRange.into()),
+                #[cfg(feature = "rtsp")]
+                b"notify-reason" => Ok(NotifyReason.into()),
+                #[cfg(feature = "rtsp")]
+                b"pipelined-requests" => Ok(PipelinedRequests.into()),
+                #[cfg(feature = "rtsp")]
+                b"request-status" => Ok(RequestStatus.into()),
+                #[cfg(feature = "rtsp")]
+                b"seek-style" => Ok(SeekStyle.into()),
+                #[cfg(feature = "rtsp")]
+                b"terminate-reason" => Ok(TerminateReason.into()),
                 b"date" => Ok(Date.into()),
                 b"etag" => Ok(Etag.into()),
                 b"from" => Ok(From.into()),
@@ -1635,6 +1789,7 @@ fn parse_hdr<'a>(
                 b"origin" => Ok(Origin.into()),
                 b"pragma" => Ok(Pragma.into()),
                 b"server" => Ok(Server.into()),
+                b"sunset" => Ok(Sunset.into()),
                 b"alt-svc" => Ok(AltSvc.into()),
                 b"expires" => Ok(Expires.into()),
                 b"referer" => Ok(Referer.into()),
@@ -1650,6 +1805,7 @@ fn parse_hdr<'a>(
                 b"set-cookie" => Ok(SetCookie.into()),
                 b"user-agent" => Ok(UserAgent.into()),
                 b"retry-after" => Ok(RetryAfter.into()),
+                b"deprecation" => Ok(Deprecation.into()),
                 b"content-type" => Ok(ContentType.into()),
                 b"max-forwards" => Ok(MaxForwards.into()),
                 b"accept-ranges" => Ok(AcceptRanges.into()),
@@ -1727,6 +1883,11 @@ impl HeaderName {
                 let val = unsafe { ByteStr::from_utf8_unchecked(buf) };
                 Ok(Custom(val).into())
             }
+            Repr::Custom(MaybeLower { buf, lower: false }) if is_lowercase(buf) => {
+                let buf = Bytes::copy_from_slice(buf);
+                let val = unsafe { ByteStr::from_utf8_unchecked(buf) };
+                Ok(Custom(val).into())
+            }
             Repr::Custom(MaybeLower { buf, lower: false }) => {
                 use bytes::{BufMut};
                 let mut dst = BytesMut::with_capacity(buf.len());
@@ -1735,7 +1896,7 @@ impl HeaderName {
                     let b = HEADER_CHARS[*b as usize];
 
                     if b == 0 {
-                        return Err(InvalidHeaderName::new());
+                        return Err(InvalidHeaderName::invalid_byte(src, &HEADER_CHARS));
                     }
 
                     dst.put_u8(b);
@@ -1748,6 +1909,56 @@ impl HeaderName {
         }
     }
 
+    /// Converts a slice of bytes to an HTTP header name, following
+    /// `profile`.
+    ///
+    /// With `ParseProfile::Lenient`, the control characters of an invalid
+    /// name are stripped, and counted in `repairs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::ParseProfile;
+    /// # use httplike::header::*;
+    /// # use httplike::profile::Repairs;
+    /// let mut repairs = Repairs::new();
+    /// let hdr = HeaderName::from_bytes_with(b"\x00Host", ParseProfile::Lenient, &mut repairs);
+    /// assert_eq!(hdr.unwrap(), HOST);
+    /// assert_eq!(repairs.stripped_controls(), 1);
+    /// ```
+    pub fn from_bytes_with(
+        src: &[u8],
+        profile: ParseProfile,
+        repairs: &mut Repairs,
+    ) -> Result<HeaderName, InvalidHeaderName> {
+        match HeaderName::from_bytes(src) {
+            Err(_) if profile.is_lenient() => {
+                let mut found = Repairs::new();
+                let name = HeaderName::from_bytes(&profile::repair_name(src, &mut found))?;
+                repairs.add(&found);
+                Ok(name)
+            }
+            res => res,
+        }
+    }
+
+    /// Converts a `Bytes` buffer to a header name, sharing the buffer instead
+    /// of copying it when the name is custom and already lowercase.
+    pub(crate) fn from_shared(src: Bytes) -> Result<HeaderName, InvalidHeaderName> {
+        let mut buf = [0; 64];
+        let shared = match parse_hdr(&src, &mut buf, &HEADER_CHARS)?.inner {
+            Repr::Standard(std) => return Ok(std.into()),
+            Repr::Custom(MaybeLower { buf, lower: true }) => buf == &src[..],
+            Repr::Custom(MaybeLower { lower: false, .. }) => is_lowercase(&src),
+        };
+        if !shared {
+            return HeaderName::from_bytes(&src);
+        }
+
+        let val = unsafe { ByteStr::from_utf8_unchecked(src) };
+        Ok(Custom(val).into())
+    }
+
     /// Converts a slice of bytes to an HTTP header name.
     ///
     /// This function expects the input to only contain lowercase characters.
@@ -1778,10 +1989,8 @@ impl HeaderName {
                 Ok(Custom(val).into())
             }
             Repr::Custom(MaybeLower { buf, lower: false }) => {
-                for &b in buf.iter() {
-                    if b != HEADER_CHARS[b as usize] {
-                        return Err(InvalidHeaderName::new());
-                    }
+                if !is_lowercase(buf) {
+                    return Err(InvalidHeaderName::invalid_byte(src, &HEADER_CHARS_H2));
                 }
 
                 let buf = Bytes::copy_from_slice(buf);
@@ -1867,6 +2076,20 @@ impl HeaderName {
         }
     }
 
+    /// Returns true for headers that commonly carry credentials or session
+    /// secrets.
+    pub(super) fn is_sensitive(&self) -> bool {
+        match self.inner {
+            Repr::Standard(StandardHeader::Authorization)
+            | Repr::Standard(StandardHeader::ProxyAuthorization) => true,
+            #[cfg(feature = "http")]
+            Repr::Standard(StandardHeader::Cookie) | Repr::Standard(StandardHeader::SetCookie) => {
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(super) fn into_bytes(self) -> Bytes {
         self.inner.into()
     }
@@ -1876,7 +2099,7 @@ impl FromStr for HeaderName {
     type Err = InvalidHeaderName;
 
     fn from_str(s: &str) -> Result<HeaderName, InvalidHeaderName> {
-        HeaderName::from_bytes(s.as_bytes()).map_err(|_| InvalidHeaderName { _priv: () })
+        HeaderName::from_bytes(s.as_bytes())
     }
 }
 
@@ -1912,7 +2135,18 @@ impl fmt::Display for HeaderName {
 
 impl InvalidHeaderName {
     fn new() -> InvalidHeaderName {
-        InvalidHeaderName { _priv: () }
+        InvalidHeaderName { offset: None }
+    }
+
+    // Points at the first byte of `src` that `table` doesn't allow.
+    fn invalid_byte(src: &[u8], table: &[u8; 256]) -> InvalidHeaderName {
+        InvalidHeaderName {
+            offset: src.iter().position(|&b| table[b as usize] == 0),
+        }
+    }
+
+    pub(crate) fn offset(&self) -> Option<usize> {
+        self.offset
     }
 }
 
@@ -1984,6 +2218,31 @@ impl From<Custom> for HeaderName {
     }
 }
 
+impl PartialOrd for HeaderName {
+    #[inline]
+    fn partial_cmp(&self, other: &HeaderName) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeaderName {
+    /// Compares the lower case string representations of the header names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use httplike::header::{ACCEPT, CONTENT_LENGTH, HeaderName};
+    ///
+    /// assert!(ACCEPT < CONTENT_LENGTH);
+    /// assert!(CONTENT_LENGTH < HeaderName::from_static("content-lengthy"));
+    /// assert!(HeaderName::from_static("x-a") < HeaderName::from_static("x-b"));
+    /// ```
+    #[inline]
+    fn cmp(&self, other: &HeaderName) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
 impl<'a> PartialEq<&'a HeaderName> for HeaderName {
     #[inline]
     fn eq(&self, other: &&'a HeaderName) -> bool {
@@ -2057,17 +2316,18 @@ impl<'a> PartialEq<HeaderName> for &'a str {
 impl fmt::Debug for InvalidHeaderName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("InvalidHeaderName")
-            // skip _priv noise
+            .field("offset", &self.offset)
             .finish()
     }
 }
 
 impl fmt::Display for InvalidHeaderName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.description().fmt(f)
+        f.write_str("invalid HTTP header name")
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for InvalidHeaderName {
     fn description(&self) -> &str {
         "invalid HTTP header name"
@@ -2358,6 +2618,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_bytes_too_long() {
+        let long = vec![b'a'; super::super::MAX_HEADER_NAME_LEN];
+        assert!(HeaderName::from_bytes(&long).is_err());
+        assert!(HeaderName::from_lowercase(&long).is_err());
+        assert!(HeaderName::from_bytes(&long[1..]).is_ok());
+    }
+
+    #[test]
+    fn test_from_bytes_custom_long() {
+        let lower = "longer-than-63--thisheaderislongerthansixtythreecharactersandthushandleddifferent";
+        let upper = lower.to_ascii_uppercase();
+        assert_eq!(HeaderName::from_bytes(upper.as_bytes()).unwrap(), lower);
+        assert_eq!(HeaderName::from_lowercase(lower.as_bytes()).unwrap(), lower);
+        assert!(HeaderName::from_lowercase(upper.as_bytes()).is_err());
+
+        let mut bytes = lower.as_bytes().to_vec();
+        bytes[70] = b'_';
+        assert!(HeaderName::from_lowercase(&bytes).is_ok());
+        bytes[70] = 0;
+        assert!(HeaderName::from_bytes(&bytes).is_err());
+        assert!(HeaderName::from_lowercase(&bytes).is_err());
+    }
+
     #[test]
     fn test_from_static_custom_single_char() {
         let a = HeaderName { inner: Repr::Custom(Custom(ByteStr::from_static("a"))) };
@@ -2375,4 +2659,22 @@ mod tests {
     fn test_all_tokens() {
         HeaderName::from_static("!#$%&'*+-.^_`|~0123456789abcdefghijklmnopqrstuvwxyz");
     }
+
+    #[test]
+    fn test_ord_matches_str_ord() {
+        let mut names = vec![
+            HeaderName::from_static("x-custom"),
+            ACCEPT,
+            HeaderName::from_static("content-lengthy"),
+            CONTENT_LENGTH,
+            HeaderName::from_static("a-custom-header-that-is-rather-long"),
+        ];
+        names.sort();
+
+        let strs: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        let mut sorted = strs.clone();
+        sorted.sort();
+        assert_eq!(strs, sorted);
+        assert_eq!(names[0], "a-custom-header-that-is-rather-long");
+    }
 }
@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+use super::{HeaderMap, HeaderName, HeaderValue};
+
+/// A `Debug` adapter for `HeaderMap` that masks header values.
+///
+/// Created by [`HeaderMap::debug_redacted`]. Values flagged as sensitive and
+/// all values of the names passed to [`mask`] are rendered as
+/// `Sensitive(len=..)`.
+///
+/// [`HeaderMap::debug_redacted`]: struct.HeaderMap.html#method.debug_redacted
+/// [`mask`]: #method.mask
+pub struct DebugRedacted<'a> {
+    map: &'a HeaderMap<HeaderValue>,
+    masked: Vec<HeaderName>,
+}
+
+struct Masked(usize);
+
+impl HeaderMap {
+    /// Returns a `Debug` adapter that redacts the values of sensitive
+    /// headers.
+    ///
+    /// Additional header names can be masked using
+    /// [`DebugRedacted::mask`](struct.DebugRedacted.html#method.mask).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::{AUTHORIZATION, HOST};
+    /// let mut map = HeaderMap::new();
+    /// map.insert(AUTHORIZATION, "Bearer abc".parse().unwrap());
+    /// map.insert("x-api-key", "123456".parse().unwrap());
+    /// map.insert(HOST, "example.com".parse().unwrap());
+    ///
+    /// let out = format!("{:?}", map.debug_redacted().mask("x-api-key"));
+    /// assert_eq!(
+    ///     out,
+    ///     r#"{"authorization": Sensitive(len=10), "x-api-key": Sensitive(len=6), "host": "example.com"}"#
+    /// );
+    /// ```
+    pub fn debug_redacted(&self) -> DebugRedacted<'_> {
+        DebugRedacted {
+            map: self,
+            masked: Vec::new(),
+        }
+    }
+}
+
+impl<'a> DebugRedacted<'a> {
+    /// Masks all values of the given header name.
+    ///
+    /// Names that are not valid header names are ignored.
+    pub fn mask<K>(mut self, name: K) -> Self
+    where
+        HeaderName: TryFrom<K>,
+    {
+        if let Ok(name) = HeaderName::try_from(name) {
+            self.masked.push(name);
+        }
+        self
+    }
+
+    fn is_masked(&self, name: &HeaderName, value: &HeaderValue) -> bool {
+        value.is_sensitive() || self.masked.contains(name)
+    }
+}
+
+impl<'a> fmt::Debug for DebugRedacted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in self.map.iter() {
+            if self.is_masked(name, value) {
+                map.entry(name, &Masked(value.len()));
+            } else {
+                map.entry(name, value);
+            }
+        }
+        map.finish()
+    }
+}
+
+impl fmt::Debug for Masked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sensitive(len={})", self.0)
+    }
+}
@@ -0,0 +1,308 @@
+//! The static tables of HPACK and QPACK.
+//!
+//! HPACK, the header compression of HTTP/2, and QPACK, the one of HTTP/3,
+//! both start with a fixed table of common fields, so that a field in the
+//! table is sent as its index. The tables are those of RFC 7541 appendix A
+//! and RFC 9204 appendix A. HPACK indices start at 1 and QPACK indices at 0,
+//! as on the wire.
+//!
+//! Names are the lowercase names of `HeaderName` and the pseudo-header
+//! fields of the `pseudo` module, so `name.as_str()` can be looked up as is.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::header::{static_table, CONTENT_TYPE};
+//! use httplike::header::static_table::Match;
+//!
+//! assert_eq!(static_table::hpack_find(":status", b"200"), Some(Match::Field(8)));
+//! assert_eq!(static_table::qpack_find(":status", b"200"), Some(Match::Field(25)));
+//!
+//! // Only the name is in the HPACK table.
+//! assert_eq!(
+//!     static_table::hpack_find(CONTENT_TYPE.as_str(), b"text/plain"),
+//!     Some(Match::Name(31))
+//! );
+//! assert_eq!(static_table::qpack_entry(53), Some(("content-type", "text/plain")));
+//! ```
+
+/// The result of looking a field up in a static table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Match {
+    /// An entry has both the name and the value, at this index.
+    Field(usize),
+    /// An entry has the name but not the value, at this index. It is the
+    /// first entry with the name.
+    Name(usize),
+}
+
+impl Match {
+    /// Returns the index of the entry.
+    pub fn index(&self) -> usize {
+        match *self {
+            Match::Field(index) | Match::Name(index) => index,
+        }
+    }
+}
+
+/// Returns the entry of the HPACK static table at `index`.
+///
+/// Entries without a value have an empty one.
+pub fn hpack_entry(index: usize) -> Option<(&'static str, &'static str)> {
+    index.checked_sub(1).and_then(|i| HPACK.get(i)).cloned()
+}
+
+/// Looks a field up in the HPACK static table.
+pub fn hpack_find(name: &str, value: &[u8]) -> Option<Match> {
+    find(HPACK, name, value).map(|m| match m {
+        Match::Field(i) => Match::Field(i + 1),
+        Match::Name(i) => Match::Name(i + 1),
+    })
+}
+
+/// Returns the entry of the QPACK static table at `index`.
+///
+/// Entries without a value have an empty one.
+pub fn qpack_entry(index: usize) -> Option<(&'static str, &'static str)> {
+    QPACK.get(index).cloned()
+}
+
+/// Looks a field up in the QPACK static table.
+pub fn qpack_find(name: &str, value: &[u8]) -> Option<Match> {
+    find(QPACK, name, value)
+}
+
+fn find(table: &[(&str, &str)], name: &str, value: &[u8]) -> Option<Match> {
+    let mut first = None;
+    for (i, &(n, v)) in table.iter().enumerate() {
+        if n != name {
+            continue;
+        }
+        if v.as_bytes() == value {
+            return Some(Match::Field(i));
+        }
+        if first.is_none() {
+            first = Some(Match::Name(i));
+        }
+    }
+    first
+}
+
+const HPACK: &[(&str, &str)] = &[
+    (":authority", ""),
+    (":method", "GET"),
+    (":method", "POST"),
+    (":path", "/"),
+    (":path", "/index.html"),
+    (":scheme", "http"),
+    (":scheme", "https"),
+    (":status", "200"),
+    (":status", "204"),
+    (":status", "206"),
+    (":status", "304"),
+    (":status", "400"),
+    (":status", "404"),
+    (":status", "500"),
+    ("accept-charset", ""),
+    ("accept-encoding", "gzip, deflate"),
+    ("accept-language", ""),
+    ("accept-ranges", ""),
+    ("accept", ""),
+    ("access-control-allow-origin", ""),
+    ("age", ""),
+    ("allow", ""),
+    ("authorization", ""),
+    ("cache-control", ""),
+    ("content-disposition", ""),
+    ("content-encoding", ""),
+    ("content-language", ""),
+    ("content-length", ""),
+    ("content-location", ""),
+    ("content-range", ""),
+    ("content-type", ""),
+    ("cookie", ""),
+    ("date", ""),
+    ("etag", ""),
+    ("expect", ""),
+    ("expires", ""),
+    ("from", ""),
+    ("host", ""),
+    ("if-match", ""),
+    ("if-modified-since", ""),
+    ("if-none-match", ""),
+    ("if-range", ""),
+    ("if-unmodified-since", ""),
+    ("last-modified", ""),
+    ("link", ""),
+    ("location", ""),
+    ("max-forwards", ""),
+    ("proxy-authenticate", ""),
+    ("proxy-authorization", ""),
+    ("range", ""),
+    ("referer", ""),
+    ("refresh", ""),
+    ("retry-after", ""),
+    ("server", ""),
+    ("set-cookie", ""),
+    ("strict-transport-security", ""),
+    ("transfer-encoding", ""),
+    ("user-agent", ""),
+    ("vary", ""),
+    ("via", ""),
+    ("www-authenticate", ""),
+];
+
+const QPACK: &[(&str, &str)] = &[
+    (":authority", ""),
+    (":path", "/"),
+    ("age", "0"),
+    ("content-disposition", ""),
+    ("content-length", "0"),
+    ("cookie", ""),
+    ("date", ""),
+    ("etag", ""),
+    ("if-modified-since", ""),
+    ("if-none-match", ""),
+    ("last-modified", ""),
+    ("link", ""),
+    ("location", ""),
+    ("referer", ""),
+    ("set-cookie", ""),
+    (":method", "CONNECT"),
+    (":method", "DELETE"),
+    (":method", "GET"),
+    (":method", "HEAD"),
+    (":method", "OPTIONS"),
+    (":method", "POST"),
+    (":method", "PUT"),
+    (":scheme", "http"),
+    (":scheme", "https"),
+    (":status", "103"),
+    (":status", "200"),
+    (":status", "304"),
+    (":status", "404"),
+    (":status", "503"),
+    ("accept", "*/*"),
+    ("accept", "application/dns-message"),
+    ("accept-encoding", "gzip, deflate, br"),
+    ("accept-ranges", "bytes"),
+    ("access-control-allow-headers", "cache-control"),
+    ("access-control-allow-headers", "content-type"),
+    ("access-control-allow-origin", "*"),
+    ("cache-control", "max-age=0"),
+    ("cache-control", "max-age=2592000"),
+    ("cache-control", "max-age=604800"),
+    ("cache-control", "no-cache"),
+    ("cache-control", "no-store"),
+    ("cache-control", "public, max-age=31536000"),
+    ("content-encoding", "br"),
+    ("content-encoding", "gzip"),
+    ("content-type", "application/dns-message"),
+    ("content-type", "application/javascript"),
+    ("content-type", "application/json"),
+    ("content-type", "application/x-www-form-urlencoded"),
+    ("content-type", "image/gif"),
+    ("content-type", "image/jpeg"),
+    ("content-type", "image/png"),
+    ("content-type", "text/css"),
+    ("content-type", "text/html; charset=utf-8"),
+    ("content-type", "text/plain"),
+    ("content-type", "text/plain;charset=utf-8"),
+    ("range", "bytes=0-"),
+    ("strict-transport-security", "max-age=31536000"),
+    (
+        "strict-transport-security",
+        "max-age=31536000; includesubdomains",
+    ),
+    (
+        "strict-transport-security",
+        "max-age=31536000; includesubdomains; preload",
+    ),
+    ("vary", "accept-encoding"),
+    ("vary", "origin"),
+    ("x-content-type-options", "nosniff"),
+    ("x-xss-protection", "1; mode=block"),
+    (":status", "100"),
+    (":status", "204"),
+    (":status", "206"),
+    (":status", "302"),
+    (":status", "400"),
+    (":status", "403"),
+    (":status", "421"),
+    (":status", "425"),
+    (":status", "500"),
+    ("accept-language", ""),
+    ("access-control-allow-credentials", "FALSE"),
+    ("access-control-allow-credentials", "TRUE"),
+    ("access-control-allow-headers", "*"),
+    ("access-control-allow-methods", "get"),
+    ("access-control-allow-methods", "get, post, options"),
+    ("access-control-allow-methods", "options"),
+    ("access-control-expose-headers", "content-length"),
+    ("access-control-request-headers", "content-type"),
+    ("access-control-request-method", "get"),
+    ("access-control-request-method", "post"),
+    ("alt-svc", "clear"),
+    ("authorization", ""),
+    (
+        "content-security-policy",
+        "script-src 'none'; object-src 'none'; base-uri 'none'",
+    ),
+    ("early-data", "1"),
+    ("expect-ct", ""),
+    ("forwarded", ""),
+    ("if-range", ""),
+    ("origin", ""),
+    ("purpose", "prefetch"),
+    ("server", ""),
+    ("timing-allow-origin", "*"),
+    ("upgrade-insecure-requests", "1"),
+    ("user-agent", ""),
+    ("x-forwarded-for", ""),
+    ("x-frame-options", "deny"),
+    ("x-frame-options", "sameorigin"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::{HeaderName, HeaderValue};
+
+    #[test]
+    fn table_sizes() {
+        assert_eq!(HPACK.len(), 61);
+        assert_eq!(hpack_entry(0), None);
+        assert_eq!(hpack_entry(61), Some(("www-authenticate", "")));
+        assert_eq!(hpack_entry(62), None);
+
+        assert_eq!(QPACK.len(), 99);
+        assert_eq!(qpack_entry(98), Some(("x-frame-options", "sameorigin")));
+        assert_eq!(qpack_entry(99), None);
+    }
+
+    #[test]
+    fn entries_are_valid_fields() {
+        for &(name, value) in HPACK.iter().chain(QPACK) {
+            if !name.starts_with(':') {
+                let parsed = HeaderName::from_static(name);
+                assert_eq!(parsed.as_str(), name);
+            }
+            HeaderValue::from_static(value);
+        }
+    }
+
+    #[test]
+    fn lookups() {
+        assert_eq!(hpack_find(":method", b"GET"), Some(Match::Field(2)));
+        assert_eq!(hpack_find(":method", b"PUT"), Some(Match::Name(2)));
+        assert_eq!(hpack_find("x-custom", b""), None);
+        assert_eq!(hpack_find("host", b""), Some(Match::Field(38)));
+
+        assert_eq!(qpack_find(":method", b"PUT"), Some(Match::Field(21)));
+        assert_eq!(qpack_find(":status", b"418"), Some(Match::Name(24)));
+        assert_eq!(
+            qpack_find("cache-control", b"private").map(|m| m.index()),
+            Some(36)
+        );
+    }
+}
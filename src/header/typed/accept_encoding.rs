@@ -0,0 +1,223 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
+use super::quality_list::split_quality;
+use super::{csv, fmt_quality, ContentCoding, Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, ACCEPT_ENCODING};
+
+/// The `Accept-Encoding` header.
+///
+/// Each coding has a quality between 0 and 1000, in thousandths of the
+/// `q` parameter, so `gzip;q=0.5` has a quality of 500 and a coding without
+/// `q` a quality of 1000. A quality of 0 means the coding is not acceptable.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::{AcceptEncoding, ContentCoding};
+/// let mut map = HeaderMap::new();
+/// map.insert("accept-encoding", "gzip;q=0.8, br, identity;q=0".parse().unwrap());
+///
+/// let accept: AcceptEncoding = map.typed_get().unwrap();
+/// assert_eq!(accept.quality(&ContentCoding::Gzip), 800);
+/// assert_eq!(accept.quality(&ContentCoding::Zstd), 0);
+///
+/// let supported = [ContentCoding::Zstd, ContentCoding::Gzip, ContentCoding::Br];
+/// assert_eq!(accept.negotiate(&supported), Some(ContentCoding::Br));
+/// assert_eq!(accept.negotiate(&[ContentCoding::Zstd]), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AcceptEncoding {
+    // `None` is the `*` wildcard.
+    items: Vec<(Option<ContentCoding>, u16)>,
+}
+
+impl AcceptEncoding {
+    /// Creates an empty `AcceptEncoding`, accepting only `identity`.
+    pub fn new() -> AcceptEncoding {
+        AcceptEncoding::default()
+    }
+
+    /// Adds a coding with a quality, in thousandths.
+    ///
+    /// Qualities above 1000 are lowered to 1000.
+    pub fn with(mut self, coding: ContentCoding, quality: u16) -> AcceptEncoding {
+        self.items.push((Some(coding), cmp::min(quality, 1000)));
+        self
+    }
+
+    /// Adds the `*` wildcard with a quality, in thousandths, for the codings
+    /// not listed otherwise.
+    ///
+    /// Qualities above 1000 are lowered to 1000.
+    pub fn with_any(mut self, quality: u16) -> AcceptEncoding {
+        self.items.push((None, cmp::min(quality, 1000)));
+        self
+    }
+
+    /// Returns the quality of `coding`, in thousandths.
+    ///
+    /// A coding that is not listed has the quality of the `*` wildcard, or
+    /// of 0 without a wildcard. `identity` is the exception: it is
+    /// acceptable unless refused with `identity;q=0` or `*;q=0`, as
+    /// specified by RFC 7231 section 5.3.4.
+    pub fn quality(&self, coding: &ContentCoding) -> u16 {
+        let listed = self
+            .items
+            .iter()
+            .find(|item| item.0.as_ref() == Some(coding))
+            .or_else(|| self.items.iter().find(|item| item.0.is_none()));
+
+        match listed {
+            Some(&(_, quality)) => quality,
+            None if *coding == ContentCoding::Identity => 1000,
+            None => 0,
+        }
+    }
+
+    /// Chooses the coding of a response among the `supported` ones.
+    ///
+    /// This returns the supported coding with the highest quality. Codings
+    /// with the same quality are chosen in the order of `supported`, so the
+    /// most preferred coding should come first. When no supported coding is
+    /// acceptable, `identity` is returned if it is, and `None` if it isn't,
+    /// in which case the response should be `406 Not Acceptable` or sent
+    /// unencoded anyway.
+    pub fn negotiate(&self, supported: &[ContentCoding]) -> Option<ContentCoding> {
+        let mut best: Option<(&ContentCoding, u16)> = None;
+        for coding in supported {
+            let quality = self.quality(coding);
+            if quality > best.map_or(0, |best| best.1) {
+                best = Some((coding, quality));
+            }
+        }
+
+        match best {
+            Some((coding, _)) => Some(coding.clone()),
+            None if self.quality(&ContentCoding::Identity) > 0 => Some(ContentCoding::Identity),
+            None => None,
+        }
+    }
+}
+
+impl Header for AcceptEncoding {
+    fn name() -> &'static HeaderName {
+        &ACCEPT_ENCODING
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let mut accept = AcceptEncoding::new();
+
+        for element in csv(values)? {
+            let (item, quality) = split_quality(element).ok_or_else(InvalidTypedHeader::new)?;
+            // Codings have no parameters of their own.
+            let coding = item.split(';').next().unwrap_or("").trim();
+            let coding = if coding == "*" {
+                None
+            } else {
+                Some(coding.parse()?)
+            };
+
+            accept.items.push((coding, quality));
+        }
+
+        Ok(accept)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        // Codings are tokens, and qualities decimal numbers.
+        if let Ok(value) = HeaderValue::try_from(s) {
+            values.extend(Some(value));
+        }
+    }
+}
+
+impl fmt::Display for AcceptEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+
+        for &(ref coding, quality) in &self.items {
+            match *coding {
+                Some(ref coding) => write!(f, "{}{}", sep, coding)?,
+                None => write!(f, "{}*", sep)?,
+            }
+            fmt_quality(f, quality)?;
+            sep = ", ";
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(s: &str) -> Option<AcceptEncoding> {
+        let mut map = HeaderMap::new();
+        map.insert(ACCEPT_ENCODING, s.parse().unwrap());
+        map.typed_try_get().ok().and_then(|accept| accept)
+    }
+
+    #[test]
+    fn round_trip() {
+        let accept = decode("GZIP;Q=0.50, x-custom ; level=9, *;q=0.001").unwrap();
+        assert_eq!(accept.quality(&ContentCoding::Gzip), 500);
+        assert_eq!(
+            accept.quality(&ContentCoding::Other("x-custom".into())),
+            1000
+        );
+        assert_eq!(accept.quality(&ContentCoding::Br), 1);
+        assert_eq!(accept.to_string(), "gzip;q=0.5, x-custom, *;q=0.001");
+
+        let built = AcceptEncoding::new()
+            .with(ContentCoding::Zstd, 1000)
+            .with(ContentCoding::Identity, 0)
+            .with_any(1200);
+        assert_eq!(built.to_string(), "zstd, identity;q=0, *");
+    }
+
+    #[test]
+    fn invalid_qualities() {
+        assert!(decode("gzip;q=1.5").is_none());
+        assert!(decode("gzip;q=0.1234").is_none());
+        assert!(decode("gzip;q=").is_none());
+        assert!(decode("gzip;q=1.000").is_some());
+        assert!(decode("gz(ip").is_none());
+    }
+
+    #[test]
+    fn identity_rules() {
+        let empty = decode("").unwrap();
+        assert_eq!(
+            empty.negotiate(&[ContentCoding::Gzip]),
+            Some(ContentCoding::Identity)
+        );
+
+        let no_identity = decode("identity;q=0").unwrap();
+        assert_eq!(no_identity.negotiate(&[ContentCoding::Gzip]), None);
+
+        let nothing_else = decode("*;q=0, br;q=0.1").unwrap();
+        assert_eq!(nothing_else.quality(&ContentCoding::Identity), 0);
+        assert_eq!(
+            nothing_else.negotiate(&[ContentCoding::Gzip, ContentCoding::Br]),
+            Some(ContentCoding::Br)
+        );
+
+        let anything = decode("*").unwrap();
+        assert_eq!(
+            anything.negotiate(&[ContentCoding::Zstd, ContentCoding::Gzip]),
+            Some(ContentCoding::Zstd)
+        );
+    }
+}
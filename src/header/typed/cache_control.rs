@@ -0,0 +1,273 @@
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::time::Duration;
+
+use super::{csv, Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, CACHE_CONTROL};
+
+/// The `Cache-Control` header.
+///
+/// Unknown directives are ignored when decoding.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use httplike::header::typed::CacheControl;
+/// let cc = CacheControl::new()
+///     .with_private()
+///     .with_max_age(Duration::from_secs(60));
+///
+/// assert_eq!(cc.to_string(), "private, max-age=60");
+/// assert_eq!(cc.max_age(), Some(Duration::from_secs(60)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CacheControl {
+    flags: u16,
+    max_age: Option<u64>,
+    s_max_age: Option<u64>,
+    max_stale: Option<u64>,
+    min_fresh: Option<u64>,
+}
+
+const NO_CACHE: u16 = 1 << 0;
+const NO_STORE: u16 = 1 << 1;
+const NO_TRANSFORM: u16 = 1 << 2;
+const ONLY_IF_CACHED: u16 = 1 << 3;
+const MUST_REVALIDATE: u16 = 1 << 4;
+const PROXY_REVALIDATE: u16 = 1 << 5;
+const PUBLIC: u16 = 1 << 6;
+const PRIVATE: u16 = 1 << 7;
+const IMMUTABLE: u16 = 1 << 8;
+// `max-stale` without a value accepts any staleness.
+const MAX_STALE_ANY: u16 = 1 << 9;
+
+const FLAGS: &[(u16, &str)] = &[
+    (NO_CACHE, "no-cache"),
+    (NO_STORE, "no-store"),
+    (NO_TRANSFORM, "no-transform"),
+    (ONLY_IF_CACHED, "only-if-cached"),
+    (MUST_REVALIDATE, "must-revalidate"),
+    (PROXY_REVALIDATE, "proxy-revalidate"),
+    (PUBLIC, "public"),
+    (PRIVATE, "private"),
+    (IMMUTABLE, "immutable"),
+    (MAX_STALE_ANY, "max-stale"),
+];
+
+macro_rules! flags {
+    ($($(#[$docs:meta])* ($flag:ident, $ctor:ident, $with:ident, $is:ident);)+) => {
+        impl CacheControl {
+            $(
+                $(#[$docs])*
+                ///
+                /// Shorthand for `CacheControl::new()` with only this directive.
+                pub fn $ctor() -> CacheControl {
+                    CacheControl::new().$with()
+                }
+
+                $(#[$docs])*
+                pub fn $with(mut self) -> CacheControl {
+                    self.flags |= $flag;
+                    self
+                }
+
+                $(#[$docs])*
+                ///
+                /// Returns true if the directive is present.
+                pub fn $is(&self) -> bool {
+                    self.flags & $flag != 0
+                }
+            )+
+        }
+    }
+}
+
+flags! {
+    /// The `no-cache` directive.
+    (NO_CACHE, no_cache, with_no_cache, is_no_cache);
+    /// The `no-store` directive.
+    (NO_STORE, no_store, with_no_store, is_no_store);
+    /// The `no-transform` directive.
+    (NO_TRANSFORM, no_transform, with_no_transform, is_no_transform);
+    /// The `only-if-cached` directive.
+    (ONLY_IF_CACHED, only_if_cached, with_only_if_cached, is_only_if_cached);
+    /// The `must-revalidate` directive.
+    (MUST_REVALIDATE, must_revalidate, with_must_revalidate, is_must_revalidate);
+    /// The `proxy-revalidate` directive.
+    (PROXY_REVALIDATE, proxy_revalidate, with_proxy_revalidate, is_proxy_revalidate);
+    /// The `public` directive.
+    (PUBLIC, public, with_public, is_public);
+    /// The `private` directive.
+    (PRIVATE, private, with_private, is_private);
+    /// The `immutable` directive.
+    (IMMUTABLE, immutable, with_immutable, is_immutable);
+}
+
+impl CacheControl {
+    /// Creates an empty `CacheControl`.
+    pub fn new() -> CacheControl {
+        CacheControl::default()
+    }
+
+    /// Sets the `max-age` directive.
+    pub fn with_max_age(mut self, age: Duration) -> CacheControl {
+        self.max_age = Some(age.as_secs());
+        self
+    }
+
+    /// Sets the `s-maxage` directive.
+    pub fn with_s_max_age(mut self, age: Duration) -> CacheControl {
+        self.s_max_age = Some(age.as_secs());
+        self
+    }
+
+    /// Sets the `max-stale` directive.
+    pub fn with_max_stale(mut self, stale: Duration) -> CacheControl {
+        self.max_stale = Some(stale.as_secs());
+        self
+    }
+
+    /// Sets the `min-fresh` directive.
+    pub fn with_min_fresh(mut self, fresh: Duration) -> CacheControl {
+        self.min_fresh = Some(fresh.as_secs());
+        self
+    }
+
+    /// Returns the `max-age` directive, if present.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age.map(Duration::from_secs)
+    }
+
+    /// Returns the `s-maxage` directive, if present.
+    pub fn s_max_age(&self) -> Option<Duration> {
+        self.s_max_age.map(Duration::from_secs)
+    }
+
+    /// Returns the `max-stale` directive, if present with a value.
+    pub fn max_stale(&self) -> Option<Duration> {
+        self.max_stale.map(Duration::from_secs)
+    }
+
+    /// Returns the `min-fresh` directive, if present.
+    pub fn min_fresh(&self) -> Option<Duration> {
+        self.min_fresh.map(Duration::from_secs)
+    }
+}
+
+impl Header for CacheControl {
+    fn name() -> &'static HeaderName {
+        &CACHE_CONTROL
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let mut cc = CacheControl::new();
+
+        for directive in csv(values)? {
+            let mut parts = directive.splitn(2, '=');
+            let name = parts.next().unwrap_or("").trim();
+            let arg = parts.next().map(|v| v.trim().trim_matches('"'));
+
+            let slot = if name.eq_ignore_ascii_case("max-age") {
+                &mut cc.max_age
+            } else if name.eq_ignore_ascii_case("s-maxage") {
+                &mut cc.s_max_age
+            } else if name.eq_ignore_ascii_case("min-fresh") {
+                &mut cc.min_fresh
+            } else if name.eq_ignore_ascii_case("max-stale") && arg.is_some() {
+                &mut cc.max_stale
+            } else {
+                if let Some(&(flag, _)) = FLAGS.iter().find(|f| name.eq_ignore_ascii_case(f.1)) {
+                    cc.flags |= flag;
+                }
+                continue;
+            };
+
+            let seconds = arg.and_then(|arg| arg.parse().ok());
+            *slot = Some(seconds.ok_or_else(InvalidTypedHeader::new)?);
+        }
+
+        Ok(cc)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        // Directive names and decimal numbers are always valid header values.
+        if let Ok(value) = HeaderValue::try_from(s) {
+            values.extend(Some(value));
+        }
+    }
+}
+
+impl fmt::Display for CacheControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+
+        for &(flag, name) in FLAGS {
+            if self.flags & flag != 0 {
+                write!(f, "{}{}", sep, name)?;
+                sep = ", ";
+            }
+        }
+
+        let args = [
+            ("max-age", self.max_age),
+            ("s-maxage", self.s_max_age),
+            ("max-stale", self.max_stale),
+            ("min-fresh", self.min_fresh),
+        ];
+
+        for &(name, arg) in &args {
+            if let Some(secs) = arg {
+                write!(f, "{}{}={}", sep, name, secs)?;
+                sep = ", ";
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    #[test]
+    fn round_trip() {
+        let mut map = HeaderMap::new();
+        map.append(CACHE_CONTROL, "no-cache, max-age=0".parse().unwrap());
+        map.append(CACHE_CONTROL, "Must-Revalidate, x-ext=\"foo\"".parse().unwrap());
+
+        let cc: CacheControl = map.typed_get().unwrap();
+        assert!(cc.is_no_cache());
+        assert!(cc.is_must_revalidate());
+        assert!(!cc.is_no_store());
+        assert_eq!(cc.max_age(), Some(Duration::from_secs(0)));
+
+        map.typed_insert(cc);
+        assert_eq!(map[CACHE_CONTROL], "no-cache, must-revalidate, max-age=0");
+    }
+
+    #[test]
+    fn max_stale_without_value() {
+        let mut map = HeaderMap::new();
+        map.insert(CACHE_CONTROL, "max-stale".parse().unwrap());
+
+        let cc: CacheControl = map.typed_get().unwrap();
+        assert_eq!(cc.max_stale(), None);
+        assert_eq!(cc.to_string(), "max-stale");
+    }
+
+    #[test]
+    fn invalid_argument() {
+        let mut map = HeaderMap::new();
+        map.insert(CACHE_CONTROL, "max-age=soon".parse().unwrap());
+        assert!(map.typed_try_get::<CacheControl>().is_err());
+    }
+}
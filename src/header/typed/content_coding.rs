@@ -0,0 +1,89 @@
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use super::InvalidTypedHeader;
+
+/// A content coding, as used in `Accept-Encoding` and `Content-Encoding`.
+///
+/// The codings of the IANA registry that are in common use have their own
+/// variant, and other codings are kept as `Other` with a lowercase token.
+/// Codings are case-insensitive, and `x-gzip` is the same as `gzip`, as
+/// required by RFC 7230 section 4.2.3.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::header::typed::ContentCoding;
+/// let coding: ContentCoding = "X-GZIP".parse().unwrap();
+/// assert_eq!(coding, ContentCoding::Gzip);
+/// assert_eq!(coding.as_str(), "gzip");
+///
+/// let coding: ContentCoding = "Snappy".parse().unwrap();
+/// assert_eq!(coding.as_str(), "snappy");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ContentCoding {
+    /// `identity`, no transformation.
+    Identity,
+    /// `gzip`, RFC 1952.
+    Gzip,
+    /// `deflate`, the zlib format of RFC 1950.
+    Deflate,
+    /// `br`, Brotli, RFC 7932.
+    Br,
+    /// `zstd`, Zstandard, RFC 8878.
+    Zstd,
+    /// Any other coding, as a lowercase token.
+    Other(String),
+}
+
+impl ContentCoding {
+    /// Returns the token of the coding.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            ContentCoding::Identity => "identity",
+            ContentCoding::Gzip => "gzip",
+            ContentCoding::Deflate => "deflate",
+            ContentCoding::Br => "br",
+            ContentCoding::Zstd => "zstd",
+            ContentCoding::Other(ref token) => token,
+        }
+    }
+}
+
+impl FromStr for ContentCoding {
+    type Err = InvalidTypedHeader;
+
+    fn from_str(s: &str) -> Result<ContentCoding, InvalidTypedHeader> {
+        if s.is_empty() || !s.bytes().all(is_tchar) {
+            return Err(InvalidTypedHeader::new());
+        }
+
+        let known = [
+            ("identity", ContentCoding::Identity),
+            ("gzip", ContentCoding::Gzip),
+            ("x-gzip", ContentCoding::Gzip),
+            ("deflate", ContentCoding::Deflate),
+            ("br", ContentCoding::Br),
+            ("zstd", ContentCoding::Zstd),
+        ];
+        for &(token, ref coding) in &known {
+            if s.eq_ignore_ascii_case(token) {
+                return Ok(coding.clone());
+            }
+        }
+        Ok(ContentCoding::Other(s.to_ascii_lowercase()))
+    }
+}
+
+impl fmt::Display for ContentCoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// `tchar` of RFC 7230 section 3.2.6.
+pub(super) fn is_tchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
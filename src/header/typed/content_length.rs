@@ -0,0 +1,65 @@
+use super::{Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, CONTENT_LENGTH};
+
+/// The `Content-Length` header.
+///
+/// Repeated values are accepted as long as they are all identical, as
+/// allowed by RFC 7230 section 3.3.2.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::ContentLength;
+/// let mut map = HeaderMap::new();
+/// map.append("content-length", "5".parse().unwrap());
+/// map.append("content-length", "5".parse().unwrap());
+/// assert_eq!(map.typed_get(), Some(ContentLength(5)));
+///
+/// map.append("content-length", "6".parse().unwrap());
+/// assert!(map.typed_get::<ContentLength>().is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContentLength(pub u64);
+
+impl Header for ContentLength {
+    fn name() -> &'static HeaderName {
+        &CONTENT_LENGTH
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let mut len = None;
+
+        for value in values {
+            let parsed = parse_u64(value.as_bytes()).ok_or_else(InvalidTypedHeader::new)?;
+            match len {
+                Some(prev) if prev != parsed => return Err(InvalidTypedHeader::new()),
+                _ => len = Some(parsed),
+            }
+        }
+
+        len.map(ContentLength).ok_or_else(InvalidTypedHeader::new)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(Some(HeaderValue::from(self.0)));
+    }
+}
+
+fn parse_u64(bytes: &[u8]) -> Option<u64> {
+    if bytes.is_empty() {
+        return None;
+    }
+
+    let mut n: u64 = 0;
+    for &b in bytes {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        n = n.checked_mul(10)?.checked_add(u64::from(b - b'0'))?;
+    }
+    Some(n)
+}
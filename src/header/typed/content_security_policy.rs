@@ -0,0 +1,426 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
+use super::{Header, InvalidTypedHeader};
+use crate::header::{
+    HeaderName, HeaderValue, CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY_REPORT_ONLY,
+};
+
+/// The `Content-Security-Policy` header, restricting the resources a page
+/// may load.
+///
+/// A policy is a list of directives, each with a list of sources, which are
+/// quoted as needed when encoding. Adding a source to a directive with
+/// [`with_source`] lets several layers build one policy.
+///
+/// A header with several policies asks browsers to enforce all of them, so
+/// they can't be merged into one; only the first is decoded. Directive
+/// names are lowercased, and a directive given twice is only kept the first
+/// time, like browsers do.
+///
+/// [`with_source`]: #method.with_source
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::{ContentSecurityPolicy, Source};
+/// let csp = ContentSecurityPolicy::new()
+///     .with("default-src", vec![Source::SELF])
+///     .with_source("script-src", Source::SELF)
+///     .with_source("script-src", Source::nonce("rAnd0m").unwrap())
+///     .with_source("img-src", Source::raw("https://cdn.example.com").unwrap())
+///     .with_source("img-src", Source::scheme("data").unwrap())
+///     .with("upgrade-insecure-requests", vec![]);
+///
+/// let mut map = HeaderMap::new();
+/// map.typed_insert(csp.clone());
+/// assert_eq!(
+///     map["content-security-policy"],
+///     "default-src 'self'; script-src 'self' 'nonce-rAnd0m'; \
+///      img-src https://cdn.example.com data:; upgrade-insecure-requests"
+/// );
+/// assert_eq!(map.typed_get(), Some(csp));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ContentSecurityPolicy {
+    directives: Vec<(String, Vec<Source>)>,
+}
+
+/// The `Content-Security-Policy-Report-Only` header, a policy that browsers
+/// report violations of without enforcing it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ContentSecurityPolicyReportOnly(pub ContentSecurityPolicy);
+
+/// A source of a `Content-Security-Policy` directive, like `'self'` or
+/// `https://cdn.example.com`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Source(Cow<'static, str>);
+
+impl ContentSecurityPolicy {
+    /// Creates a policy without directives.
+    pub fn new() -> ContentSecurityPolicy {
+        ContentSecurityPolicy::default()
+    }
+
+    /// Sets a directive, replacing its sources if it is already present.
+    ///
+    /// Directives without sources, like `upgrade-insecure-requests`, are
+    /// set with an empty list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not made of ASCII letters, digits and dashes.
+    pub fn with<I>(mut self, name: &str, sources: I) -> ContentSecurityPolicy
+    where
+        I: IntoIterator<Item = Source>,
+    {
+        let sources = sources.into_iter().collect();
+        *self.directive_mut(name) = sources;
+        self
+    }
+
+    /// Adds a source to a directive, adding the directive if it is missing.
+    ///
+    /// A source already present is not added again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is not made of ASCII letters, digits and dashes.
+    pub fn with_source(mut self, name: &str, source: Source) -> ContentSecurityPolicy {
+        let sources = self.directive_mut(name);
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+        self
+    }
+
+    /// Returns the sources of a directive, if present.
+    pub fn sources(&self, name: &str) -> Option<&[Source]> {
+        self.directives
+            .iter()
+            .find(|d| d.0.eq_ignore_ascii_case(name))
+            .map(|d| &d.1[..])
+    }
+
+    /// Returns true if the policy has no directives.
+    pub fn is_empty(&self) -> bool {
+        self.directives.is_empty()
+    }
+
+    fn directive_mut(&mut self, name: &str) -> &mut Vec<Source> {
+        assert!(is_directive_name(name), "invalid directive name");
+        let i = match self
+            .directives
+            .iter()
+            .position(|d| d.0.eq_ignore_ascii_case(name))
+        {
+            Some(i) => i,
+            None => {
+                self.directives
+                    .push((name.to_ascii_lowercase(), Vec::new()));
+                self.directives.len() - 1
+            }
+        };
+        &mut self.directives[i].1
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<ContentSecurityPolicy, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(InvalidTypedHeader::new)?;
+        let s = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+
+        let mut csp = ContentSecurityPolicy::new();
+        for directive in s.split(',').next().unwrap_or("").split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let name = match tokens.next() {
+                Some(name) => name,
+                None => continue,
+            };
+            if !is_directive_name(name) {
+                return Err(InvalidTypedHeader::new());
+            }
+            if csp.sources(name).is_none() {
+                let sources = tokens.map(Source::decode).collect();
+                csp.directives.push((name.to_ascii_lowercase(), sources));
+            }
+        }
+        Ok(csp)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        let value = HeaderValue::try_from(s).expect("policies are valid header values");
+        values.extend(Some(value));
+    }
+}
+
+impl Header for ContentSecurityPolicy {
+    fn name() -> &'static HeaderName {
+        &CONTENT_SECURITY_POLICY
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        ContentSecurityPolicy::decode(values)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        ContentSecurityPolicy::encode(self, values)
+    }
+}
+
+impl Header for ContentSecurityPolicyReportOnly {
+    fn name() -> &'static HeaderName {
+        &CONTENT_SECURITY_POLICY_REPORT_ONLY
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        ContentSecurityPolicy::decode(values).map(ContentSecurityPolicyReportOnly)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        self.0.encode(values)
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+        for (name, sources) in &self.directives {
+            write!(f, "{}{}", sep, name)?;
+            for source in sources {
+                write!(f, " {}", source)?;
+            }
+            sep = "; ";
+        }
+        Ok(())
+    }
+}
+
+const KEYWORDS: &[Source] = &[
+    Source::NONE,
+    Source::SELF,
+    Source::UNSAFE_INLINE,
+    Source::UNSAFE_EVAL,
+    Source::UNSAFE_HASHES,
+    Source::STRICT_DYNAMIC,
+    Source::REPORT_SAMPLE,
+    Source::WASM_UNSAFE_EVAL,
+];
+
+impl Source {
+    /// `'none'`, allowing nothing.
+    pub const NONE: Source = Source(Cow::Borrowed("'none'"));
+    /// `'self'`, the origin of the page.
+    pub const SELF: Source = Source(Cow::Borrowed("'self'"));
+    /// `'unsafe-inline'`
+    pub const UNSAFE_INLINE: Source = Source(Cow::Borrowed("'unsafe-inline'"));
+    /// `'unsafe-eval'`
+    pub const UNSAFE_EVAL: Source = Source(Cow::Borrowed("'unsafe-eval'"));
+    /// `'unsafe-hashes'`
+    pub const UNSAFE_HASHES: Source = Source(Cow::Borrowed("'unsafe-hashes'"));
+    /// `'strict-dynamic'`
+    pub const STRICT_DYNAMIC: Source = Source(Cow::Borrowed("'strict-dynamic'"));
+    /// `'report-sample'`
+    pub const REPORT_SAMPLE: Source = Source(Cow::Borrowed("'report-sample'"));
+    /// `'wasm-unsafe-eval'`
+    pub const WASM_UNSAFE_EVAL: Source = Source(Cow::Borrowed("'wasm-unsafe-eval'"));
+
+    /// Creates a `'nonce-...'` source from a base64 nonce.
+    ///
+    /// Returns `None` if the nonce is not base64 or base64url.
+    pub fn nonce(nonce: &str) -> Option<Source> {
+        Source::quoted("nonce", nonce)
+    }
+
+    /// Creates a `'sha256-...'` source from the base64 digest of a script or
+    /// style.
+    ///
+    /// Returns `None` if the digest is not base64 or base64url.
+    pub fn sha256(digest: &str) -> Option<Source> {
+        Source::quoted("sha256", digest)
+    }
+
+    /// Creates a `'sha384-...'` source from the base64 digest of a script or
+    /// style.
+    ///
+    /// Returns `None` if the digest is not base64 or base64url.
+    pub fn sha384(digest: &str) -> Option<Source> {
+        Source::quoted("sha384", digest)
+    }
+
+    /// Creates a `'sha512-...'` source from the base64 digest of a script or
+    /// style.
+    ///
+    /// Returns `None` if the digest is not base64 or base64url.
+    pub fn sha512(digest: &str) -> Option<Source> {
+        Source::quoted("sha512", digest)
+    }
+
+    /// Creates a scheme source, like `data:` from `data`.
+    ///
+    /// Returns `None` if the scheme is not valid.
+    pub fn scheme(scheme: &str) -> Option<Source> {
+        let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.');
+        if !valid {
+            return None;
+        }
+        let mut s = String::with_capacity(scheme.len() + 1);
+        s.push_str(scheme);
+        s.push(':');
+        Some(Source(Cow::Owned(s)))
+    }
+
+    /// Creates an unquoted source, like the host source
+    /// `https://*.example.com:443`, or a value of a directive that doesn't
+    /// take sources, like `allow-scripts` for `sandbox`.
+    ///
+    /// Returns `None` if the source is empty, starts with a quote, or has
+    /// characters that would end it, like spaces, commas or semicolons.
+    pub fn raw(source: &str) -> Option<Source> {
+        let valid = !source.is_empty()
+            && !source.starts_with('\'')
+            && source
+                .bytes()
+                .all(|b| b.is_ascii_graphic() && b != b';' && b != b',');
+        if valid {
+            Some(Source(Cow::Owned(source.into())))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the source as encoded, with its quotes.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn quoted(prefix: &str, value: &str) -> Option<Source> {
+        let data = value.trim_end_matches('=');
+        let valid = !data.is_empty()
+            && value.len() - data.len() <= 2
+            && data
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"+/-_".contains(&b));
+        if !valid {
+            return None;
+        }
+        let mut s = String::with_capacity(prefix.len() + value.len() + 3);
+        let _ = write!(s, "'{}-{}'", prefix, value);
+        Some(Source(Cow::Owned(s)))
+    }
+
+    // Keywords are case-insensitive, unlike nonces and digests.
+    fn decode(token: &str) -> Source {
+        KEYWORDS
+            .iter()
+            .find(|keyword| keyword.0.eq_ignore_ascii_case(token))
+            .cloned()
+            .unwrap_or_else(|| Source(Cow::Owned(token.into())))
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn is_directive_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(s: &str) -> Option<ContentSecurityPolicy> {
+        let mut map = HeaderMap::new();
+        map.insert(CONTENT_SECURITY_POLICY, s.parse().unwrap());
+        map.typed_get()
+    }
+
+    #[test]
+    fn sources() {
+        assert_eq!(Source::nonce("a+b/c=").unwrap().as_str(), "'nonce-a+b/c='");
+        assert_eq!(
+            Source::sha256("q-_w==").unwrap().as_str(),
+            "'sha256-q-_w=='"
+        );
+        assert!(Source::sha384("a===").is_none());
+        assert!(Source::sha512("").is_none());
+        assert!(Source::nonce("a'; script-src *").is_none());
+
+        assert_eq!(Source::scheme("wss").unwrap().as_str(), "wss:");
+        assert!(Source::scheme("1http").is_none());
+        assert!(Source::scheme("").is_none());
+
+        assert!(Source::raw("*.example.com:*").is_some());
+        assert!(Source::raw("a; script-src *").is_none());
+        assert!(Source::raw("a,b").is_none());
+        assert!(Source::raw("'self'").is_none());
+        assert!(Source::raw("").is_none());
+    }
+
+    #[test]
+    fn policies() {
+        let csp = decode(
+            "Script-Src 'SELF' 'nonce-AbC' https:; ; script-src *; \
+             sandbox, default-src 'none'",
+        )
+        .unwrap();
+        assert_eq!(
+            csp.sources("script-src").unwrap(),
+            [
+                Source::SELF,
+                Source::nonce("AbC").unwrap(),
+                Source::scheme("https").unwrap()
+            ]
+        );
+        assert_eq!(csp.sources("sandbox").unwrap(), []);
+        assert_eq!(csp.sources("default-src"), None);
+        assert_eq!(
+            csp.to_string(),
+            "script-src 'self' 'nonce-AbC' https:; sandbox"
+        );
+
+        let csp = csp
+            .with("Script-Src", vec![Source::STRICT_DYNAMIC])
+            .with_source("script-src", Source::STRICT_DYNAMIC);
+        assert_eq!(csp.to_string(), "script-src 'strict-dynamic'; sandbox");
+
+        assert_eq!(decode(""), Some(ContentSecurityPolicy::new()));
+        assert_eq!(decode("script_src 'self'"), None);
+
+        let mut map = HeaderMap::new();
+        map.typed_insert(ContentSecurityPolicyReportOnly(csp.clone()));
+        assert_eq!(
+            map.typed_get::<ContentSecurityPolicyReportOnly>()
+                .unwrap()
+                .0,
+            csp
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_directive_name() {
+        ContentSecurityPolicy::new().with("default-src;", vec![]);
+    }
+}
@@ -0,0 +1,125 @@
+use core::fmt;
+
+use super::{Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+
+/// The `Content-Type` header.
+///
+/// The media type is kept as sent. Use [`mime_type`] to inspect the
+/// `type/subtype` portion without any parameters.
+///
+/// [`mime_type`]: #method.mime_type
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::header::typed::ContentType;
+/// let ct = ContentType::json();
+/// assert_eq!(ct.mime_type(), "application/json");
+///
+/// let ct: ContentType = "Text/HTML; charset=utf-8".parse().unwrap();
+/// assert_eq!(ct.mime_type(), "Text/HTML");
+/// assert!(ct.is("text/html"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ContentType(HeaderValue);
+
+impl ContentType {
+    /// `application/json`
+    pub fn json() -> ContentType {
+        ContentType(HeaderValue::from_static("application/json"))
+    }
+
+    /// `text/plain; charset=utf-8`
+    pub fn text() -> ContentType {
+        ContentType(HeaderValue::from_static("text/plain; charset=utf-8"))
+    }
+
+    /// `text/html; charset=utf-8`
+    pub fn html() -> ContentType {
+        ContentType(HeaderValue::from_static("text/html; charset=utf-8"))
+    }
+
+    /// `application/octet-stream`
+    pub fn octet_stream() -> ContentType {
+        ContentType(HeaderValue::from_static("application/octet-stream"))
+    }
+
+    /// `application/x-www-form-urlencoded`
+    pub fn form_url_encoded() -> ContentType {
+        ContentType(HeaderValue::from_static(
+            "application/x-www-form-urlencoded",
+        ))
+    }
+
+    /// `application/sdp`, used by RTSP `DESCRIBE` and SIP offers.
+    pub fn sdp() -> ContentType {
+        ContentType(HeaderValue::from_static("application/sdp"))
+    }
+
+    /// Returns the `type/subtype` portion of the media type.
+    pub fn mime_type(&self) -> &str {
+        let s = self.as_str();
+        s.split(';').next().unwrap_or(s).trim()
+    }
+
+    /// Returns true if the `type/subtype` portion matches `mime_type`,
+    /// ignoring ASCII case.
+    pub fn is(&self, mime_type: &str) -> bool {
+        self.mime_type().eq_ignore_ascii_case(mime_type)
+    }
+
+    /// Returns the full media type, including any parameters.
+    pub fn as_str(&self) -> &str {
+        // Only values that are valid strings can be constructed.
+        self.0.to_str().unwrap_or("")
+    }
+
+    fn from_value(value: &HeaderValue) -> Result<ContentType, InvalidTypedHeader> {
+        let s = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+        let mime = s.split(';').next().unwrap_or(s).trim();
+        let mut parts = mime.splitn(2, '/');
+
+        match (parts.next(), parts.next()) {
+            (Some(ty), Some(sub)) if !ty.is_empty() && !sub.is_empty() => {
+                Ok(ContentType(value.clone()))
+            }
+            _ => Err(InvalidTypedHeader::new()),
+        }
+    }
+}
+
+impl Header for ContentType {
+    fn name() -> &'static HeaderName {
+        &CONTENT_TYPE
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        values
+            .next()
+            .ok_or_else(InvalidTypedHeader::new)
+            .and_then(ContentType::from_value)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(Some(self.0.clone()));
+    }
+}
+
+impl core::str::FromStr for ContentType {
+    type Err = InvalidTypedHeader;
+
+    fn from_str(s: &str) -> Result<ContentType, InvalidTypedHeader> {
+        let value = HeaderValue::from_str(s).map_err(|_| InvalidTypedHeader::new())?;
+        ContentType::from_value(&value)
+    }
+}
+
+impl fmt::Display for ContentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
+use super::{Header, HttpDate, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, DEPRECATION};
+
+/// The `Deprecation` header, signaling that a resource is, or will be,
+/// deprecated.
+///
+/// RFC 9745 gives the date of the deprecation as a structured field date,
+/// the seconds since the Unix epoch after an `@`, which is how a date is
+/// encoded. Drafts of the RFC used `true`, or an HTTP date, and both are
+/// accepted when decoding. Parameters are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::{Deprecation, HttpDate, Sunset};
+/// let mut map = HeaderMap::new();
+/// map.insert("deprecation", "@1688169599".parse().unwrap());
+/// map.insert("sunset", "Sun, 30 Jun 2024 23:59:59 GMT".parse().unwrap());
+///
+/// let deprecation: Deprecation = map.typed_get().unwrap();
+/// let sunset: Sunset = map.typed_get().unwrap();
+/// assert!(deprecation.is_deprecated_at(sunset.0));
+/// assert!(!deprecation.is_deprecated_at(HttpDate::from_unix_secs(0).unwrap()));
+///
+/// map.typed_insert(Deprecation::Date(sunset.0));
+/// assert_eq!(map["deprecation"], "@1719791999");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Deprecation {
+    /// Deprecated, without a date, encoded as the legacy `true`.
+    Deprecated,
+    /// Deprecated from a date, which may be in the future.
+    Date(HttpDate),
+}
+
+impl Deprecation {
+    /// Returns the date of the deprecation, if one was given.
+    pub fn date(&self) -> Option<HttpDate> {
+        match *self {
+            Deprecation::Deprecated => None,
+            Deprecation::Date(date) => Some(date),
+        }
+    }
+
+    /// Returns true if the resource is deprecated at `now`.
+    ///
+    /// A deprecation without a date is always in effect.
+    pub fn is_deprecated_at(&self, now: HttpDate) -> bool {
+        match *self {
+            Deprecation::Deprecated => true,
+            Deprecation::Date(date) => date <= now,
+        }
+    }
+
+    fn from_value(value: &HeaderValue) -> Result<Deprecation, InvalidTypedHeader> {
+        let s = value
+            .to_str()
+            .map_err(|_| InvalidTypedHeader::new())?
+            .trim();
+
+        if s.as_bytes().first() == Some(&b'@') {
+            let item = s[1..].split(';').next().unwrap_or("").trim_end();
+            // A structured field integer has at most 15 digits, and negative
+            // dates are before any HTTP date.
+            if item.is_empty() || item.len() > 15 || !item.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(InvalidTypedHeader::new());
+            }
+            let secs = item.parse().map_err(|_| InvalidTypedHeader::new())?;
+            HttpDate::from_unix_secs(secs)
+                .map(Deprecation::Date)
+                .ok_or_else(InvalidTypedHeader::new)
+        } else if s == "true" || s == "?1" {
+            Ok(Deprecation::Deprecated)
+        } else {
+            s.parse().map(Deprecation::Date)
+        }
+    }
+}
+
+impl Header for Deprecation {
+    fn name() -> &'static HeaderName {
+        &DEPRECATION
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        values
+            .next()
+            .ok_or_else(InvalidTypedHeader::new)
+            .and_then(Deprecation::from_value)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        let value = HeaderValue::try_from(s).expect("deprecations are valid header values");
+        values.extend(Some(value));
+    }
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Deprecation::Deprecated => f.write_str("true"),
+            Deprecation::Date(date) => write!(f, "@{}", date.unix_secs()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(s: &str) -> Option<Deprecation> {
+        let mut map = HeaderMap::new();
+        map.insert(DEPRECATION, s.parse().unwrap());
+        map.typed_get()
+    }
+
+    #[test]
+    fn forms() {
+        let date = |secs| Some(Deprecation::Date(HttpDate::from_unix_secs(secs).unwrap()));
+        assert_eq!(decode("@1688169599"), date(1_688_169_599));
+        assert_eq!(decode("@0;reason=\"old\""), date(0));
+        assert_eq!(decode("Sun, 02 Jul 2023 23:59:59 GMT"), date(1_688_342_399));
+        assert_eq!(decode("true"), Some(Deprecation::Deprecated));
+        assert_eq!(decode("?1"), Some(Deprecation::Deprecated));
+
+        assert_eq!(decode("@"), None);
+        assert_eq!(decode("@-1"), None);
+        assert_eq!(decode("@1.5"), None);
+        assert_eq!(decode("@1000000000000000"), None);
+        assert_eq!(decode("@999999999999999"), None);
+        assert_eq!(decode("false"), None);
+
+        assert_eq!(Deprecation::Deprecated.to_string(), "true");
+        assert_eq!(date(42).unwrap().to_string(), "@42");
+        assert_eq!(Deprecation::Deprecated.date(), None);
+    }
+}
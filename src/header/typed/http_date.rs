@@ -0,0 +1,470 @@
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{Header, InvalidTypedHeader};
+use crate::header::{
+    HeaderName, HeaderValue, DATE, EXPIRES, IF_MODIFIED_SINCE, LAST_MODIFIED, SUNSET,
+};
+
+/// A date, as used in HTTP, RTSP and SIP headers.
+///
+/// Dates are parsed in the three formats of RFC 7231 section 7.1.1.1: the
+/// preferred IMF-fixdate, the obsolete RFC 850 format and the format of C's
+/// `asctime`. They are always written as IMF-fixdate. Dates are precise to
+/// the second, and range from 1970 to 9999.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::header::typed::HttpDate;
+/// let imf: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+/// let rfc850: HttpDate = "Sunday, 06-Nov-94 08:49:37 GMT".parse().unwrap();
+/// let asctime: HttpDate = "Sun Nov  6 08:49:37 1994".parse().unwrap();
+/// assert_eq!(imf, rfc850);
+/// assert_eq!(imf, asctime);
+///
+/// assert_eq!(asctime.to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+/// assert_eq!(imf.unix_secs(), 784111777);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HttpDate {
+    secs: u64,
+}
+
+// 9999-12-31 23:59:59.
+const MAX_SECS: u64 = 253_402_300_799;
+
+const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const LONG_DAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+impl HttpDate {
+    /// Creates a date from the number of seconds since the Unix epoch.
+    ///
+    /// Returns `None` for dates after the year 9999.
+    pub fn from_unix_secs(secs: u64) -> Option<HttpDate> {
+        if secs > MAX_SECS {
+            return None;
+        }
+        Some(HttpDate { secs })
+    }
+
+    /// Returns the number of seconds since the Unix epoch.
+    pub fn unix_secs(&self) -> u64 {
+        self.secs
+    }
+
+    /// Returns the current date.
+    #[cfg(feature = "std")]
+    pub fn now() -> HttpDate {
+        HttpDate::from(SystemTime::now())
+    }
+
+    fn from_parts(date: Parts) -> Option<HttpDate> {
+        let days_in_month = match date.month {
+            2 if is_leap(date.year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+        if date.year < 1970
+            || date.year > 9999
+            || date.day == 0
+            || date.day > days_in_month
+            || date.hour > 23
+            || date.min > 59
+            // A leap second is written as 60.
+            || date.sec > 60
+        {
+            return None;
+        }
+
+        let days = days_from_civil(date.year, date.month, date.day);
+        let secs = days * 86400
+            + u64::from(date.hour) * 3600
+            + u64::from(date.min) * 60
+            + u64::from(date.sec);
+        HttpDate::from_unix_secs(secs)
+    }
+}
+
+impl FromStr for HttpDate {
+    type Err = InvalidTypedHeader;
+
+    fn from_str(s: &str) -> Result<HttpDate, InvalidTypedHeader> {
+        let s = s.trim();
+        let parts = if s.len() == 29 {
+            parse_imf_fixdate(s)
+        } else if s.len() == 24 {
+            parse_asctime(s)
+        } else {
+            parse_rfc850(s)
+        };
+        parts
+            .and_then(HttpDate::from_parts)
+            .ok_or_else(InvalidTypedHeader::new)
+    }
+}
+
+impl fmt::Display for HttpDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = self.secs / 86400;
+        let secs = self.secs % 86400;
+        let (year, month, day) = civil_from_days(days);
+        // 1970-01-01 was a Thursday.
+        let weekday = DAYS[((days + 3) % 7) as usize];
+
+        write!(
+            f,
+            "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            weekday,
+            day,
+            MONTHS[usize::from(month) - 1],
+            year,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<SystemTime> for HttpDate {
+    /// Converts a system time, without its fraction of a second.
+    ///
+    /// Times before 1970 become the epoch, and times after 9999 the last
+    /// second of 9999.
+    fn from(time: SystemTime) -> HttpDate {
+        let secs = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        HttpDate {
+            secs: if secs > MAX_SECS { MAX_SECS } else { secs },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<HttpDate> for SystemTime {
+    fn from(date: HttpDate) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(date.secs)
+    }
+}
+
+struct Parts {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    min: u8,
+    sec: u8,
+}
+
+// `Sun, 06 Nov 1994 08:49:37 GMT`
+fn parse_imf_fixdate(s: &str) -> Option<Parts> {
+    let b = s.as_bytes();
+    if !DAYS.contains(&s.get(..3)?) || &b[3..5] != b", " || b[7] != b' ' || b[11] != b' ' {
+        return None;
+    }
+    if b[16] != b' ' || &b[25..] != b" GMT" {
+        return None;
+    }
+    let (hour, min, sec) = parse_time(&b[17..25])?;
+    Some(Parts {
+        year: digits(&b[12..16])?,
+        month: month(s.get(8..11)?)?,
+        day: digits(&b[5..7])? as u8,
+        hour,
+        min,
+        sec,
+    })
+}
+
+// `Sunday, 06-Nov-94 08:49:37 GMT`
+fn parse_rfc850(s: &str) -> Option<Parts> {
+    let comma = s.find(", ")?;
+    if !LONG_DAYS.contains(&&s[..comma]) {
+        return None;
+    }
+    let b = &s.as_bytes()[comma + 2..];
+    if b.len() != 22 || b[2] != b'-' || b[6] != b'-' || b[9] != b' ' || &b[18..] != b" GMT" {
+        return None;
+    }
+    // Two digit years are taken as the closest year from 1970 to 2069.
+    let year = digits(&b[7..9])?;
+    let year = if year < 70 { 2000 + year } else { 1900 + year };
+    let (hour, min, sec) = parse_time(&b[10..18])?;
+    Some(Parts {
+        year,
+        month: month(core::str::from_utf8(&b[3..6]).ok()?)?,
+        day: digits(&b[..2])? as u8,
+        hour,
+        min,
+        sec,
+    })
+}
+
+// `Sun Nov  6 08:49:37 1994`
+fn parse_asctime(s: &str) -> Option<Parts> {
+    let b = s.as_bytes();
+    if !DAYS.contains(&s.get(..3)?) || b[3] != b' ' || b[7] != b' ' || b[10] != b' ' {
+        return None;
+    }
+    if b[19] != b' ' {
+        return None;
+    }
+    // The day is padded with a space.
+    let day = if b[8] == b' ' {
+        digits(&b[9..10])?
+    } else {
+        digits(&b[8..10])?
+    };
+    let (hour, min, sec) = parse_time(&b[11..19])?;
+    Some(Parts {
+        year: digits(&b[20..24])?,
+        month: month(s.get(4..7)?)?,
+        day: day as u8,
+        hour,
+        min,
+        sec,
+    })
+}
+
+// `08:49:37`
+fn parse_time(b: &[u8]) -> Option<(u8, u8, u8)> {
+    if b[2] != b':' || b[5] != b':' {
+        return None;
+    }
+    Some((
+        digits(&b[..2])? as u8,
+        digits(&b[3..5])? as u8,
+        digits(&b[6..8])? as u8,
+    ))
+}
+
+fn digits(b: &[u8]) -> Option<u16> {
+    b.iter().try_fold(0u16, |n, &d| {
+        if d.is_ascii_digit() {
+            Some(n * 10 + u16::from(d - b'0'))
+        } else {
+            None
+        }
+    })
+}
+
+fn month(s: &str) -> Option<u8> {
+    MONTHS.iter().position(|&m| m == s).map(|i| i as u8 + 1)
+}
+
+fn is_leap(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+// The days from 1970-01-01, and back, with the algorithms of Howard
+// Hinnant's "chrono-Compatible Low-Level Date Algorithms", for years from
+// 1970 only.
+fn days_from_civil(year: u16, month: u8, day: u8) -> u64 {
+    let year = u64::from(year) - if month <= 2 { 1 } else { 0 };
+    let era = year / 400;
+    let yoe = year % 400;
+    let month = u64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + u64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: u64) -> (u64, u8, u8) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u8;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn decode_date<'i, I>(values: &mut I) -> Result<HttpDate, InvalidTypedHeader>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let value = values.next().ok_or_else(InvalidTypedHeader::new)?;
+    value
+        .to_str()
+        .map_err(|_| InvalidTypedHeader::new())?
+        .parse()
+}
+
+pub(super) fn encode_date<E: Extend<HeaderValue>>(date: &HttpDate, values: &mut E) {
+    let mut s = String::with_capacity(29);
+    let _ = write!(s, "{}", date);
+    let value = HeaderValue::try_from(s).expect("dates are valid header values");
+    values.extend(Some(value));
+}
+
+macro_rules! date_headers {
+    ($(
+        $(#[$docs:meta])*
+        ($ty:ident, $name:ident);
+    )+) => {
+        $(
+            $(#[$docs])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $ty(pub HttpDate);
+
+            impl Header for $ty {
+                fn name() -> &'static HeaderName {
+                    &$name
+                }
+
+                fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+                where
+                    I: Iterator<Item = &'i HeaderValue>,
+                {
+                    decode_date(values).map($ty)
+                }
+
+                fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+                    encode_date(&self.0, values)
+                }
+            }
+        )+
+    };
+}
+
+date_headers! {
+    /// The `Date` header, the date a message was sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::typed::{Date, HttpDate};
+    /// let mut map = HeaderMap::new();
+    /// map.typed_insert(Date(HttpDate::from_unix_secs(0).unwrap()));
+    /// assert_eq!(map["date"], "Thu, 01 Jan 1970 00:00:00 GMT");
+    /// ```
+    (Date, DATE);
+
+    /// The `Expires` header, the date after which a response is stale.
+    ///
+    /// RFC 7234 section 5.3 asks caches to take a value that is not a valid
+    /// date, like `0`, as a date in the past. Such values fail to decode.
+    (Expires, EXPIRES);
+
+    /// The `Last-Modified` header, the date a resource was last changed.
+    (LastModified, LAST_MODIFIED);
+
+    /// The `If-Modified-Since` header, asking for a resource only if it
+    /// changed after a date.
+    (IfModifiedSince, IF_MODIFIED_SINCE);
+
+    /// The `Sunset` header of RFC 8594, the date after which a resource is
+    /// expected to become unresponsive.
+    (Sunset, SUNSET);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn parse(s: &str) -> Option<u64> {
+        s.parse::<HttpDate>().ok().map(|date| date.unix_secs())
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(parse("Sun, 06 Nov 1994 08:49:37 GMT"), Some(784_111_777));
+        assert_eq!(parse("Sunday, 06-Nov-94 08:49:37 GMT"), Some(784_111_777));
+        assert_eq!(parse("Sun Nov  6 08:49:37 1994"), Some(784_111_777));
+        assert_eq!(parse("Sun Nov 16 08:49:37 1994"), Some(784_975_777));
+        assert_eq!(
+            parse("Thursday, 01-Jan-37 00:00:00 GMT"),
+            Some(2_114_380_800)
+        );
+
+        assert_eq!(parse("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(parse("Fri, 31 Dec 9999 23:59:59 GMT"), Some(MAX_SECS));
+        assert_eq!(parse("Tue, 29 Feb 2000 12:00:00 GMT"), Some(951_825_600));
+
+        assert_eq!(parse("Wed, 31 Dec 1969 23:59:59 GMT"), None);
+        assert_eq!(parse("Mon, 29 Feb 2100 00:00:00 GMT"), None);
+        assert_eq!(parse("Sun, 06 Nov 1994 24:00:00 GMT"), None);
+        assert_eq!(parse("Sun, 06 Nov 1994 08:49:37 UTC"), None);
+        assert_eq!(parse("Sun, 06 Nvb 1994 08:49:37 GMT"), None);
+        assert_eq!(parse("Sun, 6 Nov 1994 08:49:37 GMT"), None);
+        assert_eq!(parse("Sun, 06 Nov 1994 08:49:37 GMT+"), None);
+        assert_eq!(parse("Sun, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse("Sun Nov  6 08:49:37 1994 "), Some(784_111_777));
+        assert_eq!(parse("Sun Nov  6 08:49:37 19x4"), None);
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("ñññññññññññññññññññññññññ"), None);
+        assert_eq!(parse("Sun, 06 Nñ 1994 08:49:37 GMT"), None);
+        assert_eq!(parse("Sun, 06 Nov 1994 08:49:37 Gñ"), None);
+        assert_eq!(parse("Sun ñv  6 08:49:37 1994"), None);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let mut secs = 0;
+        while secs <= MAX_SECS {
+            let date = HttpDate::from_unix_secs(secs).unwrap();
+            let s = date.to_string();
+            assert_eq!(s.len(), 29, "{}", s);
+            assert_eq!(s.parse::<HttpDate>().unwrap(), date, "{}", s);
+            secs += 86_399 * 37 + 11;
+        }
+        assert!(HttpDate::from_unix_secs(MAX_SECS + 1).is_none());
+    }
+
+    #[test]
+    fn system_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(784_111_777_999);
+        let date = HttpDate::from(time);
+        assert_eq!(date.unix_secs(), 784_111_777);
+        assert_eq!(
+            SystemTime::from(date),
+            UNIX_EPOCH + Duration::from_secs(784_111_777)
+        );
+        assert_eq!(
+            HttpDate::from(UNIX_EPOCH - Duration::from_secs(1)).unix_secs(),
+            0
+        );
+        assert!(HttpDate::now().unix_secs() > 1_600_000_000);
+    }
+
+    #[test]
+    fn headers() {
+        let mut map = HeaderMap::new();
+        map.insert(
+            LAST_MODIFIED,
+            "Sunday, 06-Nov-94 08:49:37 GMT".parse().unwrap(),
+        );
+        let modified: LastModified = map.typed_get().unwrap();
+        assert_eq!(modified.0.unix_secs(), 784_111_777);
+
+        map.typed_insert(IfModifiedSince(modified.0));
+        assert_eq!(map[IF_MODIFIED_SINCE], "Sun, 06 Nov 1994 08:49:37 GMT");
+
+        map.insert(EXPIRES, "0".parse().unwrap());
+        assert!(map.typed_get::<Expires>().is_none());
+    }
+}
@@ -0,0 +1,280 @@
+//! Typed headers
+//!
+//! The [`Header`] trait describes how a strongly typed representation of a
+//! header is decoded from, and encoded into, the raw values stored in a
+//! [`HeaderMap`]. A number of common headers are provided by this module.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::HeaderMap;
+//! use httplike::header::typed::{CacheControl, ContentType};
+//!
+//! let mut map = HeaderMap::new();
+//! map.typed_insert(ContentType::json());
+//! map.typed_insert(CacheControl::no_store());
+//!
+//! assert_eq!(map["content-type"], "application/json");
+//! assert_eq!(map["cache-control"], "no-store");
+//!
+//! let cc: CacheControl = map.typed_get().unwrap();
+//! assert!(cc.is_no_store());
+//! ```
+//!
+//! [`Header`]: trait.Header.html
+//! [`HeaderMap`]: ../struct.HeaderMap.html
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use super::{HeaderMap, HeaderName, HeaderValue};
+
+mod accept_encoding;
+mod authorization;
+mod cache_control;
+mod content_coding;
+mod content_length;
+mod content_security_policy;
+mod content_type;
+mod deprecation;
+mod http_date;
+#[cfg(feature = "rtsp")]
+mod npt_range;
+mod quality_list;
+mod referrer_policy;
+mod retry_after;
+mod strict_transport_security;
+#[cfg(feature = "rtsp")]
+mod transport;
+mod warning;
+mod x_content_type_options;
+
+pub use self::accept_encoding::AcceptEncoding;
+pub use self::authorization::{Authorization, BasicCredentials};
+pub use self::cache_control::CacheControl;
+pub use self::content_coding::ContentCoding;
+pub use self::content_length::ContentLength;
+pub use self::content_security_policy::{
+    ContentSecurityPolicy, ContentSecurityPolicyReportOnly, Source,
+};
+pub use self::content_type::ContentType;
+pub use self::deprecation::Deprecation;
+pub use self::http_date::{Date, Expires, HttpDate, IfModifiedSince, LastModified, Sunset};
+#[cfg(feature = "rtsp")]
+pub use self::npt_range::{NptRange, NptTime};
+pub use self::quality_list::{QualityIter, QualityList};
+pub use self::referrer_policy::ReferrerPolicy;
+pub use self::retry_after::RetryAfter;
+pub use self::strict_transport_security::StrictTransportSecurity;
+#[cfg(feature = "rtsp")]
+pub use self::transport::{Transport, TransportSpec};
+pub use self::warning::{Warning, WarningValue};
+pub use self::x_content_type_options::XContentTypeOptions;
+
+/// A strongly typed header.
+///
+/// # Examples
+///
+/// ```
+/// use httplike::header::{HeaderName, HeaderValue};
+/// use httplike::header::typed::{Header, InvalidTypedHeader};
+///
+/// struct Dnt(bool);
+///
+/// impl Header for Dnt {
+///     fn name() -> &'static HeaderName {
+///         &httplike::header::DNT
+///     }
+///
+///     fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+///     where
+///         I: Iterator<Item = &'i HeaderValue>,
+///     {
+///         match values.next().map(HeaderValue::as_bytes) {
+///             Some(b"0") => Ok(Dnt(false)),
+///             Some(b"1") => Ok(Dnt(true)),
+///             _ => Err(InvalidTypedHeader::new()),
+///         }
+///     }
+///
+///     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+///         let value = if self.0 { "1" } else { "0" };
+///         values.extend(Some(HeaderValue::from_static(value)));
+///     }
+/// }
+/// ```
+pub trait Header: Sized {
+    /// The name of the header.
+    fn name() -> &'static HeaderName;
+
+    /// Decodes the header from the values associated with its name.
+    ///
+    /// The iterator yields the values in the order they appear in the map.
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>;
+
+    /// Encodes the header into one or more values.
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E);
+}
+
+/// A possible error when decoding a typed header.
+pub struct InvalidTypedHeader {
+    _priv: (),
+}
+
+impl InvalidTypedHeader {
+    /// Creates a new error, for use by `Header` implementations.
+    pub fn new() -> InvalidTypedHeader {
+        InvalidTypedHeader { _priv: () }
+    }
+}
+
+impl Default for InvalidTypedHeader {
+    fn default() -> InvalidTypedHeader {
+        InvalidTypedHeader::new()
+    }
+}
+
+impl fmt::Debug for InvalidTypedHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidTypedHeader")
+            // skip _priv noise
+            .finish()
+    }
+}
+
+impl fmt::Display for InvalidTypedHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid typed header")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for InvalidTypedHeader {}
+
+impl HeaderMap {
+    /// Decodes the typed header `H` from the map.
+    ///
+    /// Returns `None` if the header is not present or fails to decode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::typed::ContentLength;
+    /// let mut map = HeaderMap::new();
+    /// assert!(map.typed_get::<ContentLength>().is_none());
+    ///
+    /// map.insert("content-length", "42".parse().unwrap());
+    /// assert_eq!(map.typed_get(), Some(ContentLength(42)));
+    /// ```
+    pub fn typed_get<H: Header>(&self) -> Option<H> {
+        self.typed_try_get().unwrap_or(None)
+    }
+
+    /// Decodes the typed header `H` from the map.
+    ///
+    /// Returns `Ok(None)` if the header is not present, and an error if the
+    /// values present fail to decode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::typed::ContentLength;
+    /// let mut map = HeaderMap::new();
+    /// assert!(map.typed_try_get::<ContentLength>().unwrap().is_none());
+    ///
+    /// map.insert("content-length", "forty-two".parse().unwrap());
+    /// assert!(map.typed_try_get::<ContentLength>().is_err());
+    /// ```
+    pub fn typed_try_get<H: Header>(&self) -> Result<Option<H>, InvalidTypedHeader> {
+        let mut values = self.get_all(H::name()).iter().peekable();
+
+        if values.peek().is_none() {
+            return Ok(None);
+        }
+
+        H::decode(&mut values).map(Some)
+    }
+
+    /// Inserts the typed header `H` into the map.
+    ///
+    /// Any values previously associated with the header name are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::HeaderMap;
+    /// # use httplike::header::typed::ContentType;
+    /// let mut map = HeaderMap::new();
+    /// map.insert("content-type", "text/plain".parse().unwrap());
+    ///
+    /// map.typed_insert(ContentType::html());
+    /// assert_eq!(map["content-type"], "text/html; charset=utf-8");
+    /// ```
+    pub fn typed_insert<H: Header>(&mut self, header: H) {
+        let mut values = Vec::new();
+        header.encode(&mut values);
+
+        let name = H::name();
+        self.remove(name);
+        for value in values {
+            self.append(name, value);
+        }
+    }
+}
+
+/// Splits all values into their comma separated elements, skipping empty
+/// elements as allowed by the `#rule` list syntax.
+fn csv<'i, I>(values: I) -> Result<Vec<&'i str>, InvalidTypedHeader>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let mut elements = Vec::new();
+
+    for value in values {
+        let value = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+        elements.extend(value.split(',').map(str::trim).filter(|s| !s.is_empty()));
+    }
+
+    Ok(elements)
+}
+
+/// Parses a `qvalue` of RFC 7231 section 5.3.1, in thousandths.
+fn parse_quality(s: &str) -> Option<u16> {
+    let mut parts = s.splitn(2, '.');
+    let int = parts.next().unwrap_or("");
+    let frac = parts.next().unwrap_or("");
+    if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    match int {
+        "0" => Some(frac.bytes().chain(b"000".iter().cloned()).take(3).fold(0, |q, b| {
+            q * 10 + u16::from(b - b'0')
+        })),
+        "1" if frac.bytes().all(|b| b == b'0') => Some(1000),
+        _ => None,
+    }
+}
+
+/// Writes the `q` parameter of a quality in thousandths, unless it is 1000.
+fn fmt_quality(f: &mut fmt::Formatter<'_>, quality: u16) -> fmt::Result {
+    match quality {
+        1000 => Ok(()),
+        0 => f.write_str(";q=0"),
+        _ => {
+            let mut digits = quality;
+            let mut width = 3;
+            while digits % 10 == 0 {
+                digits /= 10;
+                width -= 1;
+            }
+            write!(f, ";q=0.{:0width$}", digits, width = width)
+        }
+    }
+}
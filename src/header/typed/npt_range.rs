@@ -0,0 +1,247 @@
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::time::Duration;
+
+use super::{Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, RANGE};
+
+/// The `Range` header of RTSP in normal play time, RFC 2326 section 12.29,
+/// the part of a stream to play, like `npt=10-20`.
+///
+/// Only `npt` ranges are decoded; `smpte` and `clock` ranges, and the
+/// `Range` header of HTTP, are an error. A `time` parameter, when the
+/// range is to start playing, is ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::{NptRange, NptTime};
+/// let mut map = HeaderMap::new();
+/// map.typed_insert(NptRange::starting_at(Duration::from_millis(12_500)));
+/// assert_eq!(map["range"], "npt=12.5-");
+///
+/// map.insert("range", "npt=0:01:02.5-now".parse().unwrap());
+/// let range: NptRange = map.typed_get().unwrap();
+/// assert_eq!(range.start(), Some(NptTime::At(Duration::from_millis(62_500))));
+/// assert_eq!(range.end(), Some(NptTime::Now));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NptRange {
+    // At least one of them is set.
+    start: Option<NptTime>,
+    end: Option<NptTime>,
+}
+
+/// A time of an `NptRange`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NptTime {
+    /// The current position of a live stream.
+    Now,
+    /// A position from the start of the stream.
+    At(Duration),
+}
+
+impl NptRange {
+    /// Creates a range from `start` to the end of the stream.
+    pub fn starting_at(start: Duration) -> NptRange {
+        NptRange {
+            start: Some(NptTime::At(start)),
+            end: None,
+        }
+    }
+
+    /// Creates a range from `start` to `end`.
+    pub fn between(start: Duration, end: Duration) -> NptRange {
+        NptRange {
+            start: Some(NptTime::At(start)),
+            end: Some(NptTime::At(end)),
+        }
+    }
+
+    /// Creates the range `npt=now-`, to play a live stream from its
+    /// current position.
+    pub fn now() -> NptRange {
+        NptRange {
+            start: Some(NptTime::Now),
+            end: None,
+        }
+    }
+
+    /// Returns the start of the range, or `None` if it is only an end,
+    /// like `npt=-20`.
+    pub fn start(&self) -> Option<NptTime> {
+        self.start
+    }
+
+    /// Returns the end of the range, or `None` if it is open.
+    pub fn end(&self) -> Option<NptTime> {
+        self.end
+    }
+
+    fn from_value(value: &HeaderValue) -> Result<NptRange, InvalidTypedHeader> {
+        let s = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+        let s = s.split(';').next().unwrap_or("").trim();
+
+        let mut parts = s.splitn(2, '=');
+        let unit = parts.next().unwrap_or("").trim();
+        if !unit.eq_ignore_ascii_case("npt") {
+            return Err(InvalidTypedHeader::new());
+        }
+        let mut parts = parts.next().unwrap_or("").splitn(2, '-');
+        let time = |s: &str| match s.trim() {
+            "" => Ok(None),
+            s => parse_time(s).map(Some).ok_or_else(InvalidTypedHeader::new),
+        };
+        let start = time(parts.next().unwrap_or(""))?;
+        let end = time(parts.next().ok_or_else(InvalidTypedHeader::new)?)?;
+        if start.is_none() && end.is_none() {
+            return Err(InvalidTypedHeader::new());
+        }
+        Ok(NptRange { start, end })
+    }
+}
+
+impl Header for NptRange {
+    fn name() -> &'static HeaderName {
+        &RANGE
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        values
+            .next()
+            .ok_or_else(InvalidTypedHeader::new)
+            .and_then(NptRange::from_value)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        let value = HeaderValue::try_from(s).expect("ranges are valid header values");
+        values.extend(Some(value));
+    }
+}
+
+impl fmt::Display for NptRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("npt=")?;
+        if let Some(start) = self.start {
+            fmt::Display::fmt(&start, f)?;
+        }
+        f.write_str("-")?;
+        if let Some(end) = self.end {
+            fmt::Display::fmt(&end, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NptTime {
+    /// Formats the time in seconds, like `62.5`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            NptTime::Now => f.write_str("now"),
+            NptTime::At(time) => {
+                write!(f, "{}", time.as_secs())?;
+                let mut nanos = time.subsec_nanos();
+                if nanos > 0 {
+                    let mut digits = 9;
+                    while nanos % 10 == 0 {
+                        nanos /= 10;
+                        digits -= 1;
+                    }
+                    write!(f, ".{:0width$}", nanos, width = digits)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Parses `now`, seconds like `62.5`, or hours, minutes and seconds like
+// `0:01:02.5`.
+fn parse_time(s: &str) -> Option<NptTime> {
+    if s.eq_ignore_ascii_case("now") {
+        return Some(NptTime::Now);
+    }
+
+    let (whole, fraction) = match s.find('.') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut fields = whole.split(':');
+    let mut secs: u64 = 0;
+    let mut count = 0;
+    for field in &mut fields {
+        if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let value: u64 = field.parse().ok()?;
+        // Minutes and seconds are two digits, below 60.
+        if count > 0 && (field.len() != 2 || value >= 60) {
+            return None;
+        }
+        secs = secs.checked_mul(if count > 0 { 60 } else { 1 })?;
+        secs = secs.checked_add(value)?;
+        count += 1;
+    }
+    if count == 2 || count > 3 {
+        return None;
+    }
+
+    let mut nanos = 0;
+    for (i, b) in fraction.bytes().take(9).enumerate() {
+        nanos += u32::from(b - b'0') * 10u32.pow(8 - i as u32);
+    }
+    Some(NptTime::At(Duration::new(secs, nanos)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(s: &str) -> Option<NptRange> {
+        let mut map = HeaderMap::new();
+        map.insert(RANGE, s.parse().unwrap());
+        map.typed_get()
+    }
+
+    #[test]
+    fn ranges() {
+        let at = |ms| Some(NptTime::At(Duration::from_millis(ms)));
+        let range = decode("npt=10-20.25").unwrap();
+        assert_eq!((range.start(), range.end()), (at(10_000), at(20_250)));
+        let range = decode("NPT = -1:00:00;time=19970123T143720Z").unwrap();
+        assert_eq!((range.start(), range.end()), (None, at(3_600_000)));
+        assert_eq!(decode("npt=now-"), Some(NptRange::now()));
+        assert_eq!(
+            decode("npt=0.-"),
+            Some(NptRange::starting_at(Duration::from_secs(0)))
+        );
+
+        assert_eq!(decode("npt=-"), None);
+        assert_eq!(decode("npt=10"), None);
+        assert_eq!(decode("npt=1:02-"), None);
+        assert_eq!(decode("npt=0:60:00-"), None);
+        assert_eq!(decode("npt=1.2.3-"), None);
+        assert_eq!(decode("smpte=10:07:00-"), None);
+        assert_eq!(decode("bytes=0-99"), None);
+
+        let range = NptRange::between(Duration::new(1, 5_000_000), Duration::from_secs(90));
+        assert_eq!(range.to_string(), "npt=1.005-90");
+        let mut map = HeaderMap::new();
+        map.typed_insert(range);
+        assert_eq!(map.typed_get(), Some(range));
+        assert_eq!(NptRange::now().to_string(), "npt=now-");
+    }
+}
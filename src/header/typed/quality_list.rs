@@ -0,0 +1,307 @@
+use alloc::string::String;
+use alloc::vec::{self, Vec};
+use core::cmp;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::slice;
+use core::str::FromStr;
+
+use super::{csv, fmt_quality, parse_quality, InvalidTypedHeader};
+use crate::header::HeaderValue;
+
+/// A list of items weighted with a `q` parameter, as in `Accept`, `TE` or
+/// `Accept-Language`.
+///
+/// Each item has a quality between 0 and 1000, in thousandths of the `q`
+/// parameter, and an item without `q` has a quality of 1000. A quality of 0
+/// means the item is not acceptable. Items are kept in the order they are
+/// listed in, which is the order of preference among items with the same
+/// quality.
+///
+/// The text of an item is everything before its `q` parameter, trimmed, so
+/// parameters of the item itself, like the `level` of `text/html;level=1`,
+/// are parsed by `T`, and parameters after `q` are ignored.
+///
+/// # Examples
+///
+/// A header using `QualityList` for its values:
+///
+/// ```
+/// use httplike::HeaderMap;
+/// use httplike::header::{self, HeaderName, HeaderValue};
+/// use httplike::header::typed::{Header, InvalidTypedHeader, QualityList};
+///
+/// struct Te(QualityList<String>);
+///
+/// impl Header for Te {
+///     fn name() -> &'static HeaderName {
+///         &header::TE
+///     }
+///
+///     fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+///     where
+///         I: Iterator<Item = &'i HeaderValue>,
+///     {
+///         QualityList::decode(values).map(Te)
+///     }
+///
+///     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+///         self.0.encode(values)
+///     }
+/// }
+///
+/// let mut map = HeaderMap::new();
+/// map.insert("te", "deflate;q=0.5, trailers, gzip;q=0".parse().unwrap());
+///
+/// let te: Te = map.typed_get().unwrap();
+/// assert_eq!(te.0.quality(&"gzip".to_string()), Some(0));
+/// assert_eq!(te.0.preferred(), ["trailers", "deflate"]);
+/// assert_eq!(te.0.to_string(), "deflate;q=0.5, trailers, gzip;q=0");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QualityList<T> {
+    items: Vec<(T, u16)>,
+}
+
+/// An iterator over the items of a `QualityList` and their qualities.
+#[derive(Debug)]
+pub struct QualityIter<'a, T> {
+    inner: slice::Iter<'a, (T, u16)>,
+}
+
+impl<T> QualityList<T> {
+    /// Creates an empty list.
+    pub fn new() -> QualityList<T> {
+        QualityList { items: Vec::new() }
+    }
+
+    /// Adds an item with a quality, in thousandths.
+    ///
+    /// Qualities above 1000 are lowered to 1000.
+    pub fn with(mut self, item: T, quality: u16) -> QualityList<T> {
+        self.push(item, quality);
+        self
+    }
+
+    /// Adds an item with a quality, in thousandths.
+    ///
+    /// Qualities above 1000 are lowered to 1000.
+    pub fn push(&mut self, item: T, quality: u16) {
+        self.items.push((item, cmp::min(quality, 1000)));
+    }
+
+    /// Returns the number of items, acceptable or not.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns true if the list has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns an iterator over the items and their qualities, in the order
+    /// of the list.
+    pub fn iter(&self) -> QualityIter<'_, T> {
+        QualityIter {
+            inner: self.items.iter(),
+        }
+    }
+
+    /// Returns the quality of the first item equal to `item`.
+    pub fn quality(&self, item: &T) -> Option<u16>
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .find(|&(t, _)| t == item)
+            .map(|(_, quality)| quality)
+    }
+
+    /// Sorts the items from the highest quality to the lowest, keeping the
+    /// order of the items with the same quality.
+    pub fn sort(&mut self) {
+        self.items.sort_by_key(|item| cmp::Reverse(item.1));
+    }
+
+    /// Removes the items with a quality of 0.
+    pub fn remove_unacceptable(&mut self) {
+        self.items.retain(|&(_, quality)| quality > 0);
+    }
+
+    /// Returns the acceptable items, from the most preferred to the least.
+    pub fn preferred(&self) -> Vec<&T> {
+        let mut items: Vec<&(T, u16)> = self.items.iter().filter(|item| item.1 > 0).collect();
+        items.sort_by_key(|item| cmp::Reverse(item.1));
+        items.into_iter().map(|item| &item.0).collect()
+    }
+
+    /// Decodes a list from header values, for use by `Header`
+    /// implementations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value is not a string, if an item fails to
+    /// parse, or if a quality is not valid.
+    pub fn decode<'i, I>(values: &mut I) -> Result<QualityList<T>, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+        T: FromStr,
+    {
+        let mut list = QualityList::new();
+        for element in csv(values)? {
+            let (item, quality) = split_quality(element).ok_or_else(InvalidTypedHeader::new)?;
+            let item = item.parse().map_err(|_| InvalidTypedHeader::new())?;
+            list.items.push((item, quality));
+        }
+        Ok(list)
+    }
+
+    /// Encodes the list into one header value, for use by `Header`
+    /// implementations.
+    ///
+    /// Nothing is encoded if the items are not valid in a header value.
+    pub fn encode<E: Extend<HeaderValue>>(&self, values: &mut E)
+    where
+        T: fmt::Display,
+    {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        if let Ok(value) = HeaderValue::try_from(s) {
+            values.extend(Some(value));
+        }
+    }
+}
+
+impl<T> Default for QualityList<T> {
+    fn default() -> QualityList<T> {
+        QualityList::new()
+    }
+}
+
+impl<T: FromStr> FromStr for QualityList<T> {
+    type Err = InvalidTypedHeader;
+
+    fn from_str(s: &str) -> Result<QualityList<T>, InvalidTypedHeader> {
+        let value = HeaderValue::from_str(s).map_err(|_| InvalidTypedHeader::new())?;
+        QualityList::decode(&mut Some(&value).into_iter())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for QualityList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+        for &(ref item, quality) in &self.items {
+            write!(f, "{}{}", sep, item)?;
+            fmt_quality(f, quality)?;
+            sep = ", ";
+        }
+        Ok(())
+    }
+}
+
+impl<T> Extend<(T, u16)> for QualityList<T> {
+    fn extend<I: IntoIterator<Item = (T, u16)>>(&mut self, iter: I) {
+        for (item, quality) in iter {
+            self.push(item, quality);
+        }
+    }
+}
+
+impl<T> core::iter::FromIterator<(T, u16)> for QualityList<T> {
+    fn from_iter<I: IntoIterator<Item = (T, u16)>>(iter: I) -> QualityList<T> {
+        let mut list = QualityList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> IntoIterator for QualityList<T> {
+    type Item = (T, u16);
+    type IntoIter = vec::IntoIter<(T, u16)>;
+
+    fn into_iter(self) -> vec::IntoIter<(T, u16)> {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a QualityList<T> {
+    type Item = (&'a T, u16);
+    type IntoIter = QualityIter<'a, T>;
+
+    fn into_iter(self) -> QualityIter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for QualityIter<'a, T> {
+    type Item = (&'a T, u16);
+
+    fn next(&mut self) -> Option<(&'a T, u16)> {
+        self.inner
+            .next()
+            .map(|&(ref item, quality)| (item, quality))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Splits an element of a weighted list into the item, before the `q`
+/// parameter, and its quality.
+///
+/// Returns `None` if the quality is not valid.
+pub(super) fn split_quality(element: &str) -> Option<(&str, u16)> {
+    let mut offset = 0;
+    for (i, param) in element.split(';').enumerate() {
+        if i > 0 {
+            let mut parts = param.splitn(2, '=');
+            if parts.next().unwrap_or("").trim().eq_ignore_ascii_case("q") {
+                let quality = parse_quality(parts.next().unwrap_or("").trim())?;
+                return Some((element[..offset - 1].trim(), quality));
+            }
+        }
+        offset += param.len() + 1;
+    }
+    Some((element.trim(), 1000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_elements() {
+        assert_eq!(split_quality("gzip"), Some(("gzip", 1000)));
+        assert_eq!(split_quality("gzip ; Q=0.25"), Some(("gzip", 250)));
+        assert_eq!(
+            split_quality("text/html;level=1;q=0.5;ext=1"),
+            Some(("text/html;level=1", 500))
+        );
+        assert_eq!(split_quality("a;q=2"), None);
+        assert_eq!(split_quality("a;qq=2"), Some(("a;qq=2", 1000)));
+    }
+
+    #[test]
+    fn lists() {
+        let mut list: QualityList<String> = "b;q=0.5, a, c;q=0, d;q=0.5".parse().unwrap();
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.quality(&"d".into()), Some(500));
+        assert_eq!(list.quality(&"e".into()), None);
+        assert_eq!(list.preferred(), ["a", "b", "d"]);
+
+        list.sort();
+        assert_eq!(list.to_string(), "a, b;q=0.5, d;q=0.5, c;q=0");
+        list.remove_unacceptable();
+        assert_eq!(list.to_string(), "a, b;q=0.5, d;q=0.5");
+
+        let numbers: QualityList<u8> = vec![(1, 100), (2, 1500)].into_iter().collect();
+        assert_eq!(numbers.to_string(), "1;q=0.1, 2");
+        assert_eq!(numbers.iter().collect::<Vec<_>>(), [(&1, 100), (&2, 1000)]);
+
+        assert!("x, y;q=1.1".parse::<QualityList<String>>().is_err());
+        assert!("1, x".parse::<QualityList<u8>>().is_err());
+        assert!("".parse::<QualityList<u8>>().unwrap().is_empty());
+    }
+}
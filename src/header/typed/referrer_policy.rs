@@ -0,0 +1,131 @@
+use core::fmt;
+use core::str::FromStr;
+
+use super::{csv, Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, REFERRER_POLICY};
+
+/// The `Referrer-Policy` header, telling browsers how much of the URL of a
+/// page to send as the `Referer` of the requests it makes.
+///
+/// When decoding a list of policies, the last known one wins, so a policy
+/// can be preceded by fallbacks for older browsers.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::ReferrerPolicy;
+/// let mut map = HeaderMap::new();
+/// map.insert("referrer-policy", "no-referrer, strict-origin-when-cross-origin".parse().unwrap());
+/// assert_eq!(map.typed_get(), Some(ReferrerPolicy::StrictOriginWhenCrossOrigin));
+///
+/// map.typed_insert(ReferrerPolicy::SameOrigin);
+/// assert_eq!(map["referrer-policy"], "same-origin");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReferrerPolicy {
+    /// `no-referrer`
+    NoReferrer,
+    /// `no-referrer-when-downgrade`
+    NoReferrerWhenDowngrade,
+    /// `same-origin`
+    SameOrigin,
+    /// `origin`
+    Origin,
+    /// `strict-origin`
+    StrictOrigin,
+    /// `origin-when-cross-origin`
+    OriginWhenCrossOrigin,
+    /// `strict-origin-when-cross-origin`, the default of browsers.
+    StrictOriginWhenCrossOrigin,
+    /// `unsafe-url`
+    UnsafeUrl,
+}
+
+const POLICIES: &[ReferrerPolicy] = &[
+    ReferrerPolicy::NoReferrer,
+    ReferrerPolicy::NoReferrerWhenDowngrade,
+    ReferrerPolicy::SameOrigin,
+    ReferrerPolicy::Origin,
+    ReferrerPolicy::StrictOrigin,
+    ReferrerPolicy::OriginWhenCrossOrigin,
+    ReferrerPolicy::StrictOriginWhenCrossOrigin,
+    ReferrerPolicy::UnsafeUrl,
+];
+
+impl ReferrerPolicy {
+    /// Returns the token of the policy.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ReferrerPolicy::NoReferrer => "no-referrer",
+            ReferrerPolicy::NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+            ReferrerPolicy::SameOrigin => "same-origin",
+            ReferrerPolicy::Origin => "origin",
+            ReferrerPolicy::StrictOrigin => "strict-origin",
+            ReferrerPolicy::OriginWhenCrossOrigin => "origin-when-cross-origin",
+            ReferrerPolicy::StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+            ReferrerPolicy::UnsafeUrl => "unsafe-url",
+        }
+    }
+}
+
+impl Header for ReferrerPolicy {
+    fn name() -> &'static HeaderName {
+        &REFERRER_POLICY
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        csv(values)?
+            .into_iter()
+            .rev()
+            .find_map(|token| token.parse().ok())
+            .ok_or_else(InvalidTypedHeader::new)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(Some(HeaderValue::from_static(self.as_str())));
+    }
+}
+
+impl FromStr for ReferrerPolicy {
+    type Err = InvalidTypedHeader;
+
+    fn from_str(s: &str) -> Result<ReferrerPolicy, InvalidTypedHeader> {
+        POLICIES
+            .iter()
+            .find(|policy| s.eq_ignore_ascii_case(policy.as_str()))
+            .cloned()
+            .ok_or_else(InvalidTypedHeader::new)
+    }
+}
+
+impl fmt::Display for ReferrerPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    #[test]
+    fn tokens() {
+        for &policy in POLICIES {
+            assert_eq!(policy.as_str().parse().ok(), Some(policy));
+        }
+        assert_eq!("Same-Origin".parse().ok(), Some(ReferrerPolicy::SameOrigin));
+
+        let mut map = HeaderMap::new();
+        map.append(REFERRER_POLICY, "origin, x-unknown".parse().unwrap());
+        map.append(REFERRER_POLICY, "UNSAFE-URL, x-newer".parse().unwrap());
+        assert_eq!(map.typed_get(), Some(ReferrerPolicy::UnsafeUrl));
+
+        map.insert(REFERRER_POLICY, "x-unknown".parse().unwrap());
+        assert_eq!(map.typed_get::<ReferrerPolicy>(), None);
+    }
+}
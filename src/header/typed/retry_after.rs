@@ -0,0 +1,148 @@
+use core::fmt;
+use core::time::Duration;
+
+use super::http_date::encode_date;
+use super::{Header, HttpDate, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, RETRY_AFTER};
+
+/// The `Retry-After` header, how long a client should wait before
+/// retrying.
+///
+/// HTTP allows a date or a delay in seconds. SIP only allows a delay, which
+/// may be followed by a comment and parameters, like in
+/// `120 (in a meeting);duration=3600`; those are accepted and ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use core::time::Duration;
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::{HttpDate, RetryAfter};
+/// let now = HttpDate::from_unix_secs(784111777).unwrap();
+///
+/// let mut map = HeaderMap::new();
+/// map.insert("retry-after", "Sun, 06 Nov 1994 08:51:37 GMT".parse().unwrap());
+/// let retry: RetryAfter = map.typed_get().unwrap();
+/// assert_eq!(retry.as_duration(now), Duration::from_secs(120));
+///
+/// map.typed_insert(RetryAfter::Delay(Duration::from_secs(30)));
+/// assert_eq!(map["retry-after"], "30");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RetryAfter {
+    /// A delay from the time the response was received, precise to the
+    /// second.
+    Delay(Duration),
+    /// A date.
+    Date(HttpDate),
+}
+
+impl RetryAfter {
+    /// Returns how long to wait from `now`.
+    ///
+    /// A date in the past is no delay.
+    pub fn as_duration(&self, now: HttpDate) -> Duration {
+        match *self {
+            RetryAfter::Delay(delay) => delay,
+            RetryAfter::Date(date) => {
+                Duration::from_secs(date.unix_secs().saturating_sub(now.unix_secs()))
+            }
+        }
+    }
+
+    fn from_value(value: &HeaderValue) -> Result<RetryAfter, InvalidTypedHeader> {
+        let s = value
+            .to_str()
+            .map_err(|_| InvalidTypedHeader::new())?
+            .trim();
+
+        let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+        if digits > 0 {
+            let rest = s[digits..].trim_start();
+            if rest.is_empty() || rest.starts_with('(') || rest.starts_with(';') {
+                let secs = s[..digits].parse().map_err(|_| InvalidTypedHeader::new())?;
+                return Ok(RetryAfter::Delay(Duration::from_secs(secs)));
+            }
+        }
+        s.parse().map(RetryAfter::Date)
+    }
+}
+
+impl Header for RetryAfter {
+    fn name() -> &'static HeaderName {
+        &RETRY_AFTER
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        values
+            .next()
+            .ok_or_else(InvalidTypedHeader::new)
+            .and_then(RetryAfter::from_value)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        match *self {
+            RetryAfter::Delay(delay) => values.extend(Some(HeaderValue::from(delay.as_secs()))),
+            RetryAfter::Date(date) => encode_date(&date, values),
+        }
+    }
+}
+
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RetryAfter::Delay(delay) => write!(f, "{}", delay.as_secs()),
+            RetryAfter::Date(date) => fmt::Display::fmt(&date, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(s: &str) -> Option<RetryAfter> {
+        let mut map = HeaderMap::new();
+        map.insert(RETRY_AFTER, s.parse().unwrap());
+        map.typed_get()
+    }
+
+    #[test]
+    fn delays_and_dates() {
+        let delay = |secs| Some(RetryAfter::Delay(Duration::from_secs(secs)));
+        assert_eq!(decode("120"), delay(120));
+        assert_eq!(decode(" 0 "), delay(0));
+        assert_eq!(decode("18000;duration=3600"), delay(18000));
+        assert_eq!(decode("120 (I'm in a meeting)"), delay(120));
+        assert_eq!(decode("12a"), None);
+        assert_eq!(decode("-1"), None);
+        assert_eq!(decode("99999999999999999999"), None);
+
+        let date: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+        assert_eq!(
+            decode("Sunday, 06-Nov-94 08:49:37 GMT"),
+            Some(RetryAfter::Date(date))
+        );
+        let later = HttpDate::from_unix_secs(date.unix_secs() + 10).unwrap();
+        assert_eq!(
+            RetryAfter::Date(date).as_duration(later),
+            Duration::from_secs(0)
+        );
+        assert_eq!(
+            RetryAfter::Date(later).as_duration(date),
+            Duration::from_secs(10)
+        );
+
+        let mut map = HeaderMap::new();
+        map.typed_insert(RetryAfter::Date(date));
+        assert_eq!(map[RETRY_AFTER], "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(
+            RetryAfter::Delay(Duration::from_millis(1500)).to_string(),
+            "1"
+        );
+    }
+}
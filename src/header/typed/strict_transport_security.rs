@@ -0,0 +1,183 @@
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::time::Duration;
+
+use super::{Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, STRICT_TRANSPORT_SECURITY};
+
+/// The `Strict-Transport-Security` header of RFC 6797, asking clients to
+/// only use secure connections to a host.
+///
+/// A `max-age` of zero asks clients to forget the host. Unknown directives
+/// are ignored when decoding, and directives given twice are an error.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::StrictTransportSecurity;
+/// let hsts = StrictTransportSecurity::new(Duration::from_secs(31_536_000))
+///     .with_include_subdomains()
+///     .with_preload();
+///
+/// let mut map = HeaderMap::new();
+/// map.typed_insert(hsts);
+/// assert_eq!(
+///     map["strict-transport-security"],
+///     "max-age=31536000; includeSubDomains; preload"
+/// );
+/// assert_eq!(map.typed_get(), Some(hsts));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StrictTransportSecurity {
+    max_age: u64,
+    include_subdomains: bool,
+    preload: bool,
+}
+
+impl StrictTransportSecurity {
+    /// Creates a header with the `max-age` directive, precise to the second.
+    pub fn new(max_age: Duration) -> StrictTransportSecurity {
+        StrictTransportSecurity {
+            max_age: max_age.as_secs(),
+            include_subdomains: false,
+            preload: false,
+        }
+    }
+
+    /// Sets the `includeSubDomains` directive.
+    pub fn with_include_subdomains(mut self) -> StrictTransportSecurity {
+        self.include_subdomains = true;
+        self
+    }
+
+    /// Sets the `preload` directive, used by the preload lists of browsers.
+    pub fn with_preload(mut self) -> StrictTransportSecurity {
+        self.preload = true;
+        self
+    }
+
+    /// Returns the `max-age` directive.
+    pub fn max_age(&self) -> Duration {
+        Duration::from_secs(self.max_age)
+    }
+
+    /// Returns true if the `includeSubDomains` directive is present.
+    pub fn includes_subdomains(&self) -> bool {
+        self.include_subdomains
+    }
+
+    /// Returns true if the `preload` directive is present.
+    pub fn is_preload(&self) -> bool {
+        self.preload
+    }
+
+    fn from_value(value: &HeaderValue) -> Result<StrictTransportSecurity, InvalidTypedHeader> {
+        let s = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+
+        let mut max_age = None;
+        let mut include_subdomains = false;
+        let mut preload = false;
+        for directive in s.split(';').map(str::trim).filter(|d| !d.is_empty()) {
+            let mut parts = directive.splitn(2, '=');
+            let name = parts.next().unwrap_or("").trim();
+            let arg = parts.next().map(|v| v.trim().trim_matches('"'));
+
+            let seen = if name.eq_ignore_ascii_case("max-age") {
+                let secs = arg.and_then(|arg| arg.parse().ok());
+                let seen = max_age.is_some();
+                max_age = Some(secs.ok_or_else(InvalidTypedHeader::new)?);
+                seen
+            } else if name.eq_ignore_ascii_case("includesubdomains") {
+                let seen = include_subdomains;
+                include_subdomains = true;
+                seen
+            } else if name.eq_ignore_ascii_case("preload") {
+                let seen = preload;
+                preload = true;
+                seen
+            } else {
+                false
+            };
+            if seen {
+                return Err(InvalidTypedHeader::new());
+            }
+        }
+
+        Ok(StrictTransportSecurity {
+            max_age: max_age.ok_or_else(InvalidTypedHeader::new)?,
+            include_subdomains,
+            preload,
+        })
+    }
+}
+
+impl Header for StrictTransportSecurity {
+    fn name() -> &'static HeaderName {
+        &STRICT_TRANSPORT_SECURITY
+    }
+
+    /// Decodes the first value, which is the only one clients use.
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        values
+            .next()
+            .ok_or_else(InvalidTypedHeader::new)
+            .and_then(StrictTransportSecurity::from_value)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        let value = HeaderValue::try_from(s).expect("directives are valid header values");
+        values.extend(Some(value));
+    }
+}
+
+impl fmt::Display for StrictTransportSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "max-age={}", self.max_age)?;
+        if self.include_subdomains {
+            f.write_str("; includeSubDomains")?;
+        }
+        if self.preload {
+            f.write_str("; preload")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(s: &str) -> Option<StrictTransportSecurity> {
+        let mut map = HeaderMap::new();
+        map.insert(STRICT_TRANSPORT_SECURITY, s.parse().unwrap());
+        map.typed_get()
+    }
+
+    #[test]
+    fn directives() {
+        let hsts = decode("max-age=\"600\"").unwrap();
+        assert_eq!(hsts.max_age(), Duration::from_secs(600));
+        assert!(!hsts.includes_subdomains());
+        assert!(!hsts.is_preload());
+
+        let hsts = decode(" INCLUDESUBDOMAINS ;max-age=0; x-ext=1;").unwrap();
+        assert_eq!(hsts.max_age(), Duration::from_secs(0));
+        assert!(hsts.includes_subdomains());
+        assert_eq!(hsts.to_string(), "max-age=0; includeSubDomains");
+
+        assert_eq!(decode("includeSubDomains"), None);
+        assert_eq!(decode("max-age"), None);
+        assert_eq!(decode("max-age=-1"), None);
+        assert_eq!(decode("max-age=1; max-age=2"), None);
+        assert_eq!(decode("max-age=1; preload; preload"), None);
+    }
+}
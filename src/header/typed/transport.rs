@@ -0,0 +1,364 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+
+use super::{Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, TRANSPORT};
+
+/// The `Transport` header of RTSP, RFC 2326 section 12.39, the transports
+/// a client can receive a stream over, or the one the server picked.
+///
+/// A client lists the transports it accepts in order of preference, and
+/// the server answers with one of them, filled in with its own ports.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::{Transport, TransportSpec};
+/// let transport = Transport::new(TransportSpec::rtp_unicast(8000))
+///     .with(TransportSpec::rtp_interleaved(0));
+///
+/// let mut map = HeaderMap::new();
+/// map.typed_insert(transport);
+/// assert_eq!(
+///     map["transport"],
+///     "RTP/AVP;unicast;client_port=8000-8001,RTP/AVP/TCP;unicast;interleaved=0-1"
+/// );
+///
+/// map.insert(
+///     "transport",
+///     "RTP/AVP;unicast;client_port=8000-8001;server_port=9000-9001;ssrc=1A2B3C4D"
+///         .parse()
+///         .unwrap(),
+/// );
+/// let transport: Transport = map.typed_get().unwrap();
+/// let spec = transport.first();
+/// assert_eq!(spec.server_port(), Some((9000, 9001)));
+/// assert_eq!(spec.ssrc(), Some(0x1A2B_3C4D));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Transport {
+    // Never empty.
+    specs: Vec<TransportSpec>,
+}
+
+/// One of the transports of a `Transport` header, like
+/// `RTP/AVP;unicast;client_port=8000-8001`.
+///
+/// The parameters are kept in their order, so unknown ones survive a
+/// round trip.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TransportSpec {
+    protocol: String,
+    params: Vec<(String, Option<String>)>,
+}
+
+impl Transport {
+    /// Creates a header with a single transport.
+    pub fn new(spec: TransportSpec) -> Transport {
+        Transport {
+            specs: alloc::vec![spec],
+        }
+    }
+
+    /// Adds a transport, less preferred than the ones already present.
+    pub fn with(mut self, spec: TransportSpec) -> Transport {
+        self.specs.push(spec);
+        self
+    }
+
+    /// Returns the transports, most preferred first.
+    pub fn specs(&self) -> &[TransportSpec] {
+        &self.specs
+    }
+
+    /// Returns the most preferred transport, the only one in a response.
+    pub fn first(&self) -> &TransportSpec {
+        &self.specs[0]
+    }
+}
+
+impl TransportSpec {
+    /// Creates an RTP transport over UDP, to be received on `client_port`
+    /// for RTP and the port after it for RTCP.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `client_port` is `u16::MAX`, which leaves no port for
+    /// RTCP.
+    pub fn rtp_unicast(client_port: u16) -> TransportSpec {
+        let rtcp = client_port.checked_add(1).expect("no port left for RTCP");
+        TransportSpec::new("RTP/AVP")
+            .with_param("unicast", None)
+            .with_param("client_port", Some(pair(client_port, rtcp)))
+    }
+
+    /// Creates an RTP transport interleaved in the RTSP connection, on
+    /// `channel` for RTP and the channel after it for RTCP.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is 255, which leaves no channel for RTCP.
+    pub fn rtp_interleaved(channel: u8) -> TransportSpec {
+        let rtcp = channel.checked_add(1).expect("no channel left for RTCP");
+        TransportSpec::new("RTP/AVP/TCP")
+            .with_param("unicast", None)
+            .with_param("interleaved", Some(pair(channel, rtcp)))
+    }
+
+    /// Sets the `server_port` parameter, the ports the server sends RTP
+    /// and RTCP from, as in the answer to a SETUP.
+    pub fn with_server_port(self, rtp: u16, rtcp: u16) -> TransportSpec {
+        self.with_param("server_port", Some(pair(rtp, rtcp)))
+    }
+
+    /// Sets the `ssrc` parameter, the synchronization source of the
+    /// stream.
+    pub fn with_ssrc(self, ssrc: u32) -> TransportSpec {
+        let mut value = String::new();
+        let _ = write!(value, "{:08X}", ssrc);
+        self.with_param("ssrc", Some(value))
+    }
+
+    /// Returns the transport protocol, profile and lower transport, like
+    /// `RTP/AVP` or `RTP/AVP/TCP`.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// Returns true if the stream is sent to a multicast address.
+    pub fn is_multicast(&self) -> bool {
+        self.params
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("multicast"))
+    }
+
+    /// Returns the `client_port` parameter, the ports the client receives
+    /// RTP and RTCP on.
+    pub fn client_port(&self) -> Option<(u16, u16)> {
+        self.param("client_port").and_then(parse_pair)
+    }
+
+    /// Returns the `server_port` parameter, the ports the server sends RTP
+    /// and RTCP from.
+    pub fn server_port(&self) -> Option<(u16, u16)> {
+        self.param("server_port").and_then(parse_pair)
+    }
+
+    /// Returns the `interleaved` parameter, the channels RTP and RTCP are
+    /// sent on in the RTSP connection.
+    pub fn interleaved(&self) -> Option<(u8, u8)> {
+        self.param("interleaved").and_then(parse_pair)
+    }
+
+    /// Returns the `ssrc` parameter.
+    pub fn ssrc(&self) -> Option<u32> {
+        self.param("ssrc")
+            .and_then(|ssrc| u32::from_str_radix(ssrc, 16).ok())
+    }
+
+    /// Returns the value of the parameter named `name`, compared without
+    /// regard to case. A parameter without `=`, like `unicast`, has an
+    /// empty value.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_ref().map_or("", String::as_str))
+    }
+
+    fn new(protocol: &str) -> TransportSpec {
+        TransportSpec {
+            protocol: protocol.into(),
+            params: Vec::new(),
+        }
+    }
+
+    fn with_param(mut self, name: &str, value: Option<String>) -> TransportSpec {
+        match self
+            .params
+            .iter_mut()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        {
+            Some(param) => param.1 = value,
+            None => self.params.push((name.into(), value)),
+        }
+        self
+    }
+
+    fn parse(s: &str) -> Result<TransportSpec, InvalidTypedHeader> {
+        let mut parts = split_unquoted(s, b';').into_iter().map(str::trim);
+        let protocol = parts.next().unwrap_or("");
+        if protocol.is_empty() || protocol.contains(|c: char| c.is_ascii_whitespace()) {
+            return Err(InvalidTypedHeader::new());
+        }
+
+        let mut spec = TransportSpec::new(protocol);
+        for param in parts.filter(|param| !param.is_empty()) {
+            let mut parts = param.splitn(2, '=');
+            let name = parts.next().unwrap_or("").trim();
+            if name.is_empty() {
+                return Err(InvalidTypedHeader::new());
+            }
+            let value = parts.next().map(|value| value.trim().into());
+            spec.params.push((name.into(), value));
+        }
+        Ok(spec)
+    }
+}
+
+impl Header for Transport {
+    fn name() -> &'static HeaderName {
+        &TRANSPORT
+    }
+
+    /// Decodes the transports of every value, in order.
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let mut specs = Vec::new();
+        for value in values {
+            let s = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+            for spec in split_unquoted(s, b',').into_iter().map(str::trim) {
+                if spec.is_empty() {
+                    continue;
+                }
+                specs.push(TransportSpec::parse(spec)?);
+            }
+        }
+        if specs.is_empty() {
+            return Err(InvalidTypedHeader::new());
+        }
+        Ok(Transport { specs })
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let mut s = String::new();
+        let _ = write!(s, "{}", self);
+        let value = HeaderValue::try_from(s).expect("transports are valid header values");
+        values.extend(Some(value));
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, spec) in self.specs.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            fmt::Display::fmt(spec, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TransportSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.protocol)?;
+        for (name, value) in &self.params {
+            write!(f, ";{}", name)?;
+            if let Some(value) = value {
+                write!(f, "={}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Splits `s` at every `sep` outside of a quoted string, like the comma of
+// `mode="PLAY,RECORD"`.
+fn split_unquoted(s: &str, sep: u8) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, b) in s.bytes().enumerate() {
+        if b == b'"' {
+            quoted = !quoted;
+        } else if b == sep && !quoted {
+            parts.push(&s[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn pair<T: fmt::Display>(a: T, b: T) -> String {
+    let mut s = String::new();
+    let _ = write!(s, "{}-{}", a, b);
+    s
+}
+
+// A single port, or channel, is also allowed; it is returned twice.
+fn parse_pair<T: core::str::FromStr + Copy>(s: &str) -> Option<(T, T)> {
+    let mut parts = s.splitn(2, '-');
+    let a = parts.next()?.parse().ok()?;
+    match parts.next() {
+        Some(b) => Some((a, b.parse().ok()?)),
+        None => Some((a, a)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(values: &[&str]) -> Option<Transport> {
+        let mut map = HeaderMap::new();
+        for value in values {
+            map.append(TRANSPORT, value.parse().unwrap());
+        }
+        map.typed_get()
+    }
+
+    #[test]
+    fn transports() {
+        let transport = decode(&[
+            "RTP/AVP;multicast;destination=224.2.0.1;ttl=16;port=3456-3457, x-real-rdt/udp",
+            "RTP/AVP/TCP;interleaved=4;mode=\"PLAY,RECORD\"",
+        ])
+        .unwrap();
+        let specs = transport.specs();
+        assert_eq!(specs.len(), 3);
+        assert!(specs[0].is_multicast());
+        assert_eq!(specs[0].param("TTL"), Some("16"));
+        assert_eq!(specs[0].client_port(), None);
+        assert_eq!(specs[1].protocol(), "x-real-rdt/udp");
+        assert_eq!(specs[2].interleaved(), Some((4, 4)));
+        assert_eq!(specs[2].param("mode"), Some("\"PLAY,RECORD\""));
+        assert_eq!(
+            transport.to_string(),
+            "RTP/AVP;multicast;destination=224.2.0.1;ttl=16;port=3456-3457,\
+             x-real-rdt/udp,RTP/AVP/TCP;interleaved=4;mode=\"PLAY,RECORD\""
+        );
+
+        assert_eq!(decode(&[""]), None);
+        assert_eq!(decode(&["RTP/AVP;=1"]), None);
+        assert_eq!(decode(&["RTP AVP"]), None);
+    }
+
+    #[test]
+    fn answers() {
+        let spec = TransportSpec::rtp_unicast(8000)
+            .with_server_port(9000, 9001)
+            .with_ssrc(0xBEEF);
+        assert_eq!(
+            spec.to_string(),
+            "RTP/AVP;unicast;client_port=8000-8001;server_port=9000-9001;ssrc=0000BEEF"
+        );
+        assert_eq!(spec.client_port(), Some((8000, 8001)));
+        assert_eq!(spec.ssrc(), Some(0xBEEF));
+        assert!(!spec.is_multicast());
+
+        let spec = spec.with_server_port(9002, 9003);
+        assert_eq!(spec.server_port(), Some((9002, 9003)));
+
+        let mut map = HeaderMap::new();
+        map.typed_insert(Transport::new(spec.clone()));
+        assert_eq!(map.typed_get(), Some(Transport::new(spec)));
+    }
+}
@@ -0,0 +1,347 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::slice;
+
+use super::{Header, HttpDate, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, WARNING};
+
+/// The `Warning` header, a list of warnings about a message.
+///
+/// HTTP deprecated the header in RFC 9111, but it is still sent by older
+/// caches and by API gateways, and SIP uses it to explain why a request
+/// failed. Each warning is encoded into its own value.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::{Warning, WarningValue};
+/// let mut map = HeaderMap::new();
+/// map.insert(
+///     "warning",
+///     r#"110 cache.example.com "Response is Stale", 299 - "Deprecated API""#
+///         .parse()
+///         .unwrap(),
+/// );
+///
+/// let warning: Warning = map.typed_get().unwrap();
+/// assert_eq!(warning.values().len(), 2);
+/// assert_eq!(warning.values()[1].code(), 299);
+/// assert_eq!(warning.values()[1].text(), "Deprecated API");
+///
+/// map.typed_insert(Warning::new().with(WarningValue::new(199, "gw", "Slow \"backend\"")));
+/// assert_eq!(map["warning"], r#"199 gw "Slow \"backend\"""#);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Warning {
+    values: Vec<WarningValue>,
+}
+
+/// One warning of a `Warning` header.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WarningValue {
+    code: u16,
+    agent: String,
+    text: String,
+    date: Option<HttpDate>,
+}
+
+impl Warning {
+    /// Creates a header without warnings.
+    pub fn new() -> Warning {
+        Warning { values: Vec::new() }
+    }
+
+    /// Adds a warning.
+    pub fn with(mut self, value: WarningValue) -> Warning {
+        self.values.push(value);
+        self
+    }
+
+    /// Adds a warning.
+    pub fn push(&mut self, value: WarningValue) {
+        self.values.push(value);
+    }
+
+    /// Returns the warnings, in the order they are listed in.
+    pub fn values(&self) -> &[WarningValue] {
+        &self.values
+    }
+
+    /// Returns an iterator over the warnings.
+    pub fn iter(&self) -> slice::Iter<'_, WarningValue> {
+        self.values.iter()
+    }
+}
+
+impl Header for Warning {
+    fn name() -> &'static HeaderName {
+        &WARNING
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let mut warning = Warning::new();
+        for value in values {
+            let s = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+            parse_list(s, &mut warning.values).ok_or_else(InvalidTypedHeader::new)?;
+        }
+        Ok(warning)
+    }
+
+    /// Encodes each warning into its own value.
+    ///
+    /// Warnings whose agent or text are not valid in a header value are
+    /// left out.
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        for warning in &self.values {
+            let mut s = String::new();
+            let _ = write!(s, "{}", warning);
+            if let Ok(value) = HeaderValue::try_from(s) {
+                values.extend(Some(value));
+            }
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Warning {
+    type Item = &'a WarningValue;
+    type IntoIter = slice::Iter<'a, WarningValue>;
+
+    fn into_iter(self) -> slice::Iter<'a, WarningValue> {
+        self.iter()
+    }
+}
+
+impl WarningValue {
+    /// Creates a warning from its code, the host or pseudonym of the agent
+    /// adding it, and its text.
+    ///
+    /// An agent that is empty or contains spaces is replaced with `-`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code doesn't have three digits.
+    pub fn new(code: u16, agent: &str, text: &str) -> WarningValue {
+        assert!(
+            (100..1000).contains(&code),
+            "warn-code must have three digits"
+        );
+        let agent = if agent.is_empty() || agent.contains(&[' ', '\t'][..]) {
+            "-"
+        } else {
+            agent
+        };
+        WarningValue {
+            code,
+            agent: agent.into(),
+            text: text.into(),
+            date: None,
+        }
+    }
+
+    /// Sets the date the warning was added, so caches can drop it from a
+    /// response that was validated since.
+    pub fn with_date(mut self, date: HttpDate) -> WarningValue {
+        self.date = Some(date);
+        self
+    }
+
+    /// Returns the three digit code of the warning.
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    /// Returns the host, or pseudonym, of the agent that added the warning,
+    /// or `-` if it is unknown.
+    pub fn agent(&self) -> &str {
+        &self.agent
+    }
+
+    /// Returns the text of the warning, unquoted.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the date the warning was added, if one was given.
+    pub fn date(&self) -> Option<HttpDate> {
+        self.date
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+        for warning in &self.values {
+            write!(f, "{}{}", sep, warning)?;
+            sep = ", ";
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WarningValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} \"", self.code, self.agent)?;
+        for c in self.text.chars() {
+            if c == '"' || c == '\\' {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        f.write_char('"')?;
+        if let Some(date) = self.date {
+            write!(f, " \"{}\"", date)?;
+        }
+        Ok(())
+    }
+}
+
+// warning = warn-code SP warn-agent SP warn-text [ SP warn-date ]
+fn parse_list(s: &str, values: &mut Vec<WarningValue>) -> Option<()> {
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(&[' ', '\t', ','][..]);
+        if rest.is_empty() {
+            return Some(());
+        }
+
+        let code = rest.get(..3)?;
+        if !code.bytes().all(|b| b.is_ascii_digit()) || code.starts_with('0') {
+            return None;
+        }
+        let code = code.parse().ok()?;
+        rest = &rest[3..];
+        if !rest.starts_with(' ') {
+            return None;
+        }
+
+        rest = &rest[1..];
+        let end = rest.find(' ')?;
+        let agent = &rest[..end];
+        if agent.is_empty() {
+            return None;
+        }
+
+        let (text, after) = quoted(&rest[end + 1..])?;
+        rest = after;
+
+        let mut date = None;
+        let trimmed = rest.trim_start_matches(&[' ', '\t'][..]);
+        if trimmed.starts_with('"') {
+            let (s, after) = quoted(trimmed)?;
+            date = Some(s.parse().ok()?);
+            rest = after;
+        }
+
+        let trimmed = rest.trim_start_matches(&[' ', '\t'][..]);
+        if !trimmed.is_empty() && !trimmed.starts_with(',') {
+            return None;
+        }
+        rest = trimmed;
+
+        values.push(WarningValue {
+            code,
+            agent: agent.into(),
+            text,
+            date,
+        });
+    }
+}
+
+// Returns the unescaped contents of the quoted string at the start of `s`,
+// and what follows it.
+fn quoted(s: &str) -> Option<(String, &str)> {
+    if !s.starts_with('"') {
+        return None;
+    }
+
+    let mut text = String::new();
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        if escaped {
+            text.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return Some((text, &s[i + 1..]));
+        } else {
+            text.push(c);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    fn decode(values: &[&str]) -> Option<Warning> {
+        let mut map = HeaderMap::new();
+        for value in values {
+            map.append(WARNING, value.parse().unwrap());
+        }
+        map.typed_get()
+    }
+
+    #[test]
+    fn lists() {
+        let warning = decode(&[
+            r#"112 - "Disconnected, \"really\"" "Sun, 06 Nov 1994 08:49:37 GMT""#,
+            r#"399 pc33.atlanta.com "Incompatible network protocol", 214 proxy:8080 "Transformed""#,
+        ])
+        .unwrap();
+        assert_eq!(warning.values().len(), 3);
+
+        let first = &warning.values()[0];
+        assert_eq!(first.code(), 112);
+        assert_eq!(first.agent(), "-");
+        assert_eq!(first.text(), "Disconnected, \"really\"");
+        assert_eq!(first.date().unwrap().unix_secs(), 784_111_777);
+        assert_eq!(warning.values()[1].agent(), "pc33.atlanta.com");
+        assert_eq!(warning.values()[2].text(), "Transformed");
+        assert_eq!(warning.values()[2].date(), None);
+
+        let mut map = HeaderMap::new();
+        map.typed_insert(warning.clone());
+        assert_eq!(map.get_all(WARNING).iter().count(), 3);
+        assert_eq!(map.typed_get(), Some(warning));
+
+        assert!(decode(&["110"]).is_none());
+        assert!(decode(&["110 -"]).is_none());
+        assert!(decode(&["110 - stale"]).is_none());
+        assert!(decode(&[r#"110 - "stale"#]).is_none());
+        assert!(decode(&[r#"11 - "stale""#]).is_none());
+        assert!(decode(&[r#"011 - "stale""#]).is_none());
+        assert!(decode(&[r#"110 - "stale" "yesterday""#]).is_none());
+        assert!(decode(&[r#"110 - "stale" x"#]).is_none());
+        assert_eq!(decode(&[""]), Some(Warning::new()));
+    }
+
+    #[test]
+    fn values() {
+        let date = HttpDate::from_unix_secs(0).unwrap();
+        let warning = WarningValue::new(299, "", "a\\b").with_date(date);
+        assert_eq!(
+            warning.to_string(),
+            r#"299 - "a\\b" "Thu, 01 Jan 1970 00:00:00 GMT""#
+        );
+        assert_eq!(WarningValue::new(199, "a b", "").agent(), "-");
+
+        let mut map = HeaderMap::new();
+        map.typed_insert(Warning::new().with(WarningValue::new(199, "-", "bad\n")));
+        assert!(map.get(WARNING).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_code() {
+        WarningValue::new(99, "-", "");
+    }
+}
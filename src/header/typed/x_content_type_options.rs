@@ -0,0 +1,58 @@
+use super::{Header, InvalidTypedHeader};
+use crate::header::{HeaderName, HeaderValue, X_CONTENT_TYPE_OPTIONS};
+
+/// The `X-Content-Type-Options` header, whose only value, `nosniff`, asks
+/// browsers not to guess the type of a response.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderMap;
+/// # use httplike::header::typed::XContentTypeOptions;
+/// let mut map = HeaderMap::new();
+/// map.typed_insert(XContentTypeOptions);
+/// assert_eq!(map["x-content-type-options"], "nosniff");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct XContentTypeOptions;
+
+impl Header for XContentTypeOptions {
+    fn name() -> &'static HeaderName {
+        &X_CONTENT_TYPE_OPTIONS
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, InvalidTypedHeader>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        // Browsers only look at the first value of a list.
+        let value = values.next().ok_or_else(InvalidTypedHeader::new)?;
+        let s = value.to_str().map_err(|_| InvalidTypedHeader::new())?;
+        let first = s.split(',').next().unwrap_or("").trim();
+        if first.eq_ignore_ascii_case("nosniff") {
+            Ok(XContentTypeOptions)
+        } else {
+            Err(InvalidTypedHeader::new())
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(Some(HeaderValue::from_static("nosniff")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::HeaderMap;
+
+    #[test]
+    fn nosniff() {
+        let mut map = HeaderMap::new();
+        map.insert(X_CONTENT_TYPE_OPTIONS, "NoSniff, other".parse().unwrap());
+        assert_eq!(map.typed_get(), Some(XContentTypeOptions));
+
+        map.insert(X_CONTENT_TYPE_OPTIONS, "sniff".parse().unwrap());
+        assert_eq!(map.typed_get::<XContentTypeOptions>(), None);
+    }
+}
@@ -1,11 +1,16 @@
 use bytes::{Bytes, BytesMut};
 
-use std::convert::TryFrom;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::str::FromStr;
+use core::{cmp, fmt, mem, str};
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::str::FromStr;
-use std::{cmp, fmt, mem, str};
 
 use crate::header::name::HeaderName;
+use crate::profile::{self, ParseProfile, Repairs};
+use crate::swar;
 
 /// Represents an HTTP header field value.
 ///
@@ -25,7 +30,7 @@ pub struct HeaderValue {
 /// A possible error when converting a `HeaderValue` from a string or byte
 /// slice.
 pub struct InvalidHeaderValue {
-    _priv: (),
+    offset: usize,
 }
 
 /// A possible error when converting a `HeaderValue` to a string representation.
@@ -59,10 +64,8 @@ impl HeaderValue {
     #[inline]
     pub fn from_static(src: &'static str) -> HeaderValue {
         let bytes = src.as_bytes();
-        for &b in bytes {
-            if !is_visible_ascii(b) {
-                panic!("invalid header value");
-            }
+        if !all_visible_ascii(bytes) {
+            panic!("invalid header value");
         }
 
         HeaderValue {
@@ -147,10 +150,45 @@ impl HeaderValue {
         HeaderValue::try_from_generic(src, Bytes::copy_from_slice)
     }
 
+    /// Attempt to convert a byte slice to a `HeaderValue`, following
+    /// `profile`.
+    ///
+    /// With `ParseProfile::Lenient`, the control characters of an invalid
+    /// value are stripped, and counted in `repairs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::{HeaderValue, ParseProfile};
+    /// # use httplike::profile::Repairs;
+    /// let mut repairs = Repairs::new();
+    /// let val = HeaderValue::from_bytes_with(b"text/\x00html", ParseProfile::Lenient, &mut repairs);
+    /// assert_eq!(val.unwrap(), "text/html");
+    /// assert_eq!(repairs.stripped_controls(), 1);
+    /// ```
+    pub fn from_bytes_with(
+        src: &[u8],
+        profile: ParseProfile,
+        repairs: &mut Repairs,
+    ) -> Result<HeaderValue, InvalidHeaderValue> {
+        match HeaderValue::from_bytes(src) {
+            Err(_) if profile.is_lenient() => {
+                let mut found = Repairs::new();
+                let repaired = profile::repair_value(src, &mut found);
+                let val = HeaderValue::try_from_generic(repaired, Bytes::from)?;
+                repairs.add(&found);
+                Ok(val)
+            }
+            res => res,
+        }
+    }
+
     /// Attempt to convert a `Bytes` buffer to a `HeaderValue`.
     ///
     /// This will try to prevent a copy if the type passed is the type used
-    /// internally, and will copy the data if it is not.
+    /// internally, and will copy the data if it is not. A few very common
+    /// values, such as `gzip` or `keep-alive`, are neither copied nor shared,
+    /// but use a static buffer instead.
     pub fn from_maybe_shared<T>(src: T) -> Result<HeaderValue, InvalidHeaderValue>
     where
         T: AsRef<[u8]> + 'static,
@@ -195,14 +233,21 @@ impl HeaderValue {
     }
 
     fn from_shared(src: Bytes) -> Result<HeaderValue, InvalidHeaderValue> {
-        HeaderValue::try_from_generic(src, std::convert::identity)
+        HeaderValue::try_from_generic(src, core::convert::identity)
     }
 
     fn try_from_generic<T: AsRef<[u8]>, F: FnOnce(T) -> Bytes>(src: T, into: F) -> Result<HeaderValue, InvalidHeaderValue> {
-        for &b in src.as_ref() {
-            if !is_valid(b) {
-                return Err(InvalidHeaderValue { _priv: () });
-            }
+        if let Some(known) = known(src.as_ref()) {
+            return Ok(HeaderValue {
+                inner: Bytes::from_static(known),
+                is_sensitive: false,
+            });
+        }
+        if !all_valid(src.as_ref()) {
+            let offset = src.as_ref().iter().position(|&b| !is_valid(b));
+            return Err(InvalidHeaderValue {
+                offset: offset.unwrap_or(0),
+            });
         }
         Ok(HeaderValue {
             inner: into(src),
@@ -226,10 +271,8 @@ impl HeaderValue {
     pub fn to_str(&self) -> Result<&str, ToStrError> {
         let bytes = self.as_ref();
 
-        for &b in bytes {
-            if !is_visible_ascii(b) {
-                return Err(ToStrError { _priv: () });
-            }
+        if !all_visible_ascii(bytes) {
+            return Err(ToStrError { _priv: () });
         }
 
         unsafe { Ok(str::from_utf8_unchecked(bytes)) }
@@ -308,7 +351,8 @@ impl HeaderValue {
     /// like caches to avoid storing the value. HPACK encoders must set the
     /// header field to never index when `is_sensitive` returns true.
     ///
-    /// Note that sensitivity is not factored into equality or ordering.
+    /// Note that sensitivity is not factored into equality or ordering. The
+    /// `Debug` output of a sensitive value only includes its length.
     ///
     /// # Examples
     ///
@@ -338,7 +382,7 @@ impl AsRef<[u8]> for HeaderValue {
 impl fmt::Debug for HeaderValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_sensitive {
-            f.write_str("Sensitive")
+            write!(f, "Sensitive(len={})", self.len())
         } else {
             f.write_str("\"")?;
             let mut from = 0;
@@ -559,20 +603,101 @@ fn is_valid(b: u8) -> bool {
     b >= 32 && b != 127 || b == b'\t'
 }
 
+macro_rules! known_values {
+    ($($value:literal,)+) => {
+        // Values common enough that converting them returns a static copy,
+        // instead of allocating or keeping a reference to the buffer they
+        // were parsed from.
+        #[inline]
+        fn known(bytes: &[u8]) -> Option<&'static [u8]> {
+            match bytes {
+                $($value => Some(&$value[..]),)+
+                _ => None,
+            }
+        }
+
+        #[cfg(test)]
+        const KNOWN_VALUES: &[&[u8]] = &[$($value,)+];
+    };
+}
+
+known_values! {
+    b"*/*",
+    b"0",
+    b"1",
+    b"br",
+    b"bytes",
+    b"chunked",
+    b"close",
+    b"Close",
+    b"deflate",
+    b"gzip",
+    b"gzip, deflate",
+    b"gzip, deflate, br",
+    b"gzip, deflate, br, zstd",
+    b"identity",
+    b"keep-alive",
+    b"Keep-Alive",
+    b"max-age=0",
+    b"no-cache",
+    b"no-store",
+    b"nosniff",
+    b"trailers",
+    b"upgrade",
+    b"Upgrade",
+    b"websocket",
+    b"application/json",
+    b"application/json; charset=utf-8",
+    b"application/octet-stream",
+    b"application/x-www-form-urlencoded",
+    b"text/css",
+    b"text/html",
+    b"text/html; charset=utf-8",
+    b"text/plain",
+    b"text/plain; charset=utf-8",
+}
+
+// Tabs are rare, so words with one are left to the byte checks.
+#[inline]
+fn all_visible_ascii(bytes: &[u8]) -> bool {
+    swar::all(
+        bytes,
+        |x| ((x & swar::HIGH) | swar::lt(x, 32) | swar::eq(x, 127)) == 0,
+        is_visible_ascii,
+    )
+}
+
+#[inline]
+fn all_valid(bytes: &[u8]) -> bool {
+    swar::all(
+        bytes,
+        |x| (swar::lt(x, 32) | swar::eq(x, 127)) == 0,
+        is_valid,
+    )
+}
+
+impl InvalidHeaderValue {
+    // The offset of the first invalid byte of the value.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
 impl fmt::Debug for InvalidHeaderValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("InvalidHeaderValue")
-            // skip _priv noise
+            .field("offset", &self.offset)
             .finish()
     }
 }
 
 impl fmt::Display for InvalidHeaderValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.description().fmt(f)
+        f.write_str("failed to parse header value")
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for InvalidHeaderValue {
     fn description(&self) -> &str {
         "failed to parse header value"
@@ -581,10 +706,11 @@ impl Error for InvalidHeaderValue {
 
 impl fmt::Display for ToStrError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.description().fmt(f)
+        f.write_str("failed to convert header to a str")
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for ToStrError {
     fn description(&self) -> &str {
         "failed to convert header to a str"
@@ -753,6 +879,35 @@ fn test_try_from() {
     HeaderValue::try_from(vec![127]).unwrap_err();
 }
 
+#[test]
+fn test_long_values() {
+    let mut bytes = b"text/html,application/xhtml+xml,application/xml;q=0.9".to_vec();
+    assert!(HeaderValue::from_bytes(&bytes).unwrap().to_str().is_ok());
+
+    bytes[20] = b'\t';
+    assert!(HeaderValue::from_bytes(&bytes).unwrap().to_str().is_ok());
+    bytes[20] = 0x80;
+    assert!(HeaderValue::from_bytes(&bytes).unwrap().to_str().is_err());
+    bytes[20] = 127;
+    assert!(HeaderValue::from_bytes(&bytes).is_err());
+    bytes[20] = b'\n';
+    assert!(HeaderValue::from_bytes(&bytes).is_err());
+}
+
+#[test]
+fn test_known_values() {
+    for &value in KNOWN_VALUES {
+        assert!(all_visible_ascii(value));
+
+        let parsed = HeaderValue::from_maybe_shared(Bytes::copy_from_slice(value)).unwrap();
+        assert_eq!(parsed, value);
+        assert_eq!(parsed.as_bytes().as_ptr(), known(value).unwrap().as_ptr());
+    }
+
+    assert!(known(b"gzip ").is_none());
+    assert!(known(b"GZIP").is_none());
+}
+
 #[test]
 fn test_debug() {
     let cases = &[
@@ -769,5 +924,5 @@ fn test_debug() {
 
     let mut sensitive = HeaderValue::from_static("password");
     sensitive.set_sensitive(true);
-    assert_eq!("Sensitive", format!("{:?}", sensitive));
+    assert_eq!("Sensitive(len=8)", format!("{:?}", sensitive));
 }
@@ -157,8 +157,92 @@
 //! assert_eq!(uri.path(), "/index.html");
 //! assert_eq!(uri.query(), None);
 //! ```
+//!
+//! # `no_std` support
+//!
+//! The `std` feature is enabled by default. Disabling it builds the crate
+//! against `core` and `alloc` only, for targets like embedded firmware
+//! without an operating system. Everything but the `serde` and `test-util`
+//! support is available in that configuration, with a few differences:
+//!
+//! * Error types do not implement `std::error::Error`, and `Error` has no
+//!   `get_ref` or `is` methods.
+//! * `Extensions` is backed by a `BTreeMap` instead of a `HashMap`.
+//! * The `Deadline`, `PeerAddr` and `LocalAddr` extension types, and
+//!   `uri::Host`, which are built on `std::time` and `std::net`, are not
+//!   available.
+//! * `multipart::Boundary::generate` is not available, as its randomness
+//!   comes from `std`; use `Boundary::from_random` instead.
+//!
+//! # `serde` support
+//!
+//! With the `serde` feature enabled, `Method`, `StatusCode`, `Version`,
+//! `Uri`, `HeaderName`, `HeaderValue`, `HeaderMap` and the request and
+//! response `Parts` implement `Serialize` and `Deserialize`, so message heads
+//! can be recorded and replayed. Extensions are not serialized.
+//!
+//! Binary formats that are not human readable, like bincode and postcard,
+//! get a compact tuple representation instead, so heads can be handed from
+//! one process to another without parsing them again.
+//!
+//! # SDP support
+//!
+//! The `sdp` feature enables the `sdp` module, which parses and writes the
+//! SDP session descriptions carried in RTSP and SIP bodies.
+//!
+//! # SIP support
+//!
+//! The `sip` feature enables SIP versions and header rules, the `sip` and
+//! `sips` schemes, the `uri::sip` module, which parses the parameters and
+//! headers of SIP URIs, and the `sip` module, which generates and matches the
+//! identifiers of SIP transactions and dialogs.
+//!
+//! # tel URIs
+//!
+//! The `tel` feature adds the `tel` scheme to `uri::Scheme`, whose URIs have
+//! a telephone number instead of an authority, and the `uri::tel` module,
+//! which parses the number and converts it to and from a SIP URI.
+//!
+//! # WebSocket support
+//!
+//! The `ws` feature adds the `ws` and `wss` schemes to `uri::Scheme`, so
+//! WebSocket URIs are parsed without allocating their scheme, and the `ws`
+//! module, which checks and answers the opening handshake. It enables the
+//! `http` feature.
+//!
+//! # International hostnames
+//!
+//! The `idna` feature adds `Authority::to_ascii` and `Authority::to_unicode`,
+//! which convert hostnames between their Unicode form and the Punycode form
+//! used on the wire.
+//!
+//! # Digest authentication
+//!
+//! The `digest` feature enables the `digest` module, which computes and
+//! verifies the Digest authentication of HTTP, RTSP and SIP requests.
+//!
+//! # Tracing
+//!
+//! The `tracing` feature enables the `tracing` module, which records the
+//! method, status, version and redacted URI of messages as structured log
+//! fields, with the standard field names.
+//!
+//! # Fuzzing
+//!
+//! The `fuzz` feature enables the `fuzz` module, which generates valid and
+//! nearly valid message heads from fuzzer input and checks that decoding and
+//! encoding them round trips.
+//!
+//! # Test utilities
+//!
+//! The `test-util` feature enables the `test_util` module, with fixtures,
+//! header assertion macros and a stable rendering of message heads for
+//! snapshot tests. It is meant to be enabled in `dev-dependencies` only.
 
 #![deny(warnings, missing_docs, missing_debug_implementations)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 #[cfg(test)]
 #[macro_use]
@@ -170,23 +254,54 @@ doctest!("../README.md");
 #[macro_use]
 mod convert;
 
+pub mod body;
+pub mod codec;
+#[cfg(feature = "http")]
+pub mod connect;
+#[cfg(feature = "digest")]
+pub mod digest;
 pub mod header;
 pub mod method;
+pub mod multipart;
+pub mod profile;
+pub mod protocol;
+#[cfg(feature = "http")]
+pub mod pseudo;
+pub mod extensions;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
 pub mod request;
 pub mod response;
+#[cfg(feature = "sdp")]
+pub mod sdp;
+#[cfg(feature = "sip")]
+pub mod sip;
 pub mod status;
+#[cfg(all(feature = "test-util", feature = "std"))]
+pub mod test_util;
+#[cfg(feature = "tracing")]
+pub mod tracing;
 pub mod uri;
+pub mod validate;
 pub mod version;
+#[cfg(feature = "ws")]
+pub mod ws;
 
 mod byte_str;
+#[cfg(all(feature = "serde", feature = "std"))]
+mod serde_impls;
 mod error;
-mod extensions;
+#[cfg(feature = "std")]
+mod random;
+mod swar;
 
-pub use crate::error::{Error, Result};
+pub use crate::error::{BuildErrors, Error, ErrorKind, Rejected, Result};
 pub use crate::extensions::Extensions;
 #[doc(no_inline)]
 pub use crate::header::{HeaderMap, HeaderValue};
 pub use crate::method::Method;
+pub use crate::profile::ParseProfile;
+pub use crate::protocol::Protocol;
 pub use crate::request::Request;
 pub use crate::response::Response;
 pub use crate::status::StatusCode;
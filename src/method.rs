@@ -17,11 +17,16 @@
 
 use self::Inner::*;
 
-use std::convert::AsRef;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::AsRef;
+use core::str::FromStr;
+use core::convert::TryFrom;
+use core::{fmt, str};
+
+use bytes::Bytes;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::str::FromStr;
-use std::convert::TryFrom;
-use std::{fmt, str};
 
 /// The Request Method (VERB)
 ///
@@ -76,6 +81,8 @@ enum Inner {
     #[cfg(feature = "rtsp")]
     Play,
     #[cfg(feature = "rtsp")]
+    PlayNotify,
+    #[cfg(feature = "rtsp")]
     Record,
     #[cfg(feature = "rtsp")]
     Redirect,
@@ -85,8 +92,9 @@ enum Inner {
     Teardown,
     // If the extension is short enough, store it inline
     ExtensionInline([u8; MAX_INLINE], u8),
-    // Otherwise, allocate it
-    ExtensionAllocated(Box<[u8]>),
+    // Otherwise, keep it in a `Bytes`, which may share the buffer it was
+    // parsed from
+    ExtensionShared(Bytes),
 }
 
 const MAX_INLINE: usize = 15;
@@ -169,6 +177,8 @@ impl Method {
     pub const DESCRIBE: Method = Method(Describe);
 
     /// ANNOUNCE
+    ///
+    /// RTSP/2.0 removed this method.
     #[cfg(feature = "rtsp")]
     pub const ANNOUNCE: Method = Method(Announce);
 
@@ -188,7 +198,16 @@ impl Method {
     #[cfg(feature = "rtsp")]
     pub const PLAY: Method = Method(Play);
 
+    /// PLAY_NOTIFY
+    ///
+    /// RTSP/2.0 added this method, for the server to notify the client of
+    /// events during playback.
+    #[cfg(feature = "rtsp")]
+    pub const PLAY_NOTIFY: Method = Method(PlayNotify);
+
     /// RECORD
+    ///
+    /// RTSP/2.0 removed this method.
     #[cfg(feature = "rtsp")]
     pub const RECORD: Method = Method(Record);
 
@@ -252,6 +271,11 @@ impl Method {
                 _ => Method::extension_inline(src),
             },
             #[cfg(feature = "rtsp")]
+            11 => match src {
+                b"PLAY_NOTIFY" => Ok(Method(PlayNotify)),
+                _ => Method::extension_inline(src),
+            },
+            #[cfg(feature = "rtsp")]
             13 => match src {
                 b"GET_PARAMETER" => Ok(Method(GetParameter)),
                 b"SET_PARAMETER" => Ok(Method(SetParameter)),
@@ -265,12 +289,25 @@ impl Method {
 
                     write_checked(src, &mut data)?;
 
-                    Ok(Method(ExtensionAllocated(data.into_boxed_slice())))
+                    Ok(Method(ExtensionShared(Bytes::from(data))))
                 }
             }
         }
     }
 
+    /// Converts a `Bytes` buffer to a method, without copying a long
+    /// extension method.
+    pub(crate) fn from_shared(src: Bytes) -> Result<Method, InvalidMethod> {
+        if src.len() < MAX_INLINE {
+            return Method::from_bytes(&src);
+        }
+
+        if src.iter().any(|&b| METHOD_CHARS[b as usize] == 0) {
+            return Err(InvalidMethod::new());
+        }
+        Ok(Method(ExtensionShared(src)))
+    }
+
     fn extension_inline(src: &[u8]) -> Result<Method, InvalidMethod> {
         let mut data: [u8; MAX_INLINE] = Default::default();
 
@@ -332,10 +369,12 @@ impl Method {
             #[cfg(feature = "rtsp")]
             SetParameter => "SET_PARAMETER",
             #[cfg(feature = "rtsp")]
-            Pause => "Pause",
+            Pause => "PAUSE",
             #[cfg(feature = "rtsp")]
             Play => "PLAY",
             #[cfg(feature = "rtsp")]
+            PlayNotify => "PLAY_NOTIFY",
+            #[cfg(feature = "rtsp")]
             Record => "RECORD",
             #[cfg(feature = "rtsp")]
             Redirect => "REDIRECT",
@@ -346,7 +385,7 @@ impl Method {
             ExtensionInline(ref data, len) => unsafe {
                 str::from_utf8_unchecked(&data[..len as usize])
             },
-            ExtensionAllocated(ref data) => unsafe { str::from_utf8_unchecked(data) },
+            ExtensionShared(ref data) => unsafe { str::from_utf8_unchecked(data) },
         }
     }
 }
@@ -483,10 +522,11 @@ impl fmt::Debug for InvalidMethod {
 
 impl fmt::Display for InvalidMethod {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.description())
+        f.write_str("invalid HTTP method")
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for InvalidMethod {
     fn description(&self) -> &str {
         "invalid HTTP method"
@@ -509,6 +549,8 @@ fn test_method_eq() {
     {
         assert_eq!("RECORD", Method::RECORD);
         assert_eq!("RECORD", &Method::RECORD);
+        assert_eq!(Method::PAUSE.as_str(), "PAUSE");
+        assert_eq!(Method::from_bytes(b"PLAY_NOTIFY").unwrap(), Method::PLAY_NOTIFY);
     }
 }
 
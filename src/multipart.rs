@@ -0,0 +1,354 @@
+//! Helpers for `multipart` bodies.
+//!
+//! A `multipart` body, RFC 2046 section 5.1, is a sequence of parts
+//! separated by a boundary, each with its own block of header lines. This
+//! module generates boundaries, writes the delimiters and part heads around
+//! the bodies of the parts, and parses part heads back into a `HeaderMap`.
+//! Finding the delimiters in a received body is left to the caller, as it
+//! depends on how the body is buffered.
+//!
+//! [`byterange_headers`] builds the headers of a part of a
+//! `multipart/byteranges` response to a request with several ranges,
+//! RFC 7233 appendix A.
+//!
+//! [`byterange_headers`]: fn.byterange_headers.html
+//!
+//! # Examples
+//!
+//! ```
+//! use bytes::BytesMut;
+//! use httplike::HeaderValue;
+//! use httplike::multipart::{byterange_headers, decode_part_head, Boundary};
+//!
+//! let boundary = Boundary::new("THIS_STRING_SEPARATES").unwrap();
+//! let content_type = HeaderValue::from_static("text/plain");
+//! let body = b"0123456789";
+//!
+//! let mut buf = BytesMut::new();
+//! for range in &[0..3, 7..10] {
+//!     let headers = byterange_headers(&content_type, range.clone(), Some(10)).unwrap();
+//!     boundary.encode_part_head(&headers, &mut buf);
+//!     buf.extend_from_slice(&body[range.start as usize..range.end as usize]);
+//!     buf.extend_from_slice(b"\r\n");
+//! }
+//! boundary.encode_end(&mut buf);
+//!
+//! assert_eq!(
+//!     boundary.content_type("byteranges"),
+//!     "multipart/byteranges; boundary=THIS_STRING_SEPARATES"
+//! );
+//! assert!(buf.starts_with(b"--THIS_STRING_SEPARATES\r\ncontent-type: text/plain\r\n"));
+//! assert!(buf.ends_with(b"789\r\n--THIS_STRING_SEPARATES--\r\n"));
+//!
+//! let mut head = BytesMut::from(&b"Content-Range: bytes 7-9/10\r\n\r\n789"[..]);
+//! let headers = decode_part_head(&mut head).unwrap().unwrap();
+//! assert_eq!(headers["content-range"], "bytes 7-9/10");
+//! assert_eq!(&head[..], b"789");
+//! ```
+
+use alloc::string::String;
+use core::fmt::{self, Write};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use bytes::BytesMut;
+
+use crate::codec::{self, Limits, ParseError};
+use crate::header::{HeaderMap, HeaderValue, CONTENT_RANGE, CONTENT_TYPE};
+
+/// The boundary separating the parts of a `multipart` body.
+///
+/// A boundary is 1 to 70 characters long, of the characters RFC 2046
+/// allows, and doesn't end with a space.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Boundary(String);
+
+/// An error parsing a `Boundary`.
+pub struct InvalidBoundary {
+    _priv: (),
+}
+
+const MAX_BOUNDARY_LEN: usize = 70;
+
+impl Boundary {
+    /// Checks that `s` is a valid boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::multipart::Boundary;
+    /// assert!(Boundary::new("simple boundary").is_ok());
+    /// assert!(Boundary::new("trailing space ").is_err());
+    /// assert!(Boundary::new("semi;colon").is_err());
+    /// ```
+    pub fn new(s: &str) -> Result<Boundary, InvalidBoundary> {
+        let valid = !s.is_empty()
+            && s.len() <= MAX_BOUNDARY_LEN
+            && !s.ends_with(' ')
+            && s.bytes().all(is_bchar);
+        if valid {
+            Ok(Boundary(s.into()))
+        } else {
+            Err(InvalidBoundary { _priv: () })
+        }
+    }
+
+    /// Creates a boundary from 16 random bytes.
+    ///
+    /// The boundary is `httplike-` followed by the bytes in hex. It can't
+    /// appear in a body that is percent-encoded, base64 or hex, and is
+    /// unlikely to appear in any other body if the bytes are random.
+    pub fn from_random(bytes: [u8; 16]) -> Boundary {
+        let mut s = String::with_capacity(41);
+        s.push_str("httplike-");
+        for b in &bytes {
+            let _ = write!(s, "{:02x}", b);
+        }
+        Boundary(s)
+    }
+
+    /// Generates a new random boundary.
+    ///
+    /// The bytes come from the keys of the standard library's `RandomState`.
+    /// They differ on every call, but are not from a cryptographic
+    /// generator, which a boundary doesn't need. Use `from_random` to
+    /// provide the bytes yourself.
+    #[cfg(feature = "std")]
+    pub fn generate() -> Boundary {
+        let mut bytes = [0; 16];
+        crate::random::fill(&mut bytes);
+        Boundary::from_random(bytes)
+    }
+
+    /// Returns the boundary as a `&str`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the `Content-Type` of a body with this boundary, like
+    /// `multipart/mixed; boundary=...`.
+    ///
+    /// The boundary is quoted if it contains characters a token can't.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subtype` isn't a valid header value.
+    pub fn content_type(&self, subtype: &str) -> HeaderValue {
+        let mut s = String::with_capacity(27 + subtype.len() + self.0.len());
+        if self.0.bytes().all(is_token) {
+            let _ = write!(s, "multipart/{}; boundary={}", subtype, self.0);
+        } else {
+            let _ = write!(s, "multipart/{}; boundary=\"{}\"", subtype, self.0);
+        }
+        HeaderValue::from_maybe_shared(s).expect("invalid multipart subtype")
+    }
+
+    /// Writes the delimiter and header lines starting a part to `dst`.
+    ///
+    /// The body of the part follows, and must be terminated by CRLF before
+    /// the next part or the end, as the CRLF belongs to the delimiter.
+    pub fn encode_part_head(&self, headers: &HeaderMap, dst: &mut BytesMut) {
+        let mut len = self.0.len() + 6;
+        for (name, value) in headers {
+            len += name.as_str().len() + value.len() + 4;
+        }
+        dst.reserve(len);
+
+        dst.extend_from_slice(b"--");
+        dst.extend_from_slice(self.0.as_bytes());
+        dst.extend_from_slice(b"\r\n");
+        for (name, value) in headers {
+            dst.extend_from_slice(name.as_str().as_bytes());
+            dst.extend_from_slice(b": ");
+            dst.extend_from_slice(value.as_bytes());
+            dst.extend_from_slice(b"\r\n");
+        }
+        dst.extend_from_slice(b"\r\n");
+    }
+
+    /// Writes the delimiter ending the body to `dst`.
+    pub fn encode_end(&self, dst: &mut BytesMut) {
+        dst.reserve(self.0.len() + 6);
+        dst.extend_from_slice(b"--");
+        dst.extend_from_slice(self.0.as_bytes());
+        dst.extend_from_slice(b"--\r\n");
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn is_bchar(b: u8) -> bool {
+    match b {
+        b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' => true,
+        b'\'' | b'(' | b')' | b'+' | b'_' | b',' | b'-' | b'.' | b'/' | b':' | b'=' | b'?' => true,
+        b' ' => true,
+        _ => false,
+    }
+}
+
+fn is_token(b: u8) -> bool {
+    match b {
+        b'(' | b')' | b',' | b'/' | b':' | b'=' | b'?' | b' ' => false,
+        b => is_bchar(b),
+    }
+}
+
+/// Returns the headers of a part of a `multipart/byteranges` body.
+///
+/// `range` is the half-open range of bytes in the part, and `complete_len`
+/// the length of the whole representation, if known. Returns `None` if the
+/// range is empty or extends past `complete_len`.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::HeaderValue;
+/// # use httplike::multipart::byterange_headers;
+/// let content_type = HeaderValue::from_static("video/mp4");
+/// let headers = byterange_headers(&content_type, 500..1000, None).unwrap();
+/// assert_eq!(headers["content-type"], "video/mp4");
+/// assert_eq!(headers["content-range"], "bytes 500-999/*");
+///
+/// assert!(byterange_headers(&content_type, 500..1000, Some(800)).is_none());
+/// ```
+pub fn byterange_headers(
+    content_type: &HeaderValue,
+    range: Range<u64>,
+    complete_len: Option<u64>,
+) -> Option<HeaderMap> {
+    if range.start >= range.end {
+        return None;
+    }
+    if let Some(len) = complete_len {
+        if range.end > len {
+            return None;
+        }
+    }
+
+    let mut s = String::with_capacity(48);
+    let _ = write!(s, "bytes {}-{}/", range.start, range.end - 1);
+    match complete_len {
+        Some(len) => {
+            let _ = write!(s, "{}", len);
+        }
+        None => s.push('*'),
+    }
+
+    let mut headers = HeaderMap::with_capacity(2);
+    headers.insert(CONTENT_TYPE, content_type.clone());
+    headers.insert(
+        CONTENT_RANGE,
+        HeaderValue::from_maybe_shared(s).expect("content-range is a valid header value"),
+    );
+    Some(headers)
+}
+
+/// Parses the header lines of a part, following its delimiter, off the
+/// front of `src`.
+///
+/// Returns `None` until the empty line ending them has been received, like
+/// the `codec` decoders. The header lines are parsed the same way as those
+/// of a message head, with the default `Limits`.
+pub fn decode_part_head(src: &mut BytesMut) -> Result<Option<HeaderMap>, ParseError> {
+    decode_part_head_with(src, &Limits::default())
+}
+
+/// Parses the header lines of a part off the front of `src`, within
+/// `limits`.
+pub fn decode_part_head_with(
+    src: &mut BytesMut,
+    limits: &Limits,
+) -> Result<Option<HeaderMap>, ParseError> {
+    codec::decode_header_block(src, limits)
+}
+
+impl fmt::Debug for InvalidBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidBoundary").finish()
+    }
+}
+
+impl fmt::Display for InvalidBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid multipart boundary")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for InvalidBoundary {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries() {
+        assert!(Boundary::new("").is_err());
+        assert!(Boundary::new(&"a".repeat(70)).is_ok());
+        assert!(Boundary::new(&"a".repeat(71)).is_err());
+
+        let generated = Boundary::generate();
+        assert_eq!(generated.as_str().len(), 41);
+        assert!(Boundary::new(generated.as_str()).is_ok());
+        assert_ne!(generated, Boundary::generate());
+        assert_eq!(
+            generated.content_type("mixed"),
+            format!("multipart/mixed; boundary={}", generated).as_str()
+        );
+
+        let quoted = Boundary::new("a:b").unwrap();
+        assert_eq!(
+            quoted.content_type("mixed"),
+            "multipart/mixed; boundary=\"a:b\""
+        );
+    }
+
+    #[test]
+    fn part_heads() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "text/html".parse().unwrap());
+        headers.append("x-part", "1".parse().unwrap());
+        headers.append("x-part", "2".parse().unwrap());
+
+        let boundary = Boundary::new("b").unwrap();
+        let mut buf = BytesMut::new();
+        boundary.encode_part_head(&headers, &mut buf);
+        assert_eq!(
+            &buf[..],
+            &b"--b\r\ncontent-type: text/html\r\nx-part: 1\r\nx-part: 2\r\n\r\n"[..]
+        );
+
+        let mut head = buf.split_off(5);
+        assert_eq!(decode_part_head(&mut head).unwrap(), Some(headers));
+        assert!(head.is_empty());
+
+        let mut empty = BytesMut::from(&b"\r\nbody"[..]);
+        assert_eq!(
+            decode_part_head(&mut empty).unwrap(),
+            Some(HeaderMap::new())
+        );
+        assert_eq!(&empty[..], b"body");
+
+        let mut partial = BytesMut::from(&b"content-type: text/plain\r\n"[..]);
+        assert_eq!(decode_part_head(&mut partial).unwrap(), None);
+        assert_eq!(partial.len(), 26);
+
+        let mut invalid = BytesMut::from(&b"no colon\r\n\r\n"[..]);
+        assert!(decode_part_head(&mut invalid).is_err());
+    }
+
+    #[test]
+    fn byteranges() {
+        let content_type = HeaderValue::from_static("text/plain");
+        let headers = byterange_headers(&content_type, 0..1, Some(1)).unwrap();
+        assert_eq!(headers[CONTENT_RANGE], "bytes 0-0/1");
+        assert!(byterange_headers(&content_type, 1..1, None).is_none());
+        assert!(byterange_headers(&content_type, 0..2, Some(1)).is_none());
+    }
+}
@@ -0,0 +1,208 @@
+//! How strictly input is parsed
+//!
+//! This module contains the `ParseProfile` type, and the `Repairs` it
+//! records. The `ParseProfile` type is intended to be accessed through the
+//! root of the crate (`httplike::ParseProfile`) rather than this module.
+//!
+//! The parsers of this crate follow the RFCs. Clients, servers and
+//! middleboxes in the wild don't always, and a transparent proxy has to
+//! forward what they send anyway. With `ParseProfile::Lenient`, the parsers
+//! repair the most common mistakes instead of failing, and count each repair
+//! in a `Repairs`, so the proxy can log them or refuse a message that needed
+//! too many:
+//!
+//! * bytes that aren't allowed in the path or query of a URI, like spaces,
+//!   are percent-encoded,
+//! * control characters in URIs, header names and header values are
+//!   stripped,
+//! * `Content-Length` headers repeating the same length are collapsed into
+//!   one by the `codec` decoders.
+//!
+//! Input that is valid is parsed the same way by both profiles. Mistakes
+//! that let two peers disagree about the framing of a message, like
+//! whitespace before the colon of a header line or `Content-Length` headers
+//! with different lengths, are never repaired.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::{ParseProfile, Uri};
+//! use httplike::profile::Repairs;
+//!
+//! let mut repairs = Repairs::new();
+//! let uri = Uri::from_bytes_with(b"/my file\x07.txt", ParseProfile::Lenient, &mut repairs).unwrap();
+//! assert_eq!(uri, "/my%20file.txt");
+//! assert_eq!(repairs.percent_encoded(), 1);
+//! assert_eq!(repairs.stripped_controls(), 1);
+//!
+//! assert!(Uri::from_bytes_with(b"/my file", ParseProfile::Strict, &mut repairs).is_err());
+//! ```
+
+use alloc::vec::Vec;
+
+/// How strictly input is parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParseProfile {
+    /// Reject anything the RFCs don't allow. This is the default.
+    Strict,
+
+    /// Repair common mistakes, recording each in a `Repairs`.
+    Lenient,
+}
+
+impl ParseProfile {
+    /// Returns true if this is `ParseProfile::Lenient`.
+    #[inline]
+    pub fn is_lenient(&self) -> bool {
+        *self == ParseProfile::Lenient
+    }
+}
+
+impl Default for ParseProfile {
+    #[inline]
+    fn default() -> ParseProfile {
+        ParseProfile::Strict
+    }
+}
+
+/// The repairs made while parsing with `ParseProfile::Lenient`.
+///
+/// The `codec` decoders store this in the extensions of a message when
+/// they had to repair it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Repairs {
+    percent_encoded: usize,
+    stripped_controls: usize,
+    collapsed_content_lengths: usize,
+}
+
+impl Repairs {
+    /// Creates a `Repairs` without any repair.
+    #[inline]
+    pub fn new() -> Repairs {
+        Repairs::default()
+    }
+
+    /// Returns true if nothing was repaired.
+    pub fn is_empty(&self) -> bool {
+        *self == Repairs::default()
+    }
+
+    /// Returns the number of bytes of URIs that were percent-encoded.
+    #[inline]
+    pub fn percent_encoded(&self) -> usize {
+        self.percent_encoded
+    }
+
+    /// Returns the number of control characters stripped from URIs, header
+    /// names and header values.
+    #[inline]
+    pub fn stripped_controls(&self) -> usize {
+        self.stripped_controls
+    }
+
+    /// Returns the number of repeated `Content-Length` headers that were
+    /// dropped.
+    #[inline]
+    pub fn collapsed_content_lengths(&self) -> usize {
+        self.collapsed_content_lengths
+    }
+
+    pub(crate) fn add(&mut self, other: &Repairs) {
+        self.percent_encoded += other.percent_encoded;
+        self.stripped_controls += other.stripped_controls;
+        self.collapsed_content_lengths += other.collapsed_content_lengths;
+    }
+
+    pub(crate) fn collapsed(&mut self, count: usize) {
+        self.collapsed_content_lengths += count;
+    }
+}
+
+fn is_control(b: u8) -> bool {
+    b < 0x20 || b == 0x7f
+}
+
+/// Percent-encodes the bytes of the path and query of `src` that URIs
+/// can't contain, and strips its control characters.
+pub(crate) fn repair_uri(src: &[u8], repairs: &mut Repairs) -> Vec<u8> {
+    // The authority is left alone; a space there is no typo to fix.
+    let path = if src.first() == Some(&b'/') {
+        0
+    } else {
+        match src.windows(3).position(|w| w == b"://") {
+            Some(i) => src[i + 3..]
+                .iter()
+                .position(|&b| b == b'/' || b == b'?')
+                .map_or(src.len(), |j| i + 3 + j),
+            None => src.len(),
+        }
+    };
+
+    let mut dst = Vec::with_capacity(src.len() + 8);
+    for (i, &b) in src.iter().enumerate() {
+        if is_control(b) {
+            repairs.stripped_controls += 1;
+        } else if i >= path && needs_encoding(b) {
+            const HEX: &[u8; 16] = b"0123456789ABCDEF";
+            dst.extend_from_slice(&[b'%', HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]]);
+            repairs.percent_encoded += 1;
+        } else {
+            dst.push(b);
+        }
+    }
+    dst
+}
+
+fn needs_encoding(b: u8) -> bool {
+    match b {
+        b' ' | b'"' | b'<' | b'>' | b'`' | b'{' | b'}' => true,
+        b => b >= 0x80,
+    }
+}
+
+/// Strips the control characters of a header value, except tabs.
+pub(crate) fn repair_value(src: &[u8], repairs: &mut Repairs) -> Vec<u8> {
+    strip(src, |b| is_control(b) && b != b'\t', repairs)
+}
+
+/// Strips the control characters of a header name.
+pub(crate) fn repair_name(src: &[u8], repairs: &mut Repairs) -> Vec<u8> {
+    strip(src, is_control, repairs)
+}
+
+fn strip<F: Fn(u8) -> bool>(src: &[u8], f: F, repairs: &mut Repairs) -> Vec<u8> {
+    let dst: Vec<u8> = src.iter().cloned().filter(|&b| !f(b)).collect();
+    repairs.stripped_controls += src.len() - dst.len();
+    dst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(src: &[u8]) -> (Vec<u8>, Repairs) {
+        let mut repairs = Repairs::new();
+        (repair_uri(src, &mut repairs), repairs)
+    }
+
+    #[test]
+    fn uris() {
+        let (fixed, repairs) = uri(b"http://a b/c d?e\x00f=\"g\"\xc3\xa9");
+        assert_eq!(&fixed[..], &b"http://a b/c%20d?ef=%22g%22%C3%A9"[..]);
+        assert_eq!(repairs.percent_encoded(), 5);
+        assert_eq!(repairs.stripped_controls(), 1);
+
+        assert_eq!(uri(b"example.com:80").0, b"example.com:80");
+        assert_eq!(uri(b"rtsp://cam?x y").0, b"rtsp://cam?x%20y");
+        assert!(uri(b"/ok").1.is_empty());
+    }
+
+    #[test]
+    fn headers() {
+        let mut repairs = Repairs::new();
+        assert_eq!(repair_value(b"a\tb\x01c\x7f", &mut repairs), b"a\tbc");
+        assert_eq!(repair_name(b"x-\tid", &mut repairs), b"x-id");
+        assert_eq!(repairs.stripped_controls(), 3);
+    }
+}
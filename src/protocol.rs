@@ -0,0 +1,339 @@
+//! The protocol a message belongs to
+//!
+//! This module contains a definition of the `Protocol` type. The
+//! `Protocol` type is intended to be accessed through the root of the crate
+//! (`httplike::Protocol`) rather than this module.
+//!
+//! Requests and responses carry a `Protocol` tag in their `Parts`, so a
+//! server that speaks several protocols through one handler type can
+//! dispatch on it. The builders set the tag from the version, and it can be
+//! set explicitly for versions that don't identify a protocol.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::{Protocol, Request, Version};
+//!
+//! let request = Request::builder()
+//!     .version(Version::HTTP_2)
+//!     .body(())
+//!     .unwrap();
+//!
+//! assert_eq!(request.protocol(), Protocol::Http);
+//! assert_eq!(Version::HTTP_10.protocol(), Protocol::Http);
+//! ```
+//!
+//! The `Spec` trait describes each protocol at compile time instead, so a
+//! server or client core can be written once over `P: Spec` and
+//! instantiated with the `Http`, `Rtsp` and `Sip` marker types, rather than
+//! branching on features and tags:
+//!
+//! ```
+//! use httplike::Request;
+//! use httplike::protocol::{Http, Spec};
+//!
+//! fn missing_headers<P: Spec, B>(request: &Request<B>) -> Vec<&'static str> {
+//!     P::required_request_headers()
+//!         .iter()
+//!         .cloned()
+//!         .filter(|name| !request.headers().contains_key(*name))
+//!         .collect()
+//! }
+//!
+//! let request = Request::get("/").body(()).unwrap();
+//! assert_eq!(missing_headers::<Http, _>(&request), ["host"]);
+//! ```
+
+use crate::{Method, StatusCode, Version};
+
+/// The protocol a message belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    /// HTTP, of any version.
+    #[cfg(feature = "http")]
+    Http,
+
+    /// RTSP, of any version.
+    #[cfg(feature = "rtsp")]
+    Rtsp,
+
+    /// SIP, of any version.
+    #[cfg(feature = "sip")]
+    Sip,
+
+    /// A protocol not known to this crate.
+    Other,
+}
+
+impl Default for Protocol {
+    /// Returns the protocol of the default `Version`.
+    #[inline]
+    fn default() -> Protocol {
+        crate::Version::default().protocol()
+    }
+}
+
+/// The static description of a protocol.
+///
+/// Lists of names are lowercase for schemes and header names, and
+/// uppercase for methods, as they are compared.
+pub trait Spec {
+    /// The tag of messages of this protocol.
+    const PROTOCOL: Protocol;
+
+    /// Returns the URI schemes of the protocol.
+    fn schemes() -> &'static [&'static str];
+
+    /// Returns the version new messages should use.
+    fn default_version() -> Version;
+
+    /// Returns the methods defined by `version` of the protocol and its
+    /// common extensions.
+    fn methods(version: Version) -> &'static [&'static str];
+
+    /// Returns the headers every request must have, ignoring the version.
+    fn required_request_headers() -> &'static [&'static str];
+
+    /// Returns the headers every response must have.
+    fn required_response_headers() -> &'static [&'static str];
+
+    /// Returns how the length of a body is determined.
+    fn framing() -> Framing;
+
+    /// Returns true if the protocol interleaves binary frames with messages
+    /// on a connection, like RTSP over TCP.
+    fn has_interleaved_frames() -> bool {
+        false
+    }
+
+    /// Returns true if `method` is one of `methods(version)`.
+    fn is_known_method(method: &Method, version: Version) -> bool {
+        Self::methods(version).contains(&method.as_str())
+    }
+
+    /// Returns true if `status` ends a request, rather than being followed
+    /// by another response.
+    fn is_final(status: StatusCode) -> bool {
+        !status.is_informational()
+    }
+
+    /// Returns true if `scheme` is one of `schemes()`, ignoring case.
+    fn is_scheme(scheme: &str) -> bool {
+        Self::schemes()
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(scheme))
+    }
+}
+
+/// How the length of a body is determined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Framing {
+    /// By `Content-Length`, chunked transfer coding, or, for responses, the
+    /// end of the connection.
+    LengthOrChunked,
+
+    /// By `Content-Length` alone; a message without one has no body.
+    Length,
+}
+
+/// HTTP, as a `Spec`.
+///
+/// Its status semantics are those of HTTP/1.1, where a `101 Switching
+/// Protocols` response is final.
+#[cfg(feature = "http")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Http;
+
+/// RTSP, as a `Spec`.
+///
+/// The default version is RTSP/1.0, which most devices still speak.
+#[cfg(feature = "rtsp")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rtsp;
+
+/// SIP, as a `Spec`.
+#[cfg(feature = "sip")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Sip;
+
+#[cfg(feature = "http")]
+impl Spec for Http {
+    const PROTOCOL: Protocol = Protocol::Http;
+
+    fn schemes() -> &'static [&'static str] {
+        &["http", "https"]
+    }
+
+    fn default_version() -> Version {
+        Version::HTTP_11
+    }
+
+    fn methods(_: Version) -> &'static [&'static str] {
+        &[
+            "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH",
+        ]
+    }
+
+    fn required_request_headers() -> &'static [&'static str] {
+        &["host"]
+    }
+
+    fn required_response_headers() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn framing() -> Framing {
+        Framing::LengthOrChunked
+    }
+
+    fn is_final(status: StatusCode) -> bool {
+        !status.is_informational() || status == StatusCode::SWITCHING_PROTOCOLS
+    }
+}
+
+#[cfg(feature = "rtsp")]
+impl Spec for Rtsp {
+    const PROTOCOL: Protocol = Protocol::Rtsp;
+
+    fn schemes() -> &'static [&'static str] {
+        &["rtsp", "rtsps", "rtspu"]
+    }
+
+    fn default_version() -> Version {
+        Version::RTSP_1
+    }
+
+    /// RTSP/2.0 drops `ANNOUNCE` and `RECORD`, and adds `PLAY_NOTIFY`. Other
+    /// versions get the methods of RTSP/1.0.
+    fn methods(version: Version) -> &'static [&'static str] {
+        if version == Version::RTSP_2 {
+            &[
+                "OPTIONS",
+                "DESCRIBE",
+                "SETUP",
+                "PLAY",
+                "PAUSE",
+                "TEARDOWN",
+                "GET_PARAMETER",
+                "SET_PARAMETER",
+                "REDIRECT",
+                "PLAY_NOTIFY",
+            ]
+        } else {
+            &[
+                "OPTIONS",
+                "DESCRIBE",
+                "ANNOUNCE",
+                "SETUP",
+                "PLAY",
+                "PAUSE",
+                "RECORD",
+                "TEARDOWN",
+                "GET_PARAMETER",
+                "SET_PARAMETER",
+                "REDIRECT",
+            ]
+        }
+    }
+
+    fn required_request_headers() -> &'static [&'static str] {
+        &["cseq"]
+    }
+
+    fn required_response_headers() -> &'static [&'static str] {
+        &["cseq"]
+    }
+
+    fn framing() -> Framing {
+        Framing::Length
+    }
+
+    fn has_interleaved_frames() -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "sip")]
+impl Spec for Sip {
+    const PROTOCOL: Protocol = Protocol::Sip;
+
+    fn schemes() -> &'static [&'static str] {
+        &["sip", "sips"]
+    }
+
+    fn default_version() -> Version {
+        Version::SIP_2
+    }
+
+    fn methods(_: Version) -> &'static [&'static str] {
+        &[
+            "INVITE",
+            "ACK",
+            "BYE",
+            "CANCEL",
+            "REGISTER",
+            "OPTIONS",
+            "PRACK",
+            "SUBSCRIBE",
+            "NOTIFY",
+            "PUBLISH",
+            "INFO",
+            "REFER",
+            "MESSAGE",
+            "UPDATE",
+        ]
+    }
+
+    fn required_request_headers() -> &'static [&'static str] {
+        &["via", "max-forwards", "from", "to", "call-id", "cseq"]
+    }
+
+    fn required_response_headers() -> &'static [&'static str] {
+        &["via", "from", "to", "call-id", "cseq"]
+    }
+
+    fn framing() -> Framing {
+        Framing::Length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http() {
+        assert_eq!(Http::default_version().protocol(), Http::PROTOCOL);
+        assert!(Http::is_scheme("HTTPS"));
+        assert!(Http::is_known_method(&Method::PATCH, Version::HTTP_2));
+        assert!(Http::is_final(StatusCode::SWITCHING_PROTOCOLS));
+        assert!(!Http::is_final(StatusCode::CONTINUE));
+    }
+
+    #[cfg(feature = "rtsp")]
+    #[test]
+    fn rtsp() {
+        assert_eq!(Rtsp::default_version().protocol(), Rtsp::PROTOCOL);
+        let version = Rtsp::default_version();
+        assert!(Rtsp::is_known_method(&Method::SETUP, version));
+        assert!(!Rtsp::is_known_method(&Method::PATCH, version));
+        assert!(!Rtsp::is_known_method(&Method::PLAY_NOTIFY, version));
+        assert!(Rtsp::is_known_method(&Method::PLAY_NOTIFY, Version::RTSP_2));
+        assert!(!Rtsp::is_known_method(&Method::RECORD, Version::RTSP_2));
+        assert!(Rtsp::has_interleaved_frames());
+        assert_eq!(Rtsp::framing(), Framing::Length);
+    }
+
+    #[cfg(feature = "sip")]
+    #[test]
+    fn sip() {
+        assert_eq!(Sip::default_version().protocol(), Sip::PROTOCOL);
+        assert!(Sip::is_known_method(
+            &Method::from_bytes(b"INVITE").unwrap(),
+            Sip::default_version()
+        ));
+        assert!(!Sip::is_scheme("tel"));
+        assert!(!Sip::is_final(StatusCode::from_u16(180).unwrap()));
+    }
+}
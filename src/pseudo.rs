@@ -0,0 +1,548 @@
+//! Pseudo-header fields of HTTP/2 and HTTP/3.
+//!
+//! HTTP/2 and HTTP/3 carry the parts of the request line and status line in
+//! fields whose name starts with a colon: `:method`, `:scheme`, `:authority`
+//! and `:path` in requests, and `:status` in responses, as described in
+//! RFC 7540 section 8.1.2 and RFC 9114 section 4.3. `PseudoHeaders` maps a
+//! `Request` or `Response` to these fields and back.
+//!
+//! Neither version has connection-specific header fields, like `Connection`
+//! or `Transfer-Encoding`. `check_headers` rejects them in a received
+//! message, and `remove_connection_headers` removes them from a message
+//! forwarded from HTTP/1.1.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::{HeaderMap, Request, Version};
+//! use httplike::pseudo::PseudoHeaders;
+//!
+//! let request = Request::get("https://example.com/index.html")
+//!     .version(Version::HTTP_2)
+//!     .body(())
+//!     .unwrap();
+//!
+//! let pseudo = PseudoHeaders::from_request(&request).unwrap();
+//! assert_eq!(
+//!     pseudo.fields(),
+//!     [
+//!         (":method", "GET"),
+//!         (":scheme", "https"),
+//!         (":authority", "example.com"),
+//!         (":path", "/index.html"),
+//!     ]
+//! );
+//!
+//! // And back, on the receiving end.
+//! let mut received = PseudoHeaders::new();
+//! for &(name, value) in &pseudo.fields() {
+//!     received.set(name.as_bytes(), value.as_bytes()).unwrap();
+//! }
+//! let request = received.into_request(HeaderMap::new(), Version::HTTP_2).unwrap();
+//! assert_eq!(request.uri(), "https://example.com/index.html");
+//! ```
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::header::{self, HeaderMap, HeaderName, HeaderValue};
+use crate::uri::{self, Authority, PathAndQuery, Scheme};
+use crate::{Method, Request, Response, StatusCode, Uri, Version};
+
+/// The pseudo-header fields of a request or response.
+///
+/// A request has `method`, `scheme`, `authority` and `path`, except for
+/// `CONNECT` requests, which only have `method` and `authority`. A response
+/// only has `status`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PseudoHeaders {
+    /// The `:method` field.
+    pub method: Option<Method>,
+    /// The `:scheme` field.
+    pub scheme: Option<Scheme>,
+    /// The `:authority` field.
+    pub authority: Option<Authority>,
+    /// The `:path` field.
+    pub path: Option<PathAndQuery>,
+    /// The `:status` field.
+    pub status: Option<StatusCode>,
+}
+
+/// An error returned for pseudo-header fields or header fields that are not
+/// valid in an HTTP/2 or HTTP/3 message.
+pub struct PseudoError {
+    kind: PseudoErrorKind,
+}
+
+#[derive(Debug)]
+enum PseudoErrorKind {
+    Missing(&'static str),
+    Unexpected(&'static str),
+    Duplicate(&'static str),
+    Invalid(&'static str),
+    Unknown,
+    Connection(HeaderName),
+}
+
+impl PseudoHeaders {
+    /// Creates an empty set of pseudo-header fields.
+    pub fn new() -> PseudoHeaders {
+        PseudoHeaders::default()
+    }
+
+    /// Returns the pseudo-header fields of `request`.
+    ///
+    /// The scheme, authority and path come from the URI of the request,
+    /// which must be absolute, except for `CONNECT` requests, whose URI is
+    /// an authority. The authority is taken from the `Host` header when the
+    /// URI doesn't have one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URI is missing a part the fields need.
+    pub fn from_request<T>(request: &Request<T>) -> Result<PseudoHeaders, PseudoError> {
+        let uri = request.uri();
+        let authority = match uri.authority() {
+            Some(authority) => Some(authority.clone()),
+            None => match request.headers().get(header::HOST) {
+                Some(host) => Some(
+                    Authority::try_from(host.as_bytes())
+                        .map_err(|_| PseudoError::new(PseudoErrorKind::Invalid(":authority")))?,
+                ),
+                None => None,
+            },
+        };
+
+        let mut pseudo = PseudoHeaders::new();
+        pseudo.method = Some(request.method().clone());
+        if request.method() == Method::CONNECT {
+            pseudo.authority = Some(
+                authority
+                    .ok_or_else(|| PseudoError::new(PseudoErrorKind::Missing(":authority")))?,
+            );
+            return Ok(pseudo);
+        }
+
+        pseudo.scheme = Some(
+            uri.scheme()
+                .cloned()
+                .ok_or_else(|| PseudoError::new(PseudoErrorKind::Missing(":scheme")))?,
+        );
+        pseudo.authority = authority;
+        pseudo.path = Some(match uri.path_and_query() {
+            Some(path) => path.clone(),
+            None => PathAndQuery::from_static("/"),
+        });
+        Ok(pseudo)
+    }
+
+    /// Returns the pseudo-header fields of `response`.
+    pub fn from_response<T>(response: &Response<T>) -> PseudoHeaders {
+        let mut pseudo = PseudoHeaders::new();
+        pseudo.status = Some(response.status());
+        pseudo
+    }
+
+    /// Sets the field `name` from its received `value`.
+    ///
+    /// A decoder calls this for each field whose name starts with a colon,
+    /// before the regular header fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not a pseudo-header field, if the
+    /// field was already set, or if the value is invalid.
+    pub fn set(&mut self, name: &[u8], value: &[u8]) -> Result<(), PseudoError> {
+        fn set<T>(
+            slot: &mut Option<T>,
+            name: &'static str,
+            value: Option<T>,
+        ) -> Result<(), PseudoError> {
+            if slot.is_some() {
+                return Err(PseudoError::new(PseudoErrorKind::Duplicate(name)));
+            }
+            *slot = Some(value.ok_or_else(|| PseudoError::new(PseudoErrorKind::Invalid(name)))?);
+            Ok(())
+        }
+
+        match name {
+            b":method" => set(&mut self.method, ":method", Method::from_bytes(value).ok()),
+            b":scheme" => set(&mut self.scheme, ":scheme", Scheme::try_from(value).ok()),
+            b":authority" => set(
+                &mut self.authority,
+                ":authority",
+                Authority::try_from(value).ok(),
+            ),
+            b":path" => set(&mut self.path, ":path", PathAndQuery::try_from(value).ok()),
+            b":status" => set(
+                &mut self.status,
+                ":status",
+                StatusCode::from_bytes(value).ok(),
+            ),
+            _ => Err(PseudoError::new(PseudoErrorKind::Unknown)),
+        }
+    }
+
+    /// Returns the fields that are set, as names and values, in the order
+    /// they should be encoded.
+    pub fn fields(&self) -> Vec<(&'static str, &str)> {
+        let mut fields = Vec::new();
+        if let Some(ref method) = self.method {
+            fields.push((":method", method.as_str()));
+        }
+        if let Some(ref scheme) = self.scheme {
+            fields.push((":scheme", scheme.as_str()));
+        }
+        if let Some(ref authority) = self.authority {
+            fields.push((":authority", authority.as_str()));
+        }
+        if let Some(ref path) = self.path {
+            fields.push((":path", path.as_str()));
+        }
+        if let Some(ref status) = self.status {
+            fields.push((":status", status.as_str()));
+        }
+        fields
+    }
+
+    /// Builds a request from the fields and the received `headers`.
+    ///
+    /// The URI of the request is made of the scheme, authority and path,
+    /// or of the authority alone for `CONNECT` requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request field is missing, if `:status` is set,
+    /// or if `check_headers` rejects `headers`.
+    pub fn into_request(
+        self,
+        headers: HeaderMap,
+        version: Version,
+    ) -> Result<Request<()>, PseudoError> {
+        if self.status.is_some() {
+            return Err(PseudoError::new(PseudoErrorKind::Unexpected(":status")));
+        }
+        check_headers(&headers)?;
+
+        let method = self
+            .method
+            .ok_or_else(|| PseudoError::new(PseudoErrorKind::Missing(":method")))?;
+        let mut parts = uri::Parts::default();
+        if method == Method::CONNECT {
+            if self.scheme.is_some() {
+                return Err(PseudoError::new(PseudoErrorKind::Unexpected(":scheme")));
+            }
+            if self.path.is_some() {
+                return Err(PseudoError::new(PseudoErrorKind::Unexpected(":path")));
+            }
+            parts.authority = Some(
+                self.authority
+                    .ok_or_else(|| PseudoError::new(PseudoErrorKind::Missing(":authority")))?,
+            );
+        } else {
+            parts.scheme = Some(
+                self.scheme
+                    .ok_or_else(|| PseudoError::new(PseudoErrorKind::Missing(":scheme")))?,
+            );
+            parts.path_and_query = Some(
+                self.path
+                    .ok_or_else(|| PseudoError::new(PseudoErrorKind::Missing(":path")))?,
+            );
+            // A URI with a scheme needs an authority, so a request without
+            // `:authority` takes it from `Host`.
+            parts.authority = match self.authority {
+                Some(authority) => Some(authority),
+                None => headers
+                    .get(header::HOST)
+                    .and_then(|host| Authority::try_from(host.as_bytes()).ok()),
+            };
+            if parts.authority.is_none() {
+                return Err(PseudoError::new(PseudoErrorKind::Missing(":authority")));
+            }
+        }
+
+        let uri = Uri::from_parts(parts)
+            .map_err(|_| PseudoError::new(PseudoErrorKind::Invalid(":path")))?;
+        let mut request = Request::new(());
+        *request.method_mut() = method;
+        *request.uri_mut() = uri;
+        *request.version_mut() = version;
+        *request.protocol_mut() = version.protocol();
+        *request.headers_mut() = headers;
+        Ok(request)
+    }
+
+    /// Builds a response from the fields and the received `headers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `:status` is missing, if a request field is set,
+    /// or if `check_headers` rejects `headers`.
+    pub fn into_response(
+        self,
+        headers: HeaderMap,
+        version: Version,
+    ) -> Result<Response<()>, PseudoError> {
+        let unexpected = [
+            (self.method.is_some(), ":method"),
+            (self.scheme.is_some(), ":scheme"),
+            (self.authority.is_some(), ":authority"),
+            (self.path.is_some(), ":path"),
+        ];
+        if let Some(&(_, name)) = unexpected.iter().find(|&&(set, _)| set) {
+            return Err(PseudoError::new(PseudoErrorKind::Unexpected(name)));
+        }
+        check_headers(&headers)?;
+
+        let status = self
+            .status
+            .ok_or_else(|| PseudoError::new(PseudoErrorKind::Missing(":status")))?;
+        let mut response = Response::new(());
+        *response.status_mut() = status;
+        *response.version_mut() = version;
+        *response.protocol_mut() = version.protocol();
+        *response.headers_mut() = headers;
+        Ok(response)
+    }
+}
+
+/// Checks that `headers` has no connection-specific header fields.
+///
+/// These are `Connection`, `Keep-Alive`, `Proxy-Connection`,
+/// `Transfer-Encoding` and `Upgrade`, as well as `TE` with any value but
+/// `trailers`. A message with such a field is malformed in HTTP/2 and
+/// HTTP/3.
+///
+/// # Errors
+///
+/// Returns an error naming the first such field.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::{HeaderMap, pseudo};
+/// let mut headers = HeaderMap::new();
+/// headers.insert("te", "trailers".parse().unwrap());
+/// assert!(pseudo::check_headers(&headers).is_ok());
+///
+/// headers.insert("connection", "keep-alive".parse().unwrap());
+/// assert!(pseudo::check_headers(&headers).is_err());
+/// ```
+pub fn check_headers(headers: &HeaderMap) -> Result<(), PseudoError> {
+    for name in headers.keys() {
+        if is_connection_header(name, headers) {
+            return Err(PseudoError::new(PseudoErrorKind::Connection(name.clone())));
+        }
+    }
+    Ok(())
+}
+
+/// Removes the connection-specific header fields from `headers`.
+///
+/// This removes the fields rejected by `check_headers`, along with the
+/// fields named in `Connection`, so a message received over HTTP/1.1 can be
+/// forwarded over HTTP/2 or HTTP/3. A `TE` field is replaced by
+/// `te: trailers` if it allows trailers.
+pub fn remove_connection_headers(headers: &mut HeaderMap) {
+    let named = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .flat_map(|value| value.as_bytes().split(|&b| b == b','))
+        .filter_map(|name| HeaderName::from_bytes(trim(name)).ok())
+        .collect::<Vec<_>>();
+    for name in named {
+        headers.remove(name);
+    }
+
+    let trailers = headers.get_all(header::TE).iter().any(has_trailers);
+    for &name in CONNECTION_HEADERS {
+        headers.remove(name);
+    }
+    headers.remove(header::TE);
+    if trailers {
+        headers.insert(header::TE, HeaderValue::from_static("trailers"));
+    }
+}
+
+const CONNECTION_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+];
+
+fn is_connection_header(name: &HeaderName, headers: &HeaderMap) -> bool {
+    if *name == header::TE {
+        return headers
+            .get_all(header::TE)
+            .iter()
+            .any(|value| value != "trailers");
+    }
+    CONNECTION_HEADERS.contains(&name.as_str())
+}
+
+// Returns true if a `TE` value lists `trailers`.
+fn has_trailers(value: &HeaderValue) -> bool {
+    value
+        .as_bytes()
+        .split(|&b| b == b',')
+        .any(|item| trim(item).eq_ignore_ascii_case(b"trailers"))
+}
+
+fn trim(mut s: &[u8]) -> &[u8] {
+    while let Some((&b, rest)) = s.split_first() {
+        if b != b' ' && b != b'\t' {
+            break;
+        }
+        s = rest;
+    }
+    while let Some((&b, rest)) = s.split_last() {
+        if b != b' ' && b != b'\t' {
+            break;
+        }
+        s = rest;
+    }
+    s
+}
+
+impl PseudoError {
+    fn new(kind: PseudoErrorKind) -> PseudoError {
+        PseudoError { kind }
+    }
+
+    /// Returns the connection-specific header field that caused the error,
+    /// if any.
+    pub fn connection_header(&self) -> Option<&HeaderName> {
+        match self.kind {
+            PseudoErrorKind::Connection(ref name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for PseudoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PseudoError").field(&self.kind).finish()
+    }
+}
+
+impl fmt::Display for PseudoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            PseudoErrorKind::Missing(name) => write!(f, "missing {} pseudo-header", name),
+            PseudoErrorKind::Unexpected(name) => write!(f, "unexpected {} pseudo-header", name),
+            PseudoErrorKind::Duplicate(name) => write!(f, "duplicate {} pseudo-header", name),
+            PseudoErrorKind::Invalid(name) => write!(f, "invalid {} pseudo-header", name),
+            PseudoErrorKind::Unknown => f.write_str("unknown pseudo-header"),
+            PseudoErrorKind::Connection(ref name) => {
+                write!(f, "connection-specific header {}", name)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for PseudoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_fields() {
+        let request = Request::options("*")
+            .header("host", "example.com:8080")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            PseudoHeaders::from_request(&request)
+                .unwrap_err()
+                .to_string(),
+            "missing :scheme pseudo-header"
+        );
+
+        let request = Request::connect("example.com:443").body(()).unwrap();
+        let pseudo = PseudoHeaders::from_request(&request).unwrap();
+        assert_eq!(
+            pseudo.fields(),
+            [(":method", "CONNECT"), (":authority", "example.com:443")]
+        );
+
+        let request = pseudo
+            .into_request(HeaderMap::new(), Version::HTTP_2)
+            .unwrap();
+        assert_eq!(request.method(), Method::CONNECT);
+        assert_eq!(request.uri(), "example.com:443");
+    }
+
+    #[test]
+    fn received_fields() {
+        let mut pseudo = PseudoHeaders::new();
+        pseudo.set(b":method", b"GET").unwrap();
+        pseudo.set(b":scheme", b"http").unwrap();
+        pseudo.set(b":path", b"/a?b").unwrap();
+        assert_eq!(
+            pseudo.set(b":path", b"/c").unwrap_err().to_string(),
+            "duplicate :path pseudo-header"
+        );
+        assert!(pseudo.set(b":protocol", b"websocket").is_err());
+        assert!(pseudo.set(b":status", b"2000").is_err());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("host", HeaderValue::from_static("example.com"));
+        let request = pseudo.into_request(headers, Version::HTTP_3).unwrap();
+        assert_eq!(request.uri(), "http://example.com/a?b");
+
+        let mut pseudo = PseudoHeaders::new();
+        pseudo.set(b":status", b"204").unwrap();
+        let response = pseudo
+            .clone()
+            .into_response(HeaderMap::new(), Version::HTTP_2)
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(PseudoHeaders::from_response(&response), pseudo);
+
+        pseudo.set(b":method", b"GET").unwrap();
+        assert_eq!(
+            pseudo
+                .into_response(HeaderMap::new(), Version::HTTP_2)
+                .unwrap_err()
+                .to_string(),
+            "unexpected :method pseudo-header"
+        );
+    }
+
+    #[test]
+    fn connection_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("connection", HeaderValue::from_static("close, X-Hop"));
+        headers.insert("x-hop", HeaderValue::from_static("1"));
+        headers.insert("keep-alive", HeaderValue::from_static("timeout=5"));
+        headers.insert("te", HeaderValue::from_static("gzip, Trailers"));
+        headers.insert("accept", HeaderValue::from_static("*/*"));
+
+        let err = check_headers(&headers).unwrap_err();
+        assert_eq!(err.connection_header().unwrap(), "connection");
+
+        remove_connection_headers(&mut headers);
+        assert!(check_headers(&headers).is_ok());
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["te"], "trailers");
+        assert_eq!(headers["accept"], "*/*");
+
+        let mut pseudo = PseudoHeaders::new();
+        pseudo.set(b":status", b"200").unwrap();
+        headers.insert("transfer-encoding", HeaderValue::from_static("chunked"));
+        assert_eq!(
+            pseudo
+                .into_response(headers, Version::HTTP_2)
+                .unwrap_err()
+                .to_string(),
+            "connection-specific header transfer-encoding"
+        );
+    }
+}
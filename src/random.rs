@@ -0,0 +1,30 @@
+//! Random bytes for identifiers, from the standard library.
+//!
+//! The standard library has no random number generator, but the keys of
+//! `RandomState` are random. Each thread seeds them once from the operating
+//! system, and every new `RandomState` of the thread adds one to them, so
+//! hashing a counter gives distinct values that are hard to guess without
+//! knowing the seed.
+//!
+//! This is not a cryptographic generator: values from the same thread are
+//! related through the seed. It is good enough for boundaries and
+//! identifiers that must be unique, and the public functions using it have
+//! variants taking bytes from a real generator instead.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Fills `buf` with random bytes.
+pub(crate) fn fill(buf: &mut [u8]) {
+    // The keys of two threads are seeded separately, but the counter makes
+    // sure they don't hash the same input if the seeds happen to match.
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    for chunk in buf.chunks_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(COUNTER.fetch_add(1, Ordering::Relaxed));
+        let bytes = hasher.finish().to_ne_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
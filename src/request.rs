@@ -52,14 +52,22 @@
 //! }
 //! ```
 
-use std::any::Any;
-use std::convert::{TryFrom};
-use std::fmt;
-
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::convert::{TryFrom};
+use core::fmt;
+use core::result;
+
+use crate::codec::Limits;
+use crate::header::typed::Header;
 use crate::header::{HeaderMap, HeaderName, HeaderValue};
 use crate::method::Method;
+use crate::protocol::Protocol;
+use crate::validate::{self, Profile, Violation};
 use crate::version::Version;
-use crate::{Extensions, Result, Uri};
+use crate::uri::{self, Authority, PathAndQuery, Scheme};
+use crate::{BuildErrors, Extensions, Result, Uri};
 
 /// Represents an HTTP request.
 ///
@@ -154,6 +162,7 @@ use crate::{Extensions, Result, Uri};
 /// #
 /// # fn main() {}
 /// ```
+#[derive(Clone)]
 pub struct Request<T> {
     head: Parts,
     body: T,
@@ -163,6 +172,7 @@ pub struct Request<T> {
 ///
 /// The HTTP request head consists of a method, uri, version, and a set of
 /// header fields.
+#[derive(Clone)]
 pub struct Parts {
     /// The request's method
     pub method: Method,
@@ -173,12 +183,24 @@ pub struct Parts {
     /// The request's version
     pub version: Version,
 
+    /// The request's protocol
+    ///
+    /// Set from the version by the builder.
+    pub protocol: Protocol,
+
     /// The request's headers
     pub headers: HeaderMap<HeaderValue>,
 
     /// The request's extensions
     pub extensions: Extensions,
 
+    /// The request's trailers
+    ///
+    /// Header fields sent after the body, such as in chunked transfer
+    /// encoding or an HTTP/2 trailing `HEADERS` frame. `None` if the request
+    /// has no trailers.
+    pub trailers: Option<HeaderMap<HeaderValue>>,
+
     _priv: (),
 }
 
@@ -189,6 +211,62 @@ pub struct Parts {
 #[derive(Debug)]
 pub struct Builder {
     inner: Result<Parts>,
+    // URI components set individually, composed into the URI by `body`
+    uri_parts: Option<uri::Parts>,
+    // Errors from calls made after `inner` became an error
+    errors: Vec<crate::Error>,
+    // Run on the finished `Parts` by `body`
+    interceptors: Vec<fn(&mut Parts) -> Result<()>>,
+    // Checked by `body`, after the interceptors
+    limits: Option<Limits>,
+}
+
+/// The form of a request target
+///
+/// A request's URI takes one of four forms, as described in
+/// [RFC 7230, Section 5.3](https://tools.ietf.org/html/rfc7230#section-5.3).
+/// The form is derived from the URI, so it always matches the URI that is
+/// actually sent.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::*;
+/// use httplike::request::TargetForm;
+///
+/// let request = Request::get("/index.html").body(()).unwrap();
+/// assert_eq!(request.target_form(), TargetForm::Origin);
+///
+/// let request = Request::get("http://example.com/").body(()).unwrap();
+/// assert_eq!(request.target_form(), TargetForm::Absolute);
+///
+/// let request = Request::connect("example.com:443").body(()).unwrap();
+/// assert_eq!(request.target_form(), TargetForm::Authority);
+///
+/// let request = Request::options("*").body(()).unwrap();
+/// assert_eq!(request.target_form(), TargetForm::Asterisk);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TargetForm {
+    /// An absolute path with an optional query, such as `/index.html?a=b`.
+    ///
+    /// Used for most requests sent directly to an origin server.
+    Origin,
+
+    /// An absolute URI, such as `http://example.com/index.html`.
+    ///
+    /// Used for requests sent to a proxy.
+    Absolute,
+
+    /// Only an authority, such as `example.com:443`.
+    ///
+    /// Used for `CONNECT` requests.
+    Authority,
+
+    /// A single asterisk, `*`.
+    ///
+    /// Used for server-wide `OPTIONS` requests.
+    Asterisk,
 }
 
 impl Request<()> {
@@ -418,6 +496,149 @@ impl Request<()> {
     {
         Builder::new().method(Method::TRACE).uri(uri)
     }
+
+    /// Creates a new `Builder` initialized for an RTSP DESCRIBE of the given
+    /// URI.
+    ///
+    /// The request asks for an SDP description with `Accept` and carries a
+    /// `CSeq` of `0`; the connection sending the request is expected to
+    /// replace it with its own sequence number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request = Request::describe("rtsp://example.com/stream")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.method(), Method::DESCRIBE);
+    /// assert_eq!(request.version(), Version::RTSP_1);
+    /// assert_eq!(request.headers()[header::ACCEPT], "application/sdp");
+    /// assert_eq!(request.headers()[header::CSEQ], "0");
+    /// ```
+    #[cfg(feature = "rtsp")]
+    pub fn describe<T>(uri: T) -> Builder
+    where
+        Uri: TryFrom<T>,
+        <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        Builder::rtsp(Method::DESCRIBE, uri)
+            .header(crate::header::ACCEPT, "application/sdp")
+    }
+
+    /// Creates a new `Builder` initialized for an RTSP SETUP of the given
+    /// URI, requesting the given `Transport`.
+    ///
+    /// Like `describe`, the request carries a placeholder `CSeq` of `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// # use httplike::header::typed::{Transport, TransportSpec};
+    /// let request = Request::setup(
+    ///     "rtsp://example.com/stream/track1",
+    ///     Transport::new(TransportSpec::rtp_unicast(8000)),
+    /// )
+    /// .body(())
+    /// .unwrap();
+    ///
+    /// assert_eq!(request.method(), Method::SETUP);
+    /// assert_eq!(
+    ///     request.headers()[header::TRANSPORT],
+    ///     "RTP/AVP;unicast;client_port=8000-8001"
+    /// );
+    /// ```
+    #[cfg(feature = "rtsp")]
+    pub fn setup<T>(uri: T, transport: crate::header::typed::Transport) -> Builder
+    where
+        Uri: TryFrom<T>,
+        <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        Builder::rtsp(Method::SETUP, uri).typed_header(transport)
+    }
+
+    /// Creates a new `Builder` initialized for an RTSP PLAY of the given
+    /// URI, playing the given `Range`.
+    ///
+    /// Like `describe`, the request carries a placeholder `CSeq` of `0`. The
+    /// `Session` returned by the server's SETUP response still has to be
+    /// added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use httplike::*;
+    /// # use httplike::header::typed::NptRange;
+    /// let range = NptRange::starting_at(Duration::from_secs(0));
+    /// let request = Request::play("rtsp://example.com/stream", range)
+    ///     .header(header::SESSION, "12345678")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.method(), Method::PLAY);
+    /// assert_eq!(request.headers()[header::RANGE], "npt=0-");
+    /// ```
+    #[cfg(feature = "rtsp")]
+    pub fn play<T>(uri: T, range: crate::header::typed::NptRange) -> Builder
+    where
+        Uri: TryFrom<T>,
+        <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        Builder::rtsp(Method::PLAY, uri).typed_header(range)
+    }
+
+    /// Creates a new `Builder` initialized for an RTSP PAUSE of the given
+    /// URI.
+    ///
+    /// Like `describe`, the request carries a placeholder `CSeq` of `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request = Request::pause("rtsp://example.com/stream")
+    ///     .header(header::SESSION, "12345678")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.method(), Method::PAUSE);
+    /// ```
+    #[cfg(feature = "rtsp")]
+    pub fn pause<T>(uri: T) -> Builder
+    where
+        Uri: TryFrom<T>,
+        <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        Builder::rtsp(Method::PAUSE, uri)
+    }
+
+    /// Creates a new `Builder` initialized for an RTSP TEARDOWN of the given
+    /// URI.
+    ///
+    /// Like `describe`, the request carries a placeholder `CSeq` of `0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request = Request::teardown("rtsp://example.com/stream")
+    ///     .header(header::SESSION, "12345678")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.method(), Method::TEARDOWN);
+    /// ```
+    #[cfg(feature = "rtsp")]
+    pub fn teardown<T>(uri: T) -> Builder
+    where
+        Uri: TryFrom<T>,
+        <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        Builder::rtsp(Method::TEARDOWN, uri)
+    }
 }
 
 impl<T> Request<T> {
@@ -521,6 +742,104 @@ impl<T> Request<T> {
         &mut self.head.uri
     }
 
+    /// Replaces the path of the request URI, keeping the query.
+    ///
+    /// The path must start with `/` and can't contain a query or fragment.
+    /// An error is also returned for a URI in authority-form, which has no
+    /// path. The URI is left unchanged on error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut request = Request::get("http://example.com/old?page=2").body(()).unwrap();
+    ///
+    /// request.set_path("/new").unwrap();
+    /// assert_eq!(request.uri(), "http://example.com/new?page=2");
+    ///
+    /// assert!(request.set_path("relative").is_err());
+    /// ```
+    pub fn set_path(&mut self, path: &str) -> Result<()> {
+        self.head.uri.set_path(path).map_err(Into::into)
+    }
+
+    /// Removes `prefix` from the start of the request path.
+    ///
+    /// The prefix only matches whole path segments, so `/api` strips
+    /// `/api/users` to `/users` and `/api` to `/`, but leaves `/apis` alone.
+    /// Returns true if the prefix was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut request = Request::get("/api/users?id=1").body(()).unwrap();
+    ///
+    /// assert!(request.strip_path_prefix("/api"));
+    /// assert_eq!(request.uri(), "/users?id=1");
+    ///
+    /// assert!(!request.strip_path_prefix("/api"));
+    /// ```
+    pub fn strip_path_prefix(&mut self, prefix: &str) -> bool {
+        let prefix = prefix.trim_end_matches('/');
+        let path = self.head.uri.path();
+
+        if !path.starts_with(prefix) {
+            return false;
+        }
+        let rest = match &path[prefix.len()..] {
+            "" => "/",
+            rest if rest.starts_with('/') => rest,
+            _ => return false,
+        };
+
+        let rest = rest.to_owned();
+        self.head.uri.set_path(&rest).is_ok()
+    }
+
+    /// Replaces the authority of the request URI.
+    ///
+    /// An error is returned if the authority is invalid, or if the URI is in
+    /// origin-form, which has no authority. The `Host` header is not
+    /// changed. The URI is left unchanged on error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut request = Request::get("http://example.com/index.html").body(()).unwrap();
+    ///
+    /// request.set_authority("backend:8080").unwrap();
+    /// assert_eq!(request.uri(), "http://backend:8080/index.html");
+    ///
+    /// let mut request = Request::get("/index.html").body(()).unwrap();
+    /// assert!(request.set_authority("backend:8080").is_err());
+    /// ```
+    pub fn set_authority<A>(&mut self, authority: A) -> Result<()>
+    where
+        Authority: TryFrom<A>,
+        <Authority as TryFrom<A>>::Error: Into<crate::Error>,
+    {
+        let authority = Authority::try_from(authority).map_err(Into::into)?;
+        self.head.uri.set_authority(authority).map_err(Into::into)
+    }
+
+    /// Returns the form of the request target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::request::TargetForm;
+    ///
+    /// let request: Request<()> = Request::default();
+    /// assert_eq!(request.target_form(), TargetForm::Origin);
+    /// ```
+    #[inline]
+    pub fn target_form(&self) -> TargetForm {
+        self.head.target_form()
+    }
+
     /// Returns the associated version.
     ///
     /// # Examples
@@ -550,6 +869,59 @@ impl<T> Request<T> {
         &mut self.head.version
     }
 
+    /// Returns the associated protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request: Request<()> = Request::default();
+    /// assert_eq!(request.protocol(), Protocol::Http);
+    /// ```
+    #[inline]
+    pub fn protocol(&self) -> Protocol {
+        self.head.protocol
+    }
+
+    /// Returns a mutable reference to the associated protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut request: Request<()> = Request::default();
+    /// *request.protocol_mut() = Protocol::Other;
+    /// assert_eq!(request.protocol(), Protocol::Other);
+    /// ```
+    #[inline]
+    pub fn protocol_mut(&mut self) -> &mut Protocol {
+        &mut self.head.protocol
+    }
+
+    /// Returns an estimate of the memory used by the request head, in bytes.
+    ///
+    /// This is the sum of the lengths of the method, the URI and every
+    /// header name and value. It is cheap to compute and doesn't serialize
+    /// the head, so it suits accounting for buffered requests. The body,
+    /// trailers and extensions are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request = Request::get("https://example.com/")
+    ///     .header("accept", "*/*")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.estimated_size(), 3 + 20 + 6 + 3);
+    /// ```
+    pub fn estimated_size(&self) -> usize {
+        self.head.method.as_str().len()
+            + self.head.uri.display_len()
+            + self.head.headers.byte_len()
+    }
+
     /// Returns a reference to the associated header field map.
     ///
     /// # Examples
@@ -610,6 +982,61 @@ impl<T> Request<T> {
         &mut self.head.extensions
     }
 
+    /// Returns a reference to the associated trailers, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request: Request<()> = Request::default();
+    /// assert!(request.trailers().is_none());
+    /// ```
+    #[inline]
+    pub fn trailers(&self) -> Option<&HeaderMap<HeaderValue>> {
+        self.head.trailers.as_ref()
+    }
+
+    /// Returns a mutable reference to the associated trailers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// # use httplike::header::*;
+    /// let mut request: Request<()> = Request::default();
+    /// request
+    ///     .trailers_mut()
+    ///     .get_or_insert_with(HeaderMap::new)
+    ///     .insert("x-checksum", HeaderValue::from_static("abc"));
+    /// assert_eq!(request.trailers().unwrap()["x-checksum"], "abc");
+    /// ```
+    #[inline]
+    pub fn trailers_mut(&mut self) -> &mut Option<HeaderMap<HeaderValue>> {
+        &mut self.head.trailers
+    }
+
+    /// Checks this request against the requirements of a protocol.
+    ///
+    /// Returns every violation found, or an empty list if the request is
+    /// valid. The body is not inspected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::validate::Profile;
+    ///
+    /// let request = Request::get("/")
+    ///     .header("host", "example.com")
+    ///     .body(())
+    ///     .unwrap();
+    /// assert!(request.validate(Profile::Http11).is_empty());
+    /// ```
+    pub fn validate(&self, profile: Profile) -> Vec<Violation> {
+        let head = &self.head;
+        validate::request(&head.method, &head.uri, head.version, &head.headers, profile)
+    }
+
     /// Returns a reference to the associated HTTP body.
     ///
     /// # Examples
@@ -669,6 +1096,59 @@ impl<T> Request<T> {
         (self.head, self.body)
     }
 
+    /// Returns mutable references to the head and body parts at once.
+    ///
+    /// This allows changing the head based on the body, or the other way
+    /// around, without taking the request apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut request = Request::new(b"hello".to_vec());
+    ///
+    /// let (head, body) = request.parts_and_body_mut();
+    /// body.extend_from_slice(b" world");
+    /// head.headers.insert("content-length", body.len().into());
+    ///
+    /// assert_eq!(request.headers()["content-length"], "11");
+    /// ```
+    #[inline]
+    pub fn parts_and_body_mut(&mut self) -> (&mut Parts, &mut T) {
+        (&mut self.head, &mut self.body)
+    }
+
+    /// Consumes the request returning a builder seeded with its head.
+    ///
+    /// Everything in the head, including the extensions, is carried over
+    /// and the body is dropped. Use `into_parts` and `Builder::from` to
+    /// keep the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request = Request::post("https://example.com/upload")
+    ///     .header("content-type", "text/plain")
+    ///     .body("payload")
+    ///     .unwrap();
+    ///
+    /// let retry = request
+    ///     .into_builder()
+    ///     .header("x-retry", "1")
+    ///     .body("payload")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(retry.method(), Method::POST);
+    /// assert_eq!(retry.uri(), "https://example.com/upload");
+    /// assert_eq!(retry.headers()["content-type"], "text/plain");
+    /// assert_eq!(retry.headers()["x-retry"], "1");
+    /// ```
+    #[inline]
+    pub fn into_builder(self) -> Builder {
+        Builder::from(self.head)
+    }
+
     /// Consumes the request returning a new request with body mapped to the
     /// return type of the passed in function.
     ///
@@ -693,6 +1173,34 @@ impl<T> Request<T> {
             head: self.head,
         }
     }
+
+    /// Consumes the request returning a new request with body mapped by the
+    /// passed in fallible function.
+    ///
+    /// If the function fails, its error is returned and the request is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let request = Request::builder().body("42").unwrap();
+    /// let mapped_request: Request<u32> = request.try_map(|b| b.parse()).unwrap();
+    /// assert_eq!(mapped_request.body(), &42);
+    ///
+    /// let request = Request::builder().body("forty-two").unwrap();
+    /// assert!(request.try_map(|b| b.parse::<u32>()).is_err());
+    /// ```
+    #[inline]
+    pub fn try_map<F, U, E>(self, f: F) -> result::Result<Request<U>, E>
+    where
+        F: FnOnce(T) -> result::Result<U, E>,
+    {
+        Ok(Request {
+            body: f(self.body)?,
+            head: self.head,
+        })
+    }
 }
 
 impl<T: Default> Default for Request<T> {
@@ -716,16 +1224,95 @@ impl<T: fmt::Debug> fmt::Debug for Request<T> {
 
 impl Parts {
     /// Creates a new default instance of `Parts`
-    fn new() -> Parts {
-        Parts {
-            method: Method::default(),
+    ///
+    /// Useful to construct a request without a builder, for example when
+    /// converting a message from another protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut parts = request::Parts::new();
+    /// parts.method = Method::POST;
+    /// parts.uri = "/upload".parse().unwrap();
+    ///
+    /// let request = Request::from_parts(parts, ());
+    /// assert_eq!(request.method(), Method::POST);
+    /// assert_eq!(request.uri(), "/upload");
+    /// ```
+    pub fn new() -> Parts {
+        Parts {
+            method: Method::default(),
             uri: Uri::default(),
             version: Version::default(),
-            headers: HeaderMap::default(),
+            protocol: Protocol::default(),
+            headers: HeaderMap::new(),
             extensions: Extensions::default(),
+            trailers: None,
             _priv: (),
         }
     }
+
+    /// Returns true if both heads describe the same request.
+    ///
+    /// The method, URI, version, protocol, headers and trailers are
+    /// compared. Headers are compared like `HeaderMap`'s `PartialEq`: the
+    /// order of values under one name matters, the order of names doesn't.
+    /// Extensions are ignored, as they can't be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let (a, _) = Request::get("/")
+    ///     .header("accept", "*/*")
+    ///     .header("x-id", "1")
+    ///     .extension(1u8)
+    ///     .body(())
+    ///     .unwrap()
+    ///     .into_parts();
+    ///
+    /// let (b, _) = Request::get("/")
+    ///     .header("x-id", "1")
+    ///     .header("accept", "*/*")
+    ///     .body(())
+    ///     .unwrap()
+    ///     .into_parts();
+    ///
+    /// assert!(a.semantic_eq(&b));
+    /// ```
+    pub fn semantic_eq(&self, other: &Parts) -> bool {
+        self.method == other.method
+            && self.uri == other.uri
+            && self.version == other.version
+            && self.protocol == other.protocol
+            && self.headers == other.headers
+            && self.trailers == other.trailers
+    }
+
+    /// Returns the form of the request target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::request::TargetForm;
+    ///
+    /// let mut parts = request::Parts::new();
+    /// parts.uri = "https://example.com/".parse().unwrap();
+    /// assert_eq!(parts.target_form(), TargetForm::Absolute);
+    /// ```
+    #[inline]
+    pub fn target_form(&self) -> TargetForm {
+        TargetForm::of(&self.uri)
+    }
+}
+
+impl Default for Parts {
+    #[inline]
+    fn default() -> Parts {
+        Parts::new()
+    }
 }
 
 impl fmt::Debug for Parts {
@@ -734,7 +1321,9 @@ impl fmt::Debug for Parts {
             .field("method", &self.method)
             .field("uri", &self.uri)
             .field("version", &self.version)
+            .field("protocol", &self.protocol)
             .field("headers", &self.headers)
+            .field("trailers", &self.trailers)
             // omits Extensions because not useful
             // omits _priv because not useful
             .finish()
@@ -788,6 +1377,32 @@ impl Builder {
         })
     }
 
+    /// Set the HTTP method for this request, failing right away if it is
+    /// invalid.
+    ///
+    /// Unlike `Builder::method`, an invalid method is returned as an error
+    /// from this call instead of from `Builder::body`. The builder is
+    /// dropped in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let builder = Request::builder().try_method("POST").unwrap();
+    /// assert_eq!(builder.method_ref(), Some(&Method::POST));
+    ///
+    /// assert!(Request::builder().try_method("GE T").is_err());
+    /// ```
+    pub fn try_method<T>(self, method: T) -> Result<Builder>
+    where
+        Method: TryFrom<T>,
+        <Method as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        let method = Method::try_from(method).map_err(Into::into)?;
+        Ok(self.method::<Method>(method))
+    }
+
     /// Get the HTTP Method for this request.
     ///
     /// By default this is `GET`. If builder has error, returns None.
@@ -829,15 +1444,131 @@ impl Builder {
         Uri: TryFrom<T>,
         <Uri as TryFrom<T>>::Error: Into<crate::Error>,
     {
-        self.and_then(move |mut head| {
+        let mut builder = self.and_then(move |mut head| {
             head.uri = TryFrom::try_from(uri).map_err(Into::into)?;
             Ok(head)
+        });
+        builder.uri_parts = None;
+        builder
+    }
+
+    /// Set the URI for this request, failing right away if it is invalid.
+    ///
+    /// Unlike `Builder::uri`, an invalid URI is returned as an error from
+    /// this call instead of from `Builder::body`. The builder is dropped in
+    /// that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let builder = Request::builder().try_uri("https://www.rust-lang.org/").unwrap();
+    /// assert_eq!(builder.uri_ref().unwrap(), "https://www.rust-lang.org/");
+    ///
+    /// assert!(Request::builder().try_uri("not a uri").is_err());
+    /// ```
+    pub fn try_uri<T>(self, uri: T) -> Result<Builder>
+    where
+        Uri: TryFrom<T>,
+        <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        let uri = Uri::try_from(uri).map_err(Into::into)?;
+        Ok(self.uri::<Uri>(uri))
+    }
+
+    /// Set the scheme of the URI for this request.
+    ///
+    /// The URI components set with `scheme`, `authority` and
+    /// `path_and_query` start out as those of the URI configured so far and
+    /// are composed into the final `Uri` when the request is built. Setting
+    /// a full URI with `uri` discards any components set before it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let req = Request::builder()
+    ///     .scheme("https")
+    ///     .authority("www.rust-lang.org")
+    ///     .path_and_query("/learn?lang=en")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(req.uri(), "https://www.rust-lang.org/learn?lang=en");
+    /// ```
+    pub fn scheme<T>(self, scheme: T) -> Builder
+    where
+        Scheme: TryFrom<T>,
+        <Scheme as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        self.and_then_uri(move |parts| {
+            parts.scheme = Some(TryFrom::try_from(scheme).map_err(Into::into)?);
+            Ok(())
+        })
+    }
+
+    /// Set the authority of the URI for this request.
+    ///
+    /// See [`scheme`](#method.scheme) for how URI components are composed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let req = Request::builder()
+    ///     .uri("http://localhost/index.html")
+    ///     .authority("example.com:8080")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(req.uri(), "http://example.com:8080/index.html");
+    /// ```
+    pub fn authority<T>(self, authority: T) -> Builder
+    where
+        Authority: TryFrom<T>,
+        <Authority as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        self.and_then_uri(move |parts| {
+            parts.authority = Some(TryFrom::try_from(authority).map_err(Into::into)?);
+            Ok(())
+        })
+    }
+
+    /// Set the path and query of the URI for this request.
+    ///
+    /// See [`scheme`](#method.scheme) for how URI components are composed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let req = Request::builder()
+    ///     .path_and_query("/search?q=rust")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(req.uri(), "/search?q=rust");
+    /// ```
+    pub fn path_and_query<T>(self, path_and_query: T) -> Builder
+    where
+        PathAndQuery: TryFrom<T>,
+        <PathAndQuery as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        self.and_then_uri(move |parts| {
+            parts.path_and_query = Some(TryFrom::try_from(path_and_query).map_err(Into::into)?);
+            Ok(())
         })
     }
 
     /// Get the URI for this request
     ///
-    /// By default this is `/`.
+    /// By default this is `/`. URI components set with `scheme`,
+    /// `authority` or `path_and_query` are not reflected until the request
+    /// is built.
     ///
     /// # Examples
     ///
@@ -874,6 +1605,31 @@ impl Builder {
     pub fn version(self, version: Version) -> Builder {
         self.and_then(move |mut head| {
             head.version = version;
+            head.protocol = version.protocol();
+            Ok(head)
+        })
+    }
+
+    /// Set the protocol for this request.
+    ///
+    /// The protocol is set from the version by `Builder::version`, so this
+    /// is only needed for versions that don't identify a protocol, and has
+    /// to come after any call to `Builder::version`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let req = Request::builder()
+    ///     .protocol(Protocol::Other)
+    ///     .body(())
+    ///     .unwrap();
+    /// assert_eq!(req.protocol(), Protocol::Other);
+    /// ```
+    pub fn protocol(self, protocol: Protocol) -> Builder {
+        self.and_then(move |mut head| {
+            head.protocol = protocol;
             Ok(head)
         })
     }
@@ -911,6 +1667,100 @@ impl Builder {
         })
     }
 
+    /// Appends a header to this request builder, failing right away if the
+    /// name or value is invalid.
+    ///
+    /// Unlike `Builder::header`, an invalid header is returned as an error
+    /// from this call instead of from `Builder::body`. The builder is
+    /// dropped in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let builder = Request::builder()
+    ///     .try_header("X-Custom-Foo", "bar")
+    ///     .unwrap();
+    /// assert_eq!(builder.headers_ref().unwrap()["x-custom-foo"], "bar");
+    ///
+    /// let err = Request::builder().try_header("X-Custom-Foo", "b\r\nar").unwrap_err();
+    /// assert!(err.is::<header::InvalidHeaderValue>());
+    /// ```
+    pub fn try_header<K, V>(self, key: K, value: V) -> Result<Builder>
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<crate::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<crate::Error>,
+    {
+        let name = <HeaderName as TryFrom<K>>::try_from(key).map_err(Into::into)?;
+        let value = <HeaderValue as TryFrom<V>>::try_from(value).map_err(Into::into)?;
+        Ok(self.header::<HeaderName, HeaderValue>(name, value))
+    }
+
+    /// Sets a typed header on this request builder.
+    ///
+    /// Any values previously set for the header name are replaced.
+    /// Essentially this is equivalent to calling `HeaderMap::typed_insert`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// # use httplike::header::typed::{CacheControl, ContentType};
+    ///
+    /// let req = Request::builder()
+    ///     .typed_header(ContentType::json())
+    ///     .typed_header(CacheControl::no_cache())
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(req.headers()["content-type"], "application/json");
+    /// assert_eq!(req.headers()["cache-control"], "no-cache");
+    /// ```
+    pub fn typed_header<H: Header>(self, header: H) -> Builder {
+        self.and_then(move |mut head| {
+            head.headers.typed_insert(header);
+            Ok(head)
+        })
+    }
+
+    /// Appends a trailer to this request builder.
+    ///
+    /// This function will append the provided key/value to the trailers of
+    /// the request being constructed, creating them if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let request = Request::builder()
+    ///     .header("Trailer", "x-checksum")
+    ///     .trailer("x-checksum", "abc")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.trailers().unwrap()["x-checksum"], "abc");
+    /// ```
+    pub fn trailer<K, V>(self, key: K, value: V) -> Builder
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<crate::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<crate::Error>,
+    {
+        self.and_then(move |mut head| {
+            let name = <HeaderName as TryFrom<K>>::try_from(key).map_err(Into::into)?;
+            let value = <HeaderValue as TryFrom<V>>::try_from(value).map_err(Into::into)?;
+            head.trailers
+                .get_or_insert_with(HeaderMap::new)
+                .append(name, value);
+            Ok(head)
+        })
+    }
+
     /// Get header on this request builder.
     /// when builder has error returns None
     ///
@@ -968,7 +1818,7 @@ impl Builder {
     /// ```
     pub fn extension<T>(self, extension: T) -> Builder
     where
-        T: Any + Send + Sync + 'static,
+        T: Any + Clone + Send + Sync + 'static,
     {
         self.and_then(move |mut head| {
             head.extensions.insert(extension);
@@ -976,6 +1826,80 @@ impl Builder {
         })
     }
 
+    /// Registers a hook run on the finished parts of the request.
+    ///
+    /// Interceptors run in the order they were registered, when `body` is
+    /// called and every other builder call succeeded. They can inspect and
+    /// modify the parts, or fail the build by returning an error, usually a
+    /// `Rejected`. Registering them in a shared constructor function
+    /// enforces invariants, like mandatory or banned headers, for every
+    /// request built with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// # use httplike::header::HeaderValue;
+    /// fn require_request_id(parts: &mut request::Parts) -> Result<()> {
+    ///     if !parts.headers.contains_key("x-request-id") {
+    ///         return Err(Rejected::new("missing x-request-id").into());
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// fn add_user_agent(parts: &mut request::Parts) -> Result<()> {
+    ///     parts.headers.insert("user-agent", HeaderValue::from_static("my-app/1.0"));
+    ///     Ok(())
+    /// }
+    ///
+    /// fn builder() -> request::Builder {
+    ///     Request::builder()
+    ///         .with_interceptor(require_request_id)
+    ///         .with_interceptor(add_user_agent)
+    /// }
+    ///
+    /// let err = builder().uri("/").body(()).unwrap_err();
+    /// assert!(err.is::<Rejected>());
+    ///
+    /// let request = builder()
+    ///     .header("x-request-id", "42")
+    ///     .body(())
+    ///     .unwrap();
+    /// assert_eq!(request.headers()["user-agent"], "my-app/1.0");
+    /// ```
+    pub fn with_interceptor(mut self, interceptor: fn(&mut Parts) -> Result<()>) -> Builder {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Makes `body` fail if the request exceeds `limits`.
+    ///
+    /// The limits are checked once the interceptors have run. The length
+    /// of the head isn't checked, as it depends on how the request is
+    /// encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::codec::Limits;
+    ///
+    /// let mut limits = Limits::new();
+    /// limits.set_max_headers(1);
+    ///
+    /// let err = Request::get("/")
+    ///     .limits(limits)
+    ///     .header("host", "example.com")
+    ///     .header("accept", "*/*")
+    ///     .body(())
+    ///     .unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+    /// ```
+    pub fn limits(mut self, limits: Limits) -> Builder {
+        self.limits = Some(limits);
+        self
+    }
+
     /// "Consumes" this builder, using the provided `body` to return a
     /// constructed `Request`.
     ///
@@ -997,22 +1921,155 @@ impl Builder {
     ///     .unwrap();
     /// ```
     pub fn body<T>(self, body: T) -> Result<Request<T>> {
-        self.inner.map(move |head| {
-            Request {
-                head,
-                body,
-            }
-        })
+        self.build(body).map_err(|(first, _)| first)
+    }
+
+    /// "Consumes" this builder, using the provided `body` to return a
+    /// constructed `Request`, or every error the builder encountered.
+    ///
+    /// Unlike `body`, which only reports the first invalid argument passed
+    /// to the builder, the returned `BuildErrors` lists the errors of all
+    /// failed calls in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let errors = Request::builder()
+    ///     .method("NOT A METHOD")
+    ///     .uri("/ok")
+    ///     .header("Bad Name", "value")
+    ///     .body_all_errors(())
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(errors.len(), 2);
+    /// assert!(errors.first().is::<method::InvalidMethod>());
+    /// ```
+    pub fn body_all_errors<T>(self, body: T) -> result::Result<Request<T>, BuildErrors> {
+        self.build(body)
+            .map_err(|(first, rest)| BuildErrors::new(first, rest))
     }
 
     // private
 
-    fn and_then<F>(self, func: F) -> Self
+    #[cfg(feature = "rtsp")]
+    fn rtsp<T>(method: Method, uri: T) -> Builder
+    where
+        Uri: TryFrom<T>,
+        <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        Builder::new()
+            .method(method)
+            .uri(uri)
+            .version(Version::RTSP_1)
+            .header(crate::header::CSEQ, HeaderValue::from_static("0"))
+    }
+
+    fn build<T>(self, body: T) -> result::Result<Request<T>, (crate::Error, Vec<crate::Error>)> {
+        let mut head = match self.inner {
+            Ok(head) => head,
+            Err(first) => return Err((first, self.errors)),
+        };
+        if let Some(parts) = self.uri_parts {
+            head.uri = Uri::from_parts(parts).map_err(|e| (e.into(), Vec::new()))?;
+        }
+        intercept(&mut head, &self.interceptors)?;
+        if let Some(ref limits) = self.limits {
+            limits
+                .check_request(&head)
+                .map_err(|e| (e.into(), Vec::new()))?;
+        }
+
+        Ok(Request {
+            head,
+            body,
+        })
+    }
+
+    fn and_then<F>(mut self, func: F) -> Self
     where
         F: FnOnce(Parts) -> Result<Parts>
     {
+        match self.inner {
+            Ok(head) => self.inner = func(head),
+            // Keep validating so `body_all_errors` can report every error.
+            Err(_) => {
+                if let Err(e) = func(Parts::new()) {
+                    self.errors.push(e);
+                }
+            }
+        }
+        self
+    }
+
+    fn and_then_uri<F>(mut self, func: F) -> Self
+    where
+        F: FnOnce(&mut uri::Parts) -> Result<()>,
+    {
+        match self.inner {
+            Ok(ref head) => {
+                let parts = self
+                    .uri_parts
+                    .get_or_insert_with(|| head.uri.clone().into_parts());
+
+                if let Err(e) = func(parts) {
+                    self.inner = Err(e);
+                }
+            }
+            Err(_) => {
+                if let Err(e) = func(&mut uri::Parts::default()) {
+                    self.errors.push(e);
+                }
+            }
+        }
+        self
+    }
+}
+
+impl TargetForm {
+    /// Returns the form of `uri` used as a request target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::request::TargetForm;
+    ///
+    /// let uri: Uri = "rtsp://example.com/stream".parse().unwrap();
+    /// assert_eq!(TargetForm::of(&uri), TargetForm::Absolute);
+    /// ```
+    pub fn of(uri: &Uri) -> TargetForm {
+        if uri.scheme().is_some() {
+            TargetForm::Absolute
+        } else if uri.authority().is_some() {
+            TargetForm::Authority
+        } else if uri.path() == "*" {
+            TargetForm::Asterisk
+        } else {
+            TargetForm::Origin
+        }
+    }
+}
+
+// Runs the interceptors in order, collecting every error.
+fn intercept(
+    head: &mut Parts,
+    interceptors: &[fn(&mut Parts) -> Result<()>],
+) -> result::Result<(), (crate::Error, Vec<crate::Error>)> {
+    let mut errors = interceptors.iter().filter_map(|f| f(head).err());
+    match errors.next() {
+        Some(first) => Err((first, errors.collect())),
+        None => Ok(()),
+    }
+}
+
+impl From<Parts> for Builder {
+    #[inline]
+    fn from(parts: Parts) -> Builder {
         Builder {
-            inner: self.inner.and_then(func),
+            inner: Ok(parts),
+            ..Builder::default()
         }
     }
 }
@@ -1022,6 +2079,10 @@ impl Default for Builder {
     fn default() -> Builder {
         Builder {
             inner: Ok(Parts::new()),
+            uri_parts: None,
+            errors: Vec::new(),
+            interceptors: Vec::new(),
+            limits: None,
         }
     }
 }
@@ -1030,6 +2091,56 @@ impl Default for Builder {
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(feature = "rtsp")]
+    fn builder_sets_protocol_from_version() {
+        let request = Request::describe("rtsp://example.com/stream").body(()).unwrap();
+        assert_eq!(request.protocol(), Protocol::Rtsp);
+
+        let request = Request::builder()
+            .version(Version::RTSP_1)
+            .protocol(Protocol::Other)
+            .body(())
+            .unwrap();
+        assert_eq!(request.protocol(), Protocol::Other);
+        assert_eq!(request.version(), Version::RTSP_1);
+    }
+
+    #[test]
+    fn builder_flags_sensitive_headers() {
+        use crate::header;
+
+        let request = Request::builder()
+            .header(header::AUTHORIZATION, "secret")
+            .header(header::HOST, "example.com")
+            .body(())
+            .unwrap();
+        assert!(request.headers()[header::AUTHORIZATION].is_sensitive());
+        assert!(!request.headers()[header::HOST].is_sensitive());
+        assert!(request.headers().is_auto_sensitive());
+    }
+
+    #[test]
+    fn debug_redacts_sensitive_headers() {
+        let request = Request::builder()
+            .uri("/")
+            .header(crate::header::AUTHORIZATION, "secret")
+            .body(())
+            .unwrap();
+        let out = format!("{:?}", request);
+        assert!(out.contains(r#""authorization": Sensitive(len=6)"#), "{}", out);
+        assert!(!out.contains("secret"), "{}", out);
+    }
+
+    #[test]
+    fn into_builder_keeps_extensions() {
+        let request = Request::builder().extension(7u8).body(()).unwrap();
+        let request = request.into_builder().method(Method::PUT).body(()).unwrap();
+
+        assert_eq!(request.method(), Method::PUT);
+        assert_eq!(request.extensions().get::<u8>(), Some(&7));
+    }
+
     #[test]
     fn it_can_map_a_body_from_one_type_to_another() {
         let request = Request::builder().body("some string").unwrap();
@@ -1039,4 +2150,116 @@ mod tests {
         });
         assert_eq!(mapped_request.body(), &123u32);
     }
+
+    #[test]
+    fn uri_components_compose_at_build() {
+        let request = Request::builder()
+            .path_and_query("/a?b")
+            .uri("http://example.com/ignored")
+            .authority("example.org")
+            .scheme("https")
+            .body(())
+            .unwrap();
+        assert_eq!(request.uri(), "https://example.org/ignored");
+
+        let request = Request::builder()
+            .scheme("https")
+            .authority("example.org")
+            .uri("/replaced")
+            .body(())
+            .unwrap();
+        assert_eq!(request.uri(), "/replaced");
+    }
+
+    #[test]
+    fn uri_components_report_errors() {
+        assert!(Request::builder().scheme("h t t p").body(()).is_err());
+
+        // An authority is required once a scheme is set.
+        assert!(Request::builder().scheme("https").body(()).is_err());
+    }
+
+    #[test]
+    fn body_all_errors_collects_every_failure() {
+        let errors = Request::builder()
+            .method("BAD METHOD")
+            .uri("/a b")
+            .header("x-ok", "fine")
+            .header("bad\nname", "value")
+            .body_all_errors(())
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+        assert!(errors.first().is::<crate::method::InvalidMethod>());
+        assert!(errors.iter().nth(1).unwrap().is::<crate::uri::InvalidUri>());
+        assert!(errors.iter().nth(2).unwrap().is::<crate::header::InvalidHeaderName>());
+    }
+
+    #[test]
+    fn body_all_errors_on_valid_builder() {
+        let req = Request::builder()
+            .uri("/ok")
+            .body_all_errors("body")
+            .unwrap();
+        assert_eq!(req.uri(), "/ok");
+    }
+
+    #[test]
+    fn clone_copies_head_and_body() {
+        let mut req = Request::builder()
+            .method("POST")
+            .uri("/clone")
+            .header("x-a", "1")
+            .extension(7u8)
+            .body(String::from("body"))
+            .unwrap();
+
+        let cloned = req.clone();
+        req.extensions_mut().insert(8u8);
+        req.headers_mut().clear();
+
+        assert_eq!(cloned.method(), Method::POST);
+        assert_eq!(cloned.uri(), "/clone");
+        assert_eq!(cloned.headers()["x-a"], "1");
+        assert_eq!(cloned.extensions().get::<u8>(), Some(&7));
+        assert_eq!(cloned.body(), "body");
+    }
+
+    #[test]
+    fn interceptors_report_every_rejection() {
+        fn need_host(parts: &mut Parts) -> Result<()> {
+            if parts.headers.contains_key("host") {
+                Ok(())
+            } else {
+                Err(crate::Rejected::new("missing host").into())
+            }
+        }
+
+        fn need_agent(parts: &mut Parts) -> Result<()> {
+            if parts.headers.contains_key("user-agent") {
+                Ok(())
+            } else {
+                Err(crate::Rejected::new("missing user-agent").into())
+            }
+        }
+
+        let errors = Request::builder()
+            .with_interceptor(need_host)
+            .with_interceptor(need_agent)
+            .body_all_errors(())
+            .unwrap_err();
+        assert_eq!(
+            errors.to_string(),
+            "message rejected: missing host; message rejected: missing user-agent"
+        );
+
+        let req = Request::builder()
+            .with_interceptor(need_host)
+            .header("host", "example.com")
+            .header("user-agent", "test")
+            .with_interceptor(need_agent)
+            .body(())
+            .unwrap();
+        assert_eq!(req.headers().len(), 2);
+    }
 }
@@ -61,14 +61,24 @@
 //! // ...
 //! ```
 
-use std::any::Any;
-use std::convert::TryFrom;
-use std::fmt;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::convert::TryFrom;
+use core::fmt;
+use core::result;
 
-use crate::header::{HeaderMap, HeaderName, HeaderValue};
+use bytes::Bytes;
+
+use crate::header::typed::Header;
+use crate::body::Body;
+use crate::codec::Limits;
+use crate::header::{self, HeaderMap, HeaderName, HeaderValue};
+use crate::protocol::Protocol;
 use crate::status::StatusCode;
+use crate::validate::{self, Profile, Violation};
 use crate::version::Version;
-use crate::{Extensions, Result};
+use crate::{BuildErrors, Extensions, Result};
 
 /// Represents an HTTP response
 ///
@@ -176,6 +186,7 @@ use crate::{Extensions, Result};
 /// #
 /// # fn main() {}
 /// ```
+#[derive(Clone)]
 pub struct Response<T> {
     head: Parts,
     body: T,
@@ -185,6 +196,7 @@ pub struct Response<T> {
 ///
 /// The HTTP response head consists of a status, version, and a set of
 /// header fields.
+#[derive(Clone)]
 pub struct Parts {
     /// The response's status
     pub status: StatusCode,
@@ -192,12 +204,29 @@ pub struct Parts {
     /// The response's version
     pub version: Version,
 
+    /// The response's protocol
+    ///
+    /// Set from the version by the builder.
+    pub protocol: Protocol,
+
     /// The response's headers
     pub headers: HeaderMap<HeaderValue>,
 
     /// The response's extensions
     pub extensions: Extensions,
 
+    /// The response's trailers
+    ///
+    /// Header fields sent after the body, such as in chunked transfer
+    /// encoding or an HTTP/2 trailing `HEADERS` frame. `None` if the response
+    /// has no trailers.
+    pub trailers: Option<HeaderMap<HeaderValue>>,
+
+    /// The reason phrase the response was received with
+    ///
+    /// `None` to use the canonical reason of the status code.
+    pub reason: Option<ReasonPhrase>,
+
     _priv: (),
 }
 
@@ -208,6 +237,76 @@ pub struct Parts {
 #[derive(Debug)]
 pub struct Builder {
     inner: Result<Parts>,
+    // Errors from calls made after `inner` became an error
+    errors: Vec<crate::Error>,
+    // Run on the finished `Parts` by `body`
+    interceptors: Vec<fn(&mut Parts) -> Result<()>>,
+    // Checked by `body`, after the interceptors
+    limits: Option<Limits>,
+}
+
+/// The interim responses received before a final response
+///
+/// Informational (`1xx`) responses, such as `103 Early Hints` in HTTP or
+/// the provisional `180 Ringing` in SIP, can precede the final response to
+/// a request. Clients that want to expose them store an `Interim` in the
+/// extensions of the final response, which is what `Response::interim` and
+/// `Response::interim_mut` do.
+///
+/// Responses are kept in the order in which they were received.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::*;
+/// let mut response = Response::new(());
+///
+/// let hints = Response::builder()
+///     .status(StatusCode::from_u16(103).unwrap())
+///     .header("link", "</style.css>; rel=preload")
+///     .body(())
+///     .unwrap();
+/// response.interim_mut().push(hints);
+///
+/// let interim = response.interim().unwrap();
+/// assert_eq!(interim.len(), 1);
+/// assert_eq!(interim.early_hints().count(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Interim {
+    responses: Vec<Response<()>>,
+}
+
+/// The reason phrase a response was received with
+///
+/// The reason phrase of a status line is normally the canonical reason of
+/// the status code, and is otherwise meaningless to the protocol. Some
+/// clients do depend on the exact phrase a server sent though, so a parser
+/// can store the phrase it received in the `reason` field of the response's
+/// `Parts`. When serializing a status line, the stored phrase takes
+/// precedence over the canonical reason.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::*;
+/// use httplike::response::ReasonPhrase;
+///
+/// let response = Response::builder()
+///     .status(StatusCode::NOT_FOUND)
+///     .reason_phrase("Nothing Here")
+///     .body(())
+///     .unwrap();
+///
+/// assert_eq!(response.reason_phrase().unwrap(), "Nothing Here");
+/// assert_eq!(response.reason(), Some("Nothing Here"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ReasonPhrase(Bytes);
+
+/// A possible error when converting a `ReasonPhrase` from bytes.
+pub struct InvalidReasonPhrase {
+    _priv: (),
 }
 
 impl Response<()> {
@@ -230,6 +329,69 @@ impl Response<()> {
     pub fn builder() -> Builder {
         Builder::new()
     }
+
+    /// Creates a new `Response` with a `404 Not Found` status and an empty
+    /// body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::not_found();
+    /// assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    /// ```
+    #[inline]
+    pub fn not_found() -> Response<()> {
+        Response::with_status(StatusCode::NOT_FOUND, ())
+    }
+
+    /// Creates a new `Response` with a `204 No Content` status and an empty
+    /// body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::no_content();
+    /// assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    /// ```
+    #[inline]
+    pub fn no_content() -> Response<()> {
+        Response::with_status(StatusCode::NO_CONTENT, ())
+    }
+
+    /// Creates a new redirect `Response` with the given status and a
+    /// `Location` header pointing at `location`.
+    ///
+    /// The status is expected to be one of the `3xx` redirection codes, or
+    /// `201 Created`, but this is not checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `location` is not a valid header value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::redirect("/login", StatusCode::SEE_OTHER).unwrap();
+    ///
+    /// assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    /// assert_eq!(response.headers()[header::LOCATION], "/login");
+    /// ```
+    #[cfg(feature = "http")]
+    pub fn redirect<U>(location: U, status: StatusCode) -> Result<Response<()>>
+    where
+        HeaderValue: TryFrom<U>,
+        <HeaderValue as TryFrom<U>>::Error: Into<crate::Error>,
+    {
+        let location = HeaderValue::try_from(location).map_err(Into::into)?;
+        let mut response = Response::with_status(status, ());
+        response
+            .headers_mut()
+            .insert(crate::header::LOCATION, location);
+        Ok(response)
+    }
 }
 
 impl<T> Response<T> {
@@ -255,6 +417,43 @@ impl<T> Response<T> {
         }
     }
 
+    /// Creates a new `Response` with a `200 OK` status and the given body
+    ///
+    /// This is the same as `Response::new`, but reads better in handlers
+    /// that also use the other status constructors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::ok("hello world");
+    ///
+    /// assert_eq!(response.status(), StatusCode::OK);
+    /// assert_eq!(*response.body(), "hello world");
+    /// ```
+    #[inline]
+    pub fn ok(body: T) -> Response<T> {
+        Response::new(body)
+    }
+
+    /// Creates a new `Response` with the given status and body
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::with_status(StatusCode::CREATED, "created");
+    ///
+    /// assert_eq!(response.status(), StatusCode::CREATED);
+    /// assert_eq!(*response.body(), "created");
+    /// ```
+    #[inline]
+    pub fn with_status(status: StatusCode, body: T) -> Response<T> {
+        let mut response = Response::new(body);
+        *response.status_mut() = status;
+        response
+    }
+
     /// Creates a new `Response` with the given head and body
     ///
     /// # Examples
@@ -336,6 +535,58 @@ impl<T> Response<T> {
         &mut self.head.version
     }
 
+    /// Returns the associated protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response: Response<()> = Response::default();
+    /// assert_eq!(response.protocol(), Protocol::Http);
+    /// ```
+    #[inline]
+    pub fn protocol(&self) -> Protocol {
+        self.head.protocol
+    }
+
+    /// Returns a mutable reference to the associated protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut response: Response<()> = Response::default();
+    /// *response.protocol_mut() = Protocol::Other;
+    /// assert_eq!(response.protocol(), Protocol::Other);
+    /// ```
+    #[inline]
+    pub fn protocol_mut(&mut self) -> &mut Protocol {
+        &mut self.head.protocol
+    }
+
+    /// Returns an estimate of the memory used by the response head, in
+    /// bytes.
+    ///
+    /// Like `Request::estimated_size`, this is the sum of the lengths of
+    /// the status code, the reason phrase if one was received and every
+    /// header name and value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::builder()
+    ///     .header("content-length", "0")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.estimated_size(), 3 + 14 + 1);
+    /// ```
+    pub fn estimated_size(&self) -> usize {
+        let reason = self.head.reason.as_ref().map_or(0, |r| r.as_bytes().len());
+        3 + reason + self.head.headers.byte_len()
+    }
+
     /// Returns a reference to the associated header field map.
     ///
     /// # Examples
@@ -396,6 +647,139 @@ impl<T> Response<T> {
         &mut self.head.extensions
     }
 
+    /// Returns a reference to the associated trailers, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response: Response<()> = Response::default();
+    /// assert!(response.trailers().is_none());
+    /// ```
+    #[inline]
+    pub fn trailers(&self) -> Option<&HeaderMap<HeaderValue>> {
+        self.head.trailers.as_ref()
+    }
+
+    /// Returns a mutable reference to the associated trailers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// # use httplike::header::*;
+    /// let mut response: Response<()> = Response::default();
+    /// response
+    ///     .trailers_mut()
+    ///     .get_or_insert_with(HeaderMap::new)
+    ///     .insert("x-checksum", HeaderValue::from_static("abc"));
+    /// assert_eq!(response.trailers().unwrap()["x-checksum"], "abc");
+    /// ```
+    #[inline]
+    pub fn trailers_mut(&mut self) -> &mut Option<HeaderMap<HeaderValue>> {
+        &mut self.head.trailers
+    }
+
+    /// Returns the reason phrase received with this response, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response: Response<()> = Response::default();
+    /// assert!(response.reason_phrase().is_none());
+    /// ```
+    #[inline]
+    pub fn reason_phrase(&self) -> Option<&ReasonPhrase> {
+        self.head.reason.as_ref()
+    }
+
+    /// Returns a mutable reference to the reason phrase received with this
+    /// response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::response::ReasonPhrase;
+    ///
+    /// let mut response: Response<()> = Response::default();
+    /// *response.reason_phrase_mut() = Some(ReasonPhrase::from_static("Okey Dokey"));
+    /// assert_eq!(response.reason(), Some("Okey Dokey"));
+    /// ```
+    pub fn reason_phrase_mut(&mut self) -> &mut Option<ReasonPhrase> {
+        &mut self.head.reason
+    }
+
+    /// Returns the reason phrase to send with this response.
+    ///
+    /// This is the received reason phrase if there is one and it is valid
+    /// UTF-8, and the canonical reason of the status code otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::not_found();
+    /// assert_eq!(response.reason(), Some("Not Found"));
+    /// ```
+    pub fn reason(&self) -> Option<&str> {
+        self.head.reason()
+    }
+
+    /// Checks this response against the requirements of a protocol.
+    ///
+    /// Returns every violation found, or an empty list if the response is
+    /// valid. The body is not inspected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::validate::Profile;
+    ///
+    /// let response = Response::builder()
+    ///     .header("content-length", "five")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// let violations = response.validate(Profile::Http11);
+    /// assert_eq!(violations[0].message(), "invalid content-length header");
+    /// ```
+    pub fn validate(&self, profile: Profile) -> Vec<Violation> {
+        validate::response(self.head.status, self.head.version, &self.head.headers, profile)
+    }
+
+    /// Returns a reference to the interim responses that preceded this
+    /// response, if any were recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response: Response<()> = Response::default();
+    /// assert!(response.interim().is_none());
+    /// ```
+    #[inline]
+    pub fn interim(&self) -> Option<&Interim> {
+        self.head.extensions.get()
+    }
+
+    /// Returns a mutable reference to the interim responses that preceded
+    /// this response, inserting an empty `Interim` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut response: Response<()> = Response::default();
+    /// response.interim_mut().push(Response::with_status(StatusCode::CONTINUE, ()));
+    /// assert_eq!(response.interim().unwrap().len(), 1);
+    /// ```
+    pub fn interim_mut(&mut self) -> &mut Interim {
+        self.head.extensions.get_or_insert_with(Interim::new)
+    }
+
     /// Returns a reference to the associated HTTP body.
     ///
     /// # Examples
@@ -455,6 +839,58 @@ impl<T> Response<T> {
         (self.head, self.body)
     }
 
+    /// Returns mutable references to the head and body parts at once.
+    ///
+    /// This allows changing the head based on the body, or the other way
+    /// around, without taking the response apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut response = Response::new(b"hello".to_vec());
+    ///
+    /// let (head, body) = response.parts_and_body_mut();
+    /// body.extend_from_slice(b" world");
+    /// head.headers.insert("content-length", body.len().into());
+    ///
+    /// assert_eq!(response.headers()["content-length"], "11");
+    /// ```
+    #[inline]
+    pub fn parts_and_body_mut(&mut self) -> (&mut Parts, &mut T) {
+        (&mut self.head, &mut self.body)
+    }
+
+    /// Consumes the response returning a builder seeded with its head.
+    ///
+    /// Everything in the head, including the extensions, is carried over
+    /// and the body is dropped. Use `into_parts` and `Builder::from` to
+    /// keep the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::builder()
+    ///     .status(StatusCode::NOT_FOUND)
+    ///     .header("x-origin", "cache")
+    ///     .body("not here")
+    ///     .unwrap();
+    ///
+    /// let rewritten = response
+    ///     .into_builder()
+    ///     .status(StatusCode::GONE)
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(rewritten.status(), StatusCode::GONE);
+    /// assert_eq!(rewritten.headers()["x-origin"], "cache");
+    /// ```
+    #[inline]
+    pub fn into_builder(self) -> Builder {
+        Builder::from(self.head)
+    }
+
     /// Consumes the response returning a new response with body mapped to the
     /// return type of the passed in function.
     ///
@@ -479,38 +915,161 @@ impl<T> Response<T> {
             head: self.head,
         }
     }
-}
 
-impl<T: Default> Default for Response<T> {
+    /// Consumes the response returning a new response with body mapped by the
+    /// passed in fallible function.
+    ///
+    /// If the function fails, its error is returned and the response is
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::builder().body("42").unwrap();
+    /// let mapped_response: Response<u32> = response.try_map(|b| b.parse()).unwrap();
+    /// assert_eq!(mapped_response.body(), &42);
+    ///
+    /// let response = Response::builder().body("forty-two").unwrap();
+    /// assert!(response.try_map(|b| b.parse::<u32>()).is_err());
+    /// ```
     #[inline]
-    fn default() -> Response<T> {
-        Response::new(T::default())
+    pub fn try_map<F, U, E>(self, f: F) -> result::Result<Response<U>, E>
+    where
+        F: FnOnce(T) -> result::Result<U, E>,
+    {
+        Ok(Response {
+            body: f(self.body)?,
+            head: self.head,
+        })
     }
 }
 
-impl<T: fmt::Debug> fmt::Debug for Response<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Response")
-            .field("status", &self.status())
-            .field("version", &self.version())
-            .field("headers", self.headers())
-            // omits Extensions because not useful
-            .field("body", self.body())
-            .finish()
+impl<T: Body> Response<T> {
+    /// Creates a new `Response` with the body and the framing headers it
+    /// needs.
+    ///
+    /// The status is `200 OK`, so this sets `Content-Length` for a body of
+    /// known length, and `Transfer-Encoding: chunked` otherwise. See
+    /// `Builder::body_with_length` for the rules used with other statuses
+    /// and versions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let response = Response::with_body_and_length("hello world");
+    ///
+    /// assert_eq!(response.headers()["content-length"], "11");
+    /// ```
+    pub fn with_body_and_length(body: T) -> Response<T> {
+        let mut response = Response::new(body);
+        frame(&mut response.head, &response.body);
+        response
+    }
+}
+
+impl<T: Default> Default for Response<T> {
+    #[inline]
+    fn default() -> Response<T> {
+        Response::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Response<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.status())
+            .field("version", &self.version())
+            .field("headers", self.headers())
+            // omits Extensions because not useful
+            .field("body", self.body())
+            .finish()
     }
 }
 
 impl Parts {
     /// Creates a new default instance of `Parts`
-    fn new() -> Parts {
+    ///
+    /// Useful to construct a response without a builder, for example when
+    /// converting a message from another protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let mut parts = response::Parts::new();
+    /// parts.status = StatusCode::NOT_FOUND;
+    ///
+    /// let response = Response::from_parts(parts, ());
+    /// assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    /// ```
+    pub fn new() -> Parts {
         Parts {
             status: StatusCode::default(),
             version: Version::default(),
-            headers: HeaderMap::default(),
+            protocol: Protocol::default(),
+            headers: HeaderMap::new(),
             extensions: Extensions::default(),
+            trailers: None,
+            reason: None,
             _priv: (),
         }
     }
+
+    /// Returns true if both heads describe the same response.
+    ///
+    /// The status, version, protocol, headers, trailers and reason phrase
+    /// are compared, the same way as `request::Parts::semantic_eq`.
+    /// Extensions are ignored, as they can't be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// let (a, _) = Response::builder()
+    ///     .status(404)
+    ///     .extension(1u8)
+    ///     .body(())
+    ///     .unwrap()
+    ///     .into_parts();
+    ///
+    /// let (b, _) = Response::<()>::not_found().into_parts();
+    /// assert!(a.semantic_eq(&b));
+    ///
+    /// let (c, _) = Response::builder()
+    ///     .status(404)
+    ///     .reason_phrase("Gone Fishing")
+    ///     .body(())
+    ///     .unwrap()
+    ///     .into_parts();
+    /// assert!(!a.semantic_eq(&c));
+    /// ```
+    pub fn semantic_eq(&self, other: &Parts) -> bool {
+        self.status == other.status
+            && self.version == other.version
+            && self.protocol == other.protocol
+            && self.headers == other.headers
+            && self.trailers == other.trailers
+            && self.reason == other.reason
+    }
+}
+
+impl Parts {
+    // The reason phrase to send, see `Response::reason`.
+    pub(crate) fn reason(&self) -> Option<&str> {
+        self.reason
+            .as_ref()
+            .and_then(|reason| reason.to_str().ok())
+            .or_else(|| self.status.canonical_reason())
+    }
+}
+
+impl Default for Parts {
+    #[inline]
+    fn default() -> Parts {
+        Parts::new()
+    }
 }
 
 impl fmt::Debug for Parts {
@@ -518,7 +1077,10 @@ impl fmt::Debug for Parts {
         f.debug_struct("Parts")
             .field("status", &self.status)
             .field("version", &self.version)
+            .field("protocol", &self.protocol)
             .field("headers", &self.headers)
+            .field("trailers", &self.trailers)
+            .field("reason", &self.reason)
             // omits Extensions because not useful
             // omits _priv because not useful
             .finish()
@@ -572,6 +1134,60 @@ impl Builder {
         })
     }
 
+    /// Set the HTTP status for this response, failing right away if it is
+    /// invalid.
+    ///
+    /// Unlike `Builder::status`, an invalid status is returned as an error
+    /// from this call instead of from `Builder::body`. The builder is
+    /// dropped in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let response = Response::builder().try_status(404).unwrap().body(()).unwrap();
+    /// assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    ///
+    /// assert!(Response::builder().try_status(1000).is_err());
+    /// ```
+    pub fn try_status<T>(self, status: T) -> Result<Builder>
+    where
+        StatusCode: TryFrom<T>,
+        <StatusCode as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        let status = StatusCode::try_from(status).map_err(Into::into)?;
+        Ok(self.status::<StatusCode>(status))
+    }
+
+    /// Set the reason phrase for this response.
+    ///
+    /// By default the canonical reason of the status code is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let response = Response::builder()
+    ///     .status(200)
+    ///     .reason_phrase("Everything Is Fine")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.reason(), Some("Everything Is Fine"));
+    /// ```
+    pub fn reason_phrase<T>(self, reason: T) -> Builder
+    where
+        ReasonPhrase: TryFrom<T>,
+        <ReasonPhrase as TryFrom<T>>::Error: Into<crate::Error>,
+    {
+        self.and_then(move |mut head| {
+            head.reason = Some(TryFrom::try_from(reason).map_err(Into::into)?);
+            Ok(head)
+        })
+    }
+
     /// Set the HTTP version for this response.
     ///
     /// This function will configure the HTTP version of the `Response` that
@@ -592,6 +1208,31 @@ impl Builder {
     pub fn version(self, version: Version) -> Builder {
         self.and_then(move |mut head| {
             head.version = version;
+            head.protocol = version.protocol();
+            Ok(head)
+        })
+    }
+
+    /// Set the protocol for this response.
+    ///
+    /// The protocol is set from the version by `Builder::version`, so this
+    /// is only needed for versions that don't identify a protocol, and has
+    /// to come after any call to `Builder::version`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let resp = Response::builder()
+    ///     .protocol(Protocol::Other)
+    ///     .body(())
+    ///     .unwrap();
+    /// assert_eq!(resp.protocol(), Protocol::Other);
+    /// ```
+    pub fn protocol(self, protocol: Protocol) -> Builder {
+        self.and_then(move |mut head| {
+            head.protocol = protocol;
             Ok(head)
         })
     }
@@ -630,6 +1271,100 @@ impl Builder {
         })
     }
 
+    /// Appends a header to this response builder, failing right away if the
+    /// name or value is invalid.
+    ///
+    /// Unlike `Builder::header`, an invalid header is returned as an error
+    /// from this call instead of from `Builder::body`. The builder is
+    /// dropped in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let builder = Response::builder()
+    ///     .try_header("X-Custom-Foo", "bar")
+    ///     .unwrap();
+    /// assert_eq!(builder.headers_ref().unwrap()["x-custom-foo"], "bar");
+    ///
+    /// let err = Response::builder().try_header("X-Custom-Foo", "b\r\nar").unwrap_err();
+    /// assert!(err.is::<header::InvalidHeaderValue>());
+    /// ```
+    pub fn try_header<K, V>(self, key: K, value: V) -> Result<Builder>
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<crate::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<crate::Error>,
+    {
+        let name = <HeaderName as TryFrom<K>>::try_from(key).map_err(Into::into)?;
+        let value = <HeaderValue as TryFrom<V>>::try_from(value).map_err(Into::into)?;
+        Ok(self.header::<HeaderName, HeaderValue>(name, value))
+    }
+
+    /// Sets a typed header on this response builder.
+    ///
+    /// Any values previously set for the header name are replaced.
+    /// Essentially this is equivalent to calling `HeaderMap::typed_insert`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// # use httplike::header::typed::{CacheControl, ContentType};
+    ///
+    /// let response = Response::builder()
+    ///     .typed_header(ContentType::json())
+    ///     .typed_header(CacheControl::no_store())
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.headers()["content-type"], "application/json");
+    /// assert_eq!(response.headers()["cache-control"], "no-store");
+    /// ```
+    pub fn typed_header<H: Header>(self, header: H) -> Builder {
+        self.and_then(move |mut head| {
+            head.headers.typed_insert(header);
+            Ok(head)
+        })
+    }
+
+    /// Appends a trailer to this response builder.
+    ///
+    /// This function will append the provided key/value to the trailers of
+    /// the response being constructed, creating them if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let response = Response::builder()
+    ///     .header("Trailer", "x-checksum")
+    ///     .trailer("x-checksum", "abc")
+    ///     .body(())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(response.trailers().unwrap()["x-checksum"], "abc");
+    /// ```
+    pub fn trailer<K, V>(self, key: K, value: V) -> Builder
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<crate::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<crate::Error>,
+    {
+        self.and_then(move |mut head| {
+            let name = <HeaderName as TryFrom<K>>::try_from(key).map_err(Into::into)?;
+            let value = <HeaderValue as TryFrom<V>>::try_from(value).map_err(Into::into)?;
+            head.trailers
+                .get_or_insert_with(HeaderMap::new)
+                .append(name, value);
+            Ok(head)
+        })
+    }
+
     /// Get header on this response builder.
     ///
     /// When builder has error returns None.
@@ -690,7 +1425,7 @@ impl Builder {
     /// ```
     pub fn extension<T>(self, extension: T) -> Builder
     where
-        T: Any + Send + Sync + 'static,
+        T: Any + Clone + Send + Sync + 'static,
     {
         self.and_then(move |mut head| {
             head.extensions.insert(extension);
@@ -698,6 +1433,63 @@ impl Builder {
         })
     }
 
+    /// Registers a hook run on the finished parts of the response.
+    ///
+    /// Interceptors run in the order they were registered, when `body` is
+    /// called and every other builder call succeeded. They can inspect and
+    /// modify the parts, or fail the build by returning an error, usually a
+    /// `Rejected`. Registering them in a shared constructor function
+    /// enforces invariants, like mandatory or banned headers, for every
+    /// response built with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// fn no_server_header(parts: &mut response::Parts) -> Result<()> {
+    ///     parts.headers.remove("server");
+    ///     Ok(())
+    /// }
+    ///
+    /// let response = Response::builder()
+    ///     .with_interceptor(no_server_header)
+    ///     .header("server", "internal/1.2.3")
+    ///     .body(())
+    ///     .unwrap();
+    /// assert!(!response.headers().contains_key("server"));
+    /// ```
+    pub fn with_interceptor(mut self, interceptor: fn(&mut Parts) -> Result<()>) -> Builder {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Makes `body` fail if the response exceeds `limits`.
+    ///
+    /// The limits are checked once the interceptors have run. The length
+    /// of the head isn't checked, as it depends on how the response is
+    /// encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    /// use httplike::codec::Limits;
+    ///
+    /// let mut limits = Limits::new();
+    /// limits.set_max_header_len(16);
+    ///
+    /// let err = Response::builder()
+    ///     .limits(limits)
+    ///     .header("location", "/a/path/longer/than/the/limit")
+    ///     .body(())
+    ///     .unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+    /// ```
+    pub fn limits(mut self, limits: Limits) -> Builder {
+        self.limits = Some(limits);
+        self
+    }
+
     /// "Consumes" this builder, using the provided `body` to return a
     /// constructed `Response`.
     ///
@@ -719,22 +1511,304 @@ impl Builder {
     ///     .unwrap();
     /// ```
     pub fn body<T>(self, body: T) -> Result<Response<T>> {
-        self.inner.map(move |head| {
-            Response {
-                head,
-                body,
-            }
+        self.build(body).map_err(|(first, _)| first)
+    }
+
+    /// "Consumes" this builder, using the provided `body` to return a
+    /// constructed `Response`, or every error the builder encountered.
+    ///
+    /// Unlike `body`, which only reports the first invalid argument passed
+    /// to the builder, the returned `BuildErrors` lists the errors of all
+    /// failed calls in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let errors = Response::builder()
+    ///     .status(1000)
+    ///     .header("Bad Name", "value")
+    ///     .header("x-fine", "value")
+    ///     .body_all_errors(())
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(errors.len(), 2);
+    /// assert!(errors.first().is::<status::InvalidStatusCode>());
+    /// ```
+    pub fn body_all_errors<T>(self, body: T) -> result::Result<Response<T>, BuildErrors> {
+        self.build(body)
+            .map_err(|(first, rest)| BuildErrors::new(first, rest))
+    }
+
+    /// "Consumes" this builder, using the provided `body` to return a
+    /// constructed `Response` with the framing headers the body needs.
+    ///
+    /// Any `Content-Length` and `Transfer-Encoding` headers already set are
+    /// replaced, following these rules:
+    ///
+    /// * Statuses that never have a body, `1xx`, `204 No Content` and
+    ///   `304 Not Modified`, get neither header.
+    /// * A body with an exact `Body::size_hint` gets a `Content-Length`.
+    /// * Any other body gets `Transfer-Encoding: chunked` on HTTP/1.1, and
+    ///   neither header on other versions, which delimit it by closing the
+    ///   connection or by their own framing.
+    ///
+    /// The headers are set before any interceptors run.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::*;
+    ///
+    /// let response = Response::builder()
+    ///     .header("content-length", "100")
+    ///     .body_with_length(vec![0u8; 42])
+    ///     .unwrap();
+    /// assert_eq!(response.headers()["content-length"], "42");
+    ///
+    /// let response = Response::builder()
+    ///     .status(StatusCode::NO_CONTENT)
+    ///     .body_with_length(())
+    ///     .unwrap();
+    /// assert!(response.headers().is_empty());
+    /// ```
+    pub fn body_with_length<T: Body>(self, body: T) -> Result<Response<T>> {
+        self.and_then(|mut head| {
+            frame(&mut head, &body);
+            Ok(head)
         })
+        .body(body)
     }
 
     // private
 
-    fn and_then<F>(self, func: F) -> Self
+    fn build<T>(self, body: T) -> result::Result<Response<T>, (crate::Error, Vec<crate::Error>)> {
+        let mut head = match self.inner {
+            Ok(head) => head,
+            Err(first) => return Err((first, self.errors)),
+        };
+        intercept(&mut head, &self.interceptors)?;
+        if let Some(ref limits) = self.limits {
+            limits
+                .check_response(&head)
+                .map_err(|e| (e.into(), Vec::new()))?;
+        }
+
+        Ok(Response {
+            head,
+            body,
+        })
+    }
+
+    fn and_then<F>(mut self, func: F) -> Self
     where
         F: FnOnce(Parts) -> Result<Parts>
     {
+        match self.inner {
+            Ok(head) => self.inner = func(head),
+            // Keep validating so `body_all_errors` can report every error.
+            Err(_) => {
+                if let Err(e) = func(Parts::new()) {
+                    self.errors.push(e);
+                }
+            }
+        }
+        self
+    }
+}
+
+impl ReasonPhrase {
+    /// Converts a static string to a `ReasonPhrase`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the argument contains invalid reason phrase
+    /// characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::response::ReasonPhrase;
+    /// let reason = ReasonPhrase::from_static("Okey Dokey");
+    /// assert_eq!(reason, "Okey Dokey");
+    /// ```
+    pub fn from_static(src: &'static str) -> ReasonPhrase {
+        if !is_valid_reason(src.as_bytes()) {
+            panic!("invalid reason phrase");
+        }
+        ReasonPhrase(Bytes::from_static(src.as_bytes()))
+    }
+
+    /// Attempts to convert a byte slice to a `ReasonPhrase`.
+    ///
+    /// Like in a status line, only horizontal tabs, spaces, visible ASCII
+    /// and bytes of `0x80` and above are allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::response::ReasonPhrase;
+    /// assert!(ReasonPhrase::from_bytes(b"Moved\tPermanently").is_ok());
+    /// assert!(ReasonPhrase::from_bytes(b"Not\r\nFound").is_err());
+    /// ```
+    pub fn from_bytes(src: &[u8]) -> result::Result<ReasonPhrase, InvalidReasonPhrase> {
+        if is_valid_reason(src) {
+            Ok(ReasonPhrase(Bytes::copy_from_slice(src)))
+        } else {
+            Err(InvalidReasonPhrase { _priv: () })
+        }
+    }
+
+    /// Returns the reason phrase as bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Yields a `&str` slice if the reason phrase is valid UTF-8.
+    #[inline]
+    pub fn to_str(&self) -> result::Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(&self.0)
+    }
+}
+
+fn is_valid_reason(bytes: &[u8]) -> bool {
+    bytes
+        .iter()
+        .all(|&b| b == b'\t' || b == b' ' || (b >= 0x21 && b != 0x7f))
+}
+
+impl AsRef<[u8]> for ReasonPhrase {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for ReasonPhrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_str() {
+            Ok(s) => fmt::Debug::fmt(s, f),
+            Err(_) => fmt::Debug::fmt(&self.0, f),
+        }
+    }
+}
+
+impl PartialEq<str> for ReasonPhrase {
+    fn eq(&self, other: &str) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<'a> PartialEq<&'a str> for ReasonPhrase {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for ReasonPhrase {
+    type Error = InvalidReasonPhrase;
+
+    #[inline]
+    fn try_from(src: &'a [u8]) -> result::Result<ReasonPhrase, InvalidReasonPhrase> {
+        ReasonPhrase::from_bytes(src)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ReasonPhrase {
+    type Error = InvalidReasonPhrase;
+
+    #[inline]
+    fn try_from(src: &'a str) -> result::Result<ReasonPhrase, InvalidReasonPhrase> {
+        ReasonPhrase::from_bytes(src.as_bytes())
+    }
+}
+
+impl TryFrom<String> for ReasonPhrase {
+    type Error = InvalidReasonPhrase;
+
+    #[inline]
+    fn try_from(src: String) -> result::Result<ReasonPhrase, InvalidReasonPhrase> {
+        ReasonPhrase::try_from(Bytes::from(src))
+    }
+}
+
+impl TryFrom<Bytes> for ReasonPhrase {
+    type Error = InvalidReasonPhrase;
+
+    fn try_from(src: Bytes) -> result::Result<ReasonPhrase, InvalidReasonPhrase> {
+        if is_valid_reason(&src) {
+            Ok(ReasonPhrase(src))
+        } else {
+            Err(InvalidReasonPhrase { _priv: () })
+        }
+    }
+}
+
+impl fmt::Debug for InvalidReasonPhrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidReasonPhrase")
+            // skip _priv noise
+            .finish()
+    }
+}
+
+impl fmt::Display for InvalidReasonPhrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid reason phrase")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidReasonPhrase {}
+
+// Sets the framing headers for the body, see `Builder::body_with_length`.
+fn frame<T: Body>(head: &mut Parts, body: &T) {
+    head.headers.remove(header::CONTENT_LENGTH);
+    head.headers.remove(header::TRANSFER_ENCODING);
+
+    let status = head.status;
+    if status.is_informational()
+        || status == StatusCode::NO_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+    {
+        return;
+    }
+
+    match body.size_hint().exact() {
+        Some(len) => {
+            head.headers.insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+        }
+        #[cfg(feature = "http")]
+        None if head.version == Version::HTTP_11 => {
+            head.headers.insert(
+                header::TRANSFER_ENCODING,
+                HeaderValue::from_static("chunked"),
+            );
+        }
+        None => {}
+    }
+}
+
+// Runs the interceptors in order, collecting every error.
+fn intercept(
+    head: &mut Parts,
+    interceptors: &[fn(&mut Parts) -> Result<()>],
+) -> result::Result<(), (crate::Error, Vec<crate::Error>)> {
+    let mut errors = interceptors.iter().filter_map(|f| f(head).err());
+    match errors.next() {
+        Some(first) => Err((first, errors.collect())),
+        None => Ok(()),
+    }
+}
+
+impl From<Parts> for Builder {
+    #[inline]
+    fn from(parts: Parts) -> Builder {
         Builder {
-            inner: self.inner.and_then(func),
+            inner: Ok(parts),
+            ..Builder::default()
         }
     }
 }
@@ -744,10 +1818,77 @@ impl Default for Builder {
     fn default() -> Builder {
         Builder {
             inner: Ok(Parts::new()),
+            errors: Vec::new(),
+            interceptors: Vec::new(),
+            limits: None,
         }
     }
 }
 
+impl Interim {
+    /// Creates an empty `Interim`.
+    #[inline]
+    pub fn new() -> Interim {
+        Interim::default()
+    }
+
+    /// Appends an interim response.
+    ///
+    /// The response is expected to have an informational (`1xx`) status,
+    /// but this is not checked.
+    #[inline]
+    pub fn push(&mut self, response: Response<()>) {
+        self.responses.push(response);
+    }
+
+    /// Returns the number of interim responses.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Returns true if there are no interim responses.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Returns an iterator over the interim responses, in the order in which
+    /// they were received.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, Response<()>> {
+        self.responses.iter()
+    }
+
+    /// Returns an iterator over the interim responses with the given status.
+    pub fn with_status(&self, status: StatusCode) -> impl Iterator<Item = &Response<()>> {
+        self.responses.iter().filter(move |r| r.status() == status)
+    }
+
+    /// Returns an iterator over the `103 Early Hints` responses.
+    pub fn early_hints(&self) -> impl Iterator<Item = &Response<()>> {
+        self.responses.iter().filter(|r| r.status().as_u16() == 103)
+    }
+}
+
+impl<'a> IntoIterator for &'a Interim {
+    type Item = &'a Response<()>;
+    type IntoIter = core::slice::Iter<'a, Response<()>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Interim {
+    type Item = Response<()>;
+    type IntoIter = alloc::vec::IntoIter<Response<()>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.responses.into_iter()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -761,4 +1902,85 @@ mod tests {
         });
         assert_eq!(mapped_response.body(), &123u32);
     }
+
+    #[test]
+    fn builder_flags_sensitive_headers() {
+        let response = Response::builder()
+            .header(header::SET_COOKIE, "id=secret")
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(())
+            .unwrap();
+        assert!(response.headers()[header::SET_COOKIE].is_sensitive());
+        assert!(!response.headers()[header::CONTENT_TYPE].is_sensitive());
+    }
+
+    #[test]
+    fn body_all_errors_collects_every_failure() {
+        let errors = Response::builder()
+            .status(42)
+            .header("bad\nname", "value")
+            .header("x-ok", "bad\nvalue")
+            .body_all_errors(())
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+        assert!(errors.first().is::<crate::status::InvalidStatusCode>());
+        assert!(crate::Error::from(errors).is::<crate::status::InvalidStatusCode>());
+    }
+
+    #[test]
+    fn interim_responses_survive_clone() {
+        let mut response = Response::new(());
+        response.interim_mut().push(Response::with_status(StatusCode::CONTINUE, ()));
+        response
+            .interim_mut()
+            .push(Response::with_status(StatusCode::from_u16(103).unwrap(), ()));
+
+        let cloned = response.clone();
+        let statuses: Vec<_> = cloned.interim().unwrap().iter().map(|r| r.status()).collect();
+        assert_eq!(statuses, [StatusCode::CONTINUE, StatusCode::from_u16(103).unwrap()]);
+        assert_eq!(cloned.interim().unwrap().with_status(StatusCode::CONTINUE).count(), 1);
+    }
+
+    #[test]
+    fn reason_phrase_falls_back_to_canonical() {
+        let mut response = Response::builder()
+            .status(StatusCode::OK)
+            .reason_phrase(&b"Tr\xe8s Bien"[..])
+            .body(())
+            .unwrap();
+
+        assert_eq!(response.reason_phrase().unwrap().as_bytes(), b"Tr\xe8s Bien");
+        assert_eq!(response.reason(), Some("OK"));
+
+        *response.reason_phrase_mut() = None;
+        assert_eq!(response.reason(), Some("OK"));
+
+        let err = Response::builder().reason_phrase("Bad\r\n").body(()).unwrap_err();
+        assert!(err.is::<InvalidReasonPhrase>());
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn body_with_length_streams_unknown_lengths() {
+        struct Stream;
+        impl Body for Stream {}
+
+        let response = Response::with_body_and_length(Stream);
+        assert_eq!(response.headers()["transfer-encoding"], "chunked");
+        assert!(!response.headers().contains_key("content-length"));
+
+        let response = Response::builder()
+            .version(Version::HTTP_10)
+            .header("transfer-encoding", "chunked")
+            .body_with_length(Stream)
+            .unwrap();
+        assert!(response.headers().is_empty());
+
+        let response = Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .body_with_length("ignored")
+            .unwrap();
+        assert!(response.headers().is_empty());
+    }
 }
@@ -0,0 +1,595 @@
+//! SDP session descriptions, enabled with the `sdp` feature.
+//!
+//! RTSP `DESCRIBE` responses, RTSP `ANNOUNCE` requests and SIP `INVITE`
+//! offers and answers carry a description of the media session in their
+//! body, in the Session Description Protocol format of RFC 4566. This module
+//! parses such bodies into a `SessionDescription` and writes them back.
+//!
+//! The origin (`o=`), session name (`s=`), connection (`c=`), timing (`t=`
+//! and `r=`), attribute (`a=`) and media (`m=`) lines are parsed into their
+//! own types. Other lines are kept as `Field`s, in their order, so a parsed
+//! description is written back with all of its content.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::sdp::SessionDescription;
+//!
+//! let sdp: SessionDescription = "v=0\r\n\
+//!     o=- 2890844526 2890842807 IN IP4 192.0.2.10\r\n\
+//!     s=Camera\r\n\
+//!     c=IN IP4 0.0.0.0\r\n\
+//!     t=0 0\r\n\
+//!     a=control:*\r\n\
+//!     m=video 0 RTP/AVP 96\r\n\
+//!     a=rtpmap:96 H264/90000\r\n\
+//!     a=control:track1\r\n"
+//!     .parse()
+//!     .unwrap();
+//!
+//! assert_eq!(sdp.session_name, "Camera");
+//! assert_eq!(sdp.attribute("control"), Some("*"));
+//!
+//! let video = &sdp.media[0];
+//! assert_eq!(video.media, "video");
+//! assert_eq!(video.formats, ["96"]);
+//! assert_eq!(video.attribute("rtpmap"), Some("96 H264/90000"));
+//!
+//! assert!(sdp.to_string().starts_with("v=0\r\no=- 2890844526 2890842807 IN IP4 192.0.2.10\r\n"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::{self, FromStr};
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// A parsed SDP session description.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SessionDescription {
+    /// The origin of the session, from the `o=` line.
+    pub origin: Origin,
+
+    /// The name of the session, from the `s=` line.
+    pub session_name: String,
+
+    /// The session level connection data, from the `c=` line.
+    pub connection: Option<Connection>,
+
+    /// The times the session is active, from the `t=` and `r=` lines.
+    pub timing: Vec<Timing>,
+
+    /// The session level attributes, from the `a=` lines.
+    pub attributes: Vec<Attribute>,
+
+    /// The media descriptions, each starting with an `m=` line.
+    pub media: Vec<MediaDescription>,
+
+    /// The other session level lines, like `i=`, `b=` or `k=`.
+    pub fields: Vec<Field>,
+}
+
+/// The origin of a session, from an `o=` line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Origin {
+    /// The login of the user on the originating host, or `-`.
+    pub username: String,
+
+    /// The numeric session id.
+    pub session_id: u64,
+
+    /// The version of the description, increased on each change.
+    pub session_version: u64,
+
+    /// The network type, usually `IN`.
+    pub net_type: String,
+
+    /// The address type, `IP4` or `IP6`.
+    pub addr_type: String,
+
+    /// The address of the originating host.
+    pub address: String,
+}
+
+/// Connection data, from a `c=` line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Connection {
+    /// The network type, usually `IN`.
+    pub net_type: String,
+
+    /// The address type, `IP4` or `IP6`.
+    pub addr_type: String,
+
+    /// The connection address, with the TTL and address count of a
+    /// multicast address if any.
+    pub address: String,
+}
+
+/// A time the session is active, from a `t=` line.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Timing {
+    /// The start time as an NTP timestamp, or 0 for an unbounded session.
+    pub start: u64,
+
+    /// The stop time as an NTP timestamp, or 0 for an unbounded session.
+    pub stop: u64,
+
+    /// The values of the `r=` lines following the `t=` line.
+    pub repeats: Vec<String>,
+}
+
+/// An attribute, from an `a=` line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Attribute {
+    /// The name of the attribute.
+    pub name: String,
+
+    /// The value after the `:`, or `None` for a property attribute like
+    /// `a=recvonly`.
+    pub value: Option<String>,
+}
+
+/// A media description, starting with an `m=` line.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MediaDescription {
+    /// The media type, like `audio`, `video` or `application`.
+    pub media: String,
+
+    /// The transport port.
+    pub port: u16,
+
+    /// The number of ports, for a `port/count` port.
+    pub port_count: Option<u16>,
+
+    /// The transport protocol, like `RTP/AVP`.
+    pub protocol: String,
+
+    /// The media formats, usually RTP payload types.
+    pub formats: Vec<String>,
+
+    /// The media level connection data, from the `c=` lines.
+    pub connections: Vec<Connection>,
+
+    /// The media level attributes, from the `a=` lines.
+    pub attributes: Vec<Attribute>,
+
+    /// The other media level lines, like `i=`, `b=` or `k=`.
+    pub fields: Vec<Field>,
+}
+
+/// A line without a dedicated type, like `i=` or `b=`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+    /// The type letter of the line.
+    pub kind: char,
+
+    /// The text after the `=`.
+    pub value: String,
+}
+
+/// An error returned when parsing an SDP session description fails.
+pub struct InvalidSdp {
+    line: usize,
+}
+
+impl SessionDescription {
+    /// Parses a session description from the bytes of a message body.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body isn't UTF-8 or not a valid session
+    /// description.
+    pub fn from_bytes(src: &[u8]) -> Result<SessionDescription, InvalidSdp> {
+        let src = str::from_utf8(src).map_err(|e| {
+            let line = src[..e.valid_up_to()]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count();
+            InvalidSdp { line: line + 1 }
+        })?;
+        src.parse()
+    }
+
+    /// Returns the value of the first session level attribute named
+    /// `name`.
+    ///
+    /// Property attributes without a value return an empty string.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+}
+
+impl MediaDescription {
+    /// Returns the value of the first media level attribute named `name`.
+    ///
+    /// Property attributes without a value return an empty string.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+}
+
+fn find_attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == name)
+        .map(|attribute| attribute.value.as_ref().map_or("", |value| &value[..]))
+}
+
+impl Default for Origin {
+    fn default() -> Origin {
+        Origin {
+            username: "-".to_string(),
+            session_id: 0,
+            session_version: 0,
+            net_type: "IN".to_string(),
+            addr_type: "IP4".to_string(),
+            address: "0.0.0.0".to_string(),
+        }
+    }
+}
+
+impl FromStr for SessionDescription {
+    type Err = InvalidSdp;
+
+    fn from_str(src: &str) -> Result<SessionDescription, InvalidSdp> {
+        let mut sdp = SessionDescription::default();
+        let mut has_origin = false;
+        let mut has_name = false;
+
+        let mut lines = src
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r'))
+            .enumerate()
+            .filter(|&(_, line)| !line.is_empty());
+
+        match lines.next() {
+            Some((_, "v=0")) => {}
+            Some((i, _)) => return Err(InvalidSdp { line: i + 1 }),
+            None => return Err(InvalidSdp { line: 1 }),
+        }
+
+        for (i, line) in lines {
+            let err = || InvalidSdp { line: i + 1 };
+            let mut chars = line.chars();
+            let kind = chars
+                .next()
+                .filter(char::is_ascii_lowercase)
+                .ok_or_else(err)?;
+            if chars.next() != Some('=') {
+                return Err(err());
+            }
+            let value = &line[2..];
+
+            if let Some(media) = sdp.media.last_mut() {
+                match kind {
+                    'm' => {}
+                    'c' => {
+                        media.connections.push(value.parse().map_err(|_| err())?);
+                        continue;
+                    }
+                    'a' => {
+                        media.attributes.push(value.parse().map_err(|_| err())?);
+                        continue;
+                    }
+                    _ => {
+                        media.fields.push(Field::new(kind, value));
+                        continue;
+                    }
+                }
+            }
+
+            match kind {
+                'o' if !has_origin => {
+                    sdp.origin = value.parse().map_err(|_| err())?;
+                    has_origin = true;
+                }
+                's' if !has_name => {
+                    sdp.session_name = value.to_string();
+                    has_name = true;
+                }
+                'c' if sdp.connection.is_none() => {
+                    sdp.connection = Some(value.parse().map_err(|_| err())?)
+                }
+                't' => sdp.timing.push(value.parse().map_err(|_| err())?),
+                'r' => match sdp.timing.last_mut() {
+                    Some(timing) => timing.repeats.push(value.to_string()),
+                    None => return Err(err()),
+                },
+                'a' => sdp.attributes.push(value.parse().map_err(|_| err())?),
+                'm' => sdp.media.push(value.parse().map_err(|_| err())?),
+                'v' | 'o' | 's' | 'c' => return Err(err()),
+                _ => sdp.fields.push(Field::new(kind, value)),
+            }
+        }
+
+        if has_origin && has_name {
+            Ok(sdp)
+        } else {
+            Err(InvalidSdp { line: 1 })
+        }
+    }
+}
+
+macro_rules! parse_line {
+    ($ty:ident, $src:ident => $parse:expr) => {
+        impl FromStr for $ty {
+            type Err = InvalidSdp;
+
+            fn from_str($src: &str) -> Result<$ty, InvalidSdp> {
+                // The line number is filled in by `SessionDescription`.
+                let parsed: Option<$ty> = $parse;
+                parsed.ok_or(InvalidSdp { line: 0 })
+            }
+        }
+    };
+}
+
+parse_line!(Origin, src => {
+    let mut parts = src.split(' ');
+    let origin = (|| {
+        Some(Origin {
+            username: parts.next()?.to_string(),
+            session_id: parts.next()?.parse().ok()?,
+            session_version: parts.next()?.parse().ok()?,
+            net_type: parts.next()?.to_string(),
+            addr_type: parts.next()?.to_string(),
+            address: parts.next()?.to_string(),
+        })
+    })();
+    origin.filter(|_| parts.next().is_none())
+});
+
+parse_line!(Connection, src => {
+    let mut parts = src.split(' ');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(net_type), Some(addr_type), Some(address), None) => Some(Connection {
+            net_type: net_type.to_string(),
+            addr_type: addr_type.to_string(),
+            address: address.to_string(),
+        }),
+        _ => None,
+    }
+});
+
+parse_line!(Timing, src => {
+    let mut parts = src.split(' ');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(start), Some(stop), None) => match (start.parse(), stop.parse()) {
+            (Ok(start), Ok(stop)) => Some(Timing {
+                start,
+                stop,
+                repeats: Vec::new(),
+            }),
+            _ => None,
+        },
+        _ => None,
+    }
+});
+
+parse_line!(Attribute, src => {
+    let (name, value) = match src.find(':') {
+        Some(colon) => (&src[..colon], Some(src[colon + 1..].to_string())),
+        None => (src, None),
+    };
+    if name.is_empty() {
+        None
+    } else {
+        Some(Attribute {
+            name: name.to_string(),
+            value,
+        })
+    }
+});
+
+parse_line!(MediaDescription, src => {
+    let mut parts = src.split(' ');
+    let media = parts.next().unwrap_or("");
+    let port = parts.next().unwrap_or("");
+    let protocol = parts.next().unwrap_or("");
+
+    let (port, port_count) = match port.find('/') {
+        Some(slash) => (&port[..slash], port[slash + 1..].parse().ok()),
+        None => (port, Some(0)),
+    };
+    match (port.parse(), port_count) {
+        (Ok(port), Some(port_count)) if !media.is_empty() && !protocol.is_empty() => {
+            Some(MediaDescription {
+                media: media.to_string(),
+                port,
+                port_count: if port_count == 0 { None } else { Some(port_count) },
+                protocol: protocol.to_string(),
+                formats: parts.map(ToString::to_string).collect(),
+                ..MediaDescription::default()
+            })
+        }
+        _ => None,
+    }
+});
+
+impl Field {
+    fn new(kind: char, value: &str) -> Field {
+        Field {
+            kind,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SessionDescription {
+    /// Writes the description with CRLF line endings, in the line order of
+    /// RFC 4566.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("v=0\r\n")?;
+        write!(f, "o={}\r\n", self.origin)?;
+        write!(f, "s={}\r\n", self.session_name)?;
+        write_fields(f, &self.fields, |kind| "iuep".contains(kind))?;
+        if let Some(ref connection) = self.connection {
+            write!(f, "c={}\r\n", connection)?;
+        }
+        write_fields(f, &self.fields, |kind| kind == 'b')?;
+        for timing in &self.timing {
+            write!(f, "t={} {}\r\n", timing.start, timing.stop)?;
+            for repeat in &timing.repeats {
+                write!(f, "r={}\r\n", repeat)?;
+            }
+        }
+        write_fields(f, &self.fields, |kind| !"iuepb".contains(kind))?;
+        write_attributes(f, &self.attributes)?;
+
+        for media in &self.media {
+            write!(f, "m={} {}", media.media, media.port)?;
+            if let Some(count) = media.port_count {
+                write!(f, "/{}", count)?;
+            }
+            write!(f, " {}", media.protocol)?;
+            for format in &media.formats {
+                write!(f, " {}", format)?;
+            }
+            f.write_str("\r\n")?;
+            write_fields(f, &media.fields, |kind| kind == 'i')?;
+            for connection in &media.connections {
+                write!(f, "c={}\r\n", connection)?;
+            }
+            write_fields(f, &media.fields, |kind| kind != 'i')?;
+            write_attributes(f, &media.attributes)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_fields<F>(f: &mut fmt::Formatter<'_>, fields: &[Field], filter: F) -> fmt::Result
+where
+    F: Fn(char) -> bool,
+{
+    for field in fields.iter().filter(|field| filter(field.kind)) {
+        write!(f, "{}={}\r\n", field.kind, field.value)?;
+    }
+    Ok(())
+}
+
+fn write_attributes(f: &mut fmt::Formatter<'_>, attributes: &[Attribute]) -> fmt::Result {
+    for attribute in attributes {
+        match attribute.value {
+            Some(ref value) => write!(f, "a={}:{}\r\n", attribute.name, value)?,
+            None => write!(f, "a={}\r\n", attribute.name)?,
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.username,
+            self.session_id,
+            self.session_version,
+            self.net_type,
+            self.addr_type,
+            self.address
+        )
+    }
+}
+
+impl fmt::Display for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.net_type, self.addr_type, self.address)
+    }
+}
+
+impl InvalidSdp {
+    /// Returns the number of the line that failed to parse, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Debug for InvalidSdp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidSdp")
+            .field("line", &self.line)
+            .finish()
+    }
+}
+
+impl fmt::Display for InvalidSdp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid SDP session description at line {}", self.line)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for InvalidSdp {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIBE: &str = "v=0\r\n\
+        o=- 1 2 IN IP4 192.0.2.10\r\n\
+        s=Stream\r\n\
+        i=A camera\r\n\
+        c=IN IP4 224.2.1.1/127\r\n\
+        b=AS:5000\r\n\
+        t=3034423619 3042462419\r\n\
+        r=7d 1h 0 25h\r\n\
+        a=recvonly\r\n\
+        m=audio 49170/2 RTP/AVP 0 8\r\n\
+        i=Microphone\r\n\
+        c=IN IP4 224.2.1.2/127\r\n\
+        b=AS:64\r\n\
+        a=control:track2\r\n";
+
+    #[test]
+    fn round_trip() {
+        let sdp: SessionDescription = DESCRIBE.parse().unwrap();
+        assert_eq!(sdp.timing[0].repeats, ["7d 1h 0 25h"]);
+        assert_eq!(sdp.attribute("recvonly"), Some(""));
+        assert_eq!(sdp.fields.len(), 2);
+
+        let audio = &sdp.media[0];
+        assert_eq!(audio.port, 49170);
+        assert_eq!(audio.port_count, Some(2));
+        assert_eq!(audio.formats, ["0", "8"]);
+        assert_eq!(audio.connections[0].address, "224.2.1.2/127");
+
+        assert_eq!(sdp.to_string(), DESCRIBE);
+        assert_eq!(
+            SessionDescription::from_bytes(DESCRIBE.as_bytes()).unwrap(),
+            sdp
+        );
+    }
+
+    #[test]
+    fn lf_line_endings() {
+        let sdp: SessionDescription = "v=0\no=- 0 0 IN IP6 ::1\ns= \n\n".parse().unwrap();
+        assert_eq!(sdp.origin.addr_type, "IP6");
+        assert_eq!(sdp.session_name, " ");
+        assert!(sdp.timing.is_empty());
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let cases: &[(&str, usize)] = &[
+            ("", 1),
+            ("v=1\r\n", 1),
+            ("v=0\r\no=- 0 0 IN IP4\r\ns=x\r\n", 2),
+            ("v=0\r\no=- 0 0 IN IP4 a\r\nX=y\r\n", 3),
+            ("v=0\r\no=- 0 0 IN IP4 a\r\ns=x\r\nr=1d\r\n", 4),
+            (
+                "v=0\r\no=- 0 0 IN IP4 a\r\ns=x\r\nm=video x RTP/AVP 96\r\n",
+                4,
+            ),
+            ("v=0\r\no=- 0 0 IN IP4 a\r\nt=0 0\r\n", 1),
+        ];
+        for &(src, line) in cases {
+            let err = src.parse::<SessionDescription>().unwrap_err();
+            assert_eq!(err.line(), line, "{:?}", src);
+        }
+
+        let err = SessionDescription::from_bytes(b"v=0\r\ns=\xff\r\n").unwrap_err();
+        assert_eq!(err.line(), 2);
+    }
+}
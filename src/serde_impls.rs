@@ -0,0 +1,818 @@
+//! `serde` support, enabled with the `serde` feature.
+//!
+//! The representation is meant to be readable in formats like JSON:
+//!
+//! * `Method`, `Uri`, `Version` and `HeaderName` are strings.
+//! * `Protocol` is a lowercase string, like `"rtsp"`, or `"other"`. The
+//!   name of a protocol whose feature is disabled is read as `"other"`.
+//! * `StatusCode` is a number.
+//! * `HeaderValue` is a string, or bytes if it is not visible ASCII.
+//! * `HeaderMap` is a sequence of `[name, value]` pairs, in iteration order,
+//!   so repeated headers round trip.
+//! * `request::Parts` and `response::Parts` are structs of the above.
+//!   Extensions are skipped, and `trailers` and `reason` are only present
+//!   if set. A reason phrase is represented like a `HeaderValue`. The
+//!   protocol follows the version, and is set from the version when it is
+//!   missing.
+//!
+//! Formats that are not human readable, like bincode and postcard, get a
+//! compact representation instead. Those formats don't store field names and
+//! can't skip fields, so:
+//!
+//! * `HeaderValue` and reason phrases are always bytes.
+//! * `request::Parts` is a tuple of a layout version, currently `2`, the
+//!   method, URI, version, protocol, headers and optional trailers.
+//! * `response::Parts` is a tuple of a layout version, currently `2`, the
+//!   status, version, protocol, headers, optional trailers and optional
+//!   reason phrase.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, SerializeStruct, SerializeTuple, Serializer};
+
+use crate::header::{HeaderMap, HeaderName, HeaderValue};
+use crate::{request, response, Method, Protocol, StatusCode, Uri, Version};
+
+impl Serialize for Method {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Method {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Method, D::Error> {
+        deserializer.deserialize_str(FromStrVisitor::new("an HTTP method"))
+    }
+}
+
+impl Serialize for Uri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Uri, D::Error> {
+        deserializer.deserialize_str(FromStrVisitor::new("a URI"))
+    }
+}
+
+impl Serialize for HeaderName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for HeaderName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HeaderName, D::Error> {
+        deserializer.deserialize_str(FromStrVisitor::new("a header name"))
+    }
+}
+
+impl Serialize for StatusCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(self.as_u16())
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<StatusCode, D::Error> {
+        let code = u16::deserialize(deserializer)?;
+        StatusCode::from_u16(code).map_err(|_| {
+            de::Error::invalid_value(de::Unexpected::Unsigned(code.into()), &"a status code")
+        })
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:?}", self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Version, D::Error> {
+        struct VersionVisitor;
+
+        impl<'de> Visitor<'de> for VersionVisitor {
+            type Value = Version;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a protocol version")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Version, E> {
+                Version::ALL
+                    .iter()
+                    .find(|version| version.as_str() == v)
+                    .cloned()
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(VersionVisitor)
+    }
+}
+
+// The names of the protocols, including those whose features are disabled.
+const PROTOCOLS: &[&str] = &["http", "rtsp", "sip", "other"];
+
+impl Serialize for Protocol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = match *self {
+            #[cfg(feature = "http")]
+            Protocol::Http => "http",
+            #[cfg(feature = "rtsp")]
+            Protocol::Rtsp => "rtsp",
+            #[cfg(feature = "sip")]
+            Protocol::Sip => "sip",
+            Protocol::Other => "other",
+        };
+        serializer.serialize_str(name)
+    }
+}
+
+impl<'de> Deserialize<'de> for Protocol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Protocol, D::Error> {
+        struct ProtocolVisitor;
+
+        impl<'de> Visitor<'de> for ProtocolVisitor {
+            type Value = Protocol;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a protocol name")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Protocol, E> {
+                match v {
+                    #[cfg(feature = "http")]
+                    "http" => Ok(Protocol::Http),
+                    #[cfg(feature = "rtsp")]
+                    "rtsp" => Ok(Protocol::Rtsp),
+                    #[cfg(feature = "sip")]
+                    "sip" => Ok(Protocol::Sip),
+                    _ if PROTOCOLS.contains(&v) => Ok(Protocol::Other),
+                    _ => Err(E::unknown_variant(v, PROTOCOLS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(ProtocolVisitor)
+    }
+}
+
+impl Serialize for HeaderValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.to_str() {
+            Ok(s) if serializer.is_human_readable() => serializer.serialize_str(s),
+            _ => serializer.serialize_bytes(self.as_bytes()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for HeaderValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HeaderValue, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ValueVisitor)
+        } else {
+            deserializer.deserialize_bytes(ValueVisitor)
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = HeaderValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a header value")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<HeaderValue, E> {
+        HeaderValue::from_str(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<HeaderValue, E> {
+        HeaderValue::from_bytes(v).map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<HeaderValue, A::Error> {
+        // Formats without a bytes type, like JSON, give a sequence.
+        let mut bytes = Vec::with_capacity(cautious(seq.size_hint()));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl Serialize for HeaderMap<HeaderValue> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for pair in self {
+            seq.serialize_element(&pair)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for HeaderMap<HeaderValue> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HeaderMap, D::Error> {
+        struct MapVisitor;
+
+        impl<'de> Visitor<'de> for MapVisitor {
+            type Value = HeaderMap;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence of header name and value pairs")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<HeaderMap, A::Error> {
+                // `new` rather than `with_capacity`, to mark sensitive headers.
+                let mut map = HeaderMap::new();
+                map.reserve(cautious(seq.size_hint()));
+                while let Some((name, value)) = seq.next_element::<(HeaderName, HeaderValue)>()? {
+                    map.append(name, value);
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_seq(MapVisitor)
+    }
+}
+
+impl Serialize for request::Parts {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serialize_compact_request(self, serializer);
+        }
+
+        let len = 5 + self.trailers.is_some() as usize;
+        let mut state = serializer.serialize_struct("Parts", len)?;
+        state.serialize_field("method", &self.method)?;
+        state.serialize_field("uri", &self.uri)?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("protocol", &self.protocol)?;
+        state.serialize_field("headers", &self.headers)?;
+        match self.trailers {
+            Some(ref trailers) => state.serialize_field("trailers", trailers)?,
+            None => state.skip_field("trailers")?,
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for request::Parts {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<request::Parts, D::Error> {
+        if !deserializer.is_human_readable() {
+            return deserialize_compact_request(deserializer);
+        }
+
+        const FIELDS: &[&str] = &[
+            "method", "uri", "version", "headers", "trailers", "protocol",
+        ];
+
+        struct PartsVisitor;
+
+        impl<'de> Visitor<'de> for PartsVisitor {
+            type Value = request::Parts;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("request parts")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<request::Parts, A::Error> {
+                let mut parts = request::Parts::new();
+                let mut protocol = None;
+                let mut seen = [false; 6];
+
+                while let Some(key) = map.next_key::<FieldIndex>()? {
+                    let i = key.index(FIELDS).ok_or_else(|| {
+                        de::Error::unknown_field(key.name(), FIELDS)
+                    })?;
+                    if seen[i] {
+                        return Err(de::Error::duplicate_field(FIELDS[i]));
+                    }
+                    seen[i] = true;
+
+                    match i {
+                        0 => parts.method = map.next_value()?,
+                        1 => parts.uri = map.next_value()?,
+                        2 => {
+                            parts.version = map.next_value()?;
+                            parts.protocol = parts.version.protocol();
+                        }
+                        3 => parts.headers = map.next_value()?,
+                        4 => parts.trailers = map.next_value()?,
+                        _ => protocol = Some(map.next_value()?),
+                    }
+                }
+
+                if let Some(i) = seen[..4].iter().position(|seen| !seen) {
+                    return Err(de::Error::missing_field(FIELDS[i]));
+                }
+                if let Some(protocol) = protocol {
+                    parts.protocol = protocol;
+                }
+                Ok(parts)
+            }
+        }
+
+        deserializer.deserialize_struct("Parts", FIELDS, PartsVisitor)
+    }
+}
+
+impl Serialize for response::Parts {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serialize_compact_response(self, serializer);
+        }
+
+        let len = 4 + self.trailers.is_some() as usize + self.reason.is_some() as usize;
+        let mut state = serializer.serialize_struct("Parts", len)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("protocol", &self.protocol)?;
+        state.serialize_field("headers", &self.headers)?;
+        match self.trailers {
+            Some(ref trailers) => state.serialize_field("trailers", trailers)?,
+            None => state.skip_field("trailers")?,
+        }
+        match self.reason {
+            Some(ref reason) => state.serialize_field("reason", reason)?,
+            None => state.skip_field("reason")?,
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for response::Parts {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<response::Parts, D::Error> {
+        if !deserializer.is_human_readable() {
+            return deserialize_compact_response(deserializer);
+        }
+
+        const FIELDS: &[&str] = &[
+            "status", "version", "headers", "trailers", "reason", "protocol",
+        ];
+
+        struct PartsVisitor;
+
+        impl<'de> Visitor<'de> for PartsVisitor {
+            type Value = response::Parts;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("response parts")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<response::Parts, A::Error> {
+                let mut parts = response::Parts::new();
+                let mut protocol = None;
+                let mut seen = [false; 6];
+
+                while let Some(key) = map.next_key::<FieldIndex>()? {
+                    let i = key.index(FIELDS).ok_or_else(|| {
+                        de::Error::unknown_field(key.name(), FIELDS)
+                    })?;
+                    if seen[i] {
+                        return Err(de::Error::duplicate_field(FIELDS[i]));
+                    }
+                    seen[i] = true;
+
+                    match i {
+                        0 => parts.status = map.next_value()?,
+                        1 => {
+                            parts.version = map.next_value()?;
+                            parts.protocol = parts.version.protocol();
+                        }
+                        2 => parts.headers = map.next_value()?,
+                        3 => parts.trailers = map.next_value()?,
+                        4 => parts.reason = map.next_value()?,
+                        _ => protocol = Some(map.next_value()?),
+                    }
+                }
+
+                if let Some(i) = seen[..3].iter().position(|seen| !seen) {
+                    return Err(de::Error::missing_field(FIELDS[i]));
+                }
+                if let Some(protocol) = protocol {
+                    parts.protocol = protocol;
+                }
+                Ok(parts)
+            }
+        }
+
+        deserializer.deserialize_struct("Parts", FIELDS, PartsVisitor)
+    }
+}
+
+impl Serialize for response::ReasonPhrase {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.to_str() {
+            Ok(s) if serializer.is_human_readable() => serializer.serialize_str(s),
+            _ => serializer.serialize_bytes(self.as_bytes()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for response::ReasonPhrase {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<response::ReasonPhrase, D::Error> {
+        // Reason phrases allow the same bytes as header values.
+        let value = HeaderValue::deserialize(deserializer)?;
+        response::ReasonPhrase::from_bytes(value.as_bytes())
+            .map_err(|_| de::Error::custom("invalid reason phrase"))
+    }
+}
+
+// The version of the compact layout, the first element of its tuples.
+const COMPACT_LAYOUT: u8 = 2;
+
+// The compact representations are written and read through these functions
+// rather than through `is_human_readable` alone, so that nested values use
+// the compact form whatever the format says.
+fn serialize_compact_request<S: Serializer>(
+    parts: &request::Parts,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(7)?;
+    tuple.serialize_element(&COMPACT_LAYOUT)?;
+    tuple.serialize_element(&parts.method)?;
+    tuple.serialize_element(&parts.uri)?;
+    tuple.serialize_element(&parts.version)?;
+    tuple.serialize_element(&parts.protocol)?;
+    tuple.serialize_element(&CompactHeaders(&parts.headers))?;
+    tuple.serialize_element(&parts.trailers.as_ref().map(CompactHeaders))?;
+    tuple.end()
+}
+
+fn deserialize_compact_request<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<request::Parts, D::Error> {
+    struct CompactVisitor;
+
+    impl<'de> Visitor<'de> for CompactVisitor {
+        type Value = request::Parts;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("compact request parts")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<request::Parts, A::Error> {
+            let mut parts = request::Parts::new();
+            compact_layout(&mut seq, &self)?;
+            parts.method = compact_element(&mut seq, 1, &self)?;
+            parts.uri = compact_element(&mut seq, 2, &self)?;
+            parts.version = compact_element(&mut seq, 3, &self)?;
+            parts.protocol = compact_element(&mut seq, 4, &self)?;
+            parts.headers = compact_element::<_, CompactHeaderMap>(&mut seq, 5, &self)?.0;
+            parts.trailers = compact_element::<_, Option<CompactHeaderMap>>(&mut seq, 6, &self)?
+                .map(|trailers| trailers.0);
+            Ok(parts)
+        }
+    }
+
+    deserializer.deserialize_tuple(7, CompactVisitor)
+}
+
+fn serialize_compact_response<S: Serializer>(
+    parts: &response::Parts,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(7)?;
+    tuple.serialize_element(&COMPACT_LAYOUT)?;
+    tuple.serialize_element(&parts.status)?;
+    tuple.serialize_element(&parts.version)?;
+    tuple.serialize_element(&parts.protocol)?;
+    tuple.serialize_element(&CompactHeaders(&parts.headers))?;
+    tuple.serialize_element(&parts.trailers.as_ref().map(CompactHeaders))?;
+    tuple.serialize_element(
+        &parts
+            .reason
+            .as_ref()
+            .map(|reason| CompactBytes(reason.as_bytes())),
+    )?;
+    tuple.end()
+}
+
+fn deserialize_compact_response<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<response::Parts, D::Error> {
+    struct CompactVisitor;
+
+    impl<'de> Visitor<'de> for CompactVisitor {
+        type Value = response::Parts;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("compact response parts")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<response::Parts, A::Error> {
+            let mut parts = response::Parts::new();
+            compact_layout(&mut seq, &self)?;
+            parts.status = compact_element(&mut seq, 1, &self)?;
+            parts.version = compact_element(&mut seq, 2, &self)?;
+            parts.protocol = compact_element(&mut seq, 3, &self)?;
+            parts.headers = compact_element::<_, CompactHeaderMap>(&mut seq, 4, &self)?.0;
+            parts.trailers = compact_element::<_, Option<CompactHeaderMap>>(&mut seq, 5, &self)?
+                .map(|trailers| trailers.0);
+            parts.reason = match compact_element::<_, Option<CompactValue>>(&mut seq, 6, &self)? {
+                Some(value) => Some(
+                    response::ReasonPhrase::from_bytes(value.0.as_bytes())
+                        .map_err(|_| de::Error::custom("invalid reason phrase"))?,
+                ),
+                None => None,
+            };
+            Ok(parts)
+        }
+    }
+
+    deserializer.deserialize_tuple(7, CompactVisitor)
+}
+
+fn compact_layout<'de, A: SeqAccess<'de>>(
+    seq: &mut A,
+    exp: &dyn de::Expected,
+) -> Result<(), A::Error> {
+    let layout: u8 = compact_element(seq, 0, exp)?;
+    if layout != COMPACT_LAYOUT {
+        return Err(de::Error::invalid_value(
+            de::Unexpected::Unsigned(layout.into()),
+            &"compact layout 2",
+        ));
+    }
+    Ok(())
+}
+
+fn compact_element<'de, A, T>(seq: &mut A, i: usize, exp: &dyn de::Expected) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| de::Error::invalid_length(i, exp))
+}
+
+// A header map of `(name, bytes)` pairs.
+struct CompactHeaders<'a>(&'a HeaderMap);
+
+impl<'a> Serialize for CompactHeaders<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for (name, value) in self.0 {
+            seq.serialize_element(&(name, CompactBytes(value.as_bytes())))?;
+        }
+        seq.end()
+    }
+}
+
+struct CompactHeaderMap(HeaderMap);
+
+impl<'de> Deserialize<'de> for CompactHeaderMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<CompactHeaderMap, D::Error> {
+        struct MapVisitor;
+
+        impl<'de> Visitor<'de> for MapVisitor {
+            type Value = CompactHeaderMap;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence of header name and value pairs")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<CompactHeaderMap, A::Error> {
+                let mut map = HeaderMap::new();
+                map.reserve(cautious(seq.size_hint()));
+                while let Some((name, value)) = seq.next_element::<(HeaderName, CompactValue)>()? {
+                    map.append(name, value.0);
+                }
+                Ok(CompactHeaderMap(map))
+            }
+        }
+
+        deserializer.deserialize_seq(MapVisitor)
+    }
+}
+
+struct CompactBytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for CompactBytes<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct CompactValue(HeaderValue);
+
+impl<'de> Deserialize<'de> for CompactValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<CompactValue, D::Error> {
+        deserializer
+            .deserialize_bytes(ValueVisitor)
+            .map(CompactValue)
+    }
+}
+
+// Don't trust size hints of untrusted input with large allocations.
+fn cautious(hint: Option<usize>) -> usize {
+    std::cmp::min(hint.unwrap_or(0), 4096)
+}
+
+// A struct field name, looked up in the field list by the struct visitor.
+struct FieldIndex(String);
+
+impl FieldIndex {
+    fn index(&self, fields: &[&str]) -> Option<usize> {
+        fields.iter().position(|f| *f == self.0)
+    }
+
+    fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldIndex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<FieldIndex, D::Error> {
+        deserializer.deserialize_identifier(FromStrVisitor::new("a field name"))
+    }
+}
+
+impl std::str::FromStr for FieldIndex {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<FieldIndex, Self::Err> {
+        Ok(FieldIndex(s.to_owned()))
+    }
+}
+
+// Deserializes any `FromStr` type from a string.
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    _marker: PhantomData<T>,
+}
+
+impl<T> FromStrVisitor<T> {
+    fn new(expecting: &'static str) -> FromStrVisitor<T> {
+        FromStrVisitor {
+            expecting,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: std::str::FromStr> Visitor<'de> for FromStrVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        v.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_parts_round_trip() {
+        let (parts, ()) = request::Request::builder()
+            .method(Method::POST)
+            .uri("https://example.com/upload?id=1")
+            .header("content-type", "text/plain")
+            .header("x-dup", "a")
+            .header("x-dup", "b")
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        let json = serde_json::to_string(&parts).unwrap();
+        assert_eq!(
+            json,
+            r#"{"method":"POST","uri":"https://example.com/upload?id=1","version":"HTTP/1.1","protocol":"http","headers":[["content-type","text/plain"],["x-dup","a"],["x-dup","b"]]}"#
+        );
+
+        let back: request::Parts = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.method, Method::POST);
+        assert_eq!(back.uri, parts.uri);
+        assert_eq!(back.version, Version::HTTP_11);
+        assert_eq!(back.headers, parts.headers);
+        assert!(back.trailers.is_none());
+    }
+
+    #[test]
+    fn response_parts_round_trip() {
+        let mut parts = response::Parts::new();
+        parts.status = StatusCode::NOT_FOUND;
+        parts.headers.insert("x-bin", HeaderValue::from_bytes(b"\xfa").unwrap());
+        parts.trailers = Some(HeaderMap::new());
+        parts.reason = Some(response::ReasonPhrase::from_static("Gone Fishing"));
+
+        let json = serde_json::to_string(&parts).unwrap();
+        assert_eq!(
+            json,
+            r#"{"status":404,"version":"HTTP/1.1","protocol":"http","headers":[["x-bin",[250]]],"trailers":[],"reason":"Gone Fishing"}"#
+        );
+
+        let back: response::Parts = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.status, StatusCode::NOT_FOUND);
+        assert_eq!(back.headers, parts.headers);
+        assert_eq!(back.trailers, Some(HeaderMap::new()));
+        assert_eq!(back.reason, parts.reason);
+    }
+
+    #[test]
+    fn compact_round_trip() {
+        let (parts, ()) = request::Request::builder()
+            .method(Method::PUT)
+            .uri("/a")
+            .header("x-bin", &b"\xfa"[..])
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        let mut json = Vec::new();
+        serialize_compact_request(&parts, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(
+            json,
+            r#"[2,"PUT","/a","HTTP/1.1","http",[["x-bin",[250]]],null]"#
+        );
+
+        let back =
+            deserialize_compact_request(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+        assert!(back.semantic_eq(&parts));
+
+        let mut parts = response::Parts::new();
+        parts.status = StatusCode::ACCEPTED;
+        parts.trailers = Some(HeaderMap::new());
+        parts.reason = Some(response::ReasonPhrase::from_static("Ok"));
+
+        let mut json = Vec::new();
+        serialize_compact_response(&parts, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(json, r#"[2,202,"HTTP/1.1","http",[],[],[79,107]]"#);
+
+        let back =
+            deserialize_compact_response(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+        assert!(back.semantic_eq(&parts));
+
+        let mut other_layout =
+            serde_json::Deserializer::from_str(r#"[1,202,"HTTP/1.1",[],null,null]"#);
+        assert!(deserialize_compact_response(&mut other_layout).is_err());
+        let mut short = serde_json::Deserializer::from_str(r#"[2,202]"#);
+        assert!(deserialize_compact_response(&mut short).is_err());
+    }
+
+    #[test]
+    fn protocol_round_trip() {
+        let mut parts = request::Parts::new();
+        parts.protocol = Protocol::Other;
+
+        let json = serde_json::to_string(&parts).unwrap();
+        assert!(json.contains(r#""protocol":"other""#), "{}", json);
+        let back: request::Parts = serde_json::from_str(&json).unwrap();
+        assert!(back.semantic_eq(&parts));
+
+        let mut compact = Vec::new();
+        serialize_compact_request(&parts, &mut serde_json::Serializer::new(&mut compact)).unwrap();
+        let compact = String::from_utf8(compact).unwrap();
+        let back =
+            deserialize_compact_request(&mut serde_json::Deserializer::from_str(&compact)).unwrap();
+        assert!(back.semantic_eq(&parts));
+
+        let mut parts = response::Parts::new();
+        parts.protocol = Protocol::Other;
+        let json = serde_json::to_string(&parts).unwrap();
+        let back: response::Parts = serde_json::from_str(&json).unwrap();
+        assert!(back.semantic_eq(&parts));
+
+        // Without a protocol, it is the protocol of the version.
+        let back: response::Parts =
+            serde_json::from_str(r#"{"status":200,"version":"HTTP/1.1","headers":[]}"#).unwrap();
+        assert_eq!(back.protocol, Version::HTTP_11.protocol());
+        assert!(serde_json::from_str::<Protocol>(r#""gopher""#).is_err());
+    }
+
+    #[test]
+    fn parts_reject_invalid() {
+        assert!(serde_json::from_str::<response::Parts>(r#"{"status":99}"#).is_err());
+        assert!(serde_json::from_str::<response::Parts>(
+            r#"{"status":200,"version":"HTTP/1.1","headers":[["bad name","x"]]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<request::Parts>(
+            r#"{"method":"GET","uri":"/","version":"HTTP/1.1","headers":[],"body":1}"#
+        )
+        .is_err());
+    }
+}
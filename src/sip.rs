@@ -0,0 +1,314 @@
+//! SIP transaction identifiers, enabled with the `sip` feature.
+//!
+//! RFC 3261 identifies a transaction by the `branch` parameter of the top
+//! `Via` header, a dialog by the `Call-ID` and the tags of `From` and `To`.
+//! This module generates such identifiers, and extracts the key a received
+//! message is matched to its transaction with, as specified by RFC 3261
+//! sections 17.1.3 and 17.2.3.
+//!
+//! The generated identifiers are random. With the `std` feature, `branch`,
+//! `tag` and `call_id` take the randomness from the keys the standard
+//! library draws from the operating system to protect `HashMap`s, which
+//! other parties can't predict. Without it, or to use another source, the
+//! `_from` functions take random bytes instead.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::sip::{self, TransactionKey};
+//! use httplike::{Method, Request};
+//!
+//! let branch = sip::branch();
+//! assert!(branch.starts_with(sip::MAGIC_COOKIE));
+//!
+//! let request = Request::builder()
+//!     .method(Method::OPTIONS)
+//!     .uri("sip:bob@example.com")
+//!     .header("via", format!("SIP/2.0/UDP pc33.example.com;branch={}", branch))
+//!     .body(())
+//!     .unwrap();
+//!
+//! let key = TransactionKey::from_request(&request).unwrap();
+//! assert_eq!(key.branch(), branch);
+//! assert_eq!(key.sent_by(), "pc33.example.com");
+//! assert_eq!(key.method(), Method::OPTIONS);
+//! ```
+
+use alloc::string::String;
+
+use crate::header::{HeaderMap, CSEQ, VIA};
+use crate::{Method, Request, Response};
+
+/// The prefix of the `branch` parameters of RFC 3261.
+///
+/// A branch starting with it is unique across space and time, so it alone
+/// identifies a transaction. Branches of older implementations don't have
+/// it, and are not supported by `TransactionKey`.
+pub const MAGIC_COOKIE: &str = "z9hG4bK";
+
+/// The key of a SIP transaction.
+///
+/// The key of a request is made of the `branch` and the sent-by of its top
+/// `Via`, and of its method, except for `ACK`, which belongs to the `INVITE`
+/// transaction it acknowledges. The key of a response is made of the same
+/// `Via` fields and of the method of its `CSeq`, so a response has the key
+/// of the request it answers.
+///
+/// Keys compare the host of the sent-by without regard to case.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TransactionKey {
+    branch: String,
+    sent_by: String,
+    method: Method,
+}
+
+impl TransactionKey {
+    /// Returns the key of the transaction `request` belongs to.
+    ///
+    /// Returns `None` if the request has no `Via`, or if the top `Via` has
+    /// no sent-by, or no branch starting with `MAGIC_COOKIE`.
+    pub fn from_request<T>(request: &Request<T>) -> Option<TransactionKey> {
+        let method = if request.method() == "ACK" {
+            Method::from_bytes(b"INVITE").expect("INVITE is a valid method")
+        } else {
+            request.method().clone()
+        };
+        TransactionKey::from_via(request.headers(), method)
+    }
+
+    /// Returns the key of the transaction `response` belongs to.
+    ///
+    /// Returns `None` if the response has no `CSeq` with a method, or if
+    /// its `Via` is not one `from_request` accepts.
+    pub fn from_response<T>(response: &Response<T>) -> Option<TransactionKey> {
+        let cseq = response.headers().get(CSEQ)?.to_str().ok()?;
+        let method = cseq.split_whitespace().nth(1)?;
+        let method = Method::from_bytes(method.as_bytes()).ok()?;
+        TransactionKey::from_via(response.headers(), method)
+    }
+
+    fn from_via(headers: &HeaderMap, method: Method) -> Option<TransactionKey> {
+        let via = top_entry(headers.get(VIA)?.to_str().ok()?);
+        let mut params = via.split(';');
+
+        // The sent-by follows the protocol name, version and transport,
+        // which are separated by slashes.
+        let protocol = params.next()?;
+        let rest = protocol.splitn(3, '/').nth(2)?.trim_start();
+        let transport_end = rest.find(char::is_whitespace)?;
+        let sent_by: String = rest[transport_end..]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if sent_by.is_empty() {
+            return None;
+        }
+
+        let branch = params.find_map(|param| {
+            let mut parts = param.splitn(2, '=');
+            if parts.next()?.trim().eq_ignore_ascii_case("branch") {
+                Some(parts.next()?.trim())
+            } else {
+                None
+            }
+        })?;
+        if !branch.starts_with(MAGIC_COOKIE) {
+            return None;
+        }
+
+        Some(TransactionKey {
+            branch: branch.into(),
+            sent_by: sent_by.to_ascii_lowercase(),
+            method,
+        })
+    }
+
+    /// Returns the branch of the key, with the magic cookie.
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Returns the sent-by of the key, a host and an optional port, in
+    /// lowercase.
+    pub fn sent_by(&self) -> &str {
+        &self.sent_by
+    }
+
+    /// Returns the method of the key.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+}
+
+/// Returns the first entry of a `Via` value listing several hops.
+fn top_entry(value: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted {
+            match c {
+                '\\' => escaped = true,
+                '"' => quoted = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            quoted = true;
+        } else if c == ',' {
+            return &value[..i];
+        }
+    }
+    value
+}
+
+/// Generates a `branch` parameter for a new transaction.
+///
+/// The branch is `MAGIC_COOKIE` followed by 128 random bits in hexadecimal.
+/// The bits come from the standard library and are unique, but not from a
+/// cryptographic generator; use `branch_from` with bytes from one when the
+/// branch must not be predictable.
+#[cfg(feature = "std")]
+pub fn branch() -> String {
+    let mut random = [0; 16];
+    crate::random::fill(&mut random);
+    branch_from(&random)
+}
+
+/// Makes a `branch` parameter from random bytes.
+///
+/// The branch is `MAGIC_COOKIE` followed by the bytes in hexadecimal. RFC
+/// 3261 requires branches to be unique, so at least 8 bytes should be
+/// given.
+pub fn branch_from(random: &[u8]) -> String {
+    let mut s = String::with_capacity(MAGIC_COOKIE.len() + random.len() * 2);
+    s.push_str(MAGIC_COOKIE);
+    push_hex(&mut s, random);
+    s
+}
+
+/// Generates a `tag` parameter for a `From` or `To` header.
+///
+/// The tag is 64 random bits in hexadecimal, more than the 32 bits RFC 3261
+/// section 19.3 requires. Like those of `branch`, the bits are not from a
+/// cryptographic generator; see `tag_from`.
+#[cfg(feature = "std")]
+pub fn tag() -> String {
+    let mut random = [0; 8];
+    crate::random::fill(&mut random);
+    tag_from(&random)
+}
+
+/// Makes a `tag` parameter from random bytes, in hexadecimal.
+pub fn tag_from(random: &[u8]) -> String {
+    let mut s = String::with_capacity(random.len() * 2);
+    push_hex(&mut s, random);
+    s
+}
+
+/// Generates a `Call-ID` for a new dialog or registration.
+///
+/// The identifier is 128 random bits in hexadecimal, followed by `@` and
+/// `host` when one is given, as RFC 3261 section 8.1.1.4 recommends. The
+/// RFC also recommends cryptographically random bits, which these are not,
+/// as with `branch`; use `call_id_from` to provide them.
+#[cfg(feature = "std")]
+pub fn call_id(host: Option<&str>) -> String {
+    let mut random = [0; 16];
+    crate::random::fill(&mut random);
+    call_id_from(&random, host)
+}
+
+/// Makes a `Call-ID` from random bytes, in hexadecimal, followed by `@` and
+/// `host` when one is given.
+pub fn call_id_from(random: &[u8], host: Option<&str>) -> String {
+    let host_len = host.map_or(0, |host| host.len() + 1);
+    let mut s = String::with_capacity(random.len() * 2 + host_len);
+    push_hex(&mut s, random);
+    if let Some(host) = host {
+        s.push('@');
+        s.push_str(host);
+    }
+    s
+}
+
+fn push_hex(s: &mut String, bytes: &[u8]) {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    for &b in bytes {
+        s.push(DIGITS[usize::from(b >> 4)] as char);
+        s.push(DIGITS[usize::from(b & 0xf)] as char);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StatusCode;
+
+    fn request(method: &str, via: &str) -> Request<()> {
+        Request::builder()
+            .method(method)
+            .uri("sip:bob@example.com")
+            .header("via", via)
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(branch_from(&[0x00, 0xab, 0x7f]), "z9hG4bK00ab7f");
+        assert_eq!(tag_from(&[0x1f, 0x20]), "1f20");
+        assert_eq!(call_id_from(&[0xff], Some("example.com")), "ff@example.com");
+        assert_eq!(call_id_from(&[0xff], None), "ff");
+
+        let branch = branch();
+        assert_eq!(branch.len(), 39);
+        assert_ne!(branch, super::branch());
+        assert_eq!(tag().len(), 16);
+        assert_ne!(tag(), tag());
+        assert!(call_id(Some("example.com")).ends_with("@example.com"));
+    }
+
+    #[test]
+    fn request_keys() {
+        let via = "SIP / 2.0 / UDP  PC33.Example.com : 5060 ;received=192.0.2.1;BRANCH=z9hG4bK776asdhds, \
+                   SIP/2.0/TCP proxy.example.com;branch=z9hG4bKother";
+        let invite = TransactionKey::from_request(&request("INVITE", via)).unwrap();
+        assert_eq!(invite.branch(), "z9hG4bK776asdhds");
+        assert_eq!(invite.sent_by(), "pc33.example.com:5060");
+        assert_eq!(invite.method(), "INVITE");
+
+        let ack = TransactionKey::from_request(&request("ACK", via)).unwrap();
+        assert_eq!(ack, invite);
+        let cancel = TransactionKey::from_request(&request("CANCEL", via)).unwrap();
+        assert_ne!(cancel, invite);
+
+        let old = "SIP/2.0/UDP pc33.example.com;branch=776asdhds";
+        assert_eq!(TransactionKey::from_request(&request("INVITE", old)), None);
+        let no_sent_by = "SIP/2.0/UDP;branch=z9hG4bK776asdhds";
+        assert_eq!(
+            TransactionKey::from_request(&request("INVITE", no_sent_by)),
+            None
+        );
+        let no_via = Request::get("sip:bob@example.com").body(()).unwrap();
+        assert_eq!(TransactionKey::from_request(&no_via), None);
+    }
+
+    #[test]
+    fn response_keys() {
+        let via = "SIP/2.0/UDP pc33.example.com;branch=z9hG4bK776asdhds";
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .header("via", via)
+            .header("cseq", "314159 INVITE")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            TransactionKey::from_response(&response),
+            TransactionKey::from_request(&request("INVITE", via))
+        );
+
+        let response = Response::builder().header("via", via).body(()).unwrap();
+        assert_eq!(TransactionKey::from_response(&response), None);
+    }
+}
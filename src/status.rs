@@ -14,10 +14,11 @@
 //! assert!(StatusCode::OK.is_success());
 //! ```
 
-use std::convert::TryFrom;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt;
-use std::str::FromStr;
 
 /// An HTTP status code (`status-code` in RFC 7230 et al.).
 ///
@@ -587,10 +588,11 @@ impl fmt::Debug for InvalidStatusCode {
 
 impl fmt::Display for InvalidStatusCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(self.description())
+        f.write_str("invalid status code")
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for InvalidStatusCode {
     fn description(&self) -> &str {
         "invalid status code"
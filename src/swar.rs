@@ -0,0 +1,140 @@
+//! Byte validation a word at a time.
+//!
+//! Header and URI parsing check every byte against a set of allowed bytes.
+//! The helpers here test eight bytes at once with plain integer arithmetic
+//! ("SIMD within a register"), and only fall back to checking each byte of
+//! a word when the word has a byte the fast test can't accept.
+//!
+//! The masks have the high bit of each byte set when the byte matches, and
+//! are exact for every byte, so they can be combined freely.
+
+const ONES: u64 = 0x0101_0101_0101_0101;
+pub(crate) const HIGH: u64 = 0x8080_8080_8080_8080;
+
+/// Returns whether every byte of `bytes` is accepted, testing whole words
+/// with `word_ok` and the bytes of the other words, and of the tail, with
+/// `byte_ok`.
+///
+/// `word_ok` may reject words that contain only accepted bytes, but must
+/// not accept a word with a byte that `byte_ok` rejects.
+#[inline]
+pub(crate) fn all<W, B>(bytes: &[u8], word_ok: W, byte_ok: B) -> bool
+where
+    W: Fn(u64) -> bool,
+    B: Fn(u8) -> bool,
+{
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+        if !word_ok(read(chunk)) && !chunk.iter().all(|&b| byte_ok(b)) {
+            return false;
+        }
+    }
+    chunks.remainder().iter().all(|&b| byte_ok(b))
+}
+
+/// Returns the length of the longest prefix of `bytes` made of whole words
+/// accepted by `word_ok`.
+///
+/// Scanners use this to jump over the bytes they would only step over, and
+/// continue byte by byte from there.
+#[inline]
+pub(crate) fn skip<W>(bytes: &[u8], word_ok: W) -> usize
+where
+    W: Fn(u64) -> bool,
+{
+    let mut i = 0;
+    while i + 8 <= bytes.len() && word_ok(read(&bytes[i..i + 8])) {
+        i += 8;
+    }
+    i
+}
+
+#[inline]
+fn read(chunk: &[u8]) -> u64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(chunk);
+    u64::from_ne_bytes(buf)
+}
+
+/// Marks the bytes whose low seven bits are at least `n`, for `n <= 0x80`.
+#[inline]
+fn ge7(x: u64, n: u8) -> u64 {
+    // Setting the high bit first keeps the subtraction from borrowing
+    // across bytes.
+    (((x & !HIGH) | HIGH) - ONES * u64::from(n)) & HIGH
+}
+
+/// Marks the bytes less than `n`, for `n <= 0x80`.
+#[inline]
+pub(crate) fn lt(x: u64, n: u8) -> u64 {
+    !(x | ge7(x, n)) & HIGH
+}
+
+/// Marks the bytes between `lo` and `hi` inclusive, for `hi < 0x80`.
+#[inline]
+pub(crate) fn range(x: u64, lo: u8, hi: u8) -> u64 {
+    ge7(x, lo) & !ge7(x, hi + 1) & !x & HIGH
+}
+
+/// Marks the bytes equal to `c`, for `c < 0x80`.
+#[inline]
+pub(crate) fn eq(x: u64, c: u8) -> u64 {
+    range(x, c, c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn lanes(mask: u64) -> [bool; 8] {
+        let mut out = [false; 8];
+        for (i, lane) in mask.to_ne_bytes().iter().enumerate() {
+            assert!(*lane == 0 || *lane == 0x80);
+            out[i] = *lane == 0x80;
+        }
+        out
+    }
+
+    #[test]
+    fn masks_are_exact() {
+        for b in 0..=255u8 {
+            // Surround the byte with bytes that could borrow or carry into it.
+            for &fill in &[0x00, 0x7f, 0x80, 0xff] {
+                let mut bytes = [fill; 8];
+                bytes[3] = b;
+                let x = read(&bytes);
+
+                assert_eq!(lanes(lt(x, 0x20))[3], b < 0x20, "{:#x}", b);
+                assert_eq!(lanes(lt(x, 0x80))[3], b < 0x80, "{:#x}", b);
+                assert_eq!(lanes(eq(x, 0x7f))[3], b == 0x7f, "{:#x}", b);
+                assert_eq!(lanes(eq(x, 0))[3], b == 0, "{:#x}", b);
+                assert_eq!(
+                    lanes(range(x, b'a', b'z'))[3],
+                    (b'a'..=b'z').contains(&b),
+                    "{:#x}",
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn all_words_and_tail() {
+        let is_digit = |b: u8| b.is_ascii_digit();
+        let digits = |x: u64| range(x, b'0', b'9') == HIGH;
+
+        for len in 0..40 {
+            let mut bytes = vec![b'5'; len];
+            assert!(all(&bytes, digits, is_digit));
+            assert_eq!(skip(&bytes, digits), len - len % 8);
+
+            for i in 0..len {
+                bytes[i] = b'x';
+                assert!(!all(&bytes, digits, is_digit));
+                assert_eq!(skip(&bytes, digits), i - i % 8);
+                bytes[i] = b'5';
+            }
+        }
+    }
+}
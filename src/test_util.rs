@@ -0,0 +1,279 @@
+//! Helpers for testing code that uses this crate, enabled with the
+//! `test-util` feature.
+//!
+//! This module contains fixtures for common requests and responses, the
+//! [`assert_header!`] and [`assert_no_header!`] macros, and a golden
+//! rendering of message heads for snapshot tests.
+//!
+//! The rendering is line based and meant to be stable, so it can be stored
+//! next to the tests and compared with `assert_eq!`. It is not the wire
+//! format: lines end with `\n`, and bytes outside of visible ASCII are
+//! escaped.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::{assert_header, Method, StatusCode};
+//! use httplike::test_util;
+//!
+//! let request = test_util::http_request(Method::GET, "http://example.com/index.html")
+//!     .header("accept", "text/html")
+//!     .body(())
+//!     .unwrap();
+//!
+//! assert_header!(request, "host", "example.com");
+//! assert_eq!(
+//!     test_util::render_request(&request),
+//!     "GET http://example.com/index.html HTTP/1.1\n\
+//!      host: example.com\n\
+//!      accept: text/html\n"
+//! );
+//!
+//! let response = test_util::text_response(StatusCode::NOT_FOUND, "nothing here");
+//! assert_eq!(
+//!     test_util::render_response(&response),
+//!     "HTTP/1.1 404 Not Found\n\
+//!      content-type: text/plain; charset=utf-8\n\
+//!      content-length: 12\n"
+//! );
+//! ```
+//!
+//! [`assert_header!`]: ../macro.assert_header.html
+//! [`assert_no_header!`]: ../macro.assert_no_header.html
+
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use crate::header::{self, HeaderMap, HeaderValue};
+use crate::{request, Method, Request, Response, StatusCode, Uri};
+
+/// Asserts that a request or response has a header with the given value.
+///
+/// The first argument is anything with a `headers()` method returning a
+/// `HeaderMap`, the name is anything that converts to a `HeaderName`, and
+/// the value anything a `HeaderValue` can be compared to. Only the first
+/// value stored under the name is compared.
+///
+/// # Panics
+///
+/// Panics if the header is missing or has a different value.
+///
+/// # Examples
+///
+/// ```
+/// use httplike::{assert_header, Response};
+/// use httplike::header::CONTENT_TYPE;
+///
+/// let response = Response::builder()
+///     .header(CONTENT_TYPE, "application/json")
+///     .body(())
+///     .unwrap();
+///
+/// assert_header!(response, CONTENT_TYPE, "application/json");
+/// assert_header!(response, "content-type", "application/json");
+/// ```
+#[macro_export]
+macro_rules! assert_header {
+    ($message:expr, $name:expr, $value:expr) => {{
+        let name: $crate::header::HeaderName =
+            ::std::convert::TryFrom::try_from($name).expect("invalid header name");
+        match $message.headers().get(&name) {
+            Some(actual) => assert!(
+                actual == $value,
+                "header {:?} is {:?}, expected {:?}",
+                name,
+                actual,
+                $value
+            ),
+            None => panic!("header {:?} is missing, expected {:?}", name, $value),
+        }
+    }};
+}
+
+/// Asserts that a request or response doesn't have a header.
+///
+/// # Panics
+///
+/// Panics if the header is present.
+///
+/// # Examples
+///
+/// ```
+/// use httplike::{assert_no_header, Response};
+///
+/// let response = Response::new(());
+/// assert_no_header!(response, "content-type");
+/// ```
+#[macro_export]
+macro_rules! assert_no_header {
+    ($message:expr, $name:expr) => {{
+        let name: $crate::header::HeaderName =
+            ::std::convert::TryFrom::try_from($name).expect("invalid header name");
+        if let Some(actual) = $message.headers().get(&name) {
+            panic!("header {:?} is {:?}, expected it to be missing", name, actual);
+        }
+    }};
+}
+
+/// Returns a builder for an HTTP/1.1 request with a `Host` header.
+///
+/// The host is taken from the authority of the URI. URIs without an
+/// authority, like `/index.html`, get no `Host` header.
+#[cfg(feature = "http")]
+pub fn http_request<T>(method: Method, uri: T) -> request::Builder
+where
+    Uri: TryFrom<T>,
+    <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+{
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .version(crate::Version::HTTP_11);
+
+    let host = builder
+        .uri_ref()
+        .and_then(Uri::authority)
+        .map(|authority| authority.as_str().to_owned());
+
+    match host {
+        Some(host) => builder.header(header::HOST, host),
+        None => builder,
+    }
+}
+
+/// Returns a builder for an RTSP/1.0 request with a `CSeq` header.
+#[cfg(feature = "rtsp")]
+pub fn rtsp_request<T>(method: Method, uri: T, cseq: u32) -> request::Builder
+where
+    Uri: TryFrom<T>,
+    <Uri as TryFrom<T>>::Error: Into<crate::Error>,
+{
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .version(crate::Version::RTSP_1)
+        .header(header::CSEQ, cseq)
+}
+
+/// Returns a response with a `text/plain` body and a `Content-Length`.
+pub fn text_response(status: StatusCode, body: &str) -> Response<String> {
+    with_body(status, "text/plain; charset=utf-8", body)
+}
+
+/// Returns a response with an `application/json` body and a
+/// `Content-Length`.
+///
+/// The body is used as is, it is not checked to be valid JSON.
+pub fn json_response(status: StatusCode, body: &str) -> Response<String> {
+    with_body(status, "application/json", body)
+}
+
+fn with_body(status: StatusCode, content_type: &'static str, body: &str) -> Response<String> {
+    let mut response = Response::with_status(status, body.to_owned());
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+    response
+}
+
+/// Renders the head of a request for snapshot tests.
+///
+/// The request line is followed by one line per header value, in the
+/// order of the header map. If the request has trailers, they follow after
+/// an empty line. Extensions and the body are not rendered.
+pub fn render_request<T>(request: &Request<T>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{} {} {:?}",
+        request.method(),
+        request.uri(),
+        request.version()
+    );
+    render_headers(&mut out, request.headers(), request.trailers());
+    out
+}
+
+/// Renders the head of a response for snapshot tests.
+///
+/// The status line uses `Response::reason`, so a reason phrase received
+/// with the response is rendered instead of the canonical one. The rest is
+/// rendered like `render_request`.
+pub fn render_response<T>(response: &Response<T>) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "{:?} {}", response.version(), response.status().as_str());
+    if let Some(reason) = response.reason() {
+        let _ = write!(out, " {}", reason);
+    }
+    out.push('\n');
+    render_headers(&mut out, response.headers(), response.trailers());
+    out
+}
+
+fn render_headers(out: &mut String, headers: &HeaderMap, trailers: Option<&HeaderMap>) {
+    render_fields(out, headers);
+    if let Some(trailers) = trailers {
+        out.push('\n');
+        render_fields(out, trailers);
+    }
+}
+
+fn render_fields(out: &mut String, headers: &HeaderMap) {
+    for (name, value) in headers {
+        out.push_str(name.as_str());
+        out.push_str(": ");
+        for &b in value.as_bytes() {
+            if b == b'\\' {
+                out.push_str("\\\\");
+            } else if b == b' ' || b.is_ascii_graphic() {
+                out.push(b as char);
+            } else {
+                let _ = write!(out, "\\x{:02x}", b);
+            }
+        }
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_escapes_and_trailers() {
+        let mut response = Response::builder()
+            .status(299)
+            .header("x-bin", &b"caf\xc3\xa9\t\\"[..])
+            .body(())
+            .unwrap();
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-checksum", HeaderValue::from_static("abc"));
+        *response.trailers_mut() = Some(trailers);
+
+        assert_eq!(
+            render_response(&response),
+            "HTTP/1.1 299\n\
+             x-bin: caf\\xc3\\xa9\\x09\\\\\n\
+             \n\
+             x-checksum: abc\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "header \"content-type\" is missing")]
+    fn assert_header_missing() {
+        assert_header!(Response::new(()), "content-type", "text/plain");
+    }
+
+    #[test]
+    #[cfg(feature = "rtsp")]
+    fn rtsp_fixture() {
+        let request = rtsp_request(Method::OPTIONS, "rtsp://example.com/", 3)
+            .body(())
+            .unwrap();
+
+        assert_header!(request, header::CSEQ, "3");
+        assert_no_header!(request, header::HOST);
+        assert_eq!(request.version(), crate::Version::RTSP_1);
+    }
+}
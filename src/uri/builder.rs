@@ -1,4 +1,4 @@
-use std::convert::{TryFrom, TryInto};
+use core::convert::{TryFrom, TryInto};
 
 use super::{Authority, Parts, PathAndQuery, Scheme};
 use crate::Uri;
@@ -152,3 +152,26 @@ impl Default for Builder {
         }
     }
 }
+
+/// Starts from the parts of an existing `Uri`, to change some of them.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::*;
+/// let uri: Uri = "rtsp://camera.local:554/live?profile=1".parse().unwrap();
+///
+/// let uri = uri::Builder::from(uri)
+///     .path_and_query("/playback")
+///     .build()
+///     .unwrap();
+/// assert_eq!(uri, "rtsp://camera.local:554/playback");
+/// ```
+impl From<Uri> for Builder {
+    #[inline]
+    fn from(uri: Uri) -> Builder {
+        Builder {
+            parts: Ok(uri.into()),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// The host of an authority, classified.
+///
+/// IPv6 literals are returned without their brackets and zone identifier;
+/// the zone is returned by `Authority::zone_id`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Host<'a> {
+    /// An IPv4 address, like `192.168.0.1`.
+    Ipv4(Ipv4Addr),
+    /// An IPv6 address, like `[::1]`.
+    Ipv6(Ipv6Addr),
+    /// A registered name, like `example.com`, as it is in the URI.
+    ///
+    /// A bracketed literal that isn't an IPv6 address, like an `IPvFuture`
+    /// one, is returned here with its brackets.
+    RegName(&'a str),
+}
+
+impl<'a> Host<'a> {
+    pub(super) fn parse(host: &'a str) -> Host<'a> {
+        if host.starts_with('[') && host.ends_with(']') {
+            let literal = &host[1..host.len() - 1];
+            let ip = &literal[..literal.find('%').unwrap_or(literal.len())];
+            if let Ok(ip) = ip.parse() {
+                return Host::Ipv6(ip);
+            }
+        } else if let Ok(ip) = host.parse() {
+            return Host::Ipv4(ip);
+        }
+        Host::RegName(host)
+    }
+
+    /// Returns the IP address, if this isn't a registered name.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match *self {
+            Host::Ipv4(ip) => Some(IpAddr::V4(ip)),
+            Host::Ipv6(ip) => Some(IpAddr::V6(ip)),
+            Host::RegName(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify() {
+        assert_eq!(
+            Host::parse("10.0.0.1"),
+            Host::Ipv4(Ipv4Addr::new(10, 0, 0, 1))
+        );
+        assert_eq!(Host::parse("[::1]"), Host::Ipv6(Ipv6Addr::LOCALHOST));
+        assert_eq!(
+            Host::parse("[fe80::1%25eth0]").ip(),
+            Some("fe80::1".parse().unwrap())
+        );
+        assert_eq!(Host::parse("cam.local"), Host::RegName("cam.local"));
+        assert_eq!(Host::parse("10.0.0.256"), Host::RegName("10.0.0.256"));
+        assert_eq!(Host::parse("[v1.x]"), Host::RegName("[v1.x]"));
+        assert_eq!(Host::parse("[1.2.3.4]"), Host::RegName("[1.2.3.4]"));
+    }
+}
@@ -0,0 +1,259 @@
+//! Punycode, RFC 3492, and the conversion of hostnames between their
+//! Unicode and ASCII forms, RFC 5891.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use super::{Authority, ErrorKind, InvalidUri};
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+const PREFIX: &str = "xn--";
+
+/// Parses an authority with a Unicode host, converting the host to its
+/// ASCII form.
+pub(super) fn to_ascii(src: &str) -> Result<Authority, InvalidUri> {
+    let start = src.rfind('@').map_or(0, |i| i + 1);
+    let end = if src[start..].starts_with('[') {
+        src.len()
+    } else {
+        src[start..].rfind(':').map_or(src.len(), |i| start + i)
+    };
+    let (prefix, host, suffix) = (&src[..start], &src[start..end], &src[end..]);
+
+    let mut s = String::with_capacity(src.len() + 8);
+    s.push_str(prefix);
+    for (i, label) in host.split('.').enumerate() {
+        if i > 0 {
+            s.push('.');
+        }
+        let start = s.len();
+        if label.is_ascii() {
+            s.push_str(label);
+            s[start..].make_ascii_lowercase();
+        } else {
+            let chars: Vec<char> = label.chars().flat_map(char::to_lowercase).collect();
+            s.push_str(PREFIX);
+            encode(&chars, &mut s).ok_or(ErrorKind::InvalidAuthority)?;
+        }
+        if s.len() - start > 63 {
+            return Err(ErrorKind::InvalidAuthority.into());
+        }
+    }
+    s.push_str(suffix);
+    Authority::try_from(s.as_str())
+}
+
+/// Converts the host of `authority` to its Unicode form.
+pub(super) fn to_unicode(authority: &Authority) -> String {
+    let s = authority.as_str();
+    let start = s.rfind('@').map_or(0, |i| i + 1);
+    let end = start + authority.host().len();
+    let (prefix, host, suffix) = (&s[..start], &s[start..end], &s[end..]);
+
+    let mut s = String::with_capacity(authority.as_str().len());
+    s.push_str(prefix);
+    for (i, label) in host.split('.').enumerate() {
+        if i > 0 {
+            s.push('.');
+        }
+        let decoded =
+            if label.len() > PREFIX.len() && label[..PREFIX.len()].eq_ignore_ascii_case(PREFIX) {
+                decode(&label[PREFIX.len()..])
+            } else {
+                None
+            };
+        match decoded {
+            Some(decoded) => s.extend(decoded),
+            None => s.push_str(label),
+        }
+    }
+    s.push_str(suffix);
+    s
+}
+
+fn adapt(mut delta: u32, points: u32, first: bool) -> u32 {
+    delta /= if first { DAMP } else { 2 };
+    delta += delta / points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn encode_digit(d: u32) -> char {
+    let d = d as u8;
+    (if d < 26 { b'a' + d } else { b'0' + d - 26 }) as char
+}
+
+fn decode_digit(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 26),
+        _ => None,
+    }
+}
+
+/// Appends the Punycode encoding of `input` to `dst`, or returns `None` on
+/// overflow.
+fn encode(input: &[char], dst: &mut String) -> Option<()> {
+    let basic = input.iter().filter(|c| c.is_ascii()).count() as u32;
+    dst.extend(input.iter().filter(|c| c.is_ascii()));
+    if basic > 0 {
+        dst.push('-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut handled = basic;
+    while (handled as usize) < input.len() {
+        let m = input.iter().map(|&c| c as u32).filter(|&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in input {
+            let c = c as u32;
+            if c < n {
+                delta = delta.checked_add(1)?;
+            } else if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    dst.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                dst.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+    Some(())
+}
+
+/// Decodes a Punycode string, or returns `None` if it is invalid.
+fn decode(input: &str) -> Option<Vec<char>> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+    let mut output: Vec<char> = basic.chars().collect();
+
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.chars();
+    while digits.as_str() != "" {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = decode_digit(digits.next()?)?;
+            i = i.checked_add(digit.checked_mul(w)?)?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+        let len = output.len() as u32 + 1;
+        bias = adapt(i - old_i, len, old_i == 0);
+        n = n.checked_add(i / len)?;
+        i %= len;
+        let c = core::char::from_u32(n)?;
+        if c.is_ascii() {
+            return None;
+        }
+        output.insert(i as usize, c);
+        i += 1;
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn punycode(s: &str) -> String {
+        let chars: Vec<char> = s.chars().collect();
+        let mut dst = String::new();
+        encode(&chars, &mut dst).unwrap();
+        assert_eq!(decode(&dst).unwrap(), chars);
+        dst
+    }
+
+    #[test]
+    fn rfc3492_samples() {
+        assert_eq!(punycode("bücher"), "bcher-kva");
+        assert_eq!(punycode("ü"), "tda");
+        assert_eq!(punycode("3年B組金八先生"), "3B-ww4c5e180e575a65lsy2b");
+        assert_eq!(
+            punycode("\u{644}\u{64a}\u{647}\u{645}\u{627}\u{628}\u{62a}\u{643}\u{644}\u{645}\u{648}\u{634}\u{639}\u{631}\u{628}\u{64a}\u{61f}"),
+            "egbpdaj6bu4bxfgehfvwxn"
+        );
+        assert_eq!(punycode("abc"), "abc-");
+    }
+
+    #[test]
+    fn invalid_punycode() {
+        assert_eq!(decode("99999999999"), None);
+        assert_eq!(decode("bcher-kv!"), None);
+        assert_eq!(decode("bcher-kv"), None);
+        assert_eq!(decode("ab-"), Some("ab".chars().collect()));
+    }
+
+    #[test]
+    fn authorities() {
+        assert_eq!(to_ascii("Bücher.DE").unwrap(), "xn--bcher-kva.de");
+        assert_eq!(
+            to_ascii("u@bücher.de:8080").unwrap().as_str(),
+            "u@xn--bcher-kva.de:8080"
+        );
+        assert_eq!(to_ascii("[::1]:80").unwrap().as_str(), "[::1]:80");
+        assert_eq!(to_ascii("a..b").unwrap(), "a..b");
+        assert!(to_ascii("ü@bücher.de").is_err());
+        assert!(to_ascii("a/b.de").is_err());
+        assert!(to_ascii(&"ü".repeat(60)).is_err());
+        assert!(to_ascii("").is_err());
+
+        let unicode = |s: &'static str| to_unicode(&Authority::from_static(s));
+        assert_eq!(unicode("u@XN--bcher-kva.de:80"), "u@bücher.de:80");
+        assert_eq!(unicode("[::1]:80"), "[::1]:80");
+        assert_eq!(
+            unicode("xn--.xn--99999999999.de"),
+            "xn--.xn--99999999999.de"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use alloc::string::String;
+
+use bytes::Bytes;
+
+use super::percent;
+use super::resolve::remove_dot_segments;
+use super::{Authority, Uri};
+
+/// Returns the syntax-based normalization of `uri`, RFC 3986 section 6.2.2,
+/// with the default port removed as in section 6.2.3.
+pub(super) fn normalize(uri: &Uri) -> Uri {
+    let mut s = String::with_capacity(uri.display_len());
+
+    if let Some(scheme) = uri.scheme() {
+        s.push_str(scheme.as_str());
+        s.make_ascii_lowercase();
+        s.push_str(scheme.delimiter());
+    }
+
+    if let Some(authority) = uri.authority() {
+        if let Some(userinfo) = authority.userinfo() {
+            percent::normalize(userinfo.as_str(), &mut s);
+            s.push('@');
+        }
+        let start = s.len();
+        percent::normalize(authority.host(), &mut s);
+        s[start..].make_ascii_lowercase();
+
+        let default = uri.scheme().and_then(|scheme| scheme.default_port());
+        if authority.host().is_empty() {
+            // An authority of only `:` and a port is kept as it is, as
+            // dropping the port would leave it empty.
+            s.push_str(host_and_port(authority));
+        } else if let Some(port) = authority.port() {
+            if Some(port.as_u16()) != default {
+                s.push(':');
+                s.push_str(port.as_str());
+            }
+        }
+    }
+
+    let path = uri.path();
+    if uri.is_opaque() {
+        percent::normalize(path, &mut s);
+    } else if path == "*" {
+        s.push('*');
+    } else if !path.is_empty() {
+        let mut encoded = String::with_capacity(path.len());
+        percent::normalize(path, &mut encoded);
+        let start = s.len();
+        remove_dot_segments(&encoded, &mut s);
+        if uri.authority().is_none() && s[start..].starts_with("//") {
+            // `//` would start an authority; `/.` keeps the path a path.
+            s.insert_str(start, "/.");
+        } else if s.len() == start {
+            s.push('/');
+        }
+    }
+
+    if let Some(query) = uri.query() {
+        s.push('?');
+        percent::normalize(query, &mut s);
+    }
+
+    if let Some(fragment) = uri.fragment() {
+        s.push('#');
+        percent::normalize(fragment, &mut s);
+    }
+
+    // The `/` or `/.` added to the path can take a URI at the length limit
+    // past it; such a URI is returned as it is.
+    Uri::from_maybe_shared(Bytes::from(s)).unwrap_or_else(|_| uri.clone())
+}
+
+/// Returns true if `a` and `b` are equal once normalized, allocating only
+/// if a path has dot segments.
+pub(super) fn eq_normalized(a: &Uri, b: &Uri) -> bool {
+    if a.scheme() != b.scheme() {
+        return false;
+    }
+
+    match (a.authority(), b.authority()) {
+        (Some(x), Some(y)) => {
+            let userinfo = match (x.userinfo(), y.userinfo()) {
+                (Some(u), Some(v)) => eq_bytes(u.as_str(), v.as_str(), false),
+                (None, None) => true,
+                _ => false,
+            };
+            if !userinfo || !eq_bytes(x.host(), y.host(), true) {
+                return false;
+            }
+            let default = a.scheme().and_then(|scheme| scheme.default_port());
+            let port = |authority: &Authority| authority.port_u16().or(default);
+            if x.host().is_empty() {
+                if host_and_port(x) != host_and_port(y) {
+                    return false;
+                }
+            } else if port(x) != port(y) {
+                return false;
+            }
+        }
+        (None, None) => {}
+        _ => return false,
+    }
+
+    let (x, y) = (a.path(), b.path());
+    if !a.is_opaque() && (has_dot_segments(x) || has_dot_segments(y)) {
+        let (x, y) = (normalize(a), normalize(b));
+        if x.path() != y.path() {
+            return false;
+        }
+    } else if !eq_bytes(x, y, false) {
+        return false;
+    }
+
+    match (a.query(), b.query()) {
+        (Some(x), Some(y)) => eq_bytes(x, y, false),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn host_and_port(authority: &Authority) -> &str {
+    let s = authority.as_str();
+    &s[s.rfind('@').map_or(0, |i| i + 1)..]
+}
+
+fn eq_bytes(a: &str, b: &str, lowercase: bool) -> bool {
+    percent::normalized_bytes(a, lowercase).eq(percent::normalized_bytes(b, lowercase))
+}
+
+fn has_dot_segments(path: &str) -> bool {
+    // Encoded dots are unreserved, so they count too.
+    path.split('/').any(|segment| {
+        let mut dots = percent::normalized_bytes(segment, false);
+        match (dots.next(), dots.next(), dots.next()) {
+            (Some(b'.'), None, _) | (Some(b'.'), Some(b'.'), None) => true,
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(s: &str) -> String {
+        normalize(&s.parse().unwrap()).to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn http() {
+        assert_eq!(
+            normalized("HTTP://User@Example.COM:80/a/./b/../%7euser/%2f?q=%3d%41"),
+            "http://User@example.com/a/~user/%2F?q=%3DA"
+        );
+        assert_eq!(
+            normalized("https://example.com:443"),
+            "https://example.com/"
+        );
+        assert_eq!(
+            normalized("https://example.com:8443/"),
+            "https://example.com:8443/"
+        );
+        assert_eq!(normalized("http://[FE80::1]/"), "http://[fe80::1]/");
+
+        assert_eq!(normalized("http://:80/x"), "http://:80/x");
+        assert_eq!(normalized("http://:/x"), "http://:/x");
+        assert_eq!(normalized("http://u@:80/"), "http://u@:80/");
+        assert_eq!(normalized("http://u:p@:/./a"), "http://u:p@:/a");
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn equivalence() {
+        let eq = |a: &str, b: &str| eq_normalized(&a.parse().unwrap(), &b.parse().unwrap());
+        assert!(eq(
+            "HTTP://Example.COM:80/%7Ea?q=%2f",
+            "http://example.com/~a?q=%2F"
+        ));
+        assert!(eq("http://example.com", "http://example.com/"));
+        assert!(eq(
+            "http://example.com/a/./b/../c",
+            "http://example.com/a/c"
+        ));
+        assert!(eq("http://example.com/%2E%2E/a", "http://example.com/a"));
+        assert!(eq("https://u@h:443", "https://u@H"));
+        assert!(!eq("http://example.com/A", "http://example.com/a"));
+        assert!(!eq("http://u@h/", "http://h/"));
+        assert!(!eq("http://@h/", "http://h/"));
+        assert!(!eq("http://h:8080/", "http://h/"));
+        assert!(!eq("http://h/?", "http://h/"));
+        assert!(!eq("http://h/%2F", "http://h//"));
+        assert!(!eq("https://h/", "http://h/"));
+        assert!(eq("http://:80/a/../x", "http://:80/x"));
+        assert!(!eq("http://:/./x", "http://:80/x"));
+    }
+
+    #[test]
+    fn relative() {
+        assert_eq!(normalized("/a/../../b"), "/b");
+        assert_eq!(normalized("/.//b"), "/.//b");
+        assert_eq!(normalized("/a/.."), "/");
+        assert_eq!(normalized("*"), "*");
+        assert_eq!(normalized("Example.com:443"), "example.com:443");
+    }
+
+    #[test]
+    #[cfg(feature = "rtsp")]
+    fn empty_host() {
+        assert_eq!(normalized("rtsp://:/s"), "rtsp://:/s");
+        assert_eq!(normalized("rtsp://:554/./s"), "rtsp://:554/s");
+    }
+}
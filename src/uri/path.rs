@@ -1,23 +1,67 @@
-use std::convert::TryFrom;
-use std::str::FromStr;
-use std::{cmp, fmt, str};
+use core::convert::TryFrom;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::str::FromStr;
+use core::{cmp, fmt, str};
 
 use bytes::Bytes;
 
-use super::{ErrorKind, InvalidUri};
+use super::percent;
+use super::{InvalidPercentEncoding, InvalidUri, Segments};
 use crate::byte_str::ByteStr;
+use crate::swar;
+
+// The bytes `from_shared` accepts in the path, other than `?` and `#`.
+#[inline]
+fn is_path_word(x: u64) -> bool {
+    let ok = swar::eq(x, 0x21)
+        | swar::range(x, 0x24, 0x3B)
+        | swar::eq(x, 0x3D)
+        | swar::range(x, 0x40, 0x5F)
+        | swar::range(x, 0x61, 0x7A)
+        | swar::eq(x, 0x7C)
+        | swar::eq(x, 0x7E);
+    ok == swar::HIGH
+}
+
+// The bytes `from_shared` accepts in the query, other than `#`.
+#[inline]
+fn is_query_word(x: u64) -> bool {
+    let ok = swar::eq(x, 0x21)
+        | swar::range(x, 0x24, 0x3B)
+        | swar::eq(x, 0x3D)
+        | swar::range(x, 0x3F, 0x7E);
+    ok == swar::HIGH
+}
+
+// The bytes allowed in a fragment, the same as in a query.
+fn is_fragment_byte(b: u8) -> bool {
+    match b {
+        0x21 | 0x24..=0x3B | 0x3D | 0x3F..=0x7E => true,
+        _ => false,
+    }
+}
+
+// Copies a fragment, without the `#`, checking its bytes.
+pub(super) fn parse_fragment(fragment: &str) -> Result<ByteStr, InvalidUri> {
+    match fragment.bytes().position(|b| !is_fragment_byte(b)) {
+        Some(i) => Err(InvalidUri::char_at(i)),
+        None => Ok(ByteStr::from(fragment)),
+    }
+}
 
 /// Represents the path component of a URI
 #[derive(Clone)]
 pub struct PathAndQuery {
     pub(super) data: ByteStr,
     pub(super) query: u16,
+    // Kept apart from `data`, so it's never sent in a request line.
+    pub(super) fragment: Option<ByteStr>,
 }
 
-const NONE: u16 = ::std::u16::MAX;
+const NONE: u16 = ::core::u16::MAX;
 
 impl PathAndQuery {
-    // Not public while `bytes` is unstable.
     pub(super) fn from_shared(mut src: Bytes) -> Result<Self, InvalidUri> {
         let mut query = NONE;
         let mut fragment = None;
@@ -28,7 +72,8 @@ impl PathAndQuery {
         // because of minimum Rust version
         #[allow(warnings)]
         {
-            let mut iter = src.as_ref().iter().enumerate();
+            let bytes = src.as_ref();
+            let mut iter = bytes.iter().enumerate().skip(swar::skip(bytes, is_path_word));
 
             // path ...
             for (i, &b) in &mut iter {
@@ -55,7 +100,7 @@ impl PathAndQuery {
                     0x7C |
                     0x7E => {},
 
-                    _ => return Err(ErrorKind::InvalidUriChar.into()),
+                    _ => return Err(InvalidUri::char_at(i)),
                 }
             }
 
@@ -64,8 +109,9 @@ impl PathAndQuery {
 
                 // allow: `...` pattersn are now `..=`, but we cannot update yet
                 // because of minimum Rust version
+                let rest = &bytes[query as usize + 1..];
                 #[allow(warnings)]
-                for (i, &b) in iter {
+                for (i, &b) in iter.skip(swar::skip(rest, is_query_word)) {
                     match b {
                         // While queries *should* be percent-encoded, most
                         // bytes are actually allowed...
@@ -82,19 +128,28 @@ impl PathAndQuery {
                             break;
                         }
 
-                        _ => return Err(ErrorKind::InvalidUriChar.into()),
+                        _ => return Err(InvalidUri::char_at(i)),
                     }
                 }
             }
         }
 
-        if let Some(i) = fragment {
+        // A fragment with bytes that aren't allowed in it is dropped rather
+        // than rejected, so such URIs still parse.
+        let fragment = fragment.and_then(|i| {
+            let fragment = src.split_off(i + 1);
             src.truncate(i);
-        }
+            if fragment.iter().all(|&b| is_fragment_byte(b)) {
+                Some(unsafe { ByteStr::from_utf8_unchecked(fragment) })
+            } else {
+                None
+            }
+        });
 
         Ok(PathAndQuery {
             data: unsafe { ByteStr::from_utf8_unchecked(src) },
             query: query,
+            fragment,
         })
     }
 
@@ -142,6 +197,7 @@ impl PathAndQuery {
         PathAndQuery {
             data: ByteStr::new(),
             query: NONE,
+            fragment: None,
         }
     }
 
@@ -149,6 +205,7 @@ impl PathAndQuery {
         PathAndQuery {
             data: ByteStr::from_static("/"),
             query: NONE,
+            fragment: None,
         }
     }
 
@@ -156,6 +213,7 @@ impl PathAndQuery {
         PathAndQuery {
             data: ByteStr::from_static("*"),
             query: NONE,
+            fragment: None,
         }
     }
 
@@ -183,11 +241,7 @@ impl PathAndQuery {
     /// ```
     #[inline]
     pub fn path(&self) -> &str {
-        let ret = if self.query == NONE {
-            &self.data[..]
-        } else {
-            &self.data[..self.query as usize]
-        };
+        let ret = self.raw_path();
 
         if ret.is_empty() {
             return "/";
@@ -196,6 +250,15 @@ impl PathAndQuery {
         ret
     }
 
+    // The path, without turning an empty one into `/`.
+    pub(super) fn raw_path(&self) -> &str {
+        if self.query == NONE {
+            &self.data[..]
+        } else {
+            &self.data[..self.query as usize]
+        }
+    }
+
     /// Returns the query string component
     ///
     /// The query component contains non-hierarchical data that, along with data
@@ -240,6 +303,112 @@ impl PathAndQuery {
         }
     }
 
+    /// Returns the path with its `%XX` sequences decoded.
+    ///
+    /// The path is borrowed if there is nothing to decode. Note that an
+    /// encoded `%2F` decodes to a `/` like any other, so the decoded path
+    /// can have more segments than the path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a `%` isn't followed by two hex digits, or if the
+    /// decoded path isn't UTF-8. See `decoded_path_lossy` to decode it
+    /// anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::PathAndQuery;
+    /// let path_and_query: PathAndQuery = "/my%20files/caf%C3%A9".parse().unwrap();
+    /// assert_eq!(path_and_query.decoded_path().unwrap(), "/my files/café");
+    ///
+    /// let path_and_query: PathAndQuery = "/100%".parse().unwrap();
+    /// assert!(path_and_query.decoded_path().is_err());
+    /// ```
+    pub fn decoded_path(&self) -> Result<Cow<'_, str>, InvalidPercentEncoding> {
+        percent::decode(self.path(), false)
+    }
+
+    /// Returns the path with its `%XX` sequences decoded, keeping malformed
+    /// sequences as they are and replacing invalid UTF-8 with U+FFFD.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::PathAndQuery;
+    /// let path_and_query: PathAndQuery = "/100%25/100%".parse().unwrap();
+    /// assert_eq!(path_and_query.decoded_path_lossy(), "/100%/100%");
+    /// ```
+    pub fn decoded_path_lossy(&self) -> Cow<'_, str> {
+        percent::decode_lossy(self.path(), false)
+    }
+
+    /// Returns the query with its `%XX` sequences decoded, if there is a
+    /// query.
+    ///
+    /// A `+` is kept as it is; it only means a space in form data, see
+    /// `Uri::query_pairs`. The errors are those of `decoded_path`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::PathAndQuery;
+    /// let path_and_query: PathAndQuery = "/search?q=a%20b+c".parse().unwrap();
+    /// assert_eq!(path_and_query.decoded_query().unwrap().unwrap(), "q=a b+c");
+    /// ```
+    pub fn decoded_query(&self) -> Option<Result<Cow<'_, str>, InvalidPercentEncoding>> {
+        self.query().map(|query| percent::decode(query, false))
+    }
+
+    /// Returns the query with its `%XX` sequences decoded, like
+    /// `decoded_path_lossy` does for the path.
+    pub fn decoded_query_lossy(&self) -> Option<Cow<'_, str>> {
+        self.query().map(|query| percent::decode_lossy(query, false))
+    }
+
+    pub(super) fn fragment(&self) -> Option<&str> {
+        self.fragment.as_ref().map(|fragment| &fragment[..])
+    }
+
+    /// Returns an iterator over the segments of the path, with their `%XX`
+    /// sequences decoded like `decoded_path_lossy` does.
+    ///
+    /// The leading `/` is dropped and the rest of the path is split on each
+    /// `/`, so empty segments are kept: `/a//b/` has the segments `"a"`,
+    /// `""`, `"b"` and `""`, and `/` a single empty one. A path that doesn't
+    /// start with `/`, like `*`, has no segments. An encoded `%2F` stays in
+    /// its segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::PathAndQuery;
+    /// let path_and_query: PathAndQuery = "/stream/track%201?seq=1".parse().unwrap();
+    ///
+    /// let segments: Vec<_> = path_and_query.segments().collect();
+    /// assert_eq!(segments, ["stream", "track 1"]);
+    /// assert_eq!(path_and_query.segments().next_back().unwrap(), "track 1");
+    /// ```
+    pub fn segments(&self) -> Segments<'_> {
+        Segments::new(self.path(), true)
+    }
+
+    /// Returns an iterator over the segments of the path, like `segments`,
+    /// but without decoding them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::PathAndQuery;
+    /// let path_and_query: PathAndQuery = "/files/a%2Fb".parse().unwrap();
+    ///
+    /// let segments: Vec<_> = path_and_query.encoded_segments().collect();
+    /// assert_eq!(segments, ["files", "a%2Fb"]);
+    /// ```
+    pub fn encoded_segments(&self) -> Segments<'_> {
+        Segments::new(self.path(), false)
+    }
+
     /// Returns the path and query as a string component.
     ///
     /// # Examples
@@ -279,6 +448,14 @@ impl<'a> TryFrom<&'a [u8]> for PathAndQuery {
     }
 }
 
+impl TryFrom<Bytes> for PathAndQuery {
+    type Error = InvalidUri;
+    #[inline]
+    fn try_from(s: Bytes) -> Result<Self, Self::Error> {
+        PathAndQuery::from_shared(s)
+    }
+}
+
 impl<'a> TryFrom<&'a str> for PathAndQuery {
     type Error = InvalidUri;
     #[inline]
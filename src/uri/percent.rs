@@ -0,0 +1,263 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// An error decoding a percent-encoded component.
+///
+/// Either a `%` isn't followed by two hex digits, or the decoded bytes
+/// aren't UTF-8.
+pub struct InvalidPercentEncoding {
+    _priv: (),
+}
+
+impl InvalidPercentEncoding {
+    fn new() -> InvalidPercentEncoding {
+        InvalidPercentEncoding { _priv: () }
+    }
+}
+
+/// Decodes the `%XX` sequences of `src`, failing on a malformed sequence
+/// or invalid UTF-8.
+///
+/// With `plus`, a `+` decodes to a space, as in form data.
+pub(crate) fn decode(src: &str, plus: bool) -> Result<Cow<'_, str>, InvalidPercentEncoding> {
+    match unescape(src.as_bytes(), plus, true)? {
+        None => Ok(Cow::Borrowed(src)),
+        Some(bytes) => String::from_utf8(bytes)
+            .map(Cow::Owned)
+            .map_err(|_| InvalidPercentEncoding::new()),
+    }
+}
+
+/// Decodes the `%XX` sequences of `src`, keeping malformed sequences as
+/// they are and replacing invalid UTF-8 with U+FFFD.
+pub(crate) fn decode_lossy(src: &str, plus: bool) -> Cow<'_, str> {
+    match unescape(src.as_bytes(), plus, false) {
+        Ok(Some(bytes)) => match String::from_utf8(bytes) {
+            Ok(s) => Cow::Owned(s),
+            Err(e) => Cow::Owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        },
+        _ => Cow::Borrowed(src),
+    }
+}
+
+/// Appends `src` to `dst` with the `%XX` sequences of unreserved
+/// characters decoded and the others in uppercase, RFC 3986 section 6.2.2.
+pub(crate) fn normalize(src: &str, dst: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let (Some(hi), Some(lo)) = (
+                bytes.get(i + 1).and_then(hex),
+                bytes.get(i + 2).and_then(hex),
+            ) {
+                let b = hi << 4 | lo;
+                if is_unreserved(b) {
+                    dst.push(b as char);
+                } else {
+                    dst.push('%');
+                    dst.push(HEX[hi as usize] as char);
+                    dst.push(HEX[lo as usize] as char);
+                }
+                i += 3;
+                continue;
+            }
+        }
+        // `src` is ASCII, as it comes from a URI.
+        dst.push(bytes[i] as char);
+        i += 1;
+    }
+}
+
+/// Appends `src` to `dst` with every byte but the unreserved characters
+/// percent-encoded, so it can be put in any component of a URI.
+pub(crate) fn encode(src: &str, dst: &mut String) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    for &b in src.as_bytes() {
+        if is_unreserved(b) {
+            dst.push(b as char);
+        } else {
+            dst.push('%');
+            dst.push(HEX[(b >> 4) as usize] as char);
+            dst.push(HEX[(b & 0xf) as usize] as char);
+        }
+    }
+}
+
+/// The bytes of `src` normalized like `normalize` does, and lowercased
+/// with `lowercase`, without allocating.
+pub(crate) fn normalized_bytes(src: &str, lowercase: bool) -> NormalizedBytes<'_> {
+    NormalizedBytes {
+        src: src.as_bytes(),
+        lowercase,
+        pending: [0; 2],
+        pending_len: 0,
+    }
+}
+
+pub(crate) struct NormalizedBytes<'a> {
+    src: &'a [u8],
+    lowercase: bool,
+    pending: [u8; 2],
+    pending_len: usize,
+}
+
+impl<'a> Iterator for NormalizedBytes<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+        if self.pending_len > 0 {
+            let b = self.pending[2 - self.pending_len];
+            self.pending_len -= 1;
+            return Some(b);
+        }
+
+        let (&first, rest) = self.src.split_first()?;
+        let mut b = first;
+        self.src = rest;
+        if first == b'%' {
+            if let (Some(hi), Some(lo)) = (rest.first().and_then(hex), rest.get(1).and_then(hex)) {
+                self.src = &rest[2..];
+                let decoded = hi << 4 | lo;
+                if is_unreserved(decoded) {
+                    b = decoded;
+                } else {
+                    // Hex digits are compared in uppercase even when
+                    // lowercasing, so both sides agree.
+                    self.pending = [HEX[hi as usize], HEX[lo as usize]];
+                    self.pending_len = 2;
+                    return Some(b'%');
+                }
+            }
+        }
+        Some(if self.lowercase {
+            b.to_ascii_lowercase()
+        } else {
+            b
+        })
+    }
+}
+
+pub(crate) fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b'.' || b == b'_' || b == b'~'
+}
+
+/// Returns `None` if there is nothing to decode.
+fn unescape(
+    src: &[u8],
+    plus: bool,
+    strict: bool,
+) -> Result<Option<Vec<u8>>, InvalidPercentEncoding> {
+    let first = match src.iter().position(|&b| b == b'%' || (plus && b == b'+')) {
+        Some(i) => i,
+        None => return Ok(None),
+    };
+
+    let mut dst = Vec::with_capacity(src.len());
+    dst.extend_from_slice(&src[..first]);
+    let mut i = first;
+    while i < src.len() {
+        match src[i] {
+            b'%' => match (src.get(i + 1).and_then(hex), src.get(i + 2).and_then(hex)) {
+                (Some(hi), Some(lo)) => {
+                    dst.push(hi << 4 | lo);
+                    i += 3;
+                    continue;
+                }
+                _ if strict => return Err(InvalidPercentEncoding::new()),
+                _ => dst.push(b'%'),
+            },
+            b'+' if plus => dst.push(b' '),
+            b => dst.push(b),
+        }
+        i += 1;
+    }
+    Ok(Some(dst))
+}
+
+fn hex(b: &u8) -> Option<u8> {
+    match *b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+impl fmt::Debug for InvalidPercentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidPercentEncoding").finish()
+    }
+}
+
+impl fmt::Display for InvalidPercentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid percent-encoding")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for InvalidPercentEncoding {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoding() {
+        assert!(is_borrowed(decode("/a/b", false).unwrap()));
+        assert_eq!(decode("/a%20b%2Fc", false).unwrap(), "/a b/c");
+        assert_eq!(decode("%C3%A9+", false).unwrap(), "é+");
+        assert_eq!(decode("a+b", true).unwrap(), "a b");
+        assert!(decode("%", false).is_err());
+        assert!(decode("%2", false).is_err());
+        assert!(decode("%zz", false).is_err());
+        assert!(decode("%FF", false).is_err());
+    }
+
+    #[test]
+    fn lossy() {
+        assert_eq!(decode_lossy("100%", false), "100%");
+        assert_eq!(decode_lossy("%zz%41", false), "%zzA");
+        assert_eq!(decode_lossy("%FFa", false), "\u{FFFD}a");
+    }
+
+    #[test]
+    fn normalizing() {
+        let mut s = String::new();
+        normalize("%7e%41%2f%2F%zz%", &mut s);
+        assert_eq!(s, "~A%2F%2F%zz%");
+    }
+
+    #[test]
+    fn encoding() {
+        let mut s = String::new();
+        encode("a b+c&d=é~", &mut s);
+        assert_eq!(s, "a%20b%2Bc%26d%3D%C3%A9~");
+        assert_eq!(decode(&s, false).unwrap(), "a b+c&d=é~");
+    }
+
+    #[test]
+    fn normalized_iter() {
+        let bytes: Vec<u8> = normalized_bytes("A%7e%2fB", true).collect();
+        assert_eq!(bytes, b"a~%2Fb");
+        let bytes: Vec<u8> = normalized_bytes("A%7e%2fB%", false).collect();
+        assert_eq!(bytes, b"A~%2FB%");
+    }
+
+    fn is_borrowed(s: Cow<'_, str>) -> bool {
+        match s {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false,
+        }
+    }
+}
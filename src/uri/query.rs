@@ -0,0 +1,205 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::{cmp, fmt};
+
+use bytes::Bytes;
+
+use super::{percent, ErrorKind, InvalidUri, PathAndQuery};
+
+/// An iterator over the decoded name and value pairs of a query.
+///
+/// This struct is created by `Uri::query_pairs`.
+#[derive(Clone)]
+pub struct QueryPairs<'a> {
+    rest: &'a str,
+}
+
+impl<'a> QueryPairs<'a> {
+    pub(super) fn new(query: &'a str) -> QueryPairs<'a> {
+        QueryPairs { rest: query }
+    }
+}
+
+impl<'a> Iterator for QueryPairs<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let end = self.rest.find(&['&', ';'][..]).unwrap_or(self.rest.len());
+            let pair = &self.rest[..end];
+            self.rest = &self.rest[cmp::min(end + 1, self.rest.len())..];
+
+            // `a&&b` has no pair between the separators.
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (name, value) = match pair.find('=') {
+                Some(i) => (&pair[..i], &pair[i + 1..]),
+                None => (pair, ""),
+            };
+            return Some((
+                percent::decode_lossy(name, true),
+                percent::decode_lossy(value, true),
+            ));
+        }
+    }
+}
+
+impl<'a> fmt::Debug for QueryPairs<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A builder for query strings.
+///
+/// Names and values are percent-encoded, so whatever they contain, they
+/// come back unchanged out of `Uri::query_pairs`, and can't add pairs or
+/// end the query.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::uri::QueryBuilder;
+/// let path_and_query = QueryBuilder::new()
+///     .pair("q", "rock & roll")
+///     .pair("page", "2")
+///     .build("/search")
+///     .unwrap();
+/// assert_eq!(path_and_query, "/search?q=rock%20%26%20roll&page=2");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct QueryBuilder {
+    query: String,
+}
+
+impl QueryBuilder {
+    /// Creates a `QueryBuilder` without pairs.
+    #[inline]
+    pub fn new() -> QueryBuilder {
+        QueryBuilder::default()
+    }
+
+    /// Appends a name and value pair.
+    pub fn pair(mut self, name: &str, value: &str) -> Self {
+        if !self.query.is_empty() {
+            self.query.push('&');
+        }
+        percent::encode(name, &mut self.query);
+        self.query.push('=');
+        percent::encode(value, &mut self.query);
+        self
+    }
+
+    /// Appends each of the name and value pairs of `pairs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::QueryBuilder;
+    /// let query = QueryBuilder::new().pairs(vec![("a", "1"), ("b", "=")]);
+    /// assert_eq!(query.as_str(), "a=1&b=%3D");
+    /// ```
+    pub fn pairs<I, K, V>(self, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        pairs.into_iter().fold(self, |query, (name, value)| {
+            query.pair(name.as_ref(), value.as_ref())
+        })
+    }
+
+    /// Returns the encoded query, without the `?`.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.query
+    }
+
+    /// Consumes this builder, and joins the query to `path`.
+    ///
+    /// An empty query is left out, rather than leaving a `?` at the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` isn't a valid path, or has a query or a
+    /// fragment of its own.
+    pub fn build(self, path: &str) -> Result<PathAndQuery, InvalidUri> {
+        if path.contains(&['?', '#'][..]) {
+            return Err(ErrorKind::InvalidFormat.into());
+        }
+        let mut s = String::with_capacity(path.len() + 1 + self.query.len());
+        s.push_str(path);
+        if !self.query.is_empty() {
+            s.push('?');
+            s.push_str(&self.query);
+        }
+        PathAndQuery::from_shared(Bytes::from(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(query: &str) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        QueryPairs::new(query).collect()
+    }
+
+    #[test]
+    fn separators() {
+        assert_eq!(
+            pairs("a=1&b=2;c=3"),
+            [
+                ("a".into(), "1".into()),
+                ("b".into(), "2".into()),
+                ("c".into(), "3".into())
+            ]
+        );
+        assert_eq!(pairs("&&a&b=&=c&"), pairs("a=&b=&=c"));
+        assert_eq!(pairs(""), []);
+    }
+
+    #[test]
+    fn builder() {
+        let pairs = [("a b", "c&d=e;f"), ("", ""), ("é", "+%")];
+        let query = QueryBuilder::new().pairs(pairs.iter().cloned());
+        assert_eq!(query.as_str(), "a%20b=c%26d%3De%3Bf&=&%C3%A9=%2B%25");
+        let decoded: Vec<_> = QueryPairs::new(query.as_str()).collect();
+        assert_eq!(
+            decoded,
+            [
+                ("a b".into(), "c&d=e;f".into()),
+                ("".into(), "".into()),
+                ("é".into(), "+%".into())
+            ]
+        );
+
+        assert_eq!(QueryBuilder::new().build("/a").unwrap(), "/a");
+        assert_eq!(
+            QueryBuilder::new().pair("x", "#").build("*").unwrap(),
+            "*?x=%23"
+        );
+        assert!(QueryBuilder::new().build("/a?b").is_err());
+        assert!(QueryBuilder::new().build("/a#b").is_err());
+        assert!(QueryBuilder::new().build("/a b").is_err());
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(
+            pairs("q=caf%C3%A9+au+lait&x%3Dy=%26"),
+            [
+                ("q".into(), "café au lait".into()),
+                ("x=y".into(), "&".into())
+            ]
+        );
+        assert_eq!(pairs("p=100%"), [("p".into(), "100%".into())]);
+    }
+}
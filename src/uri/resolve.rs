@@ -0,0 +1,228 @@
+use alloc::string::String;
+
+use bytes::Bytes;
+
+use super::{ErrorKind, InvalidUri, Uri};
+
+/// The components of a URI reference, RFC 3986 section 4.1.
+struct Reference<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Reference<'a> {
+    fn parse(mut s: &'a str) -> Reference<'a> {
+        let fragment = match s.find('#') {
+            Some(i) => {
+                let fragment = &s[i + 1..];
+                s = &s[..i];
+                Some(fragment)
+            }
+            None => None,
+        };
+
+        let scheme = match s.find(&[':', '/', '?'][..]) {
+            Some(i) if s.as_bytes()[i] == b':' && is_scheme(&s[..i]) => {
+                let scheme = &s[..i];
+                s = &s[i + 1..];
+                Some(scheme)
+            }
+            _ => None,
+        };
+
+        let authority = if s.starts_with("//") {
+            let end = s[2..].find(&['/', '?'][..]).map_or(s.len(), |i| i + 2);
+            let authority = &s[2..end];
+            s = &s[end..];
+            Some(authority)
+        } else {
+            None
+        };
+
+        let (path, query) = match s.find('?') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        Reference {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
+}
+
+fn is_scheme(s: &str) -> bool {
+    let mut bytes = s.bytes();
+    match bytes.next() {
+        Some(b) if b.is_ascii_alphabetic() => {}
+        _ => return false,
+    }
+    bytes.all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+}
+
+/// Resolves `reference` against `base`, RFC 3986 section 5.2.2.
+pub(super) fn resolve(base: &Uri, reference: &str) -> Result<Uri, InvalidUri> {
+    let base_scheme = match base.scheme_str() {
+        Some(scheme) => scheme,
+        None => return Err(ErrorKind::SchemeMissing.into()),
+    };
+    if base.is_opaque() {
+        // SIP and tel URIs have no path to resolve against.
+        return Err(ErrorKind::InvalidFormat.into());
+    }
+    let base_authority = base.authority().map_or("", |authority| authority.as_str());
+
+    let r = Reference::parse(reference);
+    let mut path = String::new();
+    let (scheme, authority, query) = if let Some(scheme) = r.scheme {
+        // A `Uri` with a scheme always has an authority.
+        let authority = r.authority.ok_or(ErrorKind::AuthorityMissing)?;
+        remove_dot_segments(r.path, &mut path);
+        (scheme, authority, r.query)
+    } else if let Some(authority) = r.authority {
+        remove_dot_segments(r.path, &mut path);
+        (base_scheme, authority, r.query)
+    } else if r.path.is_empty() {
+        path.push_str(base.path());
+        (
+            base_scheme,
+            base_authority,
+            r.query.or_else(|| base.query()),
+        )
+    } else if r.path.starts_with('/') {
+        remove_dot_segments(r.path, &mut path);
+        (base_scheme, base_authority, r.query)
+    } else {
+        // Merge the reference with the directory of the base, section 5.2.3.
+        let base_path = base.path();
+        let mut merged = String::with_capacity(base_path.len() + r.path.len() + 1);
+        match base_path.rfind('/') {
+            Some(i) => merged.push_str(&base_path[..=i]),
+            None => merged.push('/'),
+        }
+        merged.push_str(r.path);
+        remove_dot_segments(&merged, &mut path);
+        (base_scheme, base_authority, r.query)
+    };
+
+    let mut s = String::with_capacity(scheme.len() + authority.len() + path.len() + 4);
+    s.push_str(scheme);
+    s.push_str("://");
+    s.push_str(authority);
+    s.push_str(&path);
+    if let Some(query) = query {
+        s.push('?');
+        s.push_str(query);
+    }
+    if let Some(fragment) = r.fragment {
+        s.push('#');
+        s.push_str(fragment);
+    }
+    Uri::from_maybe_shared(Bytes::from(s))
+}
+
+/// Appends `path` to `out` without its `.` and `..` segments, RFC 3986
+/// section 5.2.4.
+pub(super) fn remove_dot_segments(mut input: &str, out: &mut String) {
+    let start = out.len();
+    while !input.is_empty() {
+        if input.starts_with("../") {
+            input = &input[3..];
+        } else if input.starts_with("./") || input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input.len() == 3 { "/" } else { &input[3..] };
+            let last = out[start..].rfind('/').map_or(start, |i| start + i);
+            out.truncate(last);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let end = input[1..].find('/').map_or(input.len(), |i| i + 1);
+            out.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_segments() {
+        let remove = |path: &str| {
+            let mut out = String::new();
+            remove_dot_segments(path, &mut out);
+            out
+        };
+        assert_eq!(remove("/a/b/c/./../../g"), "/a/g");
+        assert_eq!(remove("mid/content=5/../6"), "mid/6");
+        assert_eq!(remove("/.."), "/");
+        assert_eq!(remove("/a/.."), "/");
+        assert_eq!(remove("/a/b/"), "/a/b/");
+        assert_eq!(remove("."), "");
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn rfc_examples() {
+        // RFC 3986 section 5.4, without the examples without an authority.
+        let base: Uri = "http://a/b/c/d;p?q".parse().unwrap();
+        let cases = [
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g/"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("../../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            (".g", "http://a/b/c/.g"),
+            ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g;x=1/../y", "http://a/b/c/y"),
+            ("https://other/x/../y", "https://other/y"),
+        ];
+        for &(reference, expected) in &cases {
+            let resolved = resolve(&base, reference).unwrap();
+            let mut s = resolved.to_string();
+            if let Some(fragment) = resolved.fragment() {
+                s.push('#');
+                s.push_str(fragment);
+            }
+            assert_eq!(s, expected, "resolving {:?}", reference);
+        }
+
+        assert!(resolve(&base, "g:h").is_err());
+        assert!(resolve(&"/relative".parse().unwrap(), "g").is_err());
+    }
+}
@@ -1,12 +1,14 @@
-use std::convert::TryFrom;
-use std::fmt;
-use std::hash::{Hash, Hasher};
-use std::str::FromStr;
+use core::convert::TryFrom;
+use alloc::boxed::Box;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
 
 use bytes::Bytes;
 
 use super::{ErrorKind, InvalidUri};
 use crate::byte_str::ByteStr;
+use crate::swar;
 
 /// Represents the scheme component of a URI
 #[derive(Clone)]
@@ -31,6 +33,16 @@ pub(super) enum Protocol {
     Rtsp,
     #[cfg(feature = "rtsp")]
     Rtsps,
+    #[cfg(feature = "ws")]
+    Ws,
+    #[cfg(feature = "ws")]
+    Wss,
+    #[cfg(feature = "sip")]
+    Sip,
+    #[cfg(feature = "sip")]
+    Sips,
+    #[cfg(feature = "tel")]
+    Tel,
 }
 
 impl Scheme {
@@ -58,12 +70,100 @@ impl Scheme {
         inner: Scheme2::Standard(Protocol::Rtsps),
     };
 
+    /// WebSocket protocol scheme
+    #[cfg(feature = "ws")]
+    pub const WS: Scheme = Scheme {
+        inner: Scheme2::Standard(Protocol::Ws),
+    };
+
+    /// WebSocket protocol over TLS.
+    #[cfg(feature = "ws")]
+    pub const WSS: Scheme = Scheme {
+        inner: Scheme2::Standard(Protocol::Wss),
+    };
+
+    /// SIP protocol scheme
+    #[cfg(feature = "sip")]
+    pub const SIP: Scheme = Scheme {
+        inner: Scheme2::Standard(Protocol::Sip),
+    };
+
+    /// SIP protocol over TLS.
+    #[cfg(feature = "sip")]
+    pub const SIPS: Scheme = Scheme {
+        inner: Scheme2::Standard(Protocol::Sips),
+    };
+
+    /// Telephone number scheme
+    #[cfg(feature = "tel")]
+    pub const TEL: Scheme = Scheme {
+        inner: Scheme2::Standard(Protocol::Tel),
+    };
+
     pub(super) fn empty() -> Self {
         Scheme {
             inner: Scheme2::None,
         }
     }
 
+    // tel URIs are the only ones with a scheme but no authority.
+    pub(super) fn is_tel(&self) -> bool {
+        match self.inner {
+            #[cfg(feature = "tel")]
+            Scheme2::Standard(Protocol::Tel) => true,
+            _ => false,
+        }
+    }
+
+    // What separates the scheme from the authority: `://`, except for SIP
+    // and tel URIs, which have no `//`.
+    pub(crate) fn delimiter(&self) -> &'static str {
+        match self.inner {
+            Scheme2::Standard(p) => p.delimiter(),
+            _ => "://",
+        }
+    }
+
+    /// Returns the well-known port of the scheme, or `None` if the scheme
+    /// isn't one of the standard schemes.
+    ///
+    /// This is the port a client connects to when the authority of a URI
+    /// has none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::Scheme;
+    /// assert_eq!(Scheme::HTTPS.default_port(), Some(443));
+    ///
+    /// let other: Scheme = "gopher".parse().unwrap();
+    /// assert_eq!(other.default_port(), None);
+    /// ```
+    pub fn default_port(&self) -> Option<u16> {
+        use self::Protocol::*;
+        use self::Scheme2::*;
+
+        match self.inner {
+            #[cfg(feature = "http")]
+            Standard(Http) => Some(80),
+            #[cfg(feature = "http")]
+            Standard(Https) => Some(443),
+            #[cfg(feature = "rtsp")]
+            Standard(Rtsp) => Some(554),
+            #[cfg(feature = "rtsp")]
+            Standard(Rtsps) => Some(322),
+            #[cfg(feature = "ws")]
+            Standard(Ws) => Some(80),
+            #[cfg(feature = "ws")]
+            Standard(Wss) => Some(443),
+            #[cfg(feature = "sip")]
+            Standard(Sip) => Some(5060),
+            #[cfg(feature = "sip")]
+            Standard(Sips) => Some(5061),
+            _ => Option::None,
+        }
+    }
+
     /// Return a str representation of the scheme
     ///
     /// # Examples
@@ -87,6 +187,16 @@ impl Scheme {
             Standard(Rtsp) => "rtsp",
             #[cfg(feature = "rtsp")]
             Standard(Rtsps) => "rtsps",
+            #[cfg(feature = "ws")]
+            Standard(Ws) => "ws",
+            #[cfg(feature = "ws")]
+            Standard(Wss) => "wss",
+            #[cfg(feature = "sip")]
+            Standard(Sip) => "sip",
+            #[cfg(feature = "sip")]
+            Standard(Sips) => "sips",
+            #[cfg(feature = "tel")]
+            Standard(Tel) => "tel",
             Other(ref v) => &v[..],
             None => unreachable!(),
         }
@@ -112,6 +222,23 @@ impl<'a> TryFrom<&'a [u8]> for Scheme {
     }
 }
 
+impl TryFrom<Bytes> for Scheme {
+    type Error = InvalidUri;
+    #[inline]
+    fn try_from(s: Bytes) -> Result<Self, Self::Error> {
+        use self::Scheme2::*;
+
+        match Scheme2::parse_exact(&s[..])? {
+            None => Err(ErrorKind::InvalidScheme.into()),
+            Standard(p) => Ok(Standard(p).into()),
+            Other(_) => {
+                // Unsafe: parse_exact already checks for a strict subset of UTF-8
+                Ok(Other(Box::new(unsafe { ByteStr::from_utf8_unchecked(s) })).into())
+            }
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a str> for Scheme {
     type Error = InvalidUri;
     #[inline]
@@ -161,6 +288,16 @@ impl PartialEq for Scheme {
             (&Standard(Rtsp), &Standard(Rtsp)) => true,
             #[cfg(feature = "rtsp")]
             (&Standard(Rtsps), &Standard(Rtsps)) => true,
+            #[cfg(feature = "ws")]
+            (&Standard(Ws), &Standard(Ws)) => true,
+            #[cfg(feature = "ws")]
+            (&Standard(Wss), &Standard(Wss)) => true,
+            #[cfg(feature = "sip")]
+            (&Standard(Sip), &Standard(Sip)) => true,
+            #[cfg(feature = "sip")]
+            (&Standard(Sips), &Standard(Sips)) => true,
+            #[cfg(feature = "tel")]
+            (&Standard(Tel), &Standard(Tel)) => true,
             (&Other(ref a), &Other(ref b)) => a.eq_ignore_ascii_case(b),
             (&None, _) | (_, &None) => unreachable!(),
             _ => false,
@@ -208,6 +345,16 @@ impl Hash for Scheme {
             Scheme2::Standard(Protocol::Rtsp) => state.write_u8(3),
             #[cfg(feature = "rtsp")]
             Scheme2::Standard(Protocol::Rtsps) => state.write_u8(4),
+            #[cfg(feature = "ws")]
+            Scheme2::Standard(Protocol::Ws) => state.write_u8(5),
+            #[cfg(feature = "ws")]
+            Scheme2::Standard(Protocol::Wss) => state.write_u8(6),
+            #[cfg(feature = "sip")]
+            Scheme2::Standard(Protocol::Sip) => state.write_u8(7),
+            #[cfg(feature = "sip")]
+            Scheme2::Standard(Protocol::Sips) => state.write_u8(8),
+            #[cfg(feature = "tel")]
+            Scheme2::Standard(Protocol::Tel) => state.write_u8(9),
             Scheme2::Other(ref other) => {
                 other.len().hash(state);
                 for &b in other.as_bytes() {
@@ -263,9 +410,33 @@ const SCHEME_CHARS: [u8; 256] = [
         0,     0,     0,     0,     0,     0                              // 25x
 ];
 
+// The bytes of `SCHEME_CHARS` other than `:`.
+#[inline]
+fn is_scheme_word(x: u64) -> bool {
+    let ok = swar::range(x, b'a', b'z')
+        | swar::range(x, b'A', b'Z')
+        | swar::range(x, b'0', b'9')
+        | swar::eq(x, b'+')
+        | swar::eq(x, b'-')
+        | swar::eq(x, b'.')
+        | swar::eq(x, b'~');
+    ok == swar::HIGH
+}
+
 impl Scheme2<usize> {
     fn parse_exact(s: &[u8]) -> Result<Scheme2<()>, InvalidUri> {
-        match s {
+        // Schemes are case-insensitive, and the standard ones are short.
+        let mut lower = [0; 5];
+        let key = if s.len() <= lower.len() {
+            for (dst, b) in lower.iter_mut().zip(s) {
+                *dst = b.to_ascii_lowercase();
+            }
+            &lower[..s.len()]
+        } else {
+            &[][..]
+        };
+
+        match key {
             #[cfg(feature = "http")]
             b"http" => Ok(Protocol::Http.into()),
             #[cfg(feature = "http")]
@@ -274,12 +445,22 @@ impl Scheme2<usize> {
             b"rtsp" => Ok(Protocol::Rtsp.into()),
             #[cfg(feature = "rtsp")]
             b"rtsps" => Ok(Protocol::Rtsps.into()),
+            #[cfg(feature = "ws")]
+            b"ws" => Ok(Protocol::Ws.into()),
+            #[cfg(feature = "ws")]
+            b"wss" => Ok(Protocol::Wss.into()),
+            #[cfg(feature = "sip")]
+            b"sip" => Ok(Protocol::Sip.into()),
+            #[cfg(feature = "sip")]
+            b"sips" => Ok(Protocol::Sips.into()),
+            #[cfg(feature = "tel")]
+            b"tel" => Ok(Protocol::Tel.into()),
             _ => {
                 if s.len() > MAX_SCHEME_LEN {
                     return Err(ErrorKind::SchemeTooLong.into());
                 }
 
-                for &b in s {
+                for &b in &s[swar::skip(s, is_scheme_word)..] {
                     match SCHEME_CHARS[b as usize] {
                         b':' => {
                             // Don't want :// here
@@ -298,6 +479,36 @@ impl Scheme2<usize> {
     }
 
     pub(super) fn parse(s: &[u8]) -> Result<Scheme2<usize>, InvalidUri> {
+        #[cfg(feature = "ws")]
+        {
+            // Check for WebSocket
+            if s.len() >= 5 && s[..5].eq_ignore_ascii_case(b"ws://") {
+                return Ok(Protocol::Ws.into());
+            }
+            if s.len() >= 6 && s[..6].eq_ignore_ascii_case(b"wss://") {
+                return Ok(Protocol::Wss.into());
+            }
+        }
+
+        #[cfg(feature = "sip")]
+        {
+            // Check for SIP, whose URIs have no `//`
+            if s.len() >= 4 && s[..4].eq_ignore_ascii_case(b"sip:") {
+                return Ok(Protocol::Sip.into());
+            }
+            if s.len() >= 5 && s[..5].eq_ignore_ascii_case(b"sips:") {
+                return Ok(Protocol::Sips.into());
+            }
+        }
+
+        #[cfg(feature = "tel")]
+        {
+            // Check for tel, whose URIs have no authority
+            if s.len() >= 4 && s[..4].eq_ignore_ascii_case(b"tel:") {
+                return Ok(Protocol::Tel.into());
+            }
+        }
+
         if s.len() >= 7 {
             #[cfg(feature = "http")]
             {
@@ -335,7 +546,7 @@ impl Scheme2<usize> {
         }
 
         if s.len() > 3 {
-            for i in 0..s.len() {
+            for i in swar::skip(s, is_scheme_word)..s.len() {
                 let b = s[i];
 
                 match SCHEME_CHARS[b as usize] {
@@ -379,6 +590,26 @@ impl Protocol {
             Protocol::Rtsp => 4,
             #[cfg(feature = "rtsp")]
             Protocol::Rtsps => 5,
+            #[cfg(feature = "ws")]
+            Protocol::Ws => 2,
+            #[cfg(feature = "ws")]
+            Protocol::Wss => 3,
+            #[cfg(feature = "sip")]
+            Protocol::Sip => 3,
+            #[cfg(feature = "sip")]
+            Protocol::Sips => 4,
+            #[cfg(feature = "tel")]
+            Protocol::Tel => 3,
+        }
+    }
+
+    pub(super) fn delimiter(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "sip")]
+            Protocol::Sip | Protocol::Sips => ":",
+            #[cfg(feature = "tel")]
+            Protocol::Tel => ":",
+            _ => "://",
         }
     }
 }
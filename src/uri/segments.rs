@@ -0,0 +1,110 @@
+use alloc::borrow::Cow;
+use core::fmt;
+
+use super::percent;
+
+/// An iterator over the segments of a path.
+///
+/// This struct is created by `PathAndQuery::segments` and
+/// `PathAndQuery::encoded_segments`.
+#[derive(Clone)]
+pub struct Segments<'a> {
+    rest: Option<&'a str>,
+    decode: bool,
+}
+
+impl<'a> Segments<'a> {
+    pub(super) fn new(path: &'a str, decode: bool) -> Segments<'a> {
+        let rest = if path.as_bytes().first() == Some(&b'/') {
+            Some(&path[1..])
+        } else {
+            None
+        };
+        Segments { rest, decode }
+    }
+
+    fn segment(&self, segment: &'a str) -> Cow<'a, str> {
+        if self.decode {
+            percent::decode_lossy(segment, false)
+        } else {
+            Cow::Borrowed(segment)
+        }
+    }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let segment = match rest.find('/') {
+            Some(i) => {
+                self.rest = Some(&rest[i + 1..]);
+                &rest[..i]
+            }
+            None => {
+                self.rest = None;
+                rest
+            }
+        };
+        Some(self.segment(segment))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Segments<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let segment = match rest.rfind('/') {
+            Some(i) => {
+                self.rest = Some(&rest[..i]);
+                &rest[i + 1..]
+            }
+            None => {
+                self.rest = None;
+                rest
+            }
+        };
+        Some(self.segment(segment))
+    }
+}
+
+impl<'a> fmt::Debug for Segments<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(path: &str) -> Vec<Cow<'_, str>> {
+        Segments::new(path, true).collect()
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(segments("/stream/track1"), ["stream", "track1"]);
+        assert_eq!(segments("/a/b/"), ["a", "b", ""]);
+        assert_eq!(segments("//a"), ["", "a"]);
+        assert_eq!(segments("/"), [""]);
+        assert_eq!(segments("*"), Vec::<Cow<'_, str>>::new());
+    }
+
+    #[test]
+    fn decoding() {
+        assert_eq!(segments("/a%2Fb/caf%C3%A9/100%"), ["a/b", "café", "100%"]);
+        let encoded: Vec<_> = Segments::new("/a%2Fb/c", false).collect();
+        assert_eq!(encoded, ["a%2Fb", "c"]);
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut segments = Segments::new("/a/b/c", true);
+        assert_eq!(segments.next_back().unwrap(), "c");
+        assert_eq!(segments.next().unwrap(), "a");
+        assert_eq!(segments.next_back().unwrap(), "b");
+        assert_eq!(segments.next(), None);
+        assert_eq!(segments.next_back(), None);
+    }
+}
@@ -0,0 +1,308 @@
+//! The parts of SIP URIs, enabled with the `sip` feature.
+//!
+//! A SIP URI, RFC 3261 section 19.1, has parameters after its host, like
+//! `;transport=tcp;lr`, which `Uri::path` returns as they are, and headers
+//! to put in the request sent to it, like `?subject=project&priority=urgent`,
+//! which `Uri::query` returns. `Params` and `Headers` split them.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::Uri;
+//! use httplike::uri::sip::Params;
+//!
+//! let uri: Uri = "sip:+15551234@gw.example.com;user=phone;Transport=TCP;lr".parse().unwrap();
+//! let params = Params::from_uri(&uri).unwrap();
+//!
+//! assert_eq!(params.transport(), Some("TCP"));
+//! assert_eq!(params.get("user"), Some("phone"));
+//! assert!(params.contains("lr"));
+//! ```
+
+use alloc::borrow::Cow;
+use core::{cmp, fmt};
+
+use super::{percent, Uri};
+use crate::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// The parameters of a SIP URI.
+///
+/// Parameter names are compared without regard to case, as RFC 3261
+/// section 19.1.4 says. Names and values are returned as they are in the
+/// URI, so they may be percent-encoded. A parameter without `=`, like
+/// `lr`, has an empty value.
+#[derive(Clone, Copy)]
+pub struct Params<'a> {
+    params: &'a str,
+}
+
+impl<'a> Params<'a> {
+    /// Returns the parameters of `uri`, or `None` if it isn't a SIP URI.
+    pub fn from_uri(uri: &'a Uri) -> Option<Params<'a>> {
+        if uri.is_sip() {
+            Some(Params { params: uri.path() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of the first parameter named `name`.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.iter()
+            .find(|&(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns true if there is a parameter named `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns the `transport` parameter, like `udp`, `tcp` or `ws`.
+    pub fn transport(&self) -> Option<&'a str> {
+        self.get("transport")
+    }
+
+    /// Returns true if there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Returns an iterator over the names and values of the parameters.
+    pub fn iter(&self) -> Iter<'a> {
+        Iter { rest: self.params }
+    }
+}
+
+impl<'a> IntoIterator for Params<'a> {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> fmt::Debug for Params<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the names and values of the parameters of a SIP URI.
+///
+/// This struct is created by `Params::iter`.
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let end = self.rest.find(';').unwrap_or(self.rest.len());
+            let param = &self.rest[..end];
+            self.rest = &self.rest[cmp::min(end + 1, self.rest.len())..];
+
+            // The leading `;`, or `;;`, leave an empty parameter.
+            if param.is_empty() {
+                continue;
+            }
+
+            return Some(match param.find('=') {
+                Some(i) => (&param[..i], &param[i + 1..]),
+                None => (param, ""),
+            });
+        }
+    }
+}
+
+/// The headers of a SIP URI.
+///
+/// Header names are compared without regard to case. Names and values are
+/// decoded from their `%XX` sequences; a `+` is kept as it is. The `body`
+/// pseudo-header holds the body of the request rather than a header.
+#[derive(Clone, Copy)]
+pub struct Headers<'a> {
+    headers: &'a str,
+}
+
+impl<'a> Headers<'a> {
+    /// Returns the headers of `uri`, or `None` if it isn't a SIP URI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::Uri;
+    /// # use httplike::uri::sip::Headers;
+    /// let uri: Uri = "sip:bob@example.com?subject=Project%20X&priority=urgent".parse().unwrap();
+    /// let headers = Headers::from_uri(&uri).unwrap();
+    ///
+    /// assert_eq!(headers.get("Subject").unwrap(), "Project X");
+    ///
+    /// let map = headers.to_header_map().unwrap();
+    /// assert_eq!(map["priority"], "urgent");
+    /// ```
+    pub fn from_uri(uri: &'a Uri) -> Option<Headers<'a>> {
+        if uri.is_sip() {
+            Some(Headers {
+                headers: uri.query().unwrap_or(""),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of the first header named `name`.
+    pub fn get(&self, name: &str) -> Option<Cow<'a, str>> {
+        self.iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the value of the `body` pseudo-header.
+    pub fn body(&self) -> Option<Cow<'a, str>> {
+        self.get("body")
+    }
+
+    /// Returns true if there are no headers.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Returns an iterator over the names and values of the headers.
+    pub fn iter(&self) -> HeaderPairs<'a> {
+        HeaderPairs { rest: self.headers }
+    }
+
+    /// Returns the headers in a `HeaderMap`, without the `body`
+    /// pseudo-header, to compose a request sent to this URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a name or value isn't valid in a header.
+    pub fn to_header_map(&self) -> Result<HeaderMap, crate::Error> {
+        let mut map = HeaderMap::new();
+        for (name, value) in self.iter() {
+            if name.eq_ignore_ascii_case("body") {
+                continue;
+            }
+            let name = HeaderName::from_bytes(name.as_bytes())?;
+            let value = HeaderValue::from_str(&value)?;
+            map.append(name, value);
+        }
+        Ok(map)
+    }
+}
+
+impl<'a> IntoIterator for Headers<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+    type IntoIter = HeaderPairs<'a>;
+
+    fn into_iter(self) -> HeaderPairs<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> fmt::Debug for Headers<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the decoded names and values of the headers of a SIP
+/// URI.
+///
+/// This struct is created by `Headers::iter`.
+#[derive(Clone, Debug)]
+pub struct HeaderPairs<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for HeaderPairs<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+
+            let end = self.rest.find('&').unwrap_or(self.rest.len());
+            let pair = &self.rest[..end];
+            self.rest = &self.rest[cmp::min(end + 1, self.rest.len())..];
+
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (name, value) = match pair.find('=') {
+                Some(i) => (&pair[..i], &pair[i + 1..]),
+                None => (pair, ""),
+            };
+            return Some((
+                percent::decode_lossy(name, false),
+                percent::decode_lossy(value, false),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params() {
+        let uri = Uri::from_static("sip:alice;day=tue@atlanta.com;maddr=239.255.255.1;ttl=15;;lr");
+        let params = Params::from_uri(&uri).unwrap();
+        let all: Vec<_> = params.iter().collect();
+        assert_eq!(all, [("maddr", "239.255.255.1"), ("ttl", "15"), ("lr", "")]);
+        assert_eq!(params.get("TTL"), Some("15"));
+        assert_eq!(params.get("day"), None);
+        assert_eq!(params.transport(), None);
+
+        let uri = Uri::from_static("sips:bob@biloxi.com?subject=x");
+        let params = Params::from_uri(&uri).unwrap();
+        assert!(params.is_empty());
+        assert_eq!(format!("{:?}", params), "{}");
+
+        #[cfg(feature = "http")]
+        assert!(Params::from_uri(&Uri::from_static("http://h/;a=b")).is_none());
+        assert!(Params::from_uri(&Uri::from_static("/a;b=c")).is_none());
+        assert!(Params::from_uri(&Uri::from_static("h:80")).is_none());
+    }
+
+    #[test]
+    fn headers() {
+        let uri = Uri::from_static(
+            "sip:bob@h;lr?Subject=a+b%26c&&to=%3Csip:x@y%3E&body=hi%0D%0A&route=a&route=b",
+        );
+        let headers = Headers::from_uri(&uri).unwrap();
+        assert_eq!(headers.get("subject").unwrap(), "a+b&c");
+        assert_eq!(headers.body().unwrap(), "hi\r\n");
+
+        let map = headers.to_header_map().unwrap();
+        assert_eq!(map.len(), 4);
+        assert_eq!(map["to"], "<sip:x@y>");
+        assert_eq!(map.get_all("route").iter().count(), 2);
+        assert!(!map.contains_key("body"));
+
+        let uri = Uri::from_static("sip:bob@h?x=%0A");
+        assert!(Headers::from_uri(&uri).unwrap().to_header_map().is_err());
+
+        let uri = Uri::from_static("sip:bob@h");
+        let headers = Headers::from_uri(&uri).unwrap();
+        assert!(headers.is_empty());
+        assert_eq!(headers.get("subject"), None);
+
+        assert!(Headers::from_uri(&Uri::from_static("/a?b=c")).is_none());
+        assert!(Headers::from_uri(&Uri::from_static("h:80")).is_none());
+    }
+}
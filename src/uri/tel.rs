@@ -0,0 +1,219 @@
+//! The telephone numbers of tel URIs, enabled with the `tel` feature.
+//!
+//! A tel URI, RFC 3966, is a number and its parameters, like
+//! `tel:+1-201-555-0123;ext=1234`, without an authority. `Uri::path`
+//! returns them as they are, and `TelNumber` splits them. With the `sip`
+//! feature, a `TelNumber` can also be read from, and turned into, a SIP
+//! URI with `user=phone`, RFC 3261 section 19.1.6.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::Uri;
+//! use httplike::uri::tel::TelNumber;
+//!
+//! let uri: Uri = "tel:+1-201-555-0123;ext=1234".parse().unwrap();
+//! let number = TelNumber::from_uri(&uri).unwrap();
+//!
+//! assert_eq!(number.subscriber(), "+1-201-555-0123");
+//! assert_eq!(number.digits(), "+12015550123");
+//! assert_eq!(number.extension(), Some("1234"));
+//! assert!(number.is_global());
+//! ```
+
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(feature = "sip")]
+use super::InvalidUri;
+use super::Uri;
+
+/// The telephone number of a tel URI.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TelNumber<'a> {
+    subscriber: &'a str,
+    params: &'a str,
+}
+
+impl<'a> TelNumber<'a> {
+    /// Returns the number of `uri`, or `None` if it isn't a tel URI, or a
+    /// SIP URI with the `user=phone` parameter.
+    pub fn from_uri(uri: &'a Uri) -> Option<TelNumber<'a>> {
+        let number = match uri.scheme() {
+            Some(scheme) if scheme.is_tel() => uri.path(),
+            _ => sip_user(uri)?,
+        };
+        let (subscriber, params) = match number.find(';') {
+            Some(i) => (&number[..i], &number[i..]),
+            None => (number, ""),
+        };
+        if subscriber.is_empty() {
+            return None;
+        }
+        Some(TelNumber { subscriber, params })
+    }
+
+    /// Returns the number, without its parameters, as it is in the URI.
+    #[inline]
+    pub fn subscriber(&self) -> &'a str {
+        self.subscriber
+    }
+
+    /// Returns the number without its visual separators, `-`, `.`, `(` and
+    /// `)`.
+    pub fn digits(&self) -> String {
+        self.subscriber
+            .chars()
+            .filter(|&c| !is_visual_separator(c))
+            .collect()
+    }
+
+    /// Returns true if this is a global number, starting with `+` and the
+    /// country code, rather than a local number, which needs a
+    /// `phone-context`.
+    #[inline]
+    pub fn is_global(&self) -> bool {
+        self.subscriber.starts_with('+')
+    }
+
+    /// Returns the `phone-context` parameter, the domain or global number
+    /// prefix a local number belongs to.
+    pub fn phone_context(&self) -> Option<&'a str> {
+        self.param("phone-context")
+    }
+
+    /// Returns the `ext` parameter, the extension.
+    pub fn extension(&self) -> Option<&'a str> {
+        self.param("ext")
+    }
+
+    /// Returns the value of the parameter named `name`, compared without
+    /// regard to case. A parameter without `=` has an empty value.
+    pub fn param(&self, name: &str) -> Option<&'a str> {
+        self.params
+            .split(';')
+            .filter(|param| !param.is_empty())
+            .map(|param| match param.find('=') {
+                Some(i) => (&param[..i], &param[i + 1..]),
+                None => (param, ""),
+            })
+            .find(|&(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns a SIP URI for this number at `host`, with the `user=phone`
+    /// parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `host` isn't a valid host, or the number isn't
+    /// valid in the user of a SIP URI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::Uri;
+    /// # use httplike::uri::tel::TelNumber;
+    /// let uri: Uri = "tel:+1-201-555-0123".parse().unwrap();
+    /// let number = TelNumber::from_uri(&uri).unwrap();
+    ///
+    /// let sip = number.to_sip_uri("gw.example.com").unwrap();
+    /// assert_eq!(sip, "sip:+1-201-555-0123@gw.example.com;user=phone");
+    /// assert_eq!(TelNumber::from_uri(&sip), Some(number));
+    /// ```
+    #[cfg(feature = "sip")]
+    pub fn to_sip_uri(&self, host: &str) -> Result<Uri, InvalidUri> {
+        let mut s =
+            String::with_capacity(self.subscriber.len() + self.params.len() + host.len() + 16);
+        s.push_str("sip:");
+        s.push_str(self.subscriber);
+        s.push_str(self.params);
+        s.push('@');
+        s.push_str(host);
+        s.push_str(";user=phone");
+        Uri::from_maybe_shared(bytes::Bytes::from(s))
+    }
+}
+
+impl<'a> fmt::Debug for TelNumber<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TelNumber")
+            .field("subscriber", &self.subscriber)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+impl<'a> fmt::Display for TelNumber<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.subscriber)?;
+        f.write_str(self.params)
+    }
+}
+
+fn is_visual_separator(c: char) -> bool {
+    match c {
+        '-' | '.' | '(' | ')' => true,
+        _ => false,
+    }
+}
+
+#[cfg(feature = "sip")]
+fn sip_user(uri: &Uri) -> Option<&str> {
+    if !uri.is_sip() {
+        return None;
+    }
+    let params = super::sip::Params::from_uri(uri)?;
+    if !params.get("user")?.eq_ignore_ascii_case("phone") {
+        return None;
+    }
+    Some(uri.authority()?.userinfo()?.user())
+}
+
+#[cfg(not(feature = "sip"))]
+fn sip_user(_: &Uri) -> Option<&str> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        let uri = Uri::from_static("TEL:7042;Phone-Context=example.com;isub=1");
+        let number = TelNumber::from_uri(&uri).unwrap();
+        assert_eq!(number.subscriber(), "7042");
+        assert!(!number.is_global());
+        assert_eq!(number.phone_context(), Some("example.com"));
+        assert_eq!(number.param("ISUB"), Some("1"));
+        assert_eq!(number.extension(), None);
+        assert_eq!(number.to_string(), "7042;Phone-Context=example.com;isub=1");
+
+        let uri = Uri::from_static("tel:+1-(201)-555.0123");
+        assert_eq!(TelNumber::from_uri(&uri).unwrap().digits(), "+12015550123");
+
+        for s in &["tel:", "tel:;ext=1", "tel:?a", "tel:#x"] {
+            assert!(s.parse::<Uri>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sip")]
+    fn sip_uris() {
+        let uri = Uri::from_static("sip:+1-201-555-0123;ext=1@gw.example.com;user=phone");
+        let number = TelNumber::from_uri(&uri).unwrap();
+        assert_eq!(number.subscriber(), "+1-201-555-0123");
+        assert_eq!(number.extension(), Some("1"));
+        assert_eq!(
+            number.to_sip_uri("gw.example.com").unwrap(),
+            "sip:+1-201-555-0123;ext=1@gw.example.com;user=phone"
+        );
+        assert!(number.to_sip_uri("a b").is_err());
+
+        let uri = Uri::from_static("sip:+12015550123@gw.example.com");
+        assert_eq!(TelNumber::from_uri(&uri), None);
+        assert_eq!(TelNumber::from_uri(&Uri::from_static("/a")), None);
+        assert_eq!(TelNumber::from_uri(&Uri::from_static("host:80")), None);
+    }
+}
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use super::{ErrorKind, InvalidUri, Port, Uri, URI_CHARS};
+use super::{Authority, ErrorKind, InvalidUri, PathAndQuery, Port, Scheme, Uri, URI_CHARS};
 
 #[test]
 fn test_char_table() {
@@ -517,3 +517,309 @@ fn test_partial_eq_path_with_terminating_questionmark() {
 
     assert_eq!(uri, a);
 }
+
+#[test]
+fn test_display_len_matches_display() {
+    let cases = [
+        "/",
+        "*",
+        "/a/b?c=d",
+        "/path?",
+        "hyper.rs",
+        "hyper.rs:8080",
+        "http://hyper.rs",
+        "https://user@hyper.rs:8443/p?q",
+        "rtsp://example.com/stream",
+    ];
+
+    for case in cases.iter() {
+        let uri = Uri::from_str(case).unwrap();
+        assert_eq!(uri.display_len(), uri.to_string().len(), "{}", case);
+    }
+}
+
+#[test]
+fn test_set_path_and_authority() {
+    let mut uri = Uri::from_static("*");
+    uri.set_path("/a").unwrap();
+    assert_eq!(uri, "/a");
+
+    let mut uri = Uri::from_static("http://hyper.rs/?q");
+    uri.set_path("/b/c").unwrap();
+    assert_eq!(uri, "http://hyper.rs/b/c?q");
+    assert!(uri.set_path("/d#e").is_err());
+    uri.set_authority(Authority::from_static("example.com")).unwrap();
+    assert_eq!(uri, "http://example.com/b/c?q");
+
+    let mut uri = Uri::from_static("hyper.rs:443");
+    assert!(uri.set_path("/").is_err());
+    uri.set_authority(Authority::from_static("example.com:443")).unwrap();
+    assert_eq!(uri, "example.com:443");
+
+    let mut uri = Uri::from_static("/");
+    assert!(uri.set_authority(Authority::from_static("hyper.rs")).is_err());
+    assert_eq!(uri, "/");
+}
+
+#[test]
+#[cfg(feature = "http")]
+fn test_setters() {
+    let mut uri = Uri::from_static("hyper.rs:443");
+    assert!(uri.set_query(Some("q")).is_err());
+    assert!(uri.set_path_and_query(PathAndQuery::from_static("/")).is_err());
+    uri.set_scheme(Scheme::HTTPS).unwrap();
+    assert_eq!(uri, "https://hyper.rs:443/");
+
+    uri.set_query(Some("a=1")).unwrap();
+    assert_eq!(uri, "https://hyper.rs:443/?a=1");
+    assert!(uri.set_query(Some("b#c")).is_err());
+    uri.set_path_and_query(PathAndQuery::from_static("/p?q")).unwrap();
+    assert_eq!(uri, "https://hyper.rs:443/p?q");
+    uri.set_query(None).unwrap();
+    assert_eq!(uri, "https://hyper.rs:443/p");
+
+    let mut uri = Uri::from_static("/a?b");
+    assert!(uri.set_scheme(Scheme::HTTP).is_err());
+    uri.set_query(Some("")).unwrap();
+    assert_eq!(uri.query(), Some(""));
+    assert_eq!(uri.path(), "/a");
+}
+
+#[test]
+fn test_long_path_and_query_every_position() {
+    let path = "/uploads/2010/03/hello-kitty-darth-vader";
+    let query = "X-Amz-Date=20130524T000000Z&X-Amz-Expires=86400";
+
+    for i in 1..path.len() {
+        let uri: Uri = format!("{}?{}", &path[..i], query).parse().unwrap();
+        assert_eq!(uri.path(), &path[..i]);
+        assert_eq!(uri.query(), Some(query));
+
+        let uri: Uri = format!("{}#{}", &path[..i], query).parse().unwrap();
+        assert_eq!(uri.path(), &path[..i]);
+        assert_eq!(uri.query(), None);
+
+        let mut bad = String::from(path);
+        bad.insert(i, '"');
+        assert!(bad.parse::<Uri>().is_err());
+    }
+
+    for i in 0..query.len() {
+        let uri: Uri = format!("{}?{}#frag", path, &query[..i]).parse().unwrap();
+        assert_eq!(uri.query(), Some(&query[..i]));
+
+        let mut bad = format!("{}?{}", path, query);
+        bad.insert(path.len() + 1 + i, '\x7f');
+        assert!(bad.parse::<Uri>().is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "ws")]
+fn test_ws_schemes() {
+    use super::scheme::Scheme2;
+
+    let uri = Uri::from_static("WSS://example.com/chat");
+    assert_eq!(uri.scheme(), Some(&Scheme::WSS));
+    assert_eq!(uri.scheme_str(), Some("wss"));
+    assert_eq!(uri.path(), "/chat");
+    match uri.scheme().unwrap().inner {
+        Scheme2::Standard(_) => {}
+        _ => panic!("wss is not a standard scheme"),
+    }
+
+    let uri = Uri::from_static("ws://example.com:8080");
+    assert_eq!(uri.scheme(), Some(&Scheme::WS));
+    assert_eq!(uri.port_u16(), Some(8080));
+    assert_eq!("ws".parse::<Scheme>().unwrap(), Scheme::WS);
+    assert_ne!("wsx".parse::<Scheme>().unwrap(), Scheme::WS);
+    assert_eq!(Uri::from_static("ws://Example.com:80/").normalize(), "ws://example.com/");
+}
+
+#[test]
+#[cfg(feature = "sip")]
+fn test_sip_schemes() {
+    use super::scheme::Scheme2;
+
+    let uri = Uri::from_static("SIP:alice;day=tue@atlanta.com:5070;transport=tcp;lr?subject=x");
+    assert_eq!(uri.scheme(), Some(&Scheme::SIP));
+    assert_eq!(uri.authority().unwrap(), "alice;day=tue@atlanta.com:5070");
+    assert_eq!(uri.host(), Some("atlanta.com"));
+    assert_eq!(uri.port_u16(), Some(5070));
+    assert_eq!(uri.path(), ";transport=tcp;lr");
+    assert_eq!(uri.query(), Some("subject=x"));
+    assert_eq!(
+        uri.to_string(),
+        "sip:alice;day=tue@atlanta.com:5070;transport=tcp;lr?subject=x"
+    );
+    assert_eq!(uri, "sip:alice;day=tue@atlanta.com:5070;transport=tcp;lr?subject=x");
+    assert_eq!(uri.display_len(), uri.to_string().len());
+    match uri.scheme().unwrap().inner {
+        Scheme2::Standard(_) => {}
+        _ => panic!("sip is not a standard scheme"),
+    }
+
+    let uri = Uri::from_static("sips:bob@[::1]");
+    assert_eq!(uri.scheme(), Some(&Scheme::SIPS));
+    assert_eq!(uri.path(), "");
+    assert_eq!(uri, "sips:bob@[::1]");
+    assert_eq!(uri.normalize(), "sips:bob@[::1]");
+    assert_eq!("SIPS".parse::<Scheme>().unwrap(), Scheme::SIPS);
+
+    assert!("sip:".parse::<Uri>().is_err());
+    assert!("sip:bob@".parse::<Uri>().is_err());
+    assert!("sip:bob@host/path".parse::<Uri>().is_err());
+    assert!(uri.resolve("other").is_err());
+}
+
+#[test]
+fn test_default_ports() {
+    #[cfg(feature = "http")]
+    assert_eq!(Scheme::HTTP.default_port(), Some(80));
+    #[cfg(feature = "rtsp")]
+    assert_eq!(Scheme::RTSP.default_port(), Some(554));
+    #[cfg(feature = "rtsp")]
+    assert_eq!(Scheme::RTSPS.default_port(), Some(322));
+    #[cfg(feature = "sip")]
+    assert_eq!(Scheme::SIP.default_port(), Some(5060));
+    #[cfg(feature = "sip")]
+    assert_eq!(Scheme::SIPS.default_port(), Some(5061));
+    assert_eq!("x-custom".parse::<Scheme>().unwrap().default_port(), None);
+}
+
+#[test]
+fn test_builder_from_uri() {
+    use super::Builder;
+
+    for s in &["*", "/a?b", "example.com:443", "http://a.com", "http://a.com/b?c"] {
+        let uri = Uri::from_static(s);
+        assert_eq!(Builder::from(uri.clone()).build().unwrap(), uri);
+    }
+
+    let uri = Builder::from(Uri::from_static("http://u@a.com:8080/b?c"))
+        .authority("b.com")
+        .build()
+        .unwrap();
+    assert_eq!(uri, "http://b.com/b?c");
+
+    let err = Builder::from(Uri::from_static("/a")).scheme("http").build();
+    assert!(err.is_err());
+
+    #[cfg(feature = "sip")]
+    {
+        let uri = Uri::from_static("sip:bob@host;transport=tcp?subject=x");
+        assert_eq!(Builder::from(uri.clone()).build().unwrap(), uri);
+    }
+}
+
+#[test]
+fn test_fragments() {
+    let uri = Uri::from_static("http://127.0.0.1/#?");
+    assert_eq!(uri.fragment(), Some("?"));
+    assert_eq!(Uri::from_static("/a?b#c/d?e").fragment(), Some("c/d?e"));
+    assert_eq!(Uri::from_static("http://a.com#").fragment(), Some(""));
+    assert_eq!(Uri::from_static("/a?b").fragment(), None);
+    assert_eq!(Uri::from_static("/a#b c").fragment(), None);
+    assert_eq!(Uri::from_static("/a#b#c").fragment(), None);
+
+    let mut uri = Uri::from_static("http://a.com/b?c#d");
+    assert_eq!(uri, Uri::from_static("http://a.com/b?c"));
+    assert_eq!(uri.to_string(), "http://a.com/b?c");
+    uri.set_path("/e").unwrap();
+    uri.set_query(None).unwrap();
+    assert_eq!(uri.fragment(), Some("d"));
+    assert!(uri.set_fragment(Some("x#y")).is_err());
+    uri.set_fragment(Some("%7e")).unwrap();
+    assert_eq!(uri.normalize().fragment(), Some("~"));
+    assert_eq!(uri.resolve("f").unwrap().fragment(), None);
+    uri.set_fragment(None).unwrap();
+    assert_eq!(uri.fragment(), None);
+
+    let mut uri = Uri::from_static("hyper.rs:443");
+    assert!(uri.set_fragment(Some("a")).is_err());
+}
+
+#[test]
+fn test_request_target_forms() {
+    let uri = Uri::from_static("http://u:p@[::1]:8080?q#f");
+    assert_eq!(uri.to_origin_form().unwrap(), "/?q");
+    assert_eq!(uri.to_absolute_form().unwrap(), "http://[::1]:8080/?q");
+    assert_eq!(uri.to_authority_form().unwrap(), "[::1]:8080");
+    assert!(!uri.is_asterisk());
+
+    let uri = Uri::from_static("/a/b?c");
+    assert_eq!(uri.to_origin_form().unwrap(), "/a/b?c");
+    assert_eq!(Uri::from_static("http://h").to_origin_form().unwrap(), "/");
+    assert_eq!(uri.to_absolute_form(), None);
+    assert_eq!(uri.to_authority_form(), None);
+
+    let uri = Uri::from_static("*");
+    assert!(uri.is_asterisk());
+    assert_eq!(uri.to_origin_form(), None);
+
+    let uri = Uri::from_static("example.com:443");
+    assert_eq!(uri.to_origin_form(), None);
+    assert_eq!(uri.to_authority_form().unwrap(), "example.com:443");
+    assert_eq!(Uri::from_static("x-custom://h/").to_authority_form(), None);
+
+    #[cfg(feature = "sip")]
+    {
+        let uri = Uri::from_static("sip:bob@host;transport=tcp?subject=x");
+        assert_eq!(uri.to_origin_form(), None);
+        assert_eq!(
+            uri.to_absolute_form().unwrap(),
+            "sip:bob@host;transport=tcp?subject=x"
+        );
+        assert_eq!(uri.to_authority_form(), None);
+    }
+}
+
+#[test]
+#[cfg(feature = "tel")]
+fn test_tel_scheme() {
+    let uri = Uri::from_static("Tel:+1-201-555-0123;ext=1?x#f");
+    assert_eq!(uri.scheme(), Some(&Scheme::TEL));
+    assert_eq!(uri.authority(), None);
+    assert_eq!(uri.host(), None);
+    assert_eq!(uri.path(), "+1-201-555-0123;ext=1");
+    assert_eq!(uri.query(), Some("x"));
+    assert_eq!(uri.fragment(), Some("f"));
+    assert_eq!(uri.to_string(), "tel:+1-201-555-0123;ext=1?x");
+    assert_eq!(uri, "tel:+1-201-555-0123;ext=1?x");
+    assert_eq!(uri.display_len(), uri.to_string().len());
+    assert_eq!(uri.to_origin_form(), None);
+    assert_eq!(uri.to_absolute_form().unwrap(), "tel:+1-201-555-0123;ext=1?x");
+    assert_eq!(uri.normalize(), uri);
+    assert!(uri.resolve("7042").is_err());
+    assert_eq!(super::Builder::from(uri.clone()).build().unwrap(), uri);
+    assert_eq!("TEL".parse::<Scheme>().unwrap(), Scheme::TEL);
+
+    assert!("tel:".parse::<Uri>().is_err());
+    assert!("tel:/1".parse::<Uri>().is_err());
+    assert!("tel:1 2".parse::<Uri>().is_err());
+}
+
+#[test]
+fn test_try_from_bytes() {
+    use bytes::Bytes;
+    use core::convert::TryFrom;
+
+    let src = Bytes::from_static(b"rtsp://cam.local:554/live?session=1");
+    let uri = Uri::try_from(src.clone()).unwrap();
+    assert_eq!(uri, "rtsp://cam.local:554/live?session=1");
+    assert_eq!(uri.path().as_ptr(), src[20..].as_ptr());
+
+    let authority = Authority::try_from(src.slice(7..20)).unwrap();
+    assert_eq!(authority, "cam.local:554");
+    assert_eq!(authority.as_str().as_ptr(), src[7..].as_ptr());
+
+    let path = PathAndQuery::try_from(src.slice(20..)).unwrap();
+    assert_eq!(path.query(), Some("session=1"));
+    assert_eq!(path.as_str().as_ptr(), src[20..].as_ptr());
+
+    let scheme = Scheme::try_from(Bytes::from_static(b"coap")).unwrap();
+    assert_eq!(scheme.as_str(), "coap");
+    assert!(Scheme::try_from(Bytes::from_static(b"a b")).is_err());
+    assert!(Authority::try_from(Bytes::from_static(b"a/b")).is_err());
+    assert!(Uri::try_from(Bytes::new()).is_err());
+}
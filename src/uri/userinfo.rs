@@ -0,0 +1,80 @@
+use core::fmt;
+
+/// The userinfo component of an authority, like `user:password`.
+///
+/// The user and password are returned as they appear in the URI, so they
+/// may be percent-encoded.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Userinfo<'a> {
+    data: &'a str,
+}
+
+impl<'a> Userinfo<'a> {
+    pub(super) fn new(data: &'a str) -> Userinfo<'a> {
+        Userinfo { data }
+    }
+
+    /// Returns the user, before the first `:`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::uri::Authority;
+    /// let authority: Authority = "admin:secret@camera.local".parse().unwrap();
+    ///
+    /// let userinfo = authority.userinfo().unwrap();
+    /// assert_eq!(userinfo.user(), "admin");
+    /// assert_eq!(userinfo.password(), Some("secret"));
+    /// ```
+    pub fn user(&self) -> &'a str {
+        self.data.split(':').next().unwrap_or("")
+    }
+
+    /// Returns the password, after the first `:`, if there is one.
+    ///
+    /// A password may itself contain `:`. Putting passwords in URIs is
+    /// deprecated by RFC 3986, but RTSP cameras still expect it.
+    pub fn password(&self) -> Option<&'a str> {
+        self.data.find(':').map(|i| &self.data[i + 1..])
+    }
+
+    /// Returns the userinfo as a `str`, without the trailing `@`.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.data
+    }
+}
+
+impl<'a> fmt::Debug for Userinfo<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The password is left out so it doesn't end up in logs.
+        f.debug_struct("Userinfo")
+            .field("user", &self.user())
+            .field("password", &self.password().map(|_| ".."))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        let userinfo = Userinfo::new("a:b:c");
+        assert_eq!(userinfo.user(), "a");
+        assert_eq!(userinfo.password(), Some("b:c"));
+
+        let userinfo = Userinfo::new("a");
+        assert_eq!(userinfo.user(), "a");
+        assert_eq!(userinfo.password(), None);
+
+        let userinfo = Userinfo::new(":");
+        assert_eq!(userinfo.user(), "");
+        assert_eq!(userinfo.password(), Some(""));
+        assert_eq!(
+            format!("{:?}", userinfo),
+            "Userinfo { user: \"\", password: Some(\"..\") }"
+        );
+    }
+}
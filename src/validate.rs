@@ -0,0 +1,515 @@
+//! Protocol-aware validation of message heads.
+//!
+//! The builders only check that each method, URI and header is well formed
+//! on its own. The checks in this module look at a whole message head and
+//! report the protocol requirements it violates, such as a missing `Host`
+//! in an HTTP/1.1 request or a missing `CSeq` in an RTSP message.
+//!
+//! The checks are run with `Request::validate` and `Response::validate`.
+//! Each violation has a `Severity`: an error breaks a requirement of the
+//! protocol, while a warning breaks a recommendation, and is usually safe
+//! to forward.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::{Request, Version};
+//! use httplike::validate::Profile;
+//!
+//! let request = Request::get("/index.html")
+//!     .version(Version::HTTP_11)
+//!     .body(())
+//!     .unwrap();
+//!
+//! let violations = request.validate(Profile::Http11);
+//! assert_eq!(violations.len(), 1);
+//! assert_eq!(violations[0].to_string(), "missing host header");
+//! assert!(violations[0].is_error());
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::header::{HeaderMap, HeaderName};
+#[cfg(feature = "http")]
+use crate::protocol::Http;
+#[cfg(feature = "rtsp")]
+use crate::protocol::Rtsp;
+#[cfg(feature = "sip")]
+use crate::protocol::Sip;
+#[cfg(any(feature = "http", feature = "rtsp", feature = "sip"))]
+use crate::protocol::Spec;
+use crate::{Method, StatusCode, Uri, Version};
+
+/// The protocol whose requirements a message is validated against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Profile {
+    /// HTTP/1.1, as specified by RFC 7230.
+    #[cfg(feature = "http")]
+    Http11,
+
+    /// RTSP/1.0, as specified by RFC 2326.
+    #[cfg(feature = "rtsp")]
+    Rtsp10,
+
+    /// RTSP/2.0, as specified by RFC 7826.
+    #[cfg(feature = "rtsp")]
+    Rtsp20,
+
+    /// SIP/2.0, as specified by RFC 3261.
+    #[cfg(feature = "sip")]
+    Sip20,
+}
+
+/// How serious a violation is.
+///
+/// The severities are ordered, from `Warning` to `Error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// A recommendation of the protocol, a SHOULD of its RFC, isn't
+    /// followed.
+    Warning,
+
+    /// A requirement of the protocol, a MUST of its RFC, isn't met.
+    Error,
+}
+
+/// A protocol requirement violated by a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    message: Cow<'static, str>,
+    header: Option<HeaderName>,
+    severity: Severity,
+}
+
+impl Violation {
+    #[cfg(any(feature = "http", feature = "rtsp"))]
+    fn new(message: &'static str) -> Violation {
+        Violation {
+            message: Cow::Borrowed(message),
+            header: None,
+            severity: Severity::Error,
+        }
+    }
+
+    fn header(message: &'static str, name: &'static str) -> Violation {
+        Violation::header_owned(Cow::Borrowed(message), name)
+    }
+
+    fn header_owned(message: Cow<'static, str>, name: &'static str) -> Violation {
+        Violation {
+            message,
+            header: Some(HeaderName::from_static(name)),
+            severity: Severity::Error,
+        }
+    }
+
+    #[cfg(any(feature = "http", feature = "sip"))]
+    fn warning(mut self) -> Violation {
+        self.severity = Severity::Warning;
+        self
+    }
+
+    /// Returns a description of the violation.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns how serious the violation is.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns true if the violation is an `Error`.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Returns the name of the header the violation is about, if any.
+    pub fn header_name(&self) -> Option<&HeaderName> {
+        self.header.as_ref()
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+// Some arguments are only needed by some protocols.
+#[allow(unused_variables)]
+pub(crate) fn request(
+    method: &Method,
+    uri: &Uri,
+    version: Version,
+    headers: &HeaderMap,
+    profile: Profile,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let v = &mut violations;
+
+    match profile {
+        #[cfg(feature = "http")]
+        Profile::Http11 => {
+            if version == Version::HTTP_11 {
+                if required(v, headers, Http::required_request_headers()) {
+                    host_matches(v, uri, headers);
+                }
+            } else if version != Version::HTTP_10 {
+                v.push(Violation::new("version is not HTTP/1.x"));
+            }
+            http_framing(v, headers);
+        }
+        #[cfg(feature = "rtsp")]
+        Profile::Rtsp10 => {
+            let names = Rtsp::required_request_headers();
+            rtsp(v, version, Version::RTSP_1, headers, names);
+            rtsp_method(v, method, Version::RTSP_1, Version::RTSP_2);
+            let needs_session = *method == Method::PLAY
+                || *method == Method::PAUSE
+                || *method == Method::RECORD
+                || *method == Method::TEARDOWN;
+            if needs_session && !headers.contains_key("session") {
+                v.push(Violation::header("missing session header", "session"));
+            }
+        }
+        #[cfg(feature = "rtsp")]
+        Profile::Rtsp20 => {
+            let names = Rtsp::required_request_headers();
+            rtsp(v, version, Version::RTSP_2, headers, names);
+            rtsp_method(v, method, Version::RTSP_2, Version::RTSP_1);
+            let needs_session = *method == Method::PLAY || *method == Method::PAUSE;
+            if needs_session && !headers.contains_key("session") {
+                v.push(Violation::header("missing session header", "session"));
+            }
+            // RFC 7826 section 18.33: an unsigned 32-bit identifier.
+            match headers.get("pipelined-requests").map(|value| value.to_str()) {
+                None => {}
+                Some(Ok(s)) if s.parse::<u32>().is_ok() => {}
+                Some(_) => v.push(Violation::header(
+                    "invalid pipelined-requests header",
+                    "pipelined-requests",
+                )),
+            }
+        }
+        #[cfg(feature = "sip")]
+        Profile::Sip20 => {
+            required(v, headers, Sip::required_request_headers());
+
+            let hops = headers
+                .get("max-forwards")
+                .map(|value| value.to_str().ok()?.parse::<u8>().ok());
+            match hops {
+                None => {}
+                // RFC 3261 section 8.1.1.6: it should start at 70.
+                Some(Some(hops)) if hops > 70 => v.push(
+                    Violation::header("max-forwards header above 70", "max-forwards").warning(),
+                ),
+                Some(Some(_)) => {}
+                Some(_) => v.push(Violation::header("invalid max-forwards header", "max-forwards")),
+            }
+        }
+    }
+
+    violations
+}
+
+#[allow(unused_variables)]
+pub(crate) fn response(
+    status: StatusCode,
+    version: Version,
+    headers: &HeaderMap,
+    profile: Profile,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let v = &mut violations;
+
+    match profile {
+        #[cfg(feature = "http")]
+        Profile::Http11 => {
+            if version != Version::HTTP_11 && version != Version::HTTP_10 {
+                v.push(Violation::new("version is not HTTP/1.x"));
+            }
+            if status.is_informational() || status == StatusCode::NO_CONTENT {
+                if headers.contains_key("content-length") {
+                    v.push(Violation::header(
+                        "content-length header on a response without a body",
+                        "content-length",
+                    ));
+                }
+                if headers.contains_key("transfer-encoding") {
+                    v.push(Violation::header(
+                        "transfer-encoding header on a response without a body",
+                        "transfer-encoding",
+                    ));
+                }
+            }
+            http_framing(v, headers);
+        }
+        #[cfg(feature = "rtsp")]
+        Profile::Rtsp10 => {
+            rtsp(v, version, Version::RTSP_1, headers, Rtsp::required_response_headers())
+        }
+        #[cfg(feature = "rtsp")]
+        Profile::Rtsp20 => {
+            rtsp(v, version, Version::RTSP_2, headers, Rtsp::required_response_headers())
+        }
+        #[cfg(feature = "sip")]
+        Profile::Sip20 => {
+            required(v, headers, Sip::required_response_headers());
+        }
+    }
+
+    violations
+}
+
+// RFC 7230 section 3.3: Content-Length and Transfer-Encoding.
+#[cfg(feature = "http")]
+fn http_framing(v: &mut Vec<Violation>, headers: &HeaderMap) {
+    if headers.contains_key("content-length") && headers.contains_key("transfer-encoding") {
+        v.push(Violation::header(
+            "both content-length and transfer-encoding headers",
+            "content-length",
+        ));
+    }
+
+    let mut lengths = headers.get_all("content-length").iter();
+    if let Some(first) = lengths.next() {
+        if !is_number(first.as_bytes()) {
+            v.push(Violation::header("invalid content-length header", "content-length"));
+        } else if lengths.any(|other| other != first) {
+            v.push(Violation::header(
+                "conflicting content-length headers",
+                "content-length",
+            ));
+        }
+    }
+}
+
+// RFC 7230 section 5.4: a client sending a request in absolute-form should
+// send the authority of the target as the host.
+#[cfg(feature = "http")]
+fn host_matches(v: &mut Vec<Violation>, uri: &Uri, headers: &HeaderMap) {
+    let authority = match uri.authority() {
+        Some(authority) if uri.scheme().is_some() => authority.as_str(),
+        _ => return,
+    };
+    let host = &authority[authority.rfind('@').map_or(0, |i| i + 1)..];
+    if !headers["host"].as_bytes().eq_ignore_ascii_case(host.as_bytes()) {
+        let message = "host header does not match the request target";
+        v.push(Violation::header(message, "host").warning());
+    }
+}
+
+// RFC 2326 section 12.17 and RFC 7826 section 18.20: every request and
+// response carries a CSeq.
+#[cfg(feature = "rtsp")]
+fn rtsp(
+    v: &mut Vec<Violation>,
+    version: Version,
+    expected: Version,
+    headers: &HeaderMap,
+    required_headers: &[&'static str],
+) {
+    if version != expected {
+        v.push(Violation::new(if expected == Version::RTSP_1 {
+            "version is not RTSP/1.0"
+        } else {
+            "version is not RTSP/2.0"
+        }));
+    }
+
+    if required(v, headers, required_headers) && !is_number(headers["cseq"].as_bytes()) {
+        v.push(Violation::header("invalid cseq header", "cseq"));
+    }
+}
+
+// A method of the other version of RTSP isn't defined by this one, like
+// `PLAY_NOTIFY` in RTSP/1.0. Other unknown methods are extensions.
+#[cfg(feature = "rtsp")]
+fn rtsp_method(v: &mut Vec<Violation>, method: &Method, version: Version, other: Version) {
+    if !Rtsp::is_known_method(method, version) && Rtsp::is_known_method(method, other) {
+        v.push(Violation::new(if version == Version::RTSP_1 {
+            "method is not defined by RTSP/1.0"
+        } else {
+            "method is not defined by RTSP/2.0"
+        }));
+    }
+}
+
+// Checks the headers of `Spec::required_request_headers` or
+// `Spec::required_response_headers`, returning true if they are all there.
+// Each must be present exactly once, except for SIP's Via, which is a list
+// (RFC 3261 section 8.1.1).
+#[cfg(any(feature = "http", feature = "rtsp", feature = "sip"))]
+fn required(v: &mut Vec<Violation>, headers: &HeaderMap, names: &[&'static str]) -> bool {
+    let mut ok = true;
+    for &name in names {
+        match headers.get_all(name).iter().count() {
+            0 => {
+                let message = format!("missing {} header", name);
+                v.push(Violation::header_owned(message.into(), name));
+                ok = false;
+            }
+            1 => {}
+            _ if name == "via" => {}
+            _ => {
+                let message = format!("multiple {} headers", name);
+                v.push(Violation::header_owned(message.into(), name));
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+#[cfg(any(feature = "http", feature = "rtsp"))]
+fn is_number(bytes: &[u8]) -> bool {
+    !bytes.is_empty() && bytes.iter().all(u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Request, Response};
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn http11_request() {
+        let request = Request::post("/upload")
+            .header("host", "example.com")
+            .header("content-length", "5")
+            .header("content-length", "6")
+            .header("transfer-encoding", "chunked")
+            .body(())
+            .unwrap();
+
+        let violations = request.validate(Profile::Http11);
+        let messages: Vec<_> = violations.iter().map(Violation::message).collect();
+        assert_eq!(
+            messages,
+            [
+                "both content-length and transfer-encoding headers",
+                "conflicting content-length headers",
+            ]
+        );
+        assert_eq!(violations[0].header_name(), Some(&crate::header::CONTENT_LENGTH));
+
+        let request = Request::get("/").version(Version::HTTP_10).body(()).unwrap();
+        assert!(request.validate(Profile::Http11).is_empty());
+
+        let request = Request::get("http://user@Example.com:8080/")
+            .header("host", "example.com:8080")
+            .body(())
+            .unwrap();
+        assert!(request.validate(Profile::Http11).is_empty());
+
+        let request = Request::get("http://example.com/")
+            .header("host", "example.org")
+            .body(())
+            .unwrap();
+        let violations = request.validate(Profile::Http11);
+        assert_eq!(violations[0].severity(), Severity::Warning);
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn http11_response() {
+        let response = Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .header("content-length", "0")
+            .body(())
+            .unwrap();
+
+        let violations = response.validate(Profile::Http11);
+        assert_eq!(
+            violations[0].message(),
+            "content-length header on a response without a body"
+        );
+        assert_eq!(violations.len(), 1);
+
+        assert!(Response::new(()).validate(Profile::Http11).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "rtsp")]
+    fn rtsp10_request() {
+        let request = Request::builder()
+            .method(Method::PLAY)
+            .uri("rtsp://example.com/stream")
+            .version(Version::RTSP_1)
+            .header("cseq", "two")
+            .body(())
+            .unwrap();
+
+        let violations = request.validate(Profile::Rtsp10);
+        let messages: Vec<_> = violations.iter().map(Violation::message).collect();
+        assert_eq!(messages, ["invalid cseq header", "missing session header"]);
+
+        let request = Request::describe("rtsp://example.com/stream").body(()).unwrap();
+        assert!(request.validate(Profile::Rtsp10).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "rtsp")]
+    fn rtsp20_request() {
+        let request = Request::builder()
+            .method(Method::RECORD)
+            .uri("rtsp://example.com/stream")
+            .version(Version::RTSP_2)
+            .header("cseq", "1")
+            .header("pipelined-requests", "-1")
+            .body(())
+            .unwrap();
+
+        let violations = request.validate(Profile::Rtsp20);
+        let messages: Vec<_> = violations.iter().map(Violation::message).collect();
+        assert_eq!(
+            messages,
+            ["method is not defined by RTSP/2.0", "invalid pipelined-requests header"]
+        );
+
+        let request = Request::builder()
+            .method(Method::PLAY_NOTIFY)
+            .uri("rtsp://example.com/stream")
+            .version(Version::RTSP_2)
+            .header("cseq", "2")
+            .header(crate::header::NOTIFY_REASON, "end-of-stream")
+            .body(())
+            .unwrap();
+        assert!(request.validate(Profile::Rtsp20).is_empty());
+        assert_eq!(request.validate(Profile::Rtsp10).len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "sip")]
+    fn sip20_request() {
+        let request = Request::builder()
+            .header("via", "SIP/2.0/UDP pc33.example.com")
+            .header("max-forwards", "300")
+            .header("call-id", "a84b4c76e66710")
+            .header("cseq", "314159 INVITE")
+            .header("from", "<sip:alice@example.com>")
+            .body(())
+            .unwrap();
+
+        let violations = request.validate(Profile::Sip20);
+        let messages: Vec<_> = violations.iter().map(Violation::message).collect();
+        assert_eq!(messages, ["missing to header", "invalid max-forwards header"]);
+        assert!(violations.iter().all(Violation::is_error));
+
+        let mut request = request;
+        request.headers_mut().insert("to", "<sip:bob@example.com>".parse().unwrap());
+        request.headers_mut().insert("max-forwards", "200".parse().unwrap());
+        request.headers_mut().append("via", "SIP/2.0/UDP proxy.example.com".parse().unwrap());
+        let violations = request.validate(Profile::Sip20);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].message(), "max-forwards header above 70");
+        assert!(!violations[0].is_error());
+        assert!(Severity::Warning < Severity::Error);
+    }
+}
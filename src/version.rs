@@ -19,7 +19,7 @@
 //! println!("{:?}", http2);
 //! ```
 
-use std::fmt;
+use core::fmt;
 
 /// Represents a version of the HTTP spec.
 #[derive(PartialEq, PartialOrd, Copy, Clone, Eq, Ord, Hash)]
@@ -49,6 +49,56 @@ impl Version {
     /// `RTSP/1.0`
     #[cfg(feature = "rtsp")]
     pub const RTSP_1: Version = Version(Protocol::Rtsp1);
+
+    /// `RTSP/2.0`
+    #[cfg(feature = "rtsp")]
+    pub const RTSP_2: Version = Version(Protocol::Rtsp2);
+
+    /// `SIP/2.0`
+    #[cfg(feature = "sip")]
+    pub const SIP_2: Version = Version(Protocol::Sip2);
+
+    // Every version this build knows about, to map text forms back.
+    pub(crate) const ALL: &'static [Version] = &[
+        #[cfg(feature = "http")]
+        Version::HTTP_09,
+        #[cfg(feature = "http")]
+        Version::HTTP_10,
+        #[cfg(feature = "http")]
+        Version::HTTP_11,
+        #[cfg(feature = "http")]
+        Version::HTTP_2,
+        #[cfg(feature = "http")]
+        Version::HTTP_3,
+        #[cfg(feature = "rtsp")]
+        Version::RTSP_1,
+        #[cfg(feature = "rtsp")]
+        Version::RTSP_2,
+        #[cfg(feature = "sip")]
+        Version::SIP_2,
+    ];
+
+    /// Returns the protocol this version belongs to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use httplike::{Protocol, Version};
+    /// assert_eq!(Version::HTTP_11.protocol(), Protocol::Http);
+    /// ```
+    pub fn protocol(&self) -> crate::Protocol {
+        use self::Protocol::*;
+
+        match self.0 {
+            #[cfg(feature = "http")]
+            Http09 | Http10 | Http11 | H2 | H3 => crate::Protocol::Http,
+            #[cfg(feature = "rtsp")]
+            Rtsp1 | Rtsp2 => crate::Protocol::Rtsp,
+            #[cfg(feature = "sip")]
+            Sip2 => crate::Protocol::Sip,
+            __NonExhaustive => crate::Protocol::Other,
+        }
+    }
 }
 
 #[derive(PartialEq, PartialOrd, Copy, Clone, Eq, Ord, Hash)]
@@ -65,6 +115,10 @@ enum Protocol {
     H3,
     #[cfg(feature = "rtsp")]
     Rtsp1,
+    #[cfg(feature = "rtsp")]
+    Rtsp2,
+    #[cfg(feature = "sip")]
+    Sip2,
     __NonExhaustive,
 }
 
@@ -84,15 +138,16 @@ impl Default for Version {
     #[inline]
     #[cfg(all(not(feature = "http"), not(feature = "rtsp"), feature="sip"))]
     fn default() -> Version {
-        Version::RTSP_1
+        Version::SIP_2
     }
 }
 
-impl fmt::Debug for Version {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Version {
+    // The text form used in start lines, like `HTTP/1.1`.
+    pub(crate) fn as_str(&self) -> &'static str {
         use self::Protocol::*;
 
-        f.write_str(match self.0 {
+        match self.0 {
             #[cfg(feature = "http")]
             Http09 => "HTTP/0.9",
             #[cfg(feature = "http")]
@@ -105,7 +160,17 @@ impl fmt::Debug for Version {
             H3 => "HTTP/3.0",
             #[cfg(feature = "rtsp")]
             Rtsp1  => "RTSP/1.0",
+            #[cfg(feature = "rtsp")]
+            Rtsp2 => "RTSP/2.0",
+            #[cfg(feature = "sip")]
+            Sip2 => "SIP/2.0",
             __NonExhaustive => unreachable!(),
-        })
+        }
+    }
+}
+
+impl fmt::Debug for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
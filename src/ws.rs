@@ -0,0 +1,462 @@
+//! The WebSocket opening handshake, from RFC 6455 section 4.
+//!
+//! A WebSocket connection starts as an HTTP/1.1 `GET` request asking to
+//! upgrade the connection. `validate_upgrade` checks such a request,
+//! `upgrade_response` builds the `101 Switching Protocols` response
+//! accepting it, and `accept_key` computes the `Sec-WebSocket-Accept` value
+//! a client checks the response against.
+//!
+//! # Examples
+//!
+//! ```
+//! use httplike::{ws, Request, StatusCode};
+//!
+//! let request = Request::get("/chat")
+//!     .header("host", "example.com")
+//!     .header("upgrade", "websocket")
+//!     .header("connection", "Upgrade")
+//!     .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+//!     .header("sec-websocket-version", "13")
+//!     .body(())
+//!     .unwrap();
+//!
+//! let response = ws::upgrade_response(&request).unwrap();
+//! assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+//! assert_eq!(
+//!     response.headers()["sec-websocket-accept"],
+//!     "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+//! );
+//! ```
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::header::{self, HeaderMap, HeaderName, HeaderValue};
+use crate::{Method, Request, Response, StatusCode, Version};
+
+/// The only version of the protocol, sent in `Sec-WebSocket-Version`.
+pub const VERSION: &str = "13";
+
+// Appended to the key before hashing, RFC 6455 section 1.3.
+const GUID: &[u8] = b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// An error returned when a request is not a valid WebSocket upgrade.
+pub struct UpgradeError {
+    kind: UpgradeErrorKind,
+}
+
+#[derive(Debug)]
+enum UpgradeErrorKind {
+    Method,
+    Version,
+    Host,
+    Upgrade,
+    Connection,
+    Key,
+    WebSocketVersion,
+}
+
+impl UpgradeError {
+    fn new(kind: UpgradeErrorKind) -> UpgradeError {
+        UpgradeError { kind }
+    }
+
+    /// Returns true if the request asked for a WebSocket version other than
+    /// 13.
+    ///
+    /// The handshake should then be refused with a `426 Upgrade Required`
+    /// response carrying a `Sec-WebSocket-Version` header with `VERSION`,
+    /// so the client can try again.
+    pub fn is_version(&self) -> bool {
+        match self.kind {
+            UpgradeErrorKind::WebSocketVersion => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for UpgradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("UpgradeError").field(&self.kind).finish()
+    }
+}
+
+impl fmt::Display for UpgradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.kind {
+            UpgradeErrorKind::Method => "websocket upgrade is not a GET request",
+            UpgradeErrorKind::Version => "websocket upgrade is not an HTTP/1.1 request",
+            UpgradeErrorKind::Host => "missing host header",
+            UpgradeErrorKind::Upgrade => "missing websocket upgrade header",
+            UpgradeErrorKind::Connection => "missing upgrade connection option",
+            UpgradeErrorKind::Key => "invalid sec-websocket-key header",
+            UpgradeErrorKind::WebSocketVersion => "unsupported websocket version",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for UpgradeError {}
+
+/// Computes the `Sec-WebSocket-Accept` value for a `Sec-WebSocket-Key`.
+///
+/// The value is the base64 of the SHA-1 hash of the key followed by a fixed
+/// GUID. A client compares it with the header of the response to know the
+/// server understood the handshake.
+///
+/// # Examples
+///
+/// ```
+/// # use httplike::ws;
+/// let accept = ws::accept_key(b"dGhlIHNhbXBsZSBub25jZQ==");
+/// assert_eq!(accept, "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+/// ```
+pub fn accept_key(key: &[u8]) -> HeaderValue {
+    let mut sha1 = Sha1::new();
+    sha1.update(key);
+    sha1.update(GUID);
+    let encoded = base64(&sha1.finish());
+    HeaderValue::from_bytes(&encoded).expect("base64 is a valid header value")
+}
+
+/// Checks that `request` is a valid WebSocket upgrade request.
+///
+/// The request must be an HTTP/1.1 `GET` with a `Host` header, a `websocket`
+/// token in `Upgrade`, an `upgrade` option in `Connection`, a single
+/// `Sec-WebSocket-Key` holding 16 bytes in base64, and a
+/// `Sec-WebSocket-Version` of 13. Tokens are compared without regard to
+/// case. On success, the key is returned.
+///
+/// # Errors
+///
+/// Returns an error describing the first requirement the request doesn't
+/// meet.
+pub fn validate_upgrade<T>(request: &Request<T>) -> Result<&HeaderValue, UpgradeError> {
+    if request.method() != Method::GET {
+        return Err(UpgradeError::new(UpgradeErrorKind::Method));
+    }
+    if request.version() != Version::HTTP_11 {
+        return Err(UpgradeError::new(UpgradeErrorKind::Version));
+    }
+
+    let headers = request.headers();
+    if !headers.contains_key(header::HOST) {
+        return Err(UpgradeError::new(UpgradeErrorKind::Host));
+    }
+    if !has_token(headers, header::UPGRADE, "websocket") {
+        return Err(UpgradeError::new(UpgradeErrorKind::Upgrade));
+    }
+    if !has_token(headers, header::CONNECTION, "upgrade") {
+        return Err(UpgradeError::new(UpgradeErrorKind::Connection));
+    }
+
+    let mut keys = headers.get_all(header::SEC_WEBSOCKET_KEY).iter();
+    let key = match (keys.next(), keys.next()) {
+        (Some(key), None) if is_key(key.as_bytes()) => key,
+        _ => return Err(UpgradeError::new(UpgradeErrorKind::Key)),
+    };
+
+    let mut versions = headers.get_all(header::SEC_WEBSOCKET_VERSION).iter();
+    match (versions.next(), versions.next()) {
+        (Some(version), None) if version == VERSION => Ok(key),
+        _ => Err(UpgradeError::new(UpgradeErrorKind::WebSocketVersion)),
+    }
+}
+
+/// Builds the `101 Switching Protocols` response accepting a WebSocket
+/// upgrade.
+///
+/// The request is checked with `validate_upgrade`. The response has the
+/// `Upgrade`, `Connection` and `Sec-WebSocket-Accept` headers the handshake
+/// requires; a selected subprotocol or extensions can be added to its
+/// headers before sending it.
+///
+/// # Errors
+///
+/// Returns an error if `request` is not a valid upgrade request.
+pub fn upgrade_response<T>(request: &Request<T>) -> Result<Response<()>, UpgradeError> {
+    let key = validate_upgrade(request)?;
+
+    let mut response = Response::new(());
+    *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+    *response.version_mut() = Version::HTTP_11;
+    let headers = response.headers_mut();
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(header::SEC_WEBSOCKET_ACCEPT, accept_key(key.as_bytes()));
+    Ok(response)
+}
+
+// Returns true if a comma-separated token of any value of `name` is `token`.
+fn has_token(headers: &HeaderMap, name: HeaderName, token: &str) -> bool {
+    headers.get_all(name).iter().any(|value| {
+        value
+            .as_bytes()
+            .split(|&b| b == b',')
+            .any(|item| trim(item).eq_ignore_ascii_case(token.as_bytes()))
+    })
+}
+
+fn trim(mut s: &[u8]) -> &[u8] {
+    while let Some((&b, rest)) = s.split_first() {
+        if b != b' ' && b != b'\t' {
+            break;
+        }
+        s = rest;
+    }
+    while let Some((&b, rest)) = s.split_last() {
+        if b != b' ' && b != b'\t' {
+            break;
+        }
+        s = rest;
+    }
+    s
+}
+
+// A key is 16 bytes in base64: 22 characters and two `=` of padding.
+fn is_key(key: &[u8]) -> bool {
+    key.len() == 24 && key[..22].iter().all(|&b| base64_digit(b).is_some()) && &key[22..] == b"=="
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_digit(b: u8) -> Option<u8> {
+    BASE64.iter().position(|&d| d == b).map(|i| i as u8)
+}
+
+// Standard base64 with padding, of a SHA-1 hash.
+fn base64(src: &[u8; 20]) -> [u8; 28] {
+    let mut dst = [b'='; 28];
+    for (chunk, out) in src.chunks(3).zip(dst.chunks_mut(4)) {
+        let b = [
+            chunk[0],
+            chunk.get(1).cloned().unwrap_or(0),
+            chunk.get(2).cloned().unwrap_or(0),
+        ];
+        let digits = [
+            b[0] >> 2,
+            (b[0] & 0x03) << 4 | b[1] >> 4,
+            (b[1] & 0x0f) << 2 | b[2] >> 6,
+            b[2] & 0x3f,
+        ];
+        for i in 0..=chunk.len() {
+            out[i] = BASE64[usize::from(digits[i])];
+        }
+    }
+    dst
+}
+
+// SHA-1, FIPS 180-4. Only used for the handshake, where it isn't a security
+// measure.
+struct Sha1 {
+    state: [u32; 5],
+    block: [u8; 64],
+    block_len: usize,
+    len: u64,
+}
+
+impl Sha1 {
+    fn new() -> Sha1 {
+        Sha1 {
+            state: [
+                0x6745_2301,
+                0xefcd_ab89,
+                0x98ba_dcfe,
+                0x1032_5476,
+                0xc3d2_e1f0,
+            ],
+            block: [0; 64],
+            block_len: 0,
+            len: 0,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.len += data.len() as u64;
+        for &b in data {
+            self.block[self.block_len] = b;
+            self.block_len += 1;
+            if self.block_len == 64 {
+                self.compress();
+                self.block_len = 0;
+            }
+        }
+    }
+
+    fn finish(mut self) -> [u8; 20] {
+        let bits = self.len * 8;
+        self.update(&[0x80]);
+        while self.block_len != 56 {
+            self.update(&[0]);
+        }
+        for i in 0..8 {
+            self.block[56 + i] = (bits >> (56 - 8 * i)) as u8;
+        }
+        self.compress();
+
+        let mut hash = [0; 20];
+        for (i, word) in self.state.iter().enumerate() {
+            for j in 0..4 {
+                hash[4 * i + j] = (word >> (24 - 8 * j)) as u8;
+            }
+        }
+        hash
+    }
+
+    fn compress(&mut self) {
+        let mut w = [0u32; 80];
+        for (word, b) in w.iter_mut().zip(self.block.chunks(4)) {
+            *word = u32::from(b[0]) << 24
+                | u32::from(b[1]) << 16
+                | u32::from(b[2]) << 8
+                | u32::from(b[3]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e) = (
+            self.state[0],
+            self.state[1],
+            self.state[2],
+            self.state[3],
+            self.state[4],
+        );
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (state, x) in self.state.iter_mut().zip(&[a, b, c, d, e]) {
+            *state = state.wrapping_add(*x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgrade() -> crate::request::Builder {
+        Request::get("/chat")
+            .header("host", "example.com")
+            .header("upgrade", "websocket")
+            .header("connection", "keep-alive, Upgrade")
+            .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .header("sec-websocket-version", "13")
+    }
+
+    #[test]
+    fn sha1_vectors() {
+        fn hex(hash: [u8; 20]) -> String {
+            hash.iter().map(|b| format!("{:02x}", b)).collect()
+        }
+        fn sha1(data: &[u8]) -> String {
+            let mut sha1 = Sha1::new();
+            sha1.update(data);
+            hex(sha1.finish())
+        }
+
+        assert_eq!(sha1(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(sha1(b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(
+            sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+        );
+        assert_eq!(
+            sha1(&[b'a'; 1000]),
+            "291e9a6c66994949b57ba5e650361e98fc36b1ba"
+        );
+    }
+
+    #[test]
+    fn upgrade_headers() {
+        let response = upgrade_response(&upgrade().body(()).unwrap()).unwrap();
+        assert_eq!(response.version(), Version::HTTP_11);
+        assert_eq!(response.headers()["upgrade"], "websocket");
+        assert_eq!(response.headers()["connection"], "Upgrade");
+        assert_eq!(
+            response.headers()["sec-websocket-accept"],
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+
+        let request = upgrade()
+            .header("upgrade", "h2c, WebSocket")
+            .body(())
+            .unwrap();
+        assert!(validate_upgrade(&request).is_ok());
+    }
+
+    #[test]
+    fn invalid_upgrades() {
+        fn error(request: crate::request::Builder) -> String {
+            validate_upgrade(&request.body(()).unwrap())
+                .unwrap_err()
+                .to_string()
+        }
+
+        assert_eq!(
+            error(upgrade().method(Method::POST)),
+            "websocket upgrade is not a GET request"
+        );
+        assert_eq!(
+            error(upgrade().version(Version::HTTP_10)),
+            "websocket upgrade is not an HTTP/1.1 request"
+        );
+
+        let mut request = upgrade().body(()).unwrap();
+        request.headers_mut().remove("host");
+        assert_eq!(
+            validate_upgrade(&request).unwrap_err().to_string(),
+            "missing host header"
+        );
+
+        let mut request = upgrade().body(()).unwrap();
+        request
+            .headers_mut()
+            .insert("upgrade", HeaderValue::from_static("websockets"));
+        assert!(validate_upgrade(&request).is_err());
+
+        let mut request = upgrade().body(()).unwrap();
+        request
+            .headers_mut()
+            .insert("connection", HeaderValue::from_static("close"));
+        assert!(validate_upgrade(&request).is_err());
+
+        assert_eq!(
+            error(upgrade().header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")),
+            "invalid sec-websocket-key header"
+        );
+
+        let mut request = upgrade().body(()).unwrap();
+        request.headers_mut().insert(
+            "sec-websocket-key",
+            HeaderValue::from_static("dGhlIHNhbXBsZQ=="),
+        );
+        assert!(validate_upgrade(&request).is_err());
+
+        let mut request = upgrade().body(()).unwrap();
+        request
+            .headers_mut()
+            .insert("sec-websocket-version", HeaderValue::from_static("8"));
+        let err = validate_upgrade(&request).unwrap_err();
+        assert!(err.is_version());
+        assert_eq!(err.to_string(), "unsupported websocket version");
+    }
+}
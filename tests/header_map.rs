@@ -303,6 +303,48 @@ fn append_multiple_values() {
     assert_eq!(&vals, &[&"json", &"html", &"xml"]);
 }
 
+#[test]
+fn insert_all_replaces_existing_values() {
+    let mut map = HeaderMap::new();
+
+    map.append(header::HOST, "a".parse().unwrap());
+    map.append(header::HOST, "b".parse().unwrap());
+    map.append(header::ACCEPT, "c".parse().unwrap());
+
+    map.insert_all(vec![
+        (header::HOST, "d".parse().unwrap()),
+        (header::CONTENT_TYPE, "e".parse().unwrap()),
+        (header::HOST, "f".parse().unwrap()),
+        (header::CONTENT_TYPE, "g".parse().unwrap()),
+    ]);
+
+    let hosts = map.get_all(&header::HOST).iter().collect::<Vec<_>>();
+    assert_eq!(&hosts, &[&"d", &"f"]);
+
+    let types = map.get_all(&header::CONTENT_TYPE).iter().collect::<Vec<_>>();
+    assert_eq!(&types, &[&"e", &"g"]);
+
+    assert_eq!(map[header::ACCEPT], "c");
+    assert_eq!(map.len(), 5);
+}
+
+#[test]
+fn append_all_many_custom_headers() {
+    let hdrs = custom_std(500);
+    let mut map = HeaderMap::new();
+
+    map.append_all(hdrs.iter().map(|name| (name.clone(), HeaderValue::from_static("x"))));
+    map.append_all(hdrs.iter().map(|name| (name.clone(), HeaderValue::from_static("y"))));
+
+    assert_eq!(map.len(), 1000);
+    assert_eq!(map.keys_len(), 500);
+
+    for name in &hdrs {
+        let vals = map.get_all(name).iter().collect::<Vec<_>>();
+        assert_eq!(&vals, &[&"x", &"y"]);
+    }
+}
+
 fn custom_std(n: usize) -> Vec<HeaderName> {
     (0..n)
         .map(|i| {
@@ -409,3 +451,49 @@ fn value_htab() {
     HeaderValue::from_static("hello\tworld");
     HeaderValue::from_str("hello\tworld").unwrap();
 }
+
+#[test]
+fn auto_sensitive_well_known_headers() {
+    let mut map = HeaderMap::new();
+
+    map.insert(header::AUTHORIZATION, "secret".parse().unwrap());
+    map.append(header::PROXY_AUTHORIZATION, "secret".parse().unwrap());
+    map.append(header::COOKIE, "a=b".parse().unwrap());
+    map.append(header::COOKIE, "c=d".parse().unwrap());
+    map.entry(header::SET_COOKIE)
+        .or_insert("e=f".parse().unwrap());
+    map.insert(header::HOST, "example.com".parse().unwrap());
+
+    for (name, value) in map.iter() {
+        assert_eq!(value.is_sensitive(), *name != header::HOST, "{}", name);
+    }
+
+    let mut map = HeaderMap::new();
+    map.set_auto_sensitive(false);
+    map.insert(header::AUTHORIZATION, "secret".parse().unwrap());
+    assert!(!map[header::AUTHORIZATION].is_sensitive());
+
+    let mut map = HeaderMap::default();
+    map.insert(header::AUTHORIZATION, HeaderValue::from_static("secret"));
+    assert!(!map[header::AUTHORIZATION].is_sensitive());
+}
+
+#[test]
+fn get_all_mut_rewrites_each_value() {
+    let mut map = HeaderMap::new();
+
+    map.append(header::VIA, "a".parse().unwrap());
+    map.append(header::HOST, "b".parse().unwrap());
+    map.append(header::VIA, "c".parse().unwrap());
+    map.append(header::VIA, "d".parse().unwrap());
+
+    for (i, value) in map.get_all_mut(&header::VIA).rev().enumerate() {
+        *value = HeaderValue::from(i);
+    }
+
+    let vals = map.get_all(&header::VIA).iter().collect::<Vec<_>>();
+    assert_eq!(&vals, &[&"2", &"1", &"0"]);
+    assert_eq!(map[header::HOST], "b");
+
+    assert!(map.get_all_mut("x-missing").next_back().is_none());
+}